@@ -2,11 +2,20 @@
 //!
 //! (iOS/macOS only right now... maybe tvOS one day?)
 
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
 fn main() {
-    let target = std::env::var("TARGET").unwrap();
+    let target = env::var("TARGET").unwrap();
+
+    if !target.contains("-apple-") {
+        link_headless_runtime();
+        return;
+    }
 
     println!("cargo:rustc-link-lib=framework=Foundation");
-    
+
     if target.contains("-ios") {
         println!("cargo:rustc-link-lib=framework=UIKit");
     } else {
@@ -19,13 +28,30 @@ fn main() {
 
     #[cfg(feature = "webview")]
     println!("cargo:rustc-link-lib=framework=WebKit");
-    
+
     #[cfg(feature = "cloudkit")]
     println!("cargo:rustc-link-lib=framework=CloudKit");
 
     #[cfg(feature = "user-notifications")]
     println!("cargo:rustc-link-lib=framework=UserNotifications");
-    
+
     #[cfg(feature = "quicklook")]
     println!("cargo:rustc-link-lib=framework=QuickLook");
 }
+
+/// The `headless` module provides the Objective-C and blocks runtimes itself, but `objc` and
+/// `block` still ask to link `libobjc` and `libBlocksRuntime`. Empty archives satisfy the linker
+/// without pulling in a real runtime.
+fn link_headless_runtime() {
+    if env::var_os("CARGO_FEATURE_HEADLESS").is_none() {
+        return;
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    for library in &["libobjc.a", "libBlocksRuntime.a"] {
+        fs::write(out_dir.join(library), b"!<arch>\n").unwrap();
+    }
+
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+}
@@ -2,6 +2,7 @@
 //! work with autolayout, and some basic ways to handle colors.
 
 use cacao::color::{Color, Theme};
+use cacao::foundation::MainThreadMarker;
 use cacao::layout::{Layout, LayoutConstraint};
 use cacao::view::View;
 
@@ -70,7 +71,9 @@ impl WindowDelegate for AppWindow {
 }
 
 fn main() {
+    let mtm = MainThreadMarker::new().expect("main() runs on the main thread");
+
     App::new("com.test.window", BasicApp {
-        window: Window::with(WindowConfig::default(), AppWindow::default())
+        window: Window::with(WindowConfig::default(), AppWindow::default(), mtm)
     }).run();
 }
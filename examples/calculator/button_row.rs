@@ -1,6 +1,7 @@
 use cacao::layout::{LayoutConstraint, Layout};
 use cacao::button::Button;
 use cacao::color::Color;
+use cacao::foundation::MainThreadMarker;
 use cacao::view::View;
 
 use crate::calculator::Msg;
@@ -12,8 +13,8 @@ pub struct ButtonRow {
 }
 
 impl ButtonRow {
-    pub fn new(x: [Msg; 4], color: Color, action_color: Color) -> Self {
-        let view = View::new();
+    pub fn new(x: [Msg; 4], color: Color, action_color: Color, mtm: MainThreadMarker) -> Self {
+        let view = View::new(mtm);
 
         let buttons: Vec<Button> = x.iter().map(|y| {
             let button = button(match y {
@@ -35,7 +36,7 @@ impl ButtonRow {
                 Msg::Push(i) if *i == 9 => "9",
                 _ => "W"
 
-            }, y.clone());
+            }, y.clone(), mtm);
             
             view.add_subview(&button);
             button
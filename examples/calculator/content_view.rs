@@ -2,6 +2,7 @@ use cacao::text::{Font, Label, TextAlign};
 use cacao::layout::{LayoutConstraint, Layout};
 use cacao::button::{Button, BezelStyle};
 use cacao::color::Color;
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::FocusRingType;
 use cacao::view::{View, ViewDelegate};
 
@@ -11,8 +12,8 @@ use crate::calculator::{dispatch, Msg};
 pub const BUTTON_WIDTH: f64 = 57.;
 pub const BUTTON_HEIGHT: f64 = 47.;
 
-pub fn button(text: &str, msg: Msg) -> Button {
-    let mut button = Button::new(text);
+pub fn button(text: &str, msg: Msg, mtm: MainThreadMarker) -> Button {
+    let mut button = Button::new(text, mtm);
     button.set_bordered(false);
     button.set_bezel_style(BezelStyle::SmallSquare);
     button.set_focus_ring_type(FocusRingType::None);
@@ -39,10 +40,10 @@ pub struct CalculatorView {
 }
 
 impl CalculatorView {
-    pub fn new() -> Self {
-        let results_wrapper = View::new();
+    pub fn new(mtm: MainThreadMarker) -> Self {
+        let results_wrapper = View::new(mtm);
 
-        let label = Label::new();
+        let label = Label::new(mtm);
         let font = Font::system(40.);
         label.set_font(&font);
         label.set_text("0");
@@ -55,23 +56,23 @@ impl CalculatorView {
 
             row0: ButtonRow::new([
                 Msg::Clear, Msg::Invert, Msg::Mod, Msg::Divide
-            ], Color::rgb(69, 69, 69), Color::rgb(255, 148, 10)),
+            ], Color::rgb(69, 69, 69), Color::rgb(255, 148, 10), mtm),
 
             row1: ButtonRow::new([
                 Msg::Push(7), Msg::Push(8), Msg::Push(9), Msg::Multiply
-            ], Color::rgb(100, 100, 100), Color::rgb(255, 148, 10)),
+            ], Color::rgb(100, 100, 100), Color::rgb(255, 148, 10), mtm),
 
             row2: ButtonRow::new([
                 Msg::Push(4), Msg::Push(5), Msg::Push(6), Msg::Subtract
-            ], Color::rgb(100, 100, 100), Color::rgb(255, 148, 10)),
+            ], Color::rgb(100, 100, 100), Color::rgb(255, 148, 10), mtm),
 
             row3: ButtonRow::new([
                 Msg::Push(1), Msg::Push(2), Msg::Push(3), Msg::Add
-            ], Color::rgb(100, 100, 100), Color::rgb(255, 148, 10)),
+            ], Color::rgb(100, 100, 100), Color::rgb(255, 148, 10), mtm),
 
-            zero: button("0", Msg::Push(0)),
-            dot: button(".", Msg::Decimal),
-            equals: button("=", Msg::Equals)
+            zero: button("0", Msg::Push(0), mtm),
+            dot: button(".", Msg::Decimal, mtm),
+            equals: button("=", Msg::Equals, mtm)
         }
     }
 
@@ -11,6 +11,7 @@
 
 use std::sync::RwLock;
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::{App, AppDelegate};
 use cacao::macos::window::{Window, WindowConfig, TitleVisibility};
 use cacao::macos::{Event, EventMask, EventMonitor};
@@ -98,12 +99,14 @@ impl CalculatorApp {
 }
 
 fn main() {
+    let mtm = MainThreadMarker::new().expect("main() runs on the main thread");
+
     let mut config = WindowConfig::default();
     config.set_initial_dimensions(100., 100., 240., 300.);
 
     App::new("com.example.calculator", CalculatorApp {
-        window: Window::new(config),
-        content: View::with(CalculatorView::new()),
+        window: Window::new(config, mtm),
+        content: View::with(CalculatorView::new(mtm), mtm),
         key_monitor: RwLock::new(None)
     }).run();
 }
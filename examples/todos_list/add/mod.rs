@@ -1,5 +1,6 @@
 //! Implements a window for adding a new Todo.
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::window::{Window, WindowDelegate};
 use cacao::view::ViewController;
 
@@ -13,8 +14,8 @@ pub struct AddNewTodoWindow {
 }
 
 impl AddNewTodoWindow {
-    pub fn new() -> Self {
-        let content = ViewController::new(AddNewTodoContentView::default());
+    pub fn new(mtm: MainThreadMarker) -> Self {
+        let content = ViewController::new(AddNewTodoContentView::default(), mtm);
         
         AddNewTodoWindow {
             content: content
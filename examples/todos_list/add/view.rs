@@ -43,12 +43,14 @@ impl ViewDelegate for AddNewTodoContentView {
     const NAME: &'static str = "AddNewTodoContentView";
     
     fn did_load(&mut self, view: View) {
-        let instructions = Label::new();
+        let mtm = view.main_thread_marker();
+
+        let instructions = Label::new(mtm);
         instructions.set_text("Let's be real: we both know this task isn't getting done.");
 
-        let input = TextField::new();
+        let input = TextField::new(mtm);
         
-        let mut button = Button::new("Add");
+        let mut button = Button::new("Add", mtm);
         button.set_action(|| dispatch_ui(Message::ProcessNewTodo));
         
         view.add_subview(&instructions);
@@ -1,5 +1,6 @@
 //! Implements a stock-ish Preferences window.
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::window::{Window, WindowDelegate};
 use cacao::macos::toolbar::Toolbar;
 use cacao::view::ViewController;
@@ -25,11 +26,11 @@ pub struct PreferencesWindow {
 }
 
 impl PreferencesWindow {
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         PreferencesWindow {
             toolbar: Toolbar::new("PreferencesToolbar", PreferencesToolbar::default()),
-            general: ViewController::new(GeneralPreferencesContentView::default()),
-            advanced: ViewController::new(AdvancedPreferencesContentView::default()),
+            general: ViewController::new(GeneralPreferencesContentView::default(), mtm),
+            advanced: ViewController::new(AdvancedPreferencesContentView::default(), mtm),
             window: None
         }
     }
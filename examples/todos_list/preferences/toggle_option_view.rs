@@ -16,15 +16,15 @@ pub struct ToggleOptionView {
 impl Default for ToggleOptionView {
     /// Creates and returns a stock toggle view.
     fn default() -> Self {
-        let view = View::new();
+        let view = View::default();
 
-        let switch = Switch::new("");
+        let switch = Switch::new("", view.main_thread_marker());
         view.add_subview(&switch);
 
-        let title = Label::new();
+        let title = Label::default();
         view.add_subview(&title);
         
-        let subtitle = Label::new();
+        let subtitle = Label::default();
         view.add_subview(&subtitle);
 
         LayoutConstraint::activate(&[
@@ -3,6 +3,7 @@
 //! complicated app probably wouldn't, and I figure it's worth having this here for those who might
 //! use this example as a jumping-off point.
 
+use cacao::foundation::MainThreadMarker;
 use cacao::layout::{Layout, LayoutConstraint};
 use cacao::listview::ListView;
 use cacao::view::{View, ViewDelegate};
@@ -15,15 +16,13 @@ pub struct TodosContentView {
     pub todos_list_view: ListView<TodosListView>
 }
 
-impl Default for TodosContentView {
-    fn default() -> Self {
+impl TodosContentView {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         TodosContentView {
-            todos_list_view: ListView::with(TodosListView::default())
+            todos_list_view: ListView::with(TodosListView::default(), mtm)
         }
     }
-}
 
-impl TodosContentView {
     pub fn on_message(&self, message: Message) {
         if let Some(delegate) = &self.todos_list_view.delegate {
             delegate.on_message(message);
@@ -1,5 +1,6 @@
 //! The main Todos window.
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::window::{Window, WindowDelegate};
 use cacao::macos::toolbar::Toolbar;
 use cacao::view::ViewController;
@@ -20,10 +21,10 @@ pub struct TodosWindow {
 }
 
 impl TodosWindow {
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         TodosWindow {
-            content: ViewController::new(TodosContentView::default()),
-            toolbar: Toolbar::new("TodosToolbar", TodosToolbar::default())
+            content: ViewController::new(TodosContentView::new(mtm), mtm),
+            toolbar: Toolbar::new("TodosToolbar", TodosToolbar::new(mtm))
         }
     }
     
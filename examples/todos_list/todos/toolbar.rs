@@ -1,6 +1,7 @@
 //! The main Todos window toolbar. Contains a button to enable adding a new task.
 
 use cacao::button::Button;
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::toolbar::{Toolbar, ToolbarDelegate, ToolbarItem, ToolbarDisplayMode};
 
 use crate::storage::{dispatch_ui, Message};
@@ -8,12 +9,12 @@ use crate::storage::{dispatch_ui, Message};
 #[derive(Debug)]
 pub struct TodosToolbar(ToolbarItem);
 
-impl Default for TodosToolbar {
-    fn default() -> Self {
+impl TodosToolbar {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         TodosToolbar({
             let mut item = ToolbarItem::new("AddTodoButton");
             item.set_title("Add Todo");
-            item.set_button(Button::new("+ New"));
+            item.set_button(Button::new("+ New", mtm));
             
             item.set_action(|| {
                 dispatch_ui(Message::OpenNewTodoSheet);
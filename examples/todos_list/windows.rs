@@ -5,6 +5,7 @@
 
 use std::sync::RwLock;
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::window::{Window, WindowConfig, WindowStyle, WindowDelegate, WindowToolbarStyle};
 use cacao::notification_center::Dispatcher;
 
@@ -21,20 +22,27 @@ pub struct WindowManager {
     pub add: RwLock<Option<Window<AddNewTodoWindow>>>
 }
 
+/// Windows are only ever opened and closed in response to UI messages, which arrive on the main
+/// thread.
+fn main_thread_marker() -> MainThreadMarker {
+    MainThreadMarker::new().expect("Windows must be managed on the main thread.")
+}
+
 /// A helper method to handle checking for window existence, and creating
 /// it if not - then showing it.
 fn open_or_show<T, F>(window: &RwLock<Option<Window<T>>>, vendor: F)
 where
     T: WindowDelegate + 'static,
-    F: Fn() -> (WindowConfig, T)
+    F: Fn(MainThreadMarker) -> (WindowConfig, T)
 {
     let mut lock = window.write().unwrap();
     
     if let Some(win) = &*lock {
         win.show();
     } else {
-        let (config, delegate) = vendor();
-        let win = Window::with(config, delegate);
+        let mtm = main_thread_marker();
+        let (config, delegate) = vendor(mtm);
+        let win = Window::with(config, delegate, mtm);
         win.show();
         *lock = Some(win);
     }
@@ -42,8 +50,8 @@ where
 
 impl WindowManager {
     pub fn open_main(&self) {
-        open_or_show(&self.main, || (
-            WindowConfig::default(), TodosWindow::new()
+        open_or_show(&self.main, |mtm| (
+            WindowConfig::default(), TodosWindow::new(mtm)
         ));
     }
 
@@ -72,7 +80,8 @@ impl WindowManager {
         if let Some(win) = &*lock {
             self.begin_sheet(&win, callback);
         } else {
-            let window = Window::with(WindowConfig::default(), AddNewTodoWindow::new());
+            let mtm = main_thread_marker();
+            let window = Window::with(WindowConfig::default(), AddNewTodoWindow::new(mtm), mtm);
             self.begin_sheet(&window, callback);
             *lock = Some(window);
         }
@@ -95,7 +104,7 @@ impl WindowManager {
     /// Opens a "add file" window, which asks for a code and optional server to
     /// check against.
     pub fn open_preferences(&self) {
-        open_or_show(&self.preferences, || {
+        open_or_show(&self.preferences, |mtm| {
             let mut config = WindowConfig::default();
             config.set_initial_dimensions(100., 100., 400., 400.);
 
@@ -106,7 +115,7 @@ impl WindowManager {
 
             config.toolbar_style = WindowToolbarStyle::Preferences;
 
-            (config, PreferencesWindow::new())
+            (config, PreferencesWindow::new(mtm))
         });
     }
 }
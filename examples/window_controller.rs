@@ -4,6 +4,7 @@
 //!
 //! If you're not using that, you can probably get by fine with a standard `NSWindow`.
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::{App, AppDelegate};
 use cacao::macos::window::{Window, WindowConfig, WindowController, WindowDelegate};
 
@@ -36,7 +37,9 @@ impl WindowDelegate for MyWindow {
 }
 
 fn main() {
+    let mtm = MainThreadMarker::new().expect("main() runs on the main thread");
+
     App::new("com.test.window-delegate", BasicApp {
-        window: WindowController::with(WindowConfig::default(), MyWindow::default())
+        window: WindowController::with(WindowConfig::default(), MyWindow::default(), mtm)
     }).run();
 }
@@ -1,6 +1,7 @@
 //! This example showcases setting up a basic application and window delegate.
 //! Window Delegate's give you lifecycle methods that you can respond to.
 
+use cacao::foundation::MainThreadMarker;
 use cacao::macos::{App, AppDelegate};
 use cacao::macos::window::{Window, WindowConfig, WindowDelegate};
 
@@ -45,7 +46,9 @@ impl WindowDelegate for MyWindow {
 }
 
 fn main() {
+    let mtm = MainThreadMarker::new().expect("main() runs on the main thread");
+
     App::new("com.test.window-delegate", BasicApp {
-        window: Window::with(WindowConfig::default(), MyWindow::default())
+        window: Window::with(WindowConfig::default(), MyWindow::default(), mtm)
     }).run();
 }
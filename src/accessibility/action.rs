@@ -0,0 +1,80 @@
+//! Custom actions, which assistive technologies list alongside an element's built-in ones (e.g,
+//! "Delete" or "Mark as Read" on a list row).
+
+use block::ConcreteBlock;
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::ShareId;
+
+use crate::foundation::{id, BOOL, YES, NO, NSString};
+
+/// A named action that assistive technologies can perform on an element. The handler returns
+/// whether the action succeeded.
+///
+/// This is backed by `NSAccessibilityCustomAction` on macOS (10.13+), and
+/// `UIAccessibilityCustomAction` on iOS (13+), which hold on to the handler for as long as they're
+/// alive; there's no need to keep this around once it's been set on an element.
+#[derive(Clone, Debug)]
+pub struct AccessibilityAction {
+    /// The underlying custom action object.
+    pub objc: ShareId<Object>
+}
+
+impl AccessibilityAction {
+    /// Creates a new action, shown to the user as `name`.
+    pub fn new<F: Fn() -> bool + 'static>(name: &str, handler: F) -> Self {
+        let name = NSString::new(name);
+
+        #[cfg(feature = "macos")]
+        let block = ConcreteBlock::new(move || -> BOOL {
+            match handler() {
+                true => YES,
+                false => NO
+            }
+        });
+
+        #[cfg(feature = "ios")]
+        let block = ConcreteBlock::new(move |_action: id| -> BOOL {
+            match handler() {
+                true => YES,
+                false => NO
+            }
+        });
+
+        let block = block.copy();
+
+        AccessibilityAction {
+            objc: unsafe {
+                #[cfg(feature = "macos")]
+                let action: id = {
+                    let alloc: id = msg_send![class!(NSAccessibilityCustomAction), alloc];
+                    msg_send![alloc, initWithName:name.into_inner() handler:&*block]
+                };
+
+                #[cfg(feature = "ios")]
+                let action: id = {
+                    let alloc: id = msg_send![class!(UIAccessibilityCustomAction), alloc];
+                    msg_send![alloc, initWithName:name.into_inner() actionHandler:&*block]
+                };
+
+                ShareId::from_retained_ptr(action)
+            }
+        }
+    }
+
+    /// Returns the name of this action.
+    pub fn name(&self) -> String {
+        NSString::wrap(unsafe { msg_send![&*self.objc, name] }).to_string()
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::AccessibilityAction;
+
+    #[test]
+    fn keeps_its_name() {
+        let action = AccessibilityAction::new("Mark as Read", || true);
+        assert_eq!(action.name(), "Mark as Read");
+    }
+}
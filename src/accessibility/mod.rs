@@ -0,0 +1,346 @@
+//! Accessibility support for controls - labels, hints, roles, custom actions and the like, which
+//! assistive technologies (VoiceOver, Switch Control, etc) use to describe and drive your UI.
+//!
+//! Every control gets this by way of the `Accessibility` trait, which is implemented for anything
+//! that implements `Layout`:
+//!
+//! ```rust,no_run
+//! use cacao::accessibility::{Accessibility, AccessibilityAction, AccessibilityRole};
+//! use cacao::foundation::MainThreadMarker;
+//! use cacao::view::View;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let card = View::new(mtm);
+//! card.set_accessibility_label("Inbox, 3 unread");
+//! card.set_accessibility_role(AccessibilityRole::Group);
+//! card.set_accessibility_custom_actions(vec![
+//!     AccessibilityAction::new("Mark All as Read", || {
+//!         // ...
+//!         true
+//!     })
+//! ]);
+//! ```
+//!
+//! Standard controls (buttons, text fields, etc) already describe themselves; you generally only
+//! need this for custom views, or to give a control a better label than it'd otherwise have.
+//!
+//! For changes assistive technologies can't pick up on their own, post an
+//! `AccessibilityNotification`.
+
+use objc::runtime::{Object, Sel};
+use objc::{msg_send, sel, sel_impl};
+use objc_id::ShareId;
+
+use crate::foundation::{id, nil, to_bool, NSArray, NSString, BOOL, YES, NO};
+use crate::layout::Layout;
+
+mod action;
+pub use action::AccessibilityAction;
+
+mod notification;
+pub use notification::{AccessibilityNotification, AnnouncementPriority};
+
+mod role;
+pub use role::AccessibilityRole;
+
+/// Reads an `NSString` property, returning `None` if it's unset.
+fn string_property(value: id) -> Option<String> {
+    match value.is_null() {
+        true => None,
+        false => Some(NSString::wrap(value).to_string())
+    }
+}
+
+/// Checks whether `node` implements `selector`; some of these properties are newer than the
+/// oldest OS versions we support.
+fn responds_to(node: &ShareId<Object>, selector: Sel) -> bool {
+    let responds: BOOL = unsafe { msg_send![&**node, respondsToSelector:selector] };
+    to_bool(responds)
+}
+
+/// Accessibility properties and behavior for a control. This is implemented for every type that
+/// implements `Layout`, so you shouldn't need to implement it yourself.
+///
+/// Properties map onto the `NSAccessibility` protocol on macOS, and `UIAccessibility` on iOS.
+pub trait Accessibility: Layout {
+    /// Sets the label read for this control (e.g, "Delete" for a trash can button). Labels should
+    /// be short, and shouldn't include the control type; the role covers that.
+    fn set_accessibility_label(&self, label: &str) {
+        let node = self.get_backing_node();
+        let label = NSString::new(label);
+
+        unsafe {
+            let _: () = msg_send![&*node, setAccessibilityLabel:label.into_inner()];
+        }
+    }
+
+    /// Returns the label set for this control, if any.
+    fn accessibility_label(&self) -> Option<String> {
+        let node = self.get_backing_node();
+        string_property(unsafe { msg_send![&*node, accessibilityLabel] })
+    }
+
+    /// Sets the help text for this control, describing what it does (e.g, "Moves the selected
+    /// messages to the trash"). On iOS, this is the accessibility hint.
+    fn set_accessibility_help(&self, help: &str) {
+        let node = self.get_backing_node();
+        let help = NSString::new(help);
+
+        unsafe {
+            #[cfg(feature = "macos")]
+            let _: () = msg_send![&*node, setAccessibilityHelp:help.into_inner()];
+
+            #[cfg(feature = "ios")]
+            let _: () = msg_send![&*node, setAccessibilityHint:help.into_inner()];
+        }
+    }
+
+    /// Returns the help text set for this control, if any.
+    fn accessibility_help(&self) -> Option<String> {
+        let node = self.get_backing_node();
+
+        #[cfg(feature = "macos")]
+        let help: id = unsafe { msg_send![&*node, accessibilityHelp] };
+
+        #[cfg(feature = "ios")]
+        let help: id = unsafe { msg_send![&*node, accessibilityHint] };
+
+        string_property(help)
+    }
+
+    /// Sets the role of this control. On iOS, this sets the closest matching traits.
+    fn set_accessibility_role(&self, role: AccessibilityRole) {
+        let node = self.get_backing_node();
+
+        #[cfg(feature = "macos")]
+        unsafe {
+            let role = NSString::new(role.as_str());
+            let _: () = msg_send![&*node, setAccessibilityRole:role.into_inner()];
+        }
+
+        #[cfg(feature = "ios")]
+        unsafe {
+            let _: () = msg_send![&*node, setAccessibilityTraits:role.traits()];
+        }
+    }
+
+    /// Sets the value read for this control (e.g, "50%" for a custom slider).
+    fn set_accessibility_value(&self, value: &str) {
+        let node = self.get_backing_node();
+        let value = NSString::new(value);
+
+        unsafe {
+            let _: () = msg_send![&*node, setAccessibilityValue:value.into_inner()];
+        }
+    }
+
+    /// Returns the value of this control, if it's a string. Standard controls report their own
+    /// value here unless one's been set.
+    fn accessibility_value(&self) -> Option<String> {
+        let node = self.get_backing_node();
+        let value: id = unsafe { msg_send![&*node, accessibilityValue] };
+
+        match !value.is_null() && NSString::is(value) {
+            true => Some(NSString::wrap(value).to_string()),
+            false => None
+        }
+    }
+
+    /// Sets an identifier for this control. This is never read to the user; it's for finding the
+    /// control in UI tests and automation.
+    fn set_accessibility_identifier(&self, identifier: &str) {
+        let node = self.get_backing_node();
+        let identifier = NSString::new(identifier);
+
+        unsafe {
+            let _: () = msg_send![&*node, setAccessibilityIdentifier:identifier.into_inner()];
+        }
+    }
+
+    /// Returns the identifier set for this control, if any.
+    fn accessibility_identifier(&self) -> Option<String> {
+        let node = self.get_backing_node();
+        string_property(unsafe { msg_send![&*node, accessibilityIdentifier] })
+    }
+
+    /// Sets whether this control is exposed to assistive technologies at all. Plain views aren't
+    /// by default; standard controls are.
+    fn set_accessibility_element(&self, is_element: bool) {
+        let node = self.get_backing_node();
+
+        unsafe {
+            #[cfg(feature = "macos")]
+            let _: () = msg_send![&*node, setAccessibilityElement:match is_element {
+                true => YES,
+                false => NO
+            }];
+
+            #[cfg(feature = "ios")]
+            let _: () = msg_send![&*node, setIsAccessibilityElement:match is_element {
+                true => YES,
+                false => NO
+            }];
+        }
+    }
+
+    /// Sets whether this control's children should be treated as one group, and read together.
+    ///
+    /// On macOS, this exposes the control as an element with the `Group` role (or hides it again,
+    /// if `false`). On iOS, this sets `shouldGroupAccessibilityChildren`.
+    fn set_accessibility_grouped(&self, grouped: bool) {
+        #[cfg(feature = "macos")]
+        {
+            self.set_accessibility_element(grouped);
+
+            if grouped {
+                self.set_accessibility_role(AccessibilityRole::Group);
+            }
+        }
+
+        #[cfg(feature = "ios")]
+        {
+            let node = self.get_backing_node();
+
+            unsafe {
+                let _: () = msg_send![&*node, setShouldGroupAccessibilityChildren:match grouped {
+                    true => YES,
+                    false => NO
+                }];
+            }
+        }
+    }
+
+    /// Sets the custom actions offered for this control, replacing any set before.
+    ///
+    /// Requires macOS 10.13+ or iOS 13+; on older systems, this does nothing.
+    fn set_accessibility_custom_actions(&self, actions: Vec<AccessibilityAction>) {
+        let node = self.get_backing_node();
+
+        if !responds_to(&node, sel!(setAccessibilityCustomActions:)) {
+            return;
+        }
+
+        let actions: Vec<&Object> = actions.iter().map(|action| &*action.objc).collect();
+        let actions = NSArray::from(actions);
+
+        unsafe {
+            let _: () = msg_send![&*node, setAccessibilityCustomActions:actions.into_inner()];
+        }
+    }
+
+    /// Sets the order assistive technologies move through this control's children in, overriding
+    /// the default (which follows the layout). Pass the backing nodes of the children, via
+    /// `get_backing_node()`; an empty slice restores the default.
+    ///
+    /// On macOS this sets `accessibilityChildrenInNavigationOrder` (10.13+), and on iOS
+    /// `accessibilityElements`.
+    fn set_accessibility_focus_order(&self, elements: &[ShareId<Object>]) {
+        let node = self.get_backing_node();
+
+        let order: id = match elements.is_empty() {
+            true => nil,
+            false => {
+                let elements: Vec<&Object> = elements.iter().map(|element| &**element).collect();
+                NSArray::from(elements).into_inner()
+            }
+        };
+
+        unsafe {
+            #[cfg(feature = "macos")]
+            {
+                if responds_to(&node, sel!(setAccessibilityChildrenInNavigationOrder:)) {
+                    let _: () = msg_send![&*node, setAccessibilityChildrenInNavigationOrder:order];
+                }
+            }
+
+            #[cfg(feature = "ios")]
+            let _: () = msg_send![&*node, setAccessibilityElements:order];
+        }
+    }
+
+    /// Sets the view that keyboard focus moves to when the user presses Tab from this one.
+    #[cfg(feature = "macos")]
+    fn set_next_key_view<L: Layout>(&self, view: &L) {
+        let node = self.get_backing_node();
+        let next = view.get_backing_node();
+
+        unsafe {
+            let _: () = msg_send![&*node, setNextKeyView:&*next];
+        }
+    }
+}
+
+impl<T: Layout> Accessibility for T {}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::{Accessibility, AccessibilityAction};
+    use crate::foundation::MainThreadMarker;
+    use crate::headless::{self, ObjectId, Value};
+    use crate::layout::Layout;
+    use crate::text::Label;
+    use crate::view::View;
+
+    #[test]
+    fn records_properties() {
+        let label = Label::new(MainThreadMarker::new().unwrap());
+        assert_eq!(label.accessibility_label(), None);
+
+        label.set_accessibility_label("Unread");
+        label.set_accessibility_help("Messages you haven't opened yet");
+        label.set_accessibility_value("3");
+        label.set_accessibility_identifier("inbox-unread");
+
+        assert_eq!(label.accessibility_label(), Some("Unread".to_string()));
+        assert_eq!(label.accessibility_help(), Some("Messages you haven't opened yet".to_string()));
+        assert_eq!(label.accessibility_value(), Some("3".to_string()));
+        assert_eq!(label.accessibility_identifier(), Some("inbox-unread".to_string()));
+
+        let node = headless::inspect(&label);
+        assert_eq!(node.property("accessibilityLabel"), Some(&Value::String("Unread".to_string())));
+        assert_eq!(node.property("accessibilityIdentifier"), Some(&Value::String("inbox-unread".to_string())));
+    }
+
+    #[test]
+    fn groups_children() {
+        let card = View::new(MainThreadMarker::new().unwrap());
+
+        card.set_accessibility_grouped(true);
+        let node = headless::inspect(&card);
+        assert_eq!(node.property("accessibilityElement"), Some(&Value::Bool(true)));
+        assert_eq!(node.property("accessibilityRole"), Some(&Value::String("AXGroup".to_string())));
+
+        card.set_accessibility_grouped(false);
+        let node = headless::inspect(&card);
+        assert_eq!(node.property("accessibilityElement"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn performs_custom_actions() {
+        let card = View::new(MainThreadMarker::new().unwrap());
+        let archived = Rc::new(Cell::new(0));
+
+        let counter = archived.clone();
+        card.set_accessibility_custom_actions(vec![
+            AccessibilityAction::new("Archive", move || {
+                counter.set(counter.get() + 1);
+                true
+            }),
+            AccessibilityAction::new("Delete", || false)
+        ]);
+
+        let card_id = ObjectId::of(&card.get_backing_node());
+        assert_eq!(headless::perform_accessibility_action(card_id, "Archive"), Some(true));
+        assert_eq!(headless::perform_accessibility_action(card_id, "Delete"), Some(false));
+        assert_eq!(headless::perform_accessibility_action(card_id, "Share"), None);
+        assert_eq!(archived.get(), 1);
+
+        // Setting actions again replaces them.
+        card.set_accessibility_custom_actions(vec![AccessibilityAction::new("Share", || true)]);
+        assert_eq!(headless::perform_accessibility_action(card_id, "Archive"), None);
+        assert_eq!(headless::perform_accessibility_action(card_id, "Share"), Some(true));
+    }
+}
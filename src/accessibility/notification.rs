@@ -0,0 +1,214 @@
+//! Notifications that tell assistive technologies something changed, for changes they can't
+//! observe on their own (e.g, a custom view redrawing with a new value, or a status update that
+//! should be read aloud).
+
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, NSString};
+use crate::layout::Layout;
+
+#[cfg(feature = "macos")]
+use crate::foundation::{NSDictionary, NSNumber};
+
+#[cfg(feature = "macos")]
+extern "C" {
+    fn NSAccessibilityPostNotification(element: id, notification: id);
+    fn NSAccessibilityPostNotificationWithUserInfo(element: id, notification: id, user_info: id);
+}
+
+#[cfg(feature = "ios")]
+extern "C" {
+    static UIAccessibilityLayoutChangedNotification: u32;
+    static UIAccessibilityAnnouncementNotification: u32;
+
+    fn UIAccessibilityPostNotification(notification: u32, argument: id);
+}
+
+/// How urgently an announcement should be read. Higher priority announcements interrupt whatever
+/// is currently being spoken; lower ones may be dropped if something else is.
+///
+/// On iOS, announcements are always read at the system's default priority.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AnnouncementPriority {
+    Low,
+    Medium,
+    High
+}
+
+impl From<AnnouncementPriority> for i64 {
+    fn from(priority: AnnouncementPriority) -> Self {
+        match priority {
+            AnnouncementPriority::Low => 10,
+            AnnouncementPriority::Medium => 50,
+            AnnouncementPriority::High => 90
+        }
+    }
+}
+
+/// An accessibility notification, which can be posted for an element.
+///
+/// iOS has far fewer of these than macOS does: `LayoutChanged`, `FocusedElementChanged` and
+/// `Announcement` are posted as their `UIAccessibility` equivalents, and the rest do nothing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessibilityNotification {
+    /// The element's value changed.
+    ValueChanged,
+
+    /// The element's title changed.
+    TitleChanged,
+
+    /// Elements were added, removed or moved within the element.
+    LayoutChanged,
+
+    /// Focus moved to the element.
+    FocusedElementChanged,
+
+    /// The selected children of the element (e.g, rows in a list) changed.
+    SelectedChildrenChanged,
+
+    /// The selected text in the element changed.
+    SelectedTextChanged,
+
+    /// The number of rows in the element changed.
+    RowCountChanged,
+
+    /// The element was created.
+    Created,
+
+    /// The element was destroyed.
+    Destroyed,
+
+    /// The element moved.
+    Moved,
+
+    /// The element was resized.
+    Resized,
+
+    /// Asks assistive technologies to read `message` aloud.
+    Announcement {
+        message: String,
+        priority: AnnouncementPriority
+    }
+}
+
+impl AccessibilityNotification {
+    /// Returns the `NSAccessibilityNotificationName` for this notification.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessibilityNotification::ValueChanged => "AXValueChanged",
+            AccessibilityNotification::TitleChanged => "AXTitleChanged",
+            AccessibilityNotification::LayoutChanged => "AXLayoutChanged",
+            AccessibilityNotification::FocusedElementChanged => "AXFocusedUIElementChanged",
+            AccessibilityNotification::SelectedChildrenChanged => "AXSelectedChildrenChanged",
+            AccessibilityNotification::SelectedTextChanged => "AXSelectedTextChanged",
+            AccessibilityNotification::RowCountChanged => "AXRowCountChanged",
+            AccessibilityNotification::Created => "AXCreated",
+            AccessibilityNotification::Destroyed => "AXUIElementDestroyed",
+            AccessibilityNotification::Moved => "AXMoved",
+            AccessibilityNotification::Resized => "AXResized",
+            AccessibilityNotification::Announcement { .. } => "AXAnnouncementRequested"
+        }
+    }
+
+    /// Posts this notification for `element`.
+    pub fn post<L: Layout>(&self, element: &L) {
+        let node = element.get_backing_node();
+        self.post_for(&*node as *const _ as id);
+    }
+
+    /// Posts this notification for the application as a whole. This is mostly useful for
+    /// announcements that aren't tied to any one element.
+    pub fn post_for_application(&self) {
+        #[cfg(feature = "macos")]
+        let app: id = unsafe { msg_send![class!(NSApplication), sharedApplication] };
+
+        #[cfg(feature = "ios")]
+        let app: id = unsafe { msg_send![class!(UIApplication), sharedApplication] };
+
+        self.post_for(app);
+    }
+
+    #[cfg(feature = "macos")]
+    fn post_for(&self, element: id) {
+        let name = NSString::new(self.name());
+
+        unsafe {
+            match self {
+                AccessibilityNotification::Announcement { message, priority } => {
+                    let mut user_info = NSDictionary::new();
+                    user_info.insert(NSString::new("AXAnnouncementKey"), NSString::new(message).into_inner());
+                    user_info.insert(NSString::new("AXPriorityKey"), NSNumber::integer((*priority).into()).into_inner());
+                    NSAccessibilityPostNotificationWithUserInfo(element, name.into_inner(), user_info.into_inner());
+                },
+
+                _ => NSAccessibilityPostNotification(element, name.into_inner())
+            }
+        }
+    }
+
+    #[cfg(feature = "ios")]
+    fn post_for(&self, element: id) {
+        unsafe {
+            match self {
+                AccessibilityNotification::LayoutChanged | AccessibilityNotification::FocusedElementChanged => {
+                    UIAccessibilityPostNotification(UIAccessibilityLayoutChangedNotification, element);
+                },
+
+                AccessibilityNotification::Announcement { message, .. } => {
+                    let message = NSString::new(message);
+                    UIAccessibilityPostNotification(UIAccessibilityAnnouncementNotification, message.into_inner());
+                },
+
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{AccessibilityNotification, AnnouncementPriority};
+    use crate::foundation::MainThreadMarker;
+    use crate::headless::{self, AccessibilityPost, ObjectId, Value};
+    use crate::layout::Layout;
+    use crate::view::View;
+
+    /// Returns the notifications posted for `element`; other tests post to the same store.
+    fn posted_for(element: ObjectId) -> Vec<AccessibilityPost> {
+        headless::accessibility_notifications().into_iter()
+            .filter(|post| post.element == element)
+            .collect()
+    }
+
+    #[test]
+    fn posts_for_an_element() {
+        let view = View::new(MainThreadMarker::new().unwrap());
+        let element = ObjectId::of(&view.get_backing_node());
+
+        AccessibilityNotification::ValueChanged.post(&view);
+        AccessibilityNotification::Announcement {
+            message: "Upload complete".to_string(),
+            priority: AnnouncementPriority::High
+        }.post(&view);
+
+        let mut user_info = BTreeMap::new();
+        user_info.insert("AXAnnouncementKey".to_string(), Value::String("Upload complete".to_string()));
+        user_info.insert("AXPriorityKey".to_string(), Value::Integer(90));
+
+        assert_eq!(posted_for(element), vec![
+            AccessibilityPost {
+                element: element,
+                name: "AXValueChanged".to_string(),
+                user_info: BTreeMap::new()
+            },
+
+            AccessibilityPost {
+                element: element,
+                name: "AXAnnouncementRequested".to_string(),
+                user_info: user_info
+            }
+        ]);
+    }
+}
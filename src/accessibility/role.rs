@@ -0,0 +1,134 @@
+//! Roles, which tell assistive technologies (e.g, VoiceOver) what kind of element they're
+//! looking at.
+
+/// The role of an accessibility element.
+///
+/// On macOS, these map onto `NSAccessibilityRole` values. iOS has no roles as such; there, roles
+/// map onto the closest `UIAccessibilityTraits` (and roles without one clear the traits).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    Button,
+    CheckBox,
+    RadioButton,
+    PopUpButton,
+    Link,
+    Image,
+    StaticText,
+    TextField,
+    TextArea,
+    Slider,
+    ProgressIndicator,
+
+    /// A container whose children are read together.
+    Group,
+    List,
+    Table,
+    Row,
+    Cell,
+    ScrollArea,
+    Toolbar,
+
+    /// Any other role, by its `NSAccessibilityRole` value (e.g, `AXDisclosureTriangle`).
+    Custom(String)
+}
+
+impl AccessibilityRole {
+    /// Returns the `NSAccessibilityRole` value for this role.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccessibilityRole::Button => "AXButton",
+            AccessibilityRole::CheckBox => "AXCheckBox",
+            AccessibilityRole::RadioButton => "AXRadioButton",
+            AccessibilityRole::PopUpButton => "AXPopUpButton",
+            AccessibilityRole::Link => "AXLink",
+            AccessibilityRole::Image => "AXImage",
+            AccessibilityRole::StaticText => "AXStaticText",
+            AccessibilityRole::TextField => "AXTextField",
+            AccessibilityRole::TextArea => "AXTextArea",
+            AccessibilityRole::Slider => "AXSlider",
+            AccessibilityRole::ProgressIndicator => "AXProgressIndicator",
+            AccessibilityRole::Group => "AXGroup",
+            AccessibilityRole::List => "AXList",
+            AccessibilityRole::Table => "AXTable",
+            AccessibilityRole::Row => "AXRow",
+            AccessibilityRole::Cell => "AXCell",
+            AccessibilityRole::ScrollArea => "AXScrollArea",
+            AccessibilityRole::Toolbar => "AXToolbar",
+            AccessibilityRole::Custom(role) => role
+        }
+    }
+}
+
+impl From<&str> for AccessibilityRole {
+    /// Maps an `NSAccessibilityRole` value back onto a role. Anything unrecognized becomes
+    /// `Custom`.
+    fn from(role: &str) -> Self {
+        match role {
+            "AXButton" => AccessibilityRole::Button,
+            "AXCheckBox" => AccessibilityRole::CheckBox,
+            "AXRadioButton" => AccessibilityRole::RadioButton,
+            "AXPopUpButton" => AccessibilityRole::PopUpButton,
+            "AXLink" => AccessibilityRole::Link,
+            "AXImage" => AccessibilityRole::Image,
+            "AXStaticText" => AccessibilityRole::StaticText,
+            "AXTextField" => AccessibilityRole::TextField,
+            "AXTextArea" => AccessibilityRole::TextArea,
+            "AXSlider" => AccessibilityRole::Slider,
+            "AXProgressIndicator" => AccessibilityRole::ProgressIndicator,
+            "AXGroup" => AccessibilityRole::Group,
+            "AXList" => AccessibilityRole::List,
+            "AXTable" => AccessibilityRole::Table,
+            "AXRow" => AccessibilityRole::Row,
+            "AXCell" => AccessibilityRole::Cell,
+            "AXScrollArea" => AccessibilityRole::ScrollArea,
+            "AXToolbar" => AccessibilityRole::Toolbar,
+            role => AccessibilityRole::Custom(role.to_string())
+        }
+    }
+}
+
+#[cfg(feature = "ios")]
+extern "C" {
+    static UIAccessibilityTraitNone: u64;
+    static UIAccessibilityTraitButton: u64;
+    static UIAccessibilityTraitLink: u64;
+    static UIAccessibilityTraitImage: u64;
+    static UIAccessibilityTraitStaticText: u64;
+    static UIAccessibilityTraitAdjustable: u64;
+    static UIAccessibilityTraitUpdatesFrequently: u64;
+}
+
+#[cfg(feature = "ios")]
+impl AccessibilityRole {
+    /// Returns the `UIAccessibilityTraits` closest to this role.
+    pub(crate) fn traits(&self) -> u64 {
+        unsafe {
+            match self {
+                AccessibilityRole::Button | AccessibilityRole::CheckBox | AccessibilityRole::RadioButton |
+                AccessibilityRole::PopUpButton => UIAccessibilityTraitButton,
+                AccessibilityRole::Link => UIAccessibilityTraitLink,
+                AccessibilityRole::Image => UIAccessibilityTraitImage,
+                AccessibilityRole::StaticText => UIAccessibilityTraitStaticText,
+                AccessibilityRole::Slider => UIAccessibilityTraitAdjustable,
+                AccessibilityRole::ProgressIndicator => UIAccessibilityTraitUpdatesFrequently,
+                _ => UIAccessibilityTraitNone
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AccessibilityRole;
+
+    #[test]
+    fn roles_round_trip_through_their_names() {
+        for role in &[AccessibilityRole::Button, AccessibilityRole::StaticText, AccessibilityRole::Group] {
+            assert_eq!(&AccessibilityRole::from(role.as_str()), role);
+        }
+
+        let custom = AccessibilityRole::from("AXDisclosureTriangle");
+        assert_eq!(custom, AccessibilityRole::Custom("AXDisclosureTriangle".to_string()));
+        assert_eq!(custom.as_str(), "AXDisclosureTriangle");
+    }
+}
@@ -0,0 +1,310 @@
+//! WCAG 2.x contrast checking, for plain colors and for dynamic (appearance-dependent) palettes.
+//!
+//! `Color::dynamic` picks a color per `Style`, but nothing stops you from handing it a light-mode
+//! gray that vanishes on a dark background. The functions here evaluate a palette for every
+//! `Style` (theme, contrast level, and so on) and compute contrast ratios, so you can catch that
+//! in a test rather than by squinting at the app:
+//!
+//! ```rust
+//! use cacao::color::{ColorValue, Rgba, Style, Theme};
+//! use cacao::color::contrast::{validate_dynamic, WcagLevel};
+//!
+//! fn text(style: Style) -> ColorValue {
+//!     match style.theme {
+//!         Theme::Light => ColorValue::Srgb(Rgba::from_hex(0x333333)),
+//!         Theme::Dark => ColorValue::Srgb(Rgba::from_hex(0xEEEEEE))
+//!     }
+//! }
+//!
+//! fn background(style: Style) -> ColorValue {
+//!     match style.theme {
+//!         Theme::Light => ColorValue::gray(1.),
+//!         Theme::Dark => ColorValue::gray(0.1)
+//!     }
+//! }
+//!
+//! assert!(validate_dynamic(text, background, WcagLevel::AA).is_ok());
+//! ```
+//!
+//! The palette functions return `ColorValue`s rather than `Color`s, so all of this runs without
+//! the Objective-C runtime. Use `Color::dynamic(|style| text(style).into())` to build the actual
+//! color from the same function.
+
+use crate::color::Style;
+use crate::color::value::{to_linear, ColorSpace, ColorValue};
+
+/// The WCAG 2.x conformance levels for text contrast.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WcagLevel {
+    /// Level AA for regular text: a ratio of at least 4.5:1.
+    AA,
+
+    /// Level AA for large text (18pt, or 14pt bold): a ratio of at least 3:1.
+    AALargeText,
+
+    /// Level AAA for regular text: a ratio of at least 7:1.
+    AAA,
+
+    /// Level AAA for large text (18pt, or 14pt bold): a ratio of at least 4.5:1.
+    AAALargeText
+}
+
+impl WcagLevel {
+    /// The minimum contrast ratio this level requires.
+    pub fn minimum_ratio(&self) -> f64 {
+        match self {
+            WcagLevel::AA => 4.5,
+            WcagLevel::AALargeText => 3.,
+            WcagLevel::AAA => 7.,
+            WcagLevel::AAALargeText => 4.5
+        }
+    }
+}
+
+/// Returns the WCAG relative luminance of a color, from `0.` (black) to `1.` (white). Alpha is
+/// ignored.
+pub fn relative_luminance(color: &ColorValue) -> f64 {
+    let rgba = color.to_srgb();
+    0.212_6 * to_linear(rgba.red) + 0.715_2 * to_linear(rgba.green) + 0.072_2 * to_linear(rgba.blue)
+}
+
+/// Returns the WCAG contrast ratio of `foreground` drawn on `background`, from `1.` to `21.`.
+///
+/// A translucent foreground is composited over the background first. The background's own alpha
+/// is ignored, as there's no telling what's behind it.
+pub fn contrast_ratio(foreground: &ColorValue, background: &ColorValue) -> f64 {
+    let background = background.with_alpha(1.);
+    let foreground = foreground.composite_over(&background);
+
+    let a = relative_luminance(&foreground);
+    let b = relative_luminance(&background);
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Returns a color that meets `level` against `background`, changing only the lightness of
+/// `foreground` (and by as little as possible). The result is in the same color space as
+/// `foreground`.
+///
+/// Returns `foreground` unchanged if it already passes, and `None` if no lightness would pass -
+/// e.g, AAA against a mid-gray background, or a foreground too translucent to ever stand out.
+pub fn suggest(foreground: &ColorValue, background: &ColorValue, level: WcagLevel) -> Option<ColorValue> {
+    let minimum = level.minimum_ratio();
+
+    if contrast_ratio(foreground, background) >= minimum {
+        return Some(*foreground);
+    }
+
+    let (hue, saturation, lightness, alpha) = match foreground.convert(ColorSpace::Hsl) {
+        ColorValue::Hsl { hue, saturation, lightness, alpha } => (hue, saturation, lightness, alpha),
+        _ => { return None; }
+    };
+
+    let with_lightness = |lightness: f64| ColorValue::Hsl { hue, saturation, lightness, alpha };
+    let passes = |lightness: f64| contrast_ratio(&with_lightness(lightness), background) >= minimum;
+
+    // Contrast only grows as we move further from the background's luminance, so each direction
+    // can be binary searched for the closest passing lightness.
+    let search = |target: f64| -> Option<f64> {
+        if !passes(target) {
+            return None;
+        }
+
+        let (mut failing, mut passing) = (lightness, target);
+
+        for _ in 0..32 {
+            let middle = (failing + passing) / 2.;
+
+            match passes(middle) {
+                true => { passing = middle; },
+                false => { failing = middle; }
+            }
+        }
+
+        Some(passing)
+    };
+
+    let closest = match (search(0.), search(1.)) {
+        (Some(darker), Some(lighter)) => match lightness - darker <= lighter - lightness {
+            true => darker,
+            false => lighter
+        },
+
+        (Some(darker), None) => darker,
+        (None, Some(lighter)) => lighter,
+        (None, None) => { return None; }
+    };
+
+    Some(with_lightness(closest).convert(foreground.space()))
+}
+
+/// The contrast of a dynamic palette in a single `Style`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ContrastCheck {
+    /// The style the palette was evaluated for.
+    pub style: Style,
+
+    /// The foreground color in this style.
+    pub foreground: ColorValue,
+
+    /// The background color in this style.
+    pub background: ColorValue,
+
+    /// The contrast ratio of `foreground` on `background`.
+    pub ratio: f64
+}
+
+impl ContrastCheck {
+    /// Returns whether this check meets the given level.
+    pub fn passes(&self, level: WcagLevel) -> bool {
+        self.ratio >= level.minimum_ratio()
+    }
+
+    /// Returns a foreground color that meets `level` in this style. See `suggest` for details.
+    pub fn suggest(&self, level: WcagLevel) -> Option<ColorValue> {
+        suggest(&self.foreground, &self.background, level)
+    }
+}
+
+/// Evaluates `foreground` and `background` for every style in `Style::all()`, and returns the
+/// contrast in each.
+pub fn check_dynamic<F, B>(foreground: F, background: B) -> Vec<ContrastCheck>
+where
+    F: Fn(Style) -> ColorValue,
+    B: Fn(Style) -> ColorValue
+{
+    Style::all().into_iter().map(|style| {
+        let fg = foreground(style);
+        let bg = background(style);
+
+        ContrastCheck {
+            style: style,
+            foreground: fg,
+            background: bg,
+            ratio: contrast_ratio(&fg, &bg)
+        }
+    }).collect()
+}
+
+/// Checks a dynamic palette against `level`, returning every style that falls short. Handy for
+/// failing a test (or a build) on an unreadable palette.
+pub fn validate_dynamic<F, B>(foreground: F, background: B, level: WcagLevel) -> Result<(), Vec<ContrastCheck>>
+where
+    F: Fn(Style) -> ColorValue,
+    B: Fn(Style) -> ColorValue
+{
+    let failures: Vec<ContrastCheck> = check_dynamic(foreground, background)
+        .into_iter()
+        .filter(|check| !check.passes(level))
+        .collect();
+
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(failures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_dynamic, contrast_ratio, relative_luminance, suggest, validate_dynamic, WcagLevel};
+    use crate::color::{Contrast, Rgba, Style, Theme, Transparency};
+    use crate::color::value::ColorValue;
+
+    /// A deliberately weak palette: mid-grays on light and dark backgrounds, with a little
+    /// variation per style so that each one needs its own suggestion.
+    fn foreground(style: Style) -> ColorValue {
+        let base = match style.theme {
+            Theme::Light => 0.6,
+            Theme::Dark => 0.4
+        };
+
+        let contrast = match style.contrast {
+            Contrast::Normal => 0.,
+            Contrast::High => 0.05
+        };
+
+        ColorValue::Srgb(Rgba::new(base + contrast, base, base - contrast, 1.))
+    }
+
+    fn background(style: Style) -> ColorValue {
+        match (style.theme, style.transparency) {
+            (Theme::Light, Transparency::Normal) => ColorValue::gray(1.),
+            (Theme::Light, Transparency::Reduced) => ColorValue::gray(0.95),
+            (Theme::Dark, Transparency::Normal) => ColorValue::gray(0.),
+            (Theme::Dark, Transparency::Reduced) => ColorValue::gray(0.12)
+        }
+    }
+
+    #[test]
+    fn computes_known_ratios() {
+        let black = ColorValue::gray(0.);
+        let white = ColorValue::gray(1.);
+
+        assert_eq!(relative_luminance(&black), 0.);
+        assert!((relative_luminance(&white) - 1.).abs() < 1e-9);
+
+        assert!((contrast_ratio(&black, &white) - 21.).abs() < 1e-9);
+        assert!((contrast_ratio(&white, &black) - 21.).abs() < 1e-9);
+        assert!((contrast_ratio(&white, &white) - 1.).abs() < 1e-9);
+
+        let blue = ColorValue::Srgb(Rgba::from_hex(0x336699));
+        assert!((contrast_ratio(&blue, &blue) - 1.).abs() < 1e-9);
+
+        // #777 on white is the classic "just misses AA" gray.
+        let gray = ColorValue::Srgb(Rgba::from_hex(0x777777));
+        assert!((contrast_ratio(&gray, &white) - 4.48).abs() < 0.01);
+    }
+
+    #[test]
+    fn composites_translucent_foregrounds() {
+        let translucent = ColorValue::gray(0.).with_alpha(0.);
+        let white = ColorValue::gray(1.);
+
+        assert!((contrast_ratio(&translucent, &white) - 1.).abs() < 1e-9);
+        assert_eq!(suggest(&translucent, &white, WcagLevel::AA), None);
+    }
+
+    #[test]
+    fn suggestions_meet_each_level() {
+        let levels = [WcagLevel::AA, WcagLevel::AALargeText, WcagLevel::AAA, WcagLevel::AAALargeText];
+
+        for check in check_dynamic(foreground, background) {
+            for level in levels.iter() {
+                let suggestion = check.suggest(*level).unwrap_or_else(|| {
+                    panic!("No suggestion for {:?} at {:?}", check.style, level)
+                });
+
+                let ratio = contrast_ratio(&suggestion, &check.background);
+                assert!(ratio >= level.minimum_ratio(), "{:?} at {:?}: {}", check.style, level, ratio);
+                assert_eq!(suggestion.space(), check.foreground.space());
+
+                // Suggestions move only as far as they need to.
+                match check.passes(*level) {
+                    true => assert_eq!(suggestion, check.foreground),
+                    false => assert!(ratio < level.minimum_ratio() + 0.05, "{:?} at {:?}: {}", check.style, level, ratio)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn suggests_nothing_when_no_lightness_passes() {
+        let background = ColorValue::gray(0.5);
+        let foreground = ColorValue::gray(0.45);
+
+        assert_eq!(suggest(&foreground, &background, WcagLevel::AAA), None);
+        assert!(suggest(&foreground, &background, WcagLevel::AALargeText).is_some());
+    }
+
+    #[test]
+    fn validates_dynamic_palettes() {
+        let failures = validate_dynamic(foreground, background, WcagLevel::AA).unwrap_err();
+        assert_eq!(failures.len(), Style::all().len());
+
+        let strong = |style: Style| match style.theme {
+            Theme::Light => ColorValue::gray(0.),
+            Theme::Dark => ColorValue::gray(1.)
+        };
+
+        assert_eq!(validate_dynamic(strong, background, WcagLevel::AAA), Ok(()));
+    }
+}
@@ -0,0 +1,80 @@
+//! UIKit has dynamic colors built in (`+[UIColor colorWithDynamicProvider:]`), so on iOS and
+//! tvOS there's no subclass to maintain - the provider block is handed the current trait
+//! collection, which is mapped to a `Style` and looked up in the colors resolved up front.
+
+use block::ConcreteBlock;
+
+use objc::runtime::{Object, BOOL};
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::ShareId;
+
+use crate::color::{Color, Contrast, Elevation, Style, Theme, Transparency};
+use crate::foundation::{id, NSInteger, YES};
+
+extern "C" {
+    fn UIAccessibilityIsReduceTransparencyEnabled() -> BOOL;
+}
+
+/// `UIUserInterfaceStyleDark`.
+const USER_INTERFACE_STYLE_DARK: NSInteger = 2;
+
+/// `UIAccessibilityContrastHigh`.
+const ACCESSIBILITY_CONTRAST_HIGH: NSInteger = 1;
+
+/// `UIUserInterfaceLevelElevated`.
+const USER_INTERFACE_LEVEL_ELEVATED: NSInteger = 1;
+
+/// Maps a `UITraitCollection` to the `Style` it describes.
+unsafe fn style_for_traits(traits: id) -> Style {
+    let theme: NSInteger = msg_send![traits, userInterfaceStyle];
+    let contrast: NSInteger = msg_send![traits, accessibilityContrast];
+    let level: NSInteger = msg_send![traits, userInterfaceLevel];
+
+    Style {
+        theme: match theme == USER_INTERFACE_STYLE_DARK {
+            true => Theme::Dark,
+            false => Theme::Light
+        },
+
+        contrast: match contrast == ACCESSIBILITY_CONTRAST_HIGH {
+            true => Contrast::High,
+            false => Contrast::Normal
+        },
+
+        transparency: match UIAccessibilityIsReduceTransparencyEnabled() == YES {
+            true => Transparency::Reduced,
+            false => Transparency::Normal
+        },
+
+        elevation: match level == USER_INTERFACE_LEVEL_ELEVATED {
+            true => Elevation::Elevated,
+            false => Elevation::Base
+        }
+    }
+}
+
+/// Returns a `UIColor` that resolves to the matching entry in `variants` whenever UIKit asks.
+/// `variants` is expected to hold every style in `Style::all()`; anything missing falls back to
+/// the first entry (the default style).
+pub(crate) fn color_for_variants(variants: &[(Style, Color)]) -> id {
+    let colors: Vec<(Style, ShareId<Object>)> = variants.iter().map(|(style, color)| {
+        (*style, unsafe { ShareId::from_ptr(color.to_objc()) })
+    }).collect();
+
+    let provider = ConcreteBlock::new(move |traits: id| -> id {
+        let style = unsafe { style_for_traits(traits) };
+
+        let color = colors.iter()
+            .find(|(candidate, _)| *candidate == style)
+            .or_else(|| colors.first())
+            .map(|(_, color)| color);
+
+        match color {
+            Some(color) => unsafe { msg_send![&**color, self] },
+            None => unsafe { msg_send![class!(UIColor), clearColor] }
+        }
+    });
+
+    let provider = provider.copy();
+    unsafe { msg_send![class!(UIColor), colorWithDynamicProvider:&*provider] }
+}
@@ -18,6 +18,7 @@ use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel, BOOL};
 use objc::{class, msg_send, sel, sel_impl};
 
+use crate::color::{Contrast, Elevation, Style, Theme, Transparency};
 use crate::foundation::{id, nil, YES, NO, NSInteger, NSUInteger, NSString, NSArray};
 use crate::utils::os;
 
@@ -25,6 +26,62 @@ pub(crate) const AQUA_LIGHT_COLOR_NORMAL_CONTRAST: &'static str = "AQUA_LIGHT_CO
 pub(crate) const AQUA_LIGHT_COLOR_HIGH_CONTRAST: &'static str = "AQUA_LIGHT_COLOR_HIGH_CONTRAST";
 pub(crate) const AQUA_DARK_COLOR_NORMAL_CONTRAST: &'static str = "AQUA_DARK_COLOR_NORMAL_CONTRAST";
 pub(crate) const AQUA_DARK_COLOR_HIGH_CONTRAST: &'static str = "AQUA_DARK_COLOR_HIGH_CONTRAST";
+pub(crate) const AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY: &'static str = "AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY";
+pub(crate) const AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY: &'static str = "AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY";
+pub(crate) const AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY: &'static str = "AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY";
+pub(crate) const AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY: &'static str = "AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY";
+pub(crate) const AQUA_LIGHT_COLOR_NORMAL_CONTRAST_ELEVATED: &'static str = "AQUA_LIGHT_COLOR_NORMAL_CONTRAST_ELEVATED";
+pub(crate) const AQUA_LIGHT_COLOR_HIGH_CONTRAST_ELEVATED: &'static str = "AQUA_LIGHT_COLOR_HIGH_CONTRAST_ELEVATED";
+pub(crate) const AQUA_DARK_COLOR_NORMAL_CONTRAST_ELEVATED: &'static str = "AQUA_DARK_COLOR_NORMAL_CONTRAST_ELEVATED";
+pub(crate) const AQUA_DARK_COLOR_HIGH_CONTRAST_ELEVATED: &'static str = "AQUA_DARK_COLOR_HIGH_CONTRAST_ELEVATED";
+pub(crate) const AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED: &'static str = "AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED";
+pub(crate) const AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED: &'static str = "AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED";
+pub(crate) const AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED: &'static str = "AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED";
+pub(crate) const AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED: &'static str = "AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED";
+
+/// Every ivar a `CacaoDynamicColor` stores a color in.
+const IVARS: [&'static str; 16] = [
+    AQUA_LIGHT_COLOR_NORMAL_CONTRAST,
+    AQUA_LIGHT_COLOR_HIGH_CONTRAST,
+    AQUA_DARK_COLOR_NORMAL_CONTRAST,
+    AQUA_DARK_COLOR_HIGH_CONTRAST,
+    AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY,
+    AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY,
+    AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY,
+    AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY,
+    AQUA_LIGHT_COLOR_NORMAL_CONTRAST_ELEVATED,
+    AQUA_LIGHT_COLOR_HIGH_CONTRAST_ELEVATED,
+    AQUA_DARK_COLOR_NORMAL_CONTRAST_ELEVATED,
+    AQUA_DARK_COLOR_HIGH_CONTRAST_ELEVATED,
+    AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED,
+    AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED,
+    AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED,
+    AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED
+];
+
+/// Returns the ivar that holds the color for a given `Style`. Every style has one, though macOS
+/// has no notion of elevation and only ever reads the `Elevation::Base` ones - the elevated
+/// colors are kept so that nothing passed to `Color::dynamic` is silently dropped.
+pub(crate) fn ivar_for_style(style: &Style) -> &'static str {
+    match (style.theme, style.contrast, style.transparency, style.elevation) {
+        (Theme::Light, Contrast::Normal, Transparency::Normal, Elevation::Base) => AQUA_LIGHT_COLOR_NORMAL_CONTRAST,
+        (Theme::Light, Contrast::High, Transparency::Normal, Elevation::Base) => AQUA_LIGHT_COLOR_HIGH_CONTRAST,
+        (Theme::Dark, Contrast::Normal, Transparency::Normal, Elevation::Base) => AQUA_DARK_COLOR_NORMAL_CONTRAST,
+        (Theme::Dark, Contrast::High, Transparency::Normal, Elevation::Base) => AQUA_DARK_COLOR_HIGH_CONTRAST,
+        (Theme::Light, Contrast::Normal, Transparency::Reduced, Elevation::Base) => AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY,
+        (Theme::Light, Contrast::High, Transparency::Reduced, Elevation::Base) => AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY,
+        (Theme::Dark, Contrast::Normal, Transparency::Reduced, Elevation::Base) => AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY,
+        (Theme::Dark, Contrast::High, Transparency::Reduced, Elevation::Base) => AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY,
+        (Theme::Light, Contrast::Normal, Transparency::Normal, Elevation::Elevated) => AQUA_LIGHT_COLOR_NORMAL_CONTRAST_ELEVATED,
+        (Theme::Light, Contrast::High, Transparency::Normal, Elevation::Elevated) => AQUA_LIGHT_COLOR_HIGH_CONTRAST_ELEVATED,
+        (Theme::Dark, Contrast::Normal, Transparency::Normal, Elevation::Elevated) => AQUA_DARK_COLOR_NORMAL_CONTRAST_ELEVATED,
+        (Theme::Dark, Contrast::High, Transparency::Normal, Elevation::Elevated) => AQUA_DARK_COLOR_HIGH_CONTRAST_ELEVATED,
+        (Theme::Light, Contrast::Normal, Transparency::Reduced, Elevation::Elevated) => AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED,
+        (Theme::Light, Contrast::High, Transparency::Reduced, Elevation::Elevated) => AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED,
+        (Theme::Dark, Contrast::Normal, Transparency::Reduced, Elevation::Elevated) => AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED,
+        (Theme::Dark, Contrast::High, Transparency::Reduced, Elevation::Elevated) => AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED
+    }
+}
 
 use std::os::raw::c_void;
 
@@ -42,6 +99,8 @@ extern "C" {
 ///
 /// Pull requests to implement that check would be welcome.
 fn get_effective_color(this: &Object) -> id {
+    let mut style = Style::default();
+
     if os::is_minimum_semversion(10, 14, 0) {
         unsafe {
             let mut appearance: id = msg_send![class!(NSAppearance), currentAppearance];
@@ -56,25 +115,30 @@ fn get_effective_color(this: &Object) -> id {
                 NSAppearanceNameAccessibilityHighContrastDarkAqua
             ]);
 
-            let style: id = msg_send![appearance, bestMatchFromAppearancesWithNames:names.into_inner()];
+            let name: id = msg_send![appearance, bestMatchFromAppearancesWithNames:names.into_inner()];
 
-            if style == NSAppearanceNameDarkAqua {
-                return *this.get_ivar(AQUA_DARK_COLOR_NORMAL_CONTRAST);
+            if name == NSAppearanceNameDarkAqua || name == NSAppearanceNameAccessibilityHighContrastDarkAqua {
+                style.theme = Theme::Dark;
             }
 
-            if style == NSAppearanceNameAccessibilityHighContrastAqua {
-                return *this.get_ivar(AQUA_LIGHT_COLOR_HIGH_CONTRAST);
+            if name == NSAppearanceNameAccessibilityHighContrastAqua || name == NSAppearanceNameAccessibilityHighContrastDarkAqua {
+                style.contrast = Contrast::High;
             }
+        }
+    }
 
-            if style == NSAppearanceNameAccessibilityHighContrastDarkAqua {
-                return *this.get_ivar(AQUA_DARK_COLOR_HIGH_CONTRAST);
+    if os::is_minimum_semversion(10, 10, 0) {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let reduce: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceTransparency];
+
+            if reduce == YES {
+                style.transparency = Transparency::Reduced;
             }
         }
     }
 
-    unsafe {
-        return *this.get_ivar(AQUA_LIGHT_COLOR_NORMAL_CONTRAST);
-    }
+    unsafe { *this.get_ivar(ivar_for_style(&style)) }
 }
 
 extern fn color_space(this: &Object, _: Sel) -> id {
@@ -286,13 +350,85 @@ pub(crate) fn register_class() -> *const Class {
         decl.add_method(sel!(blendedColorWithFraction:ofColor:), blended_color as extern fn(&Object, _, CGFloat, id) -> id);
         decl.add_method(sel!(colorWithSystemEffect:), color_with_system_effect as extern fn(&Object, _, NSInteger) -> id);
 
-        decl.add_ivar::<id>(AQUA_LIGHT_COLOR_NORMAL_CONTRAST);
-        decl.add_ivar::<id>(AQUA_LIGHT_COLOR_HIGH_CONTRAST);
-        decl.add_ivar::<id>(AQUA_DARK_COLOR_NORMAL_CONTRAST);
-        decl.add_ivar::<id>(AQUA_DARK_COLOR_HIGH_CONTRAST);
+        for ivar in &IVARS {
+            decl.add_ivar::<id>(ivar);
+        }
     
         VIEW_CLASS = decl.register();
     });
 
     unsafe { VIEW_CLASS }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn every_style_has_its_own_ivar() {
+        // Spelled out, so that renaming a constant (or listing one twice) fails here.
+        assert_eq!(IVARS, [
+            "AQUA_LIGHT_COLOR_NORMAL_CONTRAST",
+            "AQUA_LIGHT_COLOR_HIGH_CONTRAST",
+            "AQUA_DARK_COLOR_NORMAL_CONTRAST",
+            "AQUA_DARK_COLOR_HIGH_CONTRAST",
+            "AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY",
+            "AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY",
+            "AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY",
+            "AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY",
+            "AQUA_LIGHT_COLOR_NORMAL_CONTRAST_ELEVATED",
+            "AQUA_LIGHT_COLOR_HIGH_CONTRAST_ELEVATED",
+            "AQUA_DARK_COLOR_NORMAL_CONTRAST_ELEVATED",
+            "AQUA_DARK_COLOR_HIGH_CONTRAST_ELEVATED",
+            "AQUA_LIGHT_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED",
+            "AQUA_LIGHT_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED",
+            "AQUA_DARK_COLOR_NORMAL_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED",
+            "AQUA_DARK_COLOR_HIGH_CONTRAST_REDUCED_TRANSPARENCY_ELEVATED"
+        ]);
+
+        let styles = Style::all();
+        assert_eq!(styles.len(), IVARS.len());
+
+        let mut seen = HashSet::new();
+
+        for style in &styles {
+            let ivar = ivar_for_style(style);
+            assert!(IVARS.contains(&ivar), "{:?} maps to an undeclared ivar, {}", style, ivar);
+            assert!(seen.insert(ivar), "{:?} shares {} with another style", style, ivar);
+        }
+    }
+
+    #[test]
+    fn styles_map_to_the_expected_ivars() {
+        let expected = [
+            (Theme::Light, Contrast::Normal, "AQUA_LIGHT_COLOR_NORMAL_CONTRAST"),
+            (Theme::Light, Contrast::High, "AQUA_LIGHT_COLOR_HIGH_CONTRAST"),
+            (Theme::Dark, Contrast::Normal, "AQUA_DARK_COLOR_NORMAL_CONTRAST"),
+            (Theme::Dark, Contrast::High, "AQUA_DARK_COLOR_HIGH_CONTRAST")
+        ];
+
+        for style in Style::all() {
+            let (_, _, base) = expected.iter()
+                .find(|(theme, contrast, _)| *theme == style.theme && *contrast == style.contrast)
+                .unwrap();
+
+            let transparency = match style.transparency {
+                Transparency::Normal => "",
+                Transparency::Reduced => "_REDUCED_TRANSPARENCY"
+            };
+
+            let elevation = match style.elevation {
+                Elevation::Base => "",
+                Elevation::Elevated => "_ELEVATED"
+            };
+
+            assert_eq!(ivar_for_style(&style), format!("{}{}{}", base, transparency, elevation), "{:?}", style);
+        }
+
+        // Dark mode with high contrast once shared the light mode slot.
+        let style = Style { theme: Theme::Dark, contrast: Contrast::High, ..Style::default() };
+        assert_eq!(ivar_for_style(&style), AQUA_DARK_COLOR_HIGH_CONTRAST);
+    }
+}
@@ -15,6 +15,9 @@
 /// @TODO: bundle iOS/tvOS support.
 
 use core_graphics::base::CGFloat;
+
+// Off Apple platforms, only the `core-graphics` geometry types are available.
+#[cfg(target_vendor = "apple")]
 use core_graphics::color::CGColor;
 
 use objc::{class, msg_send, sel, sel_impl};
@@ -24,20 +27,28 @@ use objc_id::ShareId;
 use crate::foundation::id;
 use crate::utils::os;
 
+mod parse;
+pub use parse::ColorParseError;
+
+mod rgba;
+pub use rgba::Rgba;
+
+mod value;
+pub use value::{ColorSpace, ColorValue};
+
+pub mod contrast;
+
 #[cfg(feature = "macos")]
 mod macos_dynamic_color; 
 
-#[cfg(feature = "macos")]
-use macos_dynamic_color::{
-    AQUA_LIGHT_COLOR_NORMAL_CONTRAST, AQUA_LIGHT_COLOR_HIGH_CONTRAST,
-    AQUA_DARK_COLOR_NORMAL_CONTRAST, AQUA_DARK_COLOR_HIGH_CONTRAST
-};
+#[cfg(feature = "ios")]
+mod ios_dynamic_color;
 
 /// Represents a rendering style - dark mode or light mode.
 /// In the event that a new variant is introduced in later versions of
 /// macOS or iOS, calls that use the dynamic color(s) from here will likely
 /// default to the `Light` theme.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Theme {
     /// The "default" theme on a platform. On macOS, this is Aqua.
     /// On iOS and tvOS, this is whatever you call the system defined theme.
@@ -47,8 +58,9 @@ pub enum Theme {
     Dark
 }
 
-/// Represents the contrast level for a rendering context. 
-#[derive(Debug)]
+/// Represents the contrast level for a rendering context. On macOS and iOS, `High` corresponds
+/// to the "Increase contrast" accessibility setting.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Contrast {
     /// The default contrast level for the system.
     Normal,
@@ -57,16 +69,97 @@ pub enum Contrast {
     High
 }
 
+/// Represents whether translucent materials (vibrancy, blurs) should be drawn as-is. Colors
+/// that rely on showing what's behind them should usually go opaque when this is `Reduced`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Transparency {
+    /// Translucency is drawn as normal.
+    Normal,
+
+    /// The user has turned on the "Reduce transparency" accessibility setting.
+    Reduced
+}
+
+/// Represents the elevation of the surface a color is drawn on. iOS draws content presented
+/// above other content (sheets, popovers, split view panes) slightly lighter in dark mode, so
+/// backgrounds can differ by level.
+///
+/// macOS has no equivalent, and always resolves colors for `Base`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Elevation {
+    /// The base level of an interface.
+    Base,
+
+    /// Content presented above the base level.
+    Elevated
+}
+
 /// A `Style` is passed to you when doing dynamic color calculations. You can opt to
 /// provide different colors depending on the settings in here - notably, this is useful
 /// for supporting dark mode and high contrast accessibility contexts.
-#[derive(Debug)]
+///
+/// New settings may be added here over time, so prefer building these with
+/// `..Style::default()` rather than listing every field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Style {
     /// Represents the current theme for where this color may render.
     pub theme: Theme,
 
     /// Represents the current contrast level for where this color may render.
-    pub contrast: Contrast
+    pub contrast: Contrast,
+
+    /// Represents whether transparency is reduced where this color may render.
+    pub transparency: Transparency,
+
+    /// Represents the elevation of the surface this color may render on.
+    pub elevation: Elevation
+}
+
+impl Default for Style {
+    /// Light theme, normal contrast, normal transparency, base elevation.
+    fn default() -> Self {
+        Style {
+            theme: Theme::Light,
+            contrast: Contrast::Normal,
+            transparency: Transparency::Normal,
+            elevation: Elevation::Base
+        }
+    }
+}
+
+impl Style {
+    /// Returns every combination of settings a dynamic color can be resolved for, starting with
+    /// the default.
+    pub fn all() -> Vec<Style> {
+        let mut styles = Vec::new();
+
+        for theme in &[Theme::Light, Theme::Dark] {
+            for contrast in &[Contrast::Normal, Contrast::High] {
+                for transparency in &[Transparency::Normal, Transparency::Reduced] {
+                    for elevation in &[Elevation::Base, Elevation::Elevated] {
+                        styles.push(Style {
+                            theme: *theme,
+                            contrast: *contrast,
+                            transparency: *transparency,
+                            elevation: *elevation
+                        });
+                    }
+                }
+            }
+        }
+
+        styles
+    }
+
+    /// Runs `handler` for every style in `Style::all()`, and returns the results alongside the
+    /// style that produced them. Useful for reviewing or diffing a palette as data - e.g, in a
+    /// test - without toggling system settings.
+    pub fn snapshot<T, F>(handler: F) -> Vec<(Style, T)>
+    where
+        F: Fn(Style) -> T
+    {
+        Style::all().into_iter().map(|style| (style, handler(style))).collect()
+    }
 }
 
 /*
@@ -103,6 +196,16 @@ pub enum Color {
     /// the Objective-C level yourself and wrap your color in this.
     Object(ShareId<Object>),
 
+    /// A custom color with known components, alongside the `NSColor`/`UIColor` that was created
+    /// from them. You typically create this with `Color::from_value` (or the `rgba`, `hsba` and
+    /// `white` initializers), and read the components back with `Color::value`.
+    Value(ColorValue, ShareId<Object>),
+
+    /// A color that changes with the appearance it's drawn in, alongside the colors it resolves
+    /// to for each `Style`. You create this with `Color::dynamic`, and can read the variants back
+    /// with `Color::variants`.
+    Dynamic(Vec<(Style, Color)>, ShareId<Object>),
+
     /// The system-provided black. Harsh - you probably don't want to use this.
     SystemBlack,
 
@@ -246,24 +349,64 @@ pub enum Color {
 }
 
 impl Color {
-    /// Creates and returns a color in the RGB space, with the specified
-    /// alpha level.
-    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
-        let r = red as CGFloat / 255.0;
-        let g = green as CGFloat / 255.0;
-        let b = blue as CGFloat / 255.0;
-        let a = alpha as CGFloat / 255.0;
-        
+    /// Creates and returns a color from a `ColorValue`, in the matching color space. The value is
+    /// kept around, so `Color::value` will return it as-is.
+    ///
+    /// Display P3 values produce a Display P3 color; everything else produces an sRGB color.
+    pub fn from_value(value: ColorValue) -> Self {
         #[cfg(feature = "macos")]
         let color = class!(NSColor);
 
         #[cfg(feature = "ios")]
         let color = class!(UIColor);
 
-        Color::Object(unsafe {
-            #[cfg(feature = "macos")]
-            ShareId::from_ptr(msg_send![color, colorWithCalibratedRed:r green:g blue:b alpha:a])
-        })
+        let objc: id = match value {
+            ColorValue::DisplayP3(rgba) => {
+                let r = rgba.red as CGFloat;
+                let g = rgba.green as CGFloat;
+                let b = rgba.blue as CGFloat;
+                let a = rgba.alpha as CGFloat;
+
+                unsafe { msg_send![color, colorWithDisplayP3Red:r green:g blue:b alpha:a] }
+            },
+
+            _ => {
+                let rgba = value.to_srgb();
+                let r = rgba.red as CGFloat;
+                let g = rgba.green as CGFloat;
+                let b = rgba.blue as CGFloat;
+                let a = rgba.alpha as CGFloat;
+
+                #[cfg(feature = "macos")]
+                let srgb: id = unsafe { msg_send![color, colorWithSRGBRed:r green:g blue:b alpha:a] };
+
+                #[cfg(feature = "ios")]
+                let srgb: id = unsafe { msg_send![color, colorWithRed:r green:g blue:b alpha:a] };
+
+                srgb
+            }
+        };
+
+        Color::Value(value, unsafe { ShareId::from_ptr(objc) })
+    }
+
+    /// Returns the components this color was created from, if it was created from known
+    /// components. System colors (and raw `Color::Object` colors) return `None`, as their
+    /// components depend on the appearance they're drawn in.
+    pub fn value(&self) -> Option<ColorValue> {
+        match self {
+            Color::Value(value, _) => Some(*value),
+            _ => None
+        }
+    }
+
+    /// Creates and returns a color in the RGB space, with the specified
+    /// alpha level.
+    ///
+    /// On macOS, this is a calibrated (generic RGB) color, as it always has been - use
+    /// `Color::from_value` with `ColorValue::Srgb` for an sRGB one.
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Rgba::from_u8(red, green, blue, alpha).into()
     }
     
     /// Creates and returns a color in the RGB space, with the alpha level
@@ -273,7 +416,9 @@ impl Color {
     }
 
     /// Creates and returns a color in the HSB space, with the specified
-    /// alpha level.
+    /// alpha level. Hue is scaled from `0-255` to `0-360` degrees.
+    ///
+    /// On macOS, this is a calibrated color, as with `rgba`.
     pub fn hsba(hue: u8, saturation: u8, brightness: u8, alpha: u8) -> Self {
         let h = hue as CGFloat / 255.0;
         let s = saturation as CGFloat / 255.0;
@@ -281,15 +426,17 @@ impl Color {
         let a = alpha as CGFloat / 255.0;
 
         #[cfg(feature = "macos")]
-        let color = class!(NSColor);
+        let objc: id = unsafe { msg_send![class!(NSColor), colorWithCalibratedHue:h saturation:s brightness:b alpha:a] };
 
         #[cfg(feature = "ios")]
-        let color = class!(UIColor);
-
-        Color::Object(unsafe {
-            #[cfg(feature = "macos")]
-            ShareId::from_ptr(msg_send![color, colorWithCalibratedHue:h saturation:s brightness:b alpha:a])
-        })
+        let objc: id = unsafe { msg_send![class!(UIColor), colorWithHue:h saturation:s brightness:b alpha:a] };
+
+        Color::Value(ColorValue::Hsb {
+            hue: h as f64 * 360.,
+            saturation: s as f64,
+            brightness: b as f64,
+            alpha: a as f64
+        }, unsafe { ShareId::from_ptr(objc) })
     }
     
     /// Creates and returns a color in the RGB space, with the alpha level
@@ -300,17 +447,19 @@ impl Color {
 
     /// Creates and returns a white color with the specified level or intensity, along with the
     /// specified alpha.
+    ///
+    /// On macOS, this is a calibrated color, as with `rgba`.
     pub fn white_alpha(level: CGFloat, alpha: CGFloat) -> Self {
         #[cfg(feature = "macos")]
-        let color = class!(NSColor);
+        let objc: id = unsafe { msg_send![class!(NSColor), colorWithCalibratedWhite:level alpha:alpha] };
 
         #[cfg(feature = "ios")]
-        let color = class!(UIColor);
+        let objc: id = unsafe { msg_send![class!(UIColor), colorWithWhite:level alpha:alpha] };
 
-        Color::Object(unsafe {
-            #[cfg(feature = "macos")]
-            ShareId::from_ptr(msg_send![color, colorWithCalibratedWhite:level alpha:alpha])
-        })
+        Color::Value(ColorValue::Gray {
+            white: level as f64,
+            alpha: alpha as f64
+        }, unsafe { ShareId::from_ptr(objc) })
     }
 
     /// Creates and returns a white Color with the specified level or intensity, with the alpha
@@ -321,20 +470,35 @@ impl Color {
     
     /// Given a hex code and alpha level, returns a `Color` in the RGB space.
     ///
+    /// The hex code can be in any of the `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA` forms (the `#`
+    /// is optional). If it carries its own alpha, the two are multiplied together.
+    ///
     /// This method is not an ideal one to use, but is offered as a convenience method for those
     /// coming from other environments where these are more common.
-    pub fn hexa(hex: &str, alpha: u8) -> Self {
-        Color::SystemRed
+    pub fn hexa(hex: &str, alpha: u8) -> Result<Self, ColorParseError> {
+        let trimmed = hex.trim();
+        let mut rgba = parse::parse_hex(trimmed.strip_prefix('#').unwrap_or(trimmed))?;
+        rgba.alpha *= alpha as f64 / 255.;
+        Ok(rgba.into())
     }
 
     /// Given a hex code, returns a `Color` in the RGB space with alpha pre-set to `255`.
     ///
     /// This method is not an ideal one to use, but is offered as a convenience method for those
     /// coming from other environments where these are more common.
-    pub fn hex(hex: &str) -> Self {
+    pub fn hex(hex: &str) -> Result<Self, ColorParseError> {
         Color::hexa(hex, 255)
     }
 
+    /// Parses a CSS-style color string - a hex code, `rgb()`/`rgba()`, `hsl()`/`hsla()`, or a
+    /// named color - and returns a `Color` in the RGB space.
+    ///
+    /// If you want the parsed components rather than an Objective-C color, parse into an `Rgba`
+    /// instead.
+    pub fn parse(color: &str) -> Result<Self, ColorParseError> {
+        parse::parse(color).map(Color::from)
+    }
+
     /// Creates and returns a dynamic color, which stores a handler and enables returning specific
     /// colors at appearance time based on device traits (i.e, dark mode vs light mode, contrast
     /// settings, etc).
@@ -342,37 +506,42 @@ impl Color {
     /// For systems that don't support dark mode (macOS pre-Mojave) this will always paint with the
     /// "default" or "light" color.
     ///
+    /// The handler is called once for every style in `Style::all()`, up front. Use
+    /// `Color::variants` to see what it returned for each.
+    ///
     /// Returning a dynamic color in your handler is unsupported and may panic.
     pub fn dynamic<F>(handler: F) -> Self
     where
         F: Fn(Style) -> Color + 'static
     {
+        // The resolved colors are kept in the variant, which keeps them alive for as long as the
+        // dynamic color that points to them.
+        let variants = Style::snapshot(handler);
+
         #[cfg(feature = "macos")]
-        Color::Object(unsafe {
+        let color: id = unsafe {
             let color: id = msg_send![macos_dynamic_color::register_class(), new];
 
-            (&mut *color).set_ivar(AQUA_LIGHT_COLOR_NORMAL_CONTRAST, handler(Style {
-                theme: Theme::Light,
-                contrast: Contrast::Normal
-            }).to_objc());
-
-            (&mut *color).set_ivar(AQUA_LIGHT_COLOR_HIGH_CONTRAST, handler(Style {
-                theme: Theme::Light,
-                contrast: Contrast::High
-            }).to_objc());
-
-            (&mut *color).set_ivar(AQUA_DARK_COLOR_NORMAL_CONTRAST, handler(Style {
-                theme: Theme::Dark,
-                contrast: Contrast::Normal
-            }).to_objc());
-
-            (&mut *color).set_ivar(AQUA_DARK_COLOR_HIGH_CONTRAST, handler(Style {
-                theme: Theme::Light,
-                contrast: Contrast::Normal
-            }).to_objc());
-            
-            ShareId::from_ptr(color)
-        })
+            for (style, variant) in &variants {
+                (&mut *color).set_ivar(macos_dynamic_color::ivar_for_style(style), variant.to_objc());
+            }
+
+            color
+        };
+
+        #[cfg(feature = "ios")]
+        let color = ios_dynamic_color::color_for_variants(&variants);
+
+        Color::Dynamic(variants, unsafe { ShareId::from_ptr(color) })
+    }
+
+    /// For colors created with `Color::dynamic`, returns the color used for every `Style`.
+    /// Returns `None` for any other kind of color.
+    pub fn variants(&self) -> Option<&[(Style, Color)]> {
+        match self {
+            Color::Dynamic(variants, _) => Some(variants),
+            _ => None
+        }
     }
 
     /// Returns a pointer that can be used for the Objective-C runtime.
@@ -394,6 +563,7 @@ impl Color {
     /// objects. If you're painting in a context that requires dark mode support, make sure 
     /// you're not using a cached version of this unless you explicitly want the _same_ color
     /// in every context it's used in.
+    #[cfg(target_vendor = "apple")]
     pub fn cg_color(&self) -> CGColor {
         // @TODO: This should probably return a CGColorRef...
         unsafe {
@@ -403,6 +573,31 @@ impl Color {
     }
 }
 
+impl From<Rgba> for Color {
+    /// Creates a `Color` in the RGB space from a parsed or computed value. As with `Color::rgba`,
+    /// this is a calibrated color on macOS.
+    fn from(rgba: Rgba) -> Self {
+        let r = rgba.red as CGFloat;
+        let g = rgba.green as CGFloat;
+        let b = rgba.blue as CGFloat;
+        let a = rgba.alpha as CGFloat;
+
+        #[cfg(feature = "macos")]
+        let objc: id = unsafe { msg_send![class!(NSColor), colorWithCalibratedRed:r green:g blue:b alpha:a] };
+
+        #[cfg(feature = "ios")]
+        let objc: id = unsafe { msg_send![class!(UIColor), colorWithRed:r green:g blue:b alpha:a] };
+
+        Color::Value(ColorValue::Srgb(rgba), unsafe { ShareId::from_ptr(objc) })
+    }
+}
+
+impl From<ColorValue> for Color {
+    fn from(value: ColorValue) -> Self {
+        Color::from_value(value)
+    }
+}
+
 impl AsRef<Color> for Color {
     /// Provided to make passing `Color` types around less of a headache.
     #[inline]
@@ -447,6 +642,8 @@ unsafe fn to_objc(obj: &Color) -> id {
     match obj {
         // Regardless of platform, we can just dereference this one.
         Color::Object(obj) => msg_send![&**obj, self],
+        Color::Value(_, obj) => msg_send![&**obj, self],
+        Color::Dynamic(_, obj) => msg_send![&**obj, self],
 
         Color::SystemBlack => msg_send![color, blackColor],
         Color::SystemWhite => msg_send![color, whiteColor],
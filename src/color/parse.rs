@@ -0,0 +1,465 @@
+//! Parsing for the color formats you'd find in design specs and stylesheets: hex codes,
+//! `rgb()`/`rgba()`, `hsl()`/`hsla()`, and CSS named colors.
+//!
+//! Everything here is pure Rust and produces an `Rgba`; converting that into a `Color` is a
+//! separate step, so parsing can be used (and checked) without touching the Objective-C runtime.
+//!
+//! ```rust
+//! use cacao::color::Rgba;
+//!
+//! let teal: Rgba = "#008080".parse().unwrap();
+//! assert_eq!(teal, Rgba::from_u8(0, 128, 128, 255));
+//!
+//! let translucent: Rgba = "hsla(180, 100%, 25%, 0.5)".parse().unwrap();
+//! assert_eq!(translucent.to_u8(), (0, 128, 128, 128));
+//! ```
+
+use std::error;
+use std::fmt;
+
+use crate::color::rgba::Rgba;
+
+/// Errors that can occur when parsing a color string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColorParseError {
+    /// The string was empty (or only whitespace).
+    Empty,
+
+    /// A hex code had the wrong number of digits, or contained something that isn't a hex digit.
+    /// Supported lengths are 3, 4, 6 and 8 digits.
+    InvalidHex(String),
+
+    /// A functional notation (e.g, `rgb(...)`) was missing its closing parenthesis, or the
+    /// function isn't one we know about.
+    InvalidFunction(String),
+
+    /// A function was given the wrong number of arguments.
+    WrongArgumentCount {
+        /// The function that was called.
+        function: String,
+
+        /// How many arguments were found.
+        found: usize
+    },
+
+    /// An argument to a function couldn't be parsed, or was of the wrong kind (e.g, a bare
+    /// number where a percentage is required).
+    InvalidComponent(String),
+
+    /// The string isn't a hex code or function, and isn't a known CSS color name.
+    UnknownName(String)
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "Color string is empty"),
+            ColorParseError::InvalidHex(hex) => write!(f, "Invalid hex color: {}", hex),
+            ColorParseError::InvalidFunction(func) => write!(f, "Invalid color function: {}", func),
+
+            ColorParseError::WrongArgumentCount { function, found } => {
+                write!(f, "{}() expects 3 or 4 arguments, found {}", function, found)
+            },
+
+            ColorParseError::InvalidComponent(component) => write!(f, "Invalid color component: {}", component),
+            ColorParseError::UnknownName(name) => write!(f, "Unknown color name: {}", name)
+        }
+    }
+}
+
+impl error::Error for ColorParseError {}
+
+/// Parses a color string. Accepts:
+///
+/// - Hex codes: `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA` (the leading `#` is optional).
+/// - `rgb()` and `rgba()`, with components as `0-255` or percentages, and alpha as `0-1` or a
+///   percentage. Arguments can be separated by commas or by spaces, with an optional `/` before
+///   alpha.
+/// - `hsl()` and `hsla()`, with hue in degrees (or with a `deg`, `rad`, `grad` or `turn` unit),
+///   and saturation and lightness as percentages.
+/// - CSS named colors (e.g, `rebeccapurple`), plus `transparent`.
+///
+/// Parsing is case-insensitive, and surrounding whitespace is ignored.
+pub fn parse(input: &str) -> Result<Rgba, ColorParseError> {
+    let input = input.trim().to_ascii_lowercase();
+
+    if input.is_empty() {
+        return Err(ColorParseError::Empty);
+    }
+
+    if let Some(hex) = input.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    if let Some(open) = input.find('(') {
+        return parse_function(&input, open);
+    }
+
+    if let Some(rgba) = named(&input) {
+        return Ok(rgba);
+    }
+
+    // Allow hex codes without the leading `#`, as they're commonly copied around that way.
+    match input.len() {
+        3 | 4 | 6 | 8 if input.chars().all(|c| c.is_ascii_hexdigit()) => parse_hex(&input),
+        _ => Err(ColorParseError::UnknownName(input))
+    }
+}
+
+/// Parses the digits of a hex code (without the `#`).
+pub(crate) fn parse_hex(hex: &str) -> Result<Rgba, ColorParseError> {
+    let invalid = || ColorParseError::InvalidHex(hex.to_string());
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).map(|d| d * 17);
+    let pair = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+
+    let (r, g, b, a) = match hex.len() {
+        3 => (digit(0), digit(1), digit(2), Ok(255)),
+        4 => (digit(0), digit(1), digit(2), digit(3)),
+        6 => (pair(0), pair(2), pair(4), Ok(255)),
+        8 => (pair(0), pair(2), pair(4), pair(6)),
+        _ => { return Err(invalid()); }
+    };
+
+    match (r, g, b, a) {
+        (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(Rgba::from_u8(r, g, b, a)),
+        _ => Err(invalid())
+    }
+}
+
+/// Parses `name(args)`, where `open` is the index of the opening parenthesis.
+fn parse_function(input: &str, open: usize) -> Result<Rgba, ColorParseError> {
+    let name = input[..open].trim();
+
+    let body = match input[open + 1..].strip_suffix(')') {
+        Some(body) => body,
+        None => { return Err(ColorParseError::InvalidFunction(input.to_string())); }
+    };
+
+    let args = split_arguments(body);
+
+    if args.len() != 3 && args.len() != 4 {
+        return Err(ColorParseError::WrongArgumentCount {
+            function: name.to_string(),
+            found: args.len()
+        });
+    }
+
+    let alpha = match args.get(3) {
+        Some(arg) => parse_alpha(arg)?,
+        None => 1.
+    };
+
+    match name {
+        "rgb" | "rgba" => Ok(Rgba::new(
+            parse_rgb_component(args[0])?,
+            parse_rgb_component(args[1])?,
+            parse_rgb_component(args[2])?,
+            alpha
+        )),
+
+        "hsl" | "hsla" => Ok(Rgba::from_hsl(
+            parse_hue(args[0])?,
+            parse_percentage(args[1])?,
+            parse_percentage(args[2])?,
+            alpha
+        )),
+
+        _ => Err(ColorParseError::InvalidFunction(name.to_string()))
+    }
+}
+
+/// Splits function arguments on commas, whitespace, and `/` (used before alpha in the
+/// space-separated syntax).
+fn split_arguments(body: &str) -> Vec<&str> {
+    body.split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .collect()
+}
+
+/// Parses a plain number.
+fn parse_number(value: &str) -> Result<f64, ColorParseError> {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(ColorParseError::InvalidComponent(value.to_string()))
+    }
+}
+
+/// Parses a required percentage (e.g, `50%`) into `0.0..=1.0`.
+fn parse_percentage(value: &str) -> Result<f64, ColorParseError> {
+    match value.strip_suffix('%') {
+        Some(number) => Ok(parse_number(number)? / 100.),
+        None => Err(ColorParseError::InvalidComponent(value.to_string()))
+    }
+}
+
+/// Parses an RGB channel, either `0-255` or a percentage, into `0.0..=1.0`.
+fn parse_rgb_component(value: &str) -> Result<f64, ColorParseError> {
+    match value.ends_with('%') {
+        true => parse_percentage(value),
+        false => Ok(parse_number(value)? / 255.)
+    }
+}
+
+/// Parses alpha, either `0-1` or a percentage.
+fn parse_alpha(value: &str) -> Result<f64, ColorParseError> {
+    match value.ends_with('%') {
+        true => parse_percentage(value),
+        false => parse_number(value)
+    }
+}
+
+/// Parses a hue into degrees.
+fn parse_hue(value: &str) -> Result<f64, ColorParseError> {
+    if let Some(number) = value.strip_suffix("deg") {
+        return parse_number(number);
+    }
+
+    if let Some(number) = value.strip_suffix("grad") {
+        return Ok(parse_number(number)? * 0.9);
+    }
+
+    if let Some(number) = value.strip_suffix("rad") {
+        return Ok(parse_number(number)?.to_degrees());
+    }
+
+    if let Some(number) = value.strip_suffix("turn") {
+        return Ok(parse_number(number)? * 360.);
+    }
+
+    parse_number(value)
+}
+
+/// Looks up a CSS named color. `name` must already be lowercase.
+pub(crate) fn named(name: &str) -> Option<Rgba> {
+    if name == "transparent" {
+        return Some(Rgba::new(0., 0., 0., 0.));
+    }
+
+    NAMED_COLORS.binary_search_by(|(key, _)| key.cmp(&name))
+        .ok()
+        .map(|index| Rgba::from_hex(NAMED_COLORS[index].1))
+}
+
+/// The CSS Color Module Level 4 named colors, sorted by name.
+static NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32)
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, ColorParseError, NAMED_COLORS};
+    use crate::color::Rgba;
+
+    fn u8s(input: &str) -> (u8, u8, u8, u8) {
+        parse(input).unwrap().to_u8()
+    }
+
+    #[test]
+    fn parses_hex_codes() {
+        assert_eq!(u8s("#f80"), (255, 136, 0, 255));
+        assert_eq!(u8s("#f808"), (255, 136, 0, 136));
+        assert_eq!(u8s("#FF8800"), (255, 136, 0, 255));
+        assert_eq!(u8s("#ff880080"), (255, 136, 0, 128));
+
+        // The `#` is optional, and surrounding whitespace is ignored.
+        assert_eq!(u8s("  ff8800 "), (255, 136, 0, 255));
+    }
+
+    #[test]
+    fn parses_rgb_functions() {
+        assert_eq!(u8s("rgb(255, 136, 0)"), (255, 136, 0, 255));
+        assert_eq!(u8s("RGBA(255, 136, 0, 0.5)"), (255, 136, 0, 128));
+        assert_eq!(u8s("rgb(100% 0% 50% / 25%)"), (255, 0, 128, 64));
+
+        // Out of range components are clamped.
+        assert_eq!(u8s("rgb(300, -5, 0)"), (255, 0, 0, 255));
+    }
+
+    #[test]
+    fn parses_hsl_functions() {
+        assert_eq!(u8s("hsl(0, 100%, 50%)"), (255, 0, 0, 255));
+        assert_eq!(u8s("hsl(120deg 100% 25%)"), (0, 128, 0, 255));
+        assert_eq!(u8s("hsla(0.5turn, 100%, 25%, 0.5)"), (0, 128, 128, 128));
+        assert_eq!(u8s("hsl(400grad, 0%, 100%)"), (255, 255, 255, 255));
+        assert_eq!(u8s("hsl(3.14159rad, 100%, 50%)"), (0, 255, 255, 255));
+    }
+
+    #[test]
+    fn parses_named_colors() {
+        assert_eq!(parse("RebeccaPurple"), Ok(Rgba::from_hex(0x663399)));
+        assert_eq!(parse("transparent"), Ok(Rgba::new(0., 0., 0., 0.)));
+
+        // Lookups are a binary search, so the table has to stay sorted.
+        assert!(NAMED_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(parse("   "), Err(ColorParseError::Empty));
+        assert_eq!(parse("#12345"), Err(ColorParseError::InvalidHex("12345".to_string())));
+        assert_eq!(parse("#ggg"), Err(ColorParseError::InvalidHex("ggg".to_string())));
+        assert_eq!(parse("rgb(1, 2, 3"), Err(ColorParseError::InvalidFunction("rgb(1, 2, 3".to_string())));
+        assert_eq!(parse("lab(1, 2, 3)"), Err(ColorParseError::InvalidFunction("lab".to_string())));
+
+        assert_eq!(parse("rgb(1, 2)"), Err(ColorParseError::WrongArgumentCount {
+            function: "rgb".to_string(),
+            found: 2
+        }));
+
+        assert_eq!(parse("rgb(1, x, 3)"), Err(ColorParseError::InvalidComponent("x".to_string())));
+        assert_eq!(parse("hsl(0, 50, 50%)"), Err(ColorParseError::InvalidComponent("50".to_string())));
+        assert_eq!(parse("notacolor"), Err(ColorParseError::UnknownName("notacolor".to_string())));
+    }
+}
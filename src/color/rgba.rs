@@ -0,0 +1,94 @@
+//! A plain sRGB color value, independent of `NSColor`/`UIColor`.
+//!
+//! This is what the parsers in this module produce. It can be converted into a `Color` for use
+//! with controls, but all of the math here is pure Rust.
+
+use std::str::FromStr;
+
+use crate::color::parse::{parse, ColorParseError};
+
+/// Red, green, blue and alpha components, in the range `0.0..=1.0`. On its own this is an sRGB
+/// color; `ColorValue::DisplayP3` uses the same components to describe a Display P3 color.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rgba {
+    /// The red component.
+    pub red: f64,
+
+    /// The green component.
+    pub green: f64,
+
+    /// The blue component.
+    pub blue: f64,
+
+    /// The alpha (opacity) component.
+    pub alpha: f64
+}
+
+impl Rgba {
+    /// Returns a new `Rgba`, clamping each component to `0.0..=1.0`.
+    pub fn new(red: f64, green: f64, blue: f64, alpha: f64) -> Self {
+        Rgba {
+            red: red.clamp(0., 1.),
+            green: green.clamp(0., 1.),
+            blue: blue.clamp(0., 1.),
+            alpha: alpha.clamp(0., 1.)
+        }
+    }
+
+    /// Returns a new `Rgba` from 8-bit components.
+    pub fn from_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Rgba::new(red as f64 / 255., green as f64 / 255., blue as f64 / 255., alpha as f64 / 255.)
+    }
+
+    /// Returns a new, opaque `Rgba` from a packed `0xRRGGBB` value.
+    pub fn from_hex(value: u32) -> Self {
+        Rgba::from_u8((value >> 16) as u8, (value >> 8) as u8, value as u8, 255)
+    }
+
+    /// Returns a new `Rgba` from hue (in degrees), saturation, lightness and alpha. Saturation,
+    /// lightness and alpha are in the range `0.0..=1.0`.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64, alpha: f64) -> Self {
+        let hue = hue.rem_euclid(360.) / 60.;
+        let saturation = saturation.clamp(0., 1.);
+        let lightness = lightness.clamp(0., 1.);
+
+        let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+        let x = chroma * (1. - (hue % 2. - 1.).abs());
+        let m = lightness - chroma / 2.;
+
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.),
+            1 => (x, chroma, 0.),
+            2 => (0., chroma, x),
+            3 => (0., x, chroma),
+            4 => (x, 0., chroma),
+            _ => (chroma, 0., x)
+        };
+
+        Rgba::new(r + m, g + m, b + m, alpha)
+    }
+
+    /// Returns the components as 8-bit values, rounding to the nearest integer.
+    pub fn to_u8(&self) -> (u8, u8, u8, u8) {
+        let convert = |v: f64| (v * 255.).round() as u8;
+        (convert(self.red), convert(self.green), convert(self.blue), convert(self.alpha))
+    }
+
+    /// Returns this color as a `#RRGGBB` string, or `#RRGGBBAA` if it's not fully opaque.
+    pub fn to_hex_string(&self) -> String {
+        let (r, g, b, a) = self.to_u8();
+
+        match a {
+            255 => format!("#{:02X}{:02X}{:02X}", r, g, b),
+            a => format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+        }
+    }
+}
+
+impl FromStr for Rgba {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
@@ -0,0 +1,435 @@
+//! A platform-independent color value, with conversions between color spaces and the usual
+//! derived-color operations (compositing, blending, lightening and darkening).
+//!
+//! `Color` wraps an `NSColor`/`UIColor`, which can't be inspected without a running system. A
+//! `ColorValue` is plain data, so anything computed from it works the same on any machine - and
+//! `Color::from_value` keeps the value alongside the Objective-C object it creates, so it can be
+//! read back later with `Color::value`.
+//!
+//! ```rust
+//! use cacao::color::{ColorSpace, ColorValue, Rgba};
+//!
+//! let brand = ColorValue::Srgb(Rgba::from_hex(0x336699));
+//! let hover = brand.lighten(0.1);
+//! let tint = brand.with_alpha(0.2).composite_over(&ColorValue::gray(1.));
+//!
+//! assert_eq!(hover.space(), ColorSpace::Srgb);
+//! assert_eq!(tint.alpha(), 1.);
+//! ```
+//!
+//! All operations work on gamma-encoded components, which matches what browsers and design tools
+//! do. Converting a Display P3 color that's outside of the sRGB gamut into any other space clamps
+//! it to the nearest sRGB color.
+
+use crate::color::rgba::Rgba;
+
+/// Identifies the color space a `ColorValue` is expressed in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// Standard RGB.
+    Srgb,
+
+    /// Display P3, the wide gamut space used by most recent Apple displays.
+    DisplayP3,
+
+    /// Hue, saturation and brightness, over sRGB.
+    Hsb,
+
+    /// Hue, saturation and lightness, over sRGB.
+    Hsl,
+
+    /// A single white level, over sRGB.
+    Gray
+}
+
+/// A color, as components in a specific color space. Hues are in degrees; every other component
+/// is in the range `0.0..=1.0`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ColorValue {
+    /// Red, green and blue components in sRGB.
+    Srgb(Rgba),
+
+    /// Red, green and blue components in Display P3.
+    DisplayP3(Rgba),
+
+    /// Hue, saturation and brightness.
+    Hsb {
+        /// The hue, in degrees.
+        hue: f64,
+
+        /// The saturation.
+        saturation: f64,
+
+        /// The brightness.
+        brightness: f64,
+
+        /// The alpha (opacity).
+        alpha: f64
+    },
+
+    /// Hue, saturation and lightness.
+    Hsl {
+        /// The hue, in degrees.
+        hue: f64,
+
+        /// The saturation.
+        saturation: f64,
+
+        /// The lightness.
+        lightness: f64,
+
+        /// The alpha (opacity).
+        alpha: f64
+    },
+
+    /// A shade of gray.
+    Gray {
+        /// The white level, from black (`0.`) to white (`1.`).
+        white: f64,
+
+        /// The alpha (opacity).
+        alpha: f64
+    }
+}
+
+/// Converts linear Display P3 to linear sRGB.
+const P3_TO_SRGB: [[f64; 3]; 3] = [
+    [1.224_940_1, -0.224_940_4, 0.],
+    [-0.042_056_9, 1.042_057_1, 0.],
+    [-0.019_637_6, -0.078_636_1, 1.098_273_5]
+];
+
+/// Converts linear sRGB to linear Display P3.
+const SRGB_TO_P3: [[f64; 3]; 3] = [
+    [0.822_462_1, 0.177_538, 0.],
+    [0.033_194_1, 0.966_805_8, 0.],
+    [0.017_082_7, 0.072_397_4, 0.910_519_9]
+];
+
+/// The sRGB transfer function, from encoded to linear. Display P3 uses the same curve.
+pub(crate) fn to_linear(component: f64) -> f64 {
+    match component <= 0.040_45 {
+        true => component / 12.92,
+        false => ((component + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// The inverse of `to_linear`.
+pub(crate) fn from_linear(component: f64) -> f64 {
+    match component <= 0.003_130_8 {
+        true => component * 12.92,
+        false => 1.055 * component.powf(1. / 2.4) - 0.055
+    }
+}
+
+/// Converts encoded components between RGB spaces that share the sRGB transfer function.
+fn transform(rgba: Rgba, matrix: &[[f64; 3]; 3]) -> Rgba {
+    let linear = [to_linear(rgba.red), to_linear(rgba.green), to_linear(rgba.blue)];
+    let row = |r: &[f64; 3]| from_linear(r[0] * linear[0] + r[1] * linear[1] + r[2] * linear[2]);
+    Rgba::new(row(&matrix[0]), row(&matrix[1]), row(&matrix[2]), rgba.alpha)
+}
+
+/// Returns the hue (in degrees), the largest component, and the chroma for an RGB color.
+fn hue_components(rgba: &Rgba) -> (f64, f64, f64) {
+    let max = rgba.red.max(rgba.green).max(rgba.blue);
+    let min = rgba.red.min(rgba.green).min(rgba.blue);
+    let chroma = max - min;
+
+    let hue = if chroma == 0. {
+        0.
+    } else if max == rgba.red {
+        60. * ((rgba.green - rgba.blue) / chroma).rem_euclid(6.)
+    } else if max == rgba.green {
+        60. * ((rgba.blue - rgba.red) / chroma + 2.)
+    } else {
+        60. * ((rgba.red - rgba.green) / chroma + 4.)
+    };
+
+    (hue, max, chroma)
+}
+
+impl ColorValue {
+    /// Returns a shade of gray, fully opaque.
+    pub fn gray(white: f64) -> Self {
+        ColorValue::Gray { white: white, alpha: 1. }
+    }
+
+    /// Returns the color space this value is expressed in.
+    pub fn space(&self) -> ColorSpace {
+        match self {
+            ColorValue::Srgb(_) => ColorSpace::Srgb,
+            ColorValue::DisplayP3(_) => ColorSpace::DisplayP3,
+            ColorValue::Hsb { .. } => ColorSpace::Hsb,
+            ColorValue::Hsl { .. } => ColorSpace::Hsl,
+            ColorValue::Gray { .. } => ColorSpace::Gray
+        }
+    }
+
+    /// Returns the alpha component.
+    pub fn alpha(&self) -> f64 {
+        match self {
+            ColorValue::Srgb(rgba) | ColorValue::DisplayP3(rgba) => rgba.alpha,
+            ColorValue::Hsb { alpha, .. } | ColorValue::Hsl { alpha, .. } | ColorValue::Gray { alpha, .. } => *alpha
+        }
+    }
+
+    /// Returns this color with a different alpha, in the same color space.
+    pub fn with_alpha(&self, alpha: f64) -> Self {
+        let alpha = alpha.clamp(0., 1.);
+
+        match *self {
+            ColorValue::Srgb(rgba) => ColorValue::Srgb(Rgba { alpha: alpha, ..rgba }),
+            ColorValue::DisplayP3(rgba) => ColorValue::DisplayP3(Rgba { alpha: alpha, ..rgba }),
+            ColorValue::Hsb { hue, saturation, brightness, .. } => ColorValue::Hsb { hue, saturation, brightness, alpha },
+            ColorValue::Hsl { hue, saturation, lightness, .. } => ColorValue::Hsl { hue, saturation, lightness, alpha },
+            ColorValue::Gray { white, .. } => ColorValue::Gray { white, alpha }
+        }
+    }
+
+    /// Returns the sRGB components of this color.
+    pub fn to_srgb(&self) -> Rgba {
+        match *self {
+            ColorValue::Srgb(rgba) => rgba,
+            ColorValue::DisplayP3(rgba) => transform(rgba, &P3_TO_SRGB),
+
+            ColorValue::Hsb { hue, saturation, brightness, alpha } => {
+                let brightness = brightness.clamp(0., 1.);
+                let lightness = brightness * (1. - saturation.clamp(0., 1.) / 2.);
+
+                let saturation = match lightness == 0. || lightness == 1. {
+                    true => 0.,
+                    false => (brightness - lightness) / lightness.min(1. - lightness)
+                };
+
+                Rgba::from_hsl(hue, saturation, lightness, alpha)
+            },
+
+            ColorValue::Hsl { hue, saturation, lightness, alpha } => {
+                Rgba::from_hsl(hue, saturation, lightness, alpha)
+            },
+
+            ColorValue::Gray { white, alpha } => Rgba::new(white, white, white, alpha)
+        }
+    }
+
+    /// Returns the Display P3 components of this color.
+    pub fn to_display_p3(&self) -> Rgba {
+        match *self {
+            ColorValue::DisplayP3(rgba) => rgba,
+            _ => transform(self.to_srgb(), &SRGB_TO_P3)
+        }
+    }
+
+    /// Converts this color into the given color space.
+    pub fn convert(&self, space: ColorSpace) -> ColorValue {
+        if self.space() == space {
+            return *self;
+        }
+
+        if space == ColorSpace::DisplayP3 {
+            return ColorValue::DisplayP3(self.to_display_p3());
+        }
+
+        let rgba = self.to_srgb();
+
+        match space {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => ColorValue::Srgb(rgba),
+
+            ColorSpace::Hsb => {
+                let (hue, max, chroma) = hue_components(&rgba);
+
+                ColorValue::Hsb {
+                    hue: hue,
+                    saturation: match max == 0. {
+                        true => 0.,
+                        false => chroma / max
+                    },
+                    brightness: max,
+                    alpha: rgba.alpha
+                }
+            },
+
+            ColorSpace::Hsl => {
+                let (hue, max, chroma) = hue_components(&rgba);
+                let lightness = max - chroma / 2.;
+
+                ColorValue::Hsl {
+                    hue: hue,
+                    saturation: match lightness == 0. || lightness == 1. {
+                        true => 0.,
+                        false => chroma / (1. - (2. * lightness - 1.).abs())
+                    },
+                    lightness: lightness,
+                    alpha: rgba.alpha
+                }
+            },
+
+            // Preserve perceived brightness, rather than just averaging the channels.
+            ColorSpace::Gray => {
+                let luminance = 0.212_6 * to_linear(rgba.red)
+                    + 0.715_2 * to_linear(rgba.green)
+                    + 0.072_2 * to_linear(rgba.blue);
+
+                ColorValue::Gray { white: from_linear(luminance), alpha: rgba.alpha }
+            }
+        }
+    }
+
+    /// Composites this color over `background` ("source over"), returning the sRGB result.
+    pub fn composite_over(&self, background: &ColorValue) -> ColorValue {
+        let top = self.to_srgb();
+        let bottom = background.to_srgb();
+        let alpha = top.alpha + bottom.alpha * (1. - top.alpha);
+
+        if alpha == 0. {
+            return ColorValue::Srgb(Rgba::new(0., 0., 0., 0.));
+        }
+
+        let mix = |t: f64, b: f64| (t * top.alpha + b * bottom.alpha * (1. - top.alpha)) / alpha;
+
+        ColorValue::Srgb(Rgba::new(
+            mix(top.red, bottom.red),
+            mix(top.green, bottom.green),
+            mix(top.blue, bottom.blue),
+            alpha
+        ))
+    }
+
+    /// Mixes this color with `other`. An `amount` of `0.` returns this color, and `1.` returns
+    /// `other`. The result is in this color's space.
+    pub fn blend(&self, other: &ColorValue, amount: f64) -> ColorValue {
+        let amount = amount.clamp(0., 1.);
+        let from = self.to_srgb();
+        let to = other.to_srgb();
+        let mix = |a: f64, b: f64| a + (b - a) * amount;
+
+        let blended = ColorValue::Srgb(Rgba::new(
+            mix(from.red, to.red),
+            mix(from.green, to.green),
+            mix(from.blue, to.blue),
+            mix(from.alpha, to.alpha)
+        ));
+
+        blended.convert(self.space())
+    }
+
+    /// Raises lightness (as in HSL) by `amount`, returning the result in this color's space.
+    pub fn lighten(&self, amount: f64) -> ColorValue {
+        match self.convert(ColorSpace::Hsl) {
+            ColorValue::Hsl { hue, saturation, lightness, alpha } => ColorValue::Hsl {
+                hue: hue,
+                saturation: saturation,
+                lightness: (lightness + amount).clamp(0., 1.),
+                alpha: alpha
+            }.convert(self.space()),
+
+            _ => *self
+        }
+    }
+
+    /// Lowers lightness (as in HSL) by `amount`, returning the result in this color's space.
+    pub fn darken(&self, amount: f64) -> ColorValue {
+        self.lighten(-amount)
+    }
+}
+
+impl From<Rgba> for ColorValue {
+    fn from(rgba: Rgba) -> Self {
+        ColorValue::Srgb(rgba)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorSpace, ColorValue};
+    use crate::color::Rgba;
+
+    fn assert_close(actual: Rgba, expected: Rgba) {
+        let close = |a: f64, b: f64| (a - b).abs() < 0.001;
+
+        assert!(
+            close(actual.red, expected.red) && close(actual.green, expected.green) &&
+                close(actual.blue, expected.blue) && close(actual.alpha, expected.alpha),
+            "{:?} != {:?}", actual, expected
+        );
+    }
+
+    #[test]
+    fn converts_between_srgb_and_display_p3() {
+        // CSS Color 4 reference values for sRGB red and a mid-tone blue.
+        let red = ColorValue::Srgb(Rgba::new(1., 0., 0., 1.));
+        assert_close(red.to_display_p3(), Rgba::new(0.917_5, 0.200_3, 0.138_6, 1.));
+
+        let blue = ColorValue::Srgb(Rgba::from_hex(0x336699));
+        let p3 = blue.convert(ColorSpace::DisplayP3);
+        assert_eq!(p3.space(), ColorSpace::DisplayP3);
+        assert_close(p3.convert(ColorSpace::Srgb).to_srgb(), Rgba::from_hex(0x336699));
+
+        // P3's red is outside of sRGB, and clamps to the nearest sRGB color.
+        let p3_red = ColorValue::DisplayP3(Rgba::new(1., 0., 0., 0.5));
+        assert_close(p3_red.to_srgb(), Rgba::new(1., 0., 0., 0.5));
+    }
+
+    #[test]
+    fn converts_to_and_from_hsl() {
+        let blue = ColorValue::Srgb(Rgba::from_hex(0x336699));
+
+        match blue.convert(ColorSpace::Hsl) {
+            ColorValue::Hsl { hue, saturation, lightness, alpha } => {
+                assert!((hue - 210.).abs() < 0.001);
+                assert!((saturation - 0.5).abs() < 0.001);
+                assert!((lightness - 0.4).abs() < 0.001);
+                assert_eq!(alpha, 1.);
+            },
+
+            other => panic!("Expected HSL, got {:?}", other)
+        }
+
+        let hsl = ColorValue::Hsl { hue: 210., saturation: 0.5, lightness: 0.4, alpha: 0.25 };
+        assert_close(hsl.to_srgb(), Rgba::new(0.2, 0.4, 0.6, 0.25));
+    }
+
+    #[test]
+    fn converts_to_and_from_hsb() {
+        let blue = ColorValue::Srgb(Rgba::from_hex(0x336699));
+
+        match blue.convert(ColorSpace::Hsb) {
+            ColorValue::Hsb { hue, saturation, brightness, .. } => {
+                assert!((hue - 210.).abs() < 0.001);
+                assert!((saturation - 2. / 3.).abs() < 0.001);
+                assert!((brightness - 0.6).abs() < 0.001);
+            },
+
+            other => panic!("Expected HSB, got {:?}", other)
+        }
+
+        let hsb = ColorValue::Hsb { hue: 210., saturation: 2. / 3., brightness: 0.6, alpha: 1. };
+        assert_close(hsb.to_srgb(), Rgba::from_hex(0x336699));
+
+        // Black and white have no saturation.
+        let white = ColorValue::Hsb { hue: 0., saturation: 0., brightness: 1., alpha: 1. };
+        assert_close(white.to_srgb(), Rgba::new(1., 1., 1., 1.));
+        assert_close(ColorValue::Hsb { hue: 90., saturation: 1., brightness: 0., alpha: 1. }.to_srgb(), Rgba::new(0., 0., 0., 1.));
+    }
+
+    #[test]
+    fn converts_to_gray_by_luminance() {
+        match ColorValue::Srgb(Rgba::new(0., 1., 0., 1.)).convert(ColorSpace::Gray) {
+            ColorValue::Gray { white, .. } => assert!((white - 0.862_5).abs() < 0.001),
+            other => panic!("Expected gray, got {:?}", other)
+        }
+
+        assert_close(ColorValue::gray(0.5).to_srgb(), Rgba::new(0.5, 0.5, 0.5, 1.));
+    }
+
+    #[test]
+    fn composites_and_blends() {
+        let black = ColorValue::gray(0.);
+        let white = ColorValue::gray(1.);
+
+        assert_close(black.with_alpha(0.5).composite_over(&white).to_srgb(), Rgba::new(0.5, 0.5, 0.5, 1.));
+        assert_close(black.blend(&white, 0.25).to_srgb(), Rgba::new(0.25, 0.25, 0.25, 1.));
+        assert_eq!(black.blend(&white, 0.25).space(), ColorSpace::Gray);
+        assert_close(ColorValue::Srgb(Rgba::from_hex(0x336699)).lighten(0.2).to_srgb(), Rgba::new(0.4, 0.6, 0.8, 1.));
+    }
+}
@@ -0,0 +1,149 @@
+//! Compares two snapshots, view by view.
+//!
+//! Views are matched up by path. If the views at a path have different classes, the old one is
+//! reported as removed and the new one as added (along with everything beneath them); otherwise
+//! their properties and constraints are compared, and then their subviews.
+
+use std::fmt;
+
+use crate::debug::snapshot::{format_frame, Snapshot, SnapshotNode};
+
+/// A single difference between two snapshots.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapshotChange {
+    /// A view exists in the second snapshot, but not the first.
+    Added {
+        path: String,
+        class_name: String
+    },
+
+    /// A view exists in the first snapshot, but not the second.
+    Removed {
+        path: String,
+        class_name: String
+    },
+
+    /// A property of a view changed. `before` and `after` are formatted as they are in the text
+    /// tree (e.g, frames are `(x, y, width × height)`), with `-` for "not set".
+    Changed {
+        path: String,
+        property: &'static str,
+        before: String,
+        after: String
+    },
+
+    /// A constraint was installed on the view at `path`.
+    ConstraintAdded {
+        path: String,
+        constraint: String
+    },
+
+    /// A constraint was removed from the view at `path`.
+    ConstraintRemoved {
+        path: String,
+        constraint: String
+    }
+}
+
+impl fmt::Display for SnapshotChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotChange::Added { path, class_name } => write!(f, "+ {} {}", path, class_name),
+            SnapshotChange::Removed { path, class_name } => write!(f, "- {} {}", path, class_name),
+
+            SnapshotChange::Changed { path, property, before, after } => {
+                write!(f, "~ {} {}: {} -> {}", path, property, before, after)
+            },
+
+            SnapshotChange::ConstraintAdded { path, constraint } => write!(f, "+ {} · {}", path, constraint),
+            SnapshotChange::ConstraintRemoved { path, constraint } => write!(f, "- {} · {}", path, constraint)
+        }
+    }
+}
+
+impl Snapshot {
+    /// Returns everything that differs between this snapshot and `other`, in tree order. An empty
+    /// result means the two are equivalent.
+    pub fn diff(&self, other: &Snapshot) -> Vec<SnapshotChange> {
+        let mut changes = Vec::new();
+        diff_nodes(&self.root, &other.root, &mut changes);
+        changes
+    }
+}
+
+fn optional(value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!("{:?}", value),
+        None => "-".to_string()
+    }
+}
+
+fn added(node: &SnapshotNode, changes: &mut Vec<SnapshotChange>) {
+    changes.push(SnapshotChange::Added { path: node.path.clone(), class_name: node.class_name.clone() });
+
+    for child in &node.children {
+        added(child, changes);
+    }
+}
+
+fn removed(node: &SnapshotNode, changes: &mut Vec<SnapshotChange>) {
+    changes.push(SnapshotChange::Removed { path: node.path.clone(), class_name: node.class_name.clone() });
+
+    for child in &node.children {
+        removed(child, changes);
+    }
+}
+
+fn diff_nodes(before: &SnapshotNode, after: &SnapshotNode, changes: &mut Vec<SnapshotChange>) {
+    if before.class_name != after.class_name {
+        removed(before, changes);
+        added(after, changes);
+        return;
+    }
+
+    let properties = [
+        ("frame", format_frame(&before.frame), format_frame(&after.frame)),
+        ("hidden", before.hidden.to_string(), after.hidden.to_string()),
+        ("alpha", before.alpha.to_string(), after.alpha.to_string()),
+        ("background_color", optional(&before.background_color), optional(&after.background_color)),
+        ("text", optional(&before.text), optional(&after.text))
+    ];
+
+    for (property, old, new) in properties.iter() {
+        if old != new {
+            changes.push(SnapshotChange::Changed {
+                path: after.path.clone(),
+                property: property,
+                before: old.clone(),
+                after: new.clone()
+            });
+        }
+    }
+
+    // Constraints are compared as sets; the order AppKit reports them in isn't meaningful.
+    for constraint in &before.constraints {
+        if !after.constraints.contains(constraint) {
+            changes.push(SnapshotChange::ConstraintRemoved { path: before.path.clone(), constraint: constraint.to_string() });
+        }
+    }
+
+    for constraint in &after.constraints {
+        if !before.constraints.contains(constraint) {
+            changes.push(SnapshotChange::ConstraintAdded { path: after.path.clone(), constraint: constraint.to_string() });
+        }
+    }
+
+    let shared = before.children.len().min(after.children.len());
+
+    for index in 0..shared {
+        diff_nodes(&before.children[index], &after.children[index], changes);
+    }
+
+    for child in &before.children[shared..] {
+        removed(child, changes);
+    }
+
+    for child in &after.children[shared..] {
+        added(child, changes);
+    }
+}
@@ -0,0 +1,455 @@
+//! Tools for finding out what cacao actually built.
+//!
+//! `snapshot()` walks a view and its subviews, and returns a plain Rust tree describing each of
+//! them: class name, frame, hidden and alpha state, background color, text, and the constraints
+//! installed on it. Snapshots can be printed as an indented text tree or exported as JSON, and two
+//! of them can be diffed - which makes for golden-file UI tests:
+//!
+//! ```rust,no_run
+//! use cacao::debug;
+//! use cacao::foundation::MainThreadMarker;
+//! use cacao::text::Label;
+//! use cacao::layout::Layout;
+//! use cacao::view::View;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let view = View::new(mtm);
+//! let label = Label::new(mtm);
+//! label.set_text("Hello");
+//! view.add_subview(&label);
+//!
+//! let snapshot = debug::snapshot(&view);
+//! println!("{}", snapshot);
+//!
+//! // Compare against a snapshot taken earlier, and print whatever changed.
+//! let before = debug::snapshot(&view);
+//! label.set_text("Goodbye");
+//!
+//! for change in before.diff(&debug::snapshot(&view)) {
+//!     println!("{}", change);
+//! }
+//! ```
+//!
+//! Only taking the snapshot touches the Objective-C runtime; the tree, its renderings and diffing
+//! are pure Rust. With the `serde` feature, the tree types implement `Serialize` and
+//! `Deserialize`.
+
+use std::collections::HashMap;
+
+use core_graphics::base::CGFloat;
+use core_graphics::geometry::CGRect;
+
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, nil, to_bool, NSInteger, NSString, NSUInteger, BOOL};
+use crate::color::Rgba;
+use crate::geometry::Rect;
+use crate::layout::{Layout, LayoutAttribute, LayoutRelation};
+
+mod diff;
+pub use diff::SnapshotChange;
+
+mod snapshot;
+pub use snapshot::{Snapshot, SnapshotConstraint, SnapshotNode};
+
+/// Takes a snapshot of `view` and everything beneath it.
+pub fn snapshot<L: Layout>(view: &L) -> Snapshot {
+    let root = view.get_backing_node();
+    let root: id = &*root as *const Object as id;
+
+    let mut paths = HashMap::new();
+    collect_paths(root, "0".to_string(), &mut paths);
+
+    Snapshot {
+        root: node(root, "0".to_string(), &paths)
+    }
+}
+
+/// Returns the subviews of `view`, back to front.
+fn subviews(view: id) -> Vec<id> {
+    unsafe {
+        let subviews: id = msg_send![view, subviews];
+
+        if subviews == nil {
+            return Vec::new();
+        }
+
+        let count: NSUInteger = msg_send![subviews, count];
+        (0..count).map(|index| msg_send![subviews, objectAtIndex:index]).collect()
+    }
+}
+
+/// Maps the address of every view in the tree to its path.
+fn collect_paths(view: id, path: String, paths: &mut HashMap<usize, String>) {
+    for (index, subview) in subviews(view).into_iter().enumerate() {
+        collect_paths(subview, format!("{}.{}", path, index), paths);
+    }
+
+    paths.insert(view as usize, path);
+}
+
+fn class_name(object: id) -> String {
+    unsafe { (&*object).class().name().to_string() }
+}
+
+fn string(object: id) -> Option<String> {
+    match object == nil {
+        true => None,
+        false => Some(NSString::wrap(object).to_string())
+    }
+}
+
+fn responds_to(object: id, selector: objc::runtime::Sel) -> bool {
+    let result: BOOL = unsafe { msg_send![object, respondsToSelector:selector] };
+    to_bool(result)
+}
+
+fn node(view: id, path: String, paths: &HashMap<usize, String>) -> SnapshotNode {
+    let frame: CGRect = unsafe { msg_send![view, frame] };
+    let hidden: BOOL = unsafe { msg_send![view, isHidden] };
+
+    #[cfg(feature = "macos")]
+    let alpha: CGFloat = unsafe { msg_send![view, alphaValue] };
+
+    #[cfg(feature = "ios")]
+    let alpha: CGFloat = unsafe { msg_send![view, alpha] };
+
+    let children = subviews(view).into_iter().enumerate().map(|(index, subview)| {
+        node(subview, format!("{}.{}", path, index), paths)
+    }).collect();
+
+    SnapshotNode {
+        class_name: class_name(view),
+        frame: Rect::from(frame),
+        hidden: to_bool(hidden),
+        alpha: alpha as f64,
+        background_color: background_color(view),
+        text: text(view),
+        constraints: constraints(view, paths),
+        children: children,
+        path: path
+    }
+}
+
+/// `View` keeps its background color in an ivar (and applies it in `updateLayer`), while other
+/// controls set it on their layer.
+#[cfg(feature = "macos")]
+fn background_color(view: id) -> Option<String> {
+    use crate::view::BACKGROUND_COLOR;
+
+    let object = unsafe { &*view };
+
+    if object.class().instance_variable(BACKGROUND_COLOR).is_some() {
+        let color: id = unsafe { *object.get_ivar(BACKGROUND_COLOR) };
+
+        if color != nil {
+            return describe_color(color);
+        }
+    }
+
+    unsafe {
+        let layer: id = msg_send![view, layer];
+
+        if layer == nil {
+            return None;
+        }
+
+        let cg_color: id = msg_send![layer, backgroundColor];
+
+        match cg_color == nil {
+            true => None,
+            false => describe_color(msg_send![class!(NSColor), colorWithCGColor:cg_color])
+        }
+    }
+}
+
+#[cfg(feature = "ios")]
+fn background_color(view: id) -> Option<String> {
+    describe_color(unsafe { msg_send![view, backgroundColor] })
+}
+
+/// Describes a color as a hex string, if it can be converted to sRGB, or as the system describes
+/// it otherwise.
+#[cfg(feature = "macos")]
+fn describe_color(color: id) -> Option<String> {
+    if color == nil {
+        return None;
+    }
+
+    unsafe {
+        let space: id = msg_send![class!(NSColorSpace), sRGBColorSpace];
+        let srgb: id = msg_send![color, colorUsingColorSpace:space];
+
+        if srgb == nil {
+            return string(msg_send![color, description]);
+        }
+
+        let red: CGFloat = msg_send![srgb, redComponent];
+        let green: CGFloat = msg_send![srgb, greenComponent];
+        let blue: CGFloat = msg_send![srgb, blueComponent];
+        let alpha: CGFloat = msg_send![srgb, alphaComponent];
+        Some(Rgba::new(red as f64, green as f64, blue as f64, alpha as f64).to_hex_string())
+    }
+}
+
+#[cfg(feature = "ios")]
+fn describe_color(color: id) -> Option<String> {
+    if color == nil {
+        return None;
+    }
+
+    let mut red: CGFloat = 0.;
+    let mut green: CGFloat = 0.;
+    let mut blue: CGFloat = 0.;
+    let mut alpha: CGFloat = 0.;
+
+    unsafe {
+        let converted: BOOL = msg_send![color, getRed:&mut red green:&mut green blue:&mut blue alpha:&mut alpha];
+
+        match to_bool(converted) {
+            true => Some(Rgba::new(red as f64, green as f64, blue as f64, alpha as f64).to_hex_string()),
+            false => string(msg_send![color, description])
+        }
+    }
+}
+
+/// Returns the text a label, text field or button displays. Buttons answer `stringValue` too
+/// (with their state), so they're checked for first.
+#[cfg(feature = "macos")]
+fn text(view: id) -> Option<String> {
+    unsafe {
+        let is_button: BOOL = msg_send![view, isKindOfClass:class!(NSButton)];
+
+        if to_bool(is_button) {
+            return string(msg_send![view, title]);
+        }
+
+        match responds_to(view, sel!(stringValue)) {
+            true => string(msg_send![view, stringValue]),
+            false => None
+        }
+    }
+}
+
+#[cfg(feature = "ios")]
+fn text(view: id) -> Option<String> {
+    unsafe {
+        let is_button: BOOL = msg_send![view, isKindOfClass:class!(UIButton)];
+
+        if to_bool(is_button) {
+            let state: NSUInteger = 0;
+            return string(msg_send![view, titleForState:state]);
+        }
+
+        match responds_to(view, sel!(text)) {
+            true => string(msg_send![view, text]),
+            false => None
+        }
+    }
+}
+
+/// Names an item in a constraint: by path if it's in the snapshot, or by class otherwise.
+fn item_name(item: id, paths: &HashMap<usize, String>) -> String {
+    match paths.get(&(item as usize)) {
+        Some(path) => path.clone(),
+        None => format!("<{}>", class_name(item))
+    }
+}
+
+fn attribute_name(attribute: LayoutAttribute) -> String {
+    match attribute {
+        LayoutAttribute::Left => "left".to_string(),
+        LayoutAttribute::Right => "right".to_string(),
+        LayoutAttribute::Top => "top".to_string(),
+        LayoutAttribute::Bottom => "bottom".to_string(),
+        LayoutAttribute::Leading => "leading".to_string(),
+        LayoutAttribute::Trailing => "trailing".to_string(),
+        LayoutAttribute::Width => "width".to_string(),
+        LayoutAttribute::Height => "height".to_string(),
+        LayoutAttribute::CenterX => "centerX".to_string(),
+        LayoutAttribute::CenterY => "centerY".to_string(),
+        LayoutAttribute::LastBaseline => "lastBaseline".to_string(),
+        LayoutAttribute::FirstBaseline => "firstBaseline".to_string(),
+        LayoutAttribute::NotAnAttribute => "notAnAttribute".to_string(),
+        LayoutAttribute::Unknown(value) => format!("unknown({})", value)
+    }
+}
+
+fn relation_name(relation: LayoutRelation) -> String {
+    match relation {
+        LayoutRelation::LessThanOrEqual => "<=".to_string(),
+        LayoutRelation::Equal => "==".to_string(),
+        LayoutRelation::GreaterThanOrEqual => ">=".to_string(),
+        LayoutRelation::Unknown(value) => format!("unknown({})", value)
+    }
+}
+
+/// Returns the constraints installed on `view`. Constraints the system adds on its own behalf
+/// (for autoresizing masks and intrinsic content sizes) are subclasses of `NSLayoutConstraint`,
+/// and are left out; they'd make snapshots depend on the machine they were taken on.
+fn constraints(view: id, paths: &HashMap<usize, String>) -> Vec<SnapshotConstraint> {
+    let constraints: id = unsafe { msg_send![view, constraints] };
+
+    if constraints == nil {
+        return Vec::new();
+    }
+
+    let count: NSUInteger = unsafe { msg_send![constraints, count] };
+
+    (0..count).filter_map(|index| unsafe {
+        let constraint: id = msg_send![constraints, objectAtIndex:index];
+        let is_plain: BOOL = msg_send![constraint, isMemberOfClass:class!(NSLayoutConstraint)];
+
+        if !to_bool(is_plain) {
+            return None;
+        }
+
+        let first_item: id = msg_send![constraint, firstItem];
+        let first_attribute: NSInteger = msg_send![constraint, firstAttribute];
+        let relation: NSInteger = msg_send![constraint, relation];
+        let second_item: id = msg_send![constraint, secondItem];
+        let second_attribute: NSInteger = msg_send![constraint, secondAttribute];
+        let multiplier: CGFloat = msg_send![constraint, multiplier];
+        let constant: CGFloat = msg_send![constraint, constant];
+        let priority: f32 = msg_send![constraint, priority];
+
+        let (second_item, second_attribute) = match second_item == nil {
+            true => (None, None),
+            false => (
+                Some(item_name(second_item, paths)),
+                Some(attribute_name(LayoutAttribute::from(second_attribute)))
+            )
+        };
+
+        Some(SnapshotConstraint {
+            identifier: string(msg_send![constraint, identifier]),
+            first_item: item_name(first_item, paths),
+            first_attribute: attribute_name(LayoutAttribute::from(first_attribute)),
+            relation: relation_name(LayoutRelation::from(relation)),
+            second_item: second_item,
+            second_attribute: second_attribute,
+            multiplier: multiplier as f64,
+            constant: constant as f64,
+            priority: priority as f64
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Rect;
+
+    use super::{Snapshot, SnapshotChange, SnapshotConstraint, SnapshotNode};
+
+    fn node(path: &str, class_name: &str, children: Vec<SnapshotNode>) -> SnapshotNode {
+        SnapshotNode {
+            path: path.to_string(),
+            class_name: class_name.to_string(),
+            frame: Rect::new(0., 0., 100., 50.),
+            hidden: false,
+            alpha: 1.,
+            background_color: None,
+            text: None,
+            constraints: Vec::new(),
+            children: children
+        }
+    }
+
+    fn top_constraint(constant: f64) -> SnapshotConstraint {
+        SnapshotConstraint {
+            identifier: None,
+            first_item: "0.0".to_string(),
+            first_attribute: "top".to_string(),
+            relation: "==".to_string(),
+            second_item: Some("0".to_string()),
+            second_attribute: Some("top".to_string()),
+            multiplier: 1.,
+            constant: constant,
+            priority: 1000.
+        }
+    }
+
+    fn sample() -> Snapshot {
+        let mut label = node("0.0", "RSTTextField", Vec::new());
+        label.text = Some("Hello".to_string());
+
+        let mut root = node("0", "RSTView", vec![label, node("0.1", "RSTView", Vec::new())]);
+        root.background_color = Some("#FF0000".to_string());
+        root.constraints.push(top_constraint(16.));
+
+        Snapshot { root: root }
+    }
+
+    #[test]
+    fn renders_a_text_tree() {
+        assert_eq!(sample().to_pretty_string(), concat!(
+            "0 RSTView (0, 0, 100 × 50) background=#FF0000\n",
+            "│   · 0.0.top == 0.top + 16\n",
+            "├── 0.0 RSTTextField (0, 0, 100 × 50) text=\"Hello\"\n",
+            "└── 0.1 RSTView (0, 0, 100 × 50)\n"
+        ));
+    }
+
+    #[test]
+    fn formats_constraints() {
+        let mut constraint = top_constraint(-8.);
+        constraint.multiplier = 0.5;
+        constraint.priority = 750.;
+        constraint.identifier = Some("gap".to_string());
+        assert_eq!(constraint.to_string(), "[gap] 0.0.top == 0.top * 0.5 - 8 @750");
+
+        constraint.second_item = None;
+        constraint.second_attribute = None;
+        assert_eq!(constraint.to_string(), "[gap] 0.0.top == -8 @750");
+    }
+
+    #[test]
+    fn writes_json() {
+        let json = sample().to_json();
+        assert!(json.starts_with("{\n  \"root\": {\n    \"path\": \"0\",\n    \"class_name\": \"RSTView\","));
+        assert!(json.contains("\"text\": \"Hello\""));
+        assert!(json.contains("\"second_attribute\": \"top\""));
+        assert!(json.contains("\"children\": []"));
+        assert!(json.ends_with("}\n"));
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        assert!(sample().diff(&sample()).is_empty());
+    }
+
+    #[test]
+    fn diffs_properties_constraints_and_children() {
+        let before = sample();
+        let mut after = sample();
+        after.root.children[0].text = Some("Goodbye".to_string());
+        after.root.children[0].hidden = true;
+        after.root.constraints[0] = top_constraint(20.);
+        after.root.children.pop();
+        after.root.children[0].children.push(node("0.0.0", "NSImageView", Vec::new()));
+
+        let changes: Vec<String> = before.diff(&after).iter().map(SnapshotChange::to_string).collect();
+
+        assert_eq!(changes, vec![
+            "- 0 · 0.0.top == 0.top + 16",
+            "+ 0 · 0.0.top == 0.top + 20",
+            "~ 0.0 hidden: false -> true",
+            "~ 0.0 text: \"Hello\" -> \"Goodbye\"",
+            "+ 0.0.0 NSImageView",
+            "- 0.1 RSTView"
+        ]);
+    }
+
+    #[test]
+    fn a_different_class_replaces_the_view() {
+        let before = sample();
+        let mut after = sample();
+        after.root.children[1].class_name = "NSScrollView".to_string();
+
+        assert_eq!(after.diff(&before), vec![
+            SnapshotChange::Removed { path: "0.1".to_string(), class_name: "NSScrollView".to_string() },
+            SnapshotChange::Added { path: "0.1".to_string(), class_name: "RSTView".to_string() }
+        ]);
+    }
+}
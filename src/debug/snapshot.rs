@@ -0,0 +1,355 @@
+//! The snapshot tree itself, along with its text and JSON renderings. Nothing in here touches the
+//! Objective-C runtime, so snapshots can be built, stored and compared anywhere.
+
+use std::fmt;
+use std::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Rect;
+
+/// A snapshot of a view hierarchy, as returned by `debug::snapshot`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Snapshot {
+    /// The view the snapshot was taken of.
+    pub root: SnapshotNode
+}
+
+/// A single view in a `Snapshot`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SnapshotNode {
+    /// Where this view sits in the tree: the root is `0`, and its second subview is `0.1`. Paths
+    /// (unlike addresses) are stable from run to run, so constraints refer to views by path.
+    pub path: String,
+
+    /// The view's class name (e.g, `RSTView`, `NSTextField`).
+    pub class_name: String,
+
+    /// The view's frame, in its superview's coordinate space.
+    pub frame: Rect,
+
+    /// Whether the view is hidden.
+    pub hidden: bool,
+
+    /// The view's alpha value.
+    pub alpha: f64,
+
+    /// The view's background color, as a `#RRGGBB` (or `#RRGGBBAA`) string. Colors that can't be
+    /// expressed in sRGB are described however the system describes them.
+    pub background_color: Option<String>,
+
+    /// The text the view displays, for labels, text fields and buttons.
+    pub text: Option<String>,
+
+    /// The constraints installed on this view. AppKit and UIKit install a constraint on the
+    /// nearest common ancestor of the views it relates.
+    pub constraints: Vec<SnapshotConstraint>,
+
+    /// Subviews, back to front.
+    pub children: Vec<SnapshotNode>
+}
+
+/// A constraint in a `Snapshot`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SnapshotConstraint {
+    /// The constraint's identifier, if one was set.
+    pub identifier: Option<String>,
+
+    /// The path of the view on the left hand side, or its class name in angle brackets (e.g,
+    /// `<NSWindow>`) if it's outside of the snapshot.
+    pub first_item: String,
+
+    /// The attribute on the left hand side (e.g, `top`, `centerX`).
+    pub first_attribute: String,
+
+    /// The relation between the two sides: `==`, `<=` or `>=`.
+    pub relation: String,
+
+    /// The view on the right hand side, if any; named as `first_item` is.
+    pub second_item: Option<String>,
+
+    /// The attribute on the right hand side, if any.
+    pub second_attribute: Option<String>,
+
+    pub multiplier: f64,
+    pub constant: f64,
+    pub priority: f64
+}
+
+impl Snapshot {
+    /// Returns every node in the snapshot, depth first (starting with the root).
+    pub fn nodes(&self) -> Vec<&SnapshotNode> {
+        let mut nodes = vec![&self.root];
+        self.root.collect_descendants(&mut nodes);
+        nodes
+    }
+
+    /// Returns the node at `path`, if there is one.
+    pub fn node(&self, path: &str) -> Option<&SnapshotNode> {
+        self.nodes().into_iter().find(|node| node.path == path)
+    }
+
+    /// Renders the snapshot as an indented text tree, one view per line (with its constraints
+    /// beneath it). This is the same as the `Display` implementation.
+    pub fn to_pretty_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Renders the snapshot as (pretty-printed) JSON. Keys are written in a fixed order, so the
+    /// output is stable enough to check in as a golden file.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_node(&mut out, &self.root, 1);
+        format!("{{\n  \"root\": {}\n}}\n", out)
+    }
+
+    /// Parses a snapshot from JSON, as written by `to_json`. Requires the `serde` and
+    /// `serde_json` features.
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+impl SnapshotNode {
+    fn collect_descendants<'a>(&'a self, nodes: &mut Vec<&'a SnapshotNode>) {
+        for child in &self.children {
+            nodes.push(child);
+            child.collect_descendants(nodes);
+        }
+    }
+
+    /// A one-line summary of this view: class, frame, and any state worth mentioning.
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} {}", self.class_name, format_frame(&self.frame));
+
+        if self.hidden {
+            summary.push_str(" hidden");
+        }
+
+        if self.alpha != 1. {
+            let _ = write!(summary, " alpha={}", self.alpha);
+        }
+
+        if let Some(color) = &self.background_color {
+            let _ = write!(summary, " background={}", color);
+        }
+
+        if let Some(text) = &self.text {
+            let _ = write!(summary, " text={:?}", text);
+        }
+
+        summary
+    }
+
+    fn fmt_tree(&self, f: &mut fmt::Formatter, prefix: &str) -> fmt::Result {
+        let guide = match self.children.is_empty() {
+            true => "    ",
+            false => "│   "
+        };
+
+        for constraint in &self.constraints {
+            writeln!(f, "{}{}· {}", prefix, guide, constraint)?;
+        }
+
+        for (index, child) in self.children.iter().enumerate() {
+            let (branch, indent) = match index == self.children.len() - 1 {
+                true => ("└── ", "    "),
+                false => ("├── ", "│   ")
+            };
+
+            writeln!(f, "{}{}{} {}", prefix, branch, child.path, child.summary())?;
+            child.fmt_tree(f, &format!("{}{}", prefix, indent))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} {}", self.root.path, self.root.summary())?;
+        self.root.fmt_tree(f, "")
+    }
+}
+
+impl fmt::Display for SnapshotConstraint {
+    /// Formats the constraint as an equation, e.g `0.1.top == 0.top + 16`. The multiplier,
+    /// constant and priority are left out when they're the defaults (1, 0 and 1000).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(identifier) = &self.identifier {
+            write!(f, "[{}] ", identifier)?;
+        }
+
+        write!(f, "{}.{} {}", self.first_item, self.first_attribute, self.relation)?;
+
+        let has_second = match (&self.second_item, &self.second_attribute) {
+            (Some(item), Some(attribute)) => {
+                write!(f, " {}.{}", item, attribute)?;
+
+                if self.multiplier != 1. {
+                    write!(f, " * {}", self.multiplier)?;
+                }
+
+                true
+            },
+
+            _ => false
+        };
+
+        match (has_second, self.constant) {
+            (true, c) if c == 0. => {},
+            (true, c) if c < 0. => write!(f, " - {}", -c)?,
+            (true, c) => write!(f, " + {}", c)?,
+            (false, c) => write!(f, " {}", c)?
+        }
+
+        if self.priority != 1000. {
+            write!(f, " @{}", self.priority)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a frame as `(x, y, width × height)`.
+pub(crate) fn format_frame(frame: &Rect) -> String {
+    format!("({}, {}, {} × {})", frame.left, frame.top, frame.width, frame.height)
+}
+
+fn indent(level: usize) -> String {
+    "  ".repeat(level)
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); },
+            c => out.push(c)
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+fn json_optional_string(value: &Option<String>) -> String {
+    match value {
+        Some(value) => json_string(value),
+        None => "null".to_string()
+    }
+}
+
+/// JSON has no representation for infinities or NaN, so they're written as `null`.
+fn json_number(value: f64) -> String {
+    match value.is_finite() {
+        true => format!("{:?}", value),
+        false => "null".to_string()
+    }
+}
+
+/// Writes `fields` as a JSON object, for an object that starts at indentation `level`.
+fn write_object(out: &mut String, fields: &[(&str, String)], level: usize) {
+    out.push_str("{\n");
+
+    for (index, (key, value)) in fields.iter().enumerate() {
+        let separator = match index == fields.len() - 1 {
+            true => "",
+            false => ","
+        };
+
+        let _ = writeln!(out, "{}{}: {}{}", indent(level + 1), json_string(key), value, separator);
+    }
+
+    out.push_str(&indent(level));
+    out.push('}');
+}
+
+/// Writes `items` (already rendered) as a JSON array, for an array that starts at indentation
+/// `level`.
+fn write_array(out: &mut String, items: &[String], level: usize) {
+    if items.is_empty() {
+        out.push_str("[]");
+        return;
+    }
+
+    out.push_str("[\n");
+
+    for (index, item) in items.iter().enumerate() {
+        let separator = match index == items.len() - 1 {
+            true => "",
+            false => ","
+        };
+
+        let _ = writeln!(out, "{}{}{}", indent(level + 1), item, separator);
+    }
+
+    out.push_str(&indent(level));
+    out.push(']');
+}
+
+fn write_constraint(out: &mut String, constraint: &SnapshotConstraint, level: usize) {
+    write_object(out, &[
+        ("identifier", json_optional_string(&constraint.identifier)),
+        ("first_item", json_string(&constraint.first_item)),
+        ("first_attribute", json_string(&constraint.first_attribute)),
+        ("relation", json_string(&constraint.relation)),
+        ("second_item", json_optional_string(&constraint.second_item)),
+        ("second_attribute", json_optional_string(&constraint.second_attribute)),
+        ("multiplier", json_number(constraint.multiplier)),
+        ("constant", json_number(constraint.constant)),
+        ("priority", json_number(constraint.priority))
+    ], level);
+}
+
+fn write_node(out: &mut String, node: &SnapshotNode, level: usize) {
+    let mut frame = String::new();
+    write_object(&mut frame, &[
+        ("top", json_number(node.frame.top)),
+        ("left", json_number(node.frame.left)),
+        ("width", json_number(node.frame.width)),
+        ("height", json_number(node.frame.height))
+    ], level + 1);
+
+    let constraints: Vec<String> = node.constraints.iter().map(|constraint| {
+        let mut out = String::new();
+        write_constraint(&mut out, constraint, level + 2);
+        out
+    }).collect();
+
+    let mut constraints_json = String::new();
+    write_array(&mut constraints_json, &constraints, level + 1);
+
+    let children: Vec<String> = node.children.iter().map(|child| {
+        let mut out = String::new();
+        write_node(&mut out, child, level + 2);
+        out
+    }).collect();
+
+    let mut children_json = String::new();
+    write_array(&mut children_json, &children, level + 1);
+
+    write_object(out, &[
+        ("path", json_string(&node.path)),
+        ("class_name", json_string(&node.class_name)),
+        ("frame", frame),
+        ("hidden", node.hidden.to_string()),
+        ("alpha", json_number(node.alpha)),
+        ("background_color", json_optional_string(&node.background_color)),
+        ("text", json_optional_string(&node.text)),
+        ("constraints", constraints_json),
+        ("children", children_json)
+    ], level);
+}
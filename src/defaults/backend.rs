@@ -0,0 +1,307 @@
+//! Storage backends for `UserDefaults`.
+//!
+//! `UserDefaults` itself is a thin front over a `DefaultsBackend`. On a Mac you'll almost always
+//! want `NativeDefaults`, which is what `UserDefaults::standard()` and `UserDefaults::suite()`
+//! use. `MemoryDefaults` and `FileDefaults` implement the same semantics in pure Rust, so code that
+//! reads and writes preferences can be exercised anywhere - including CI machines that aren't
+//! Macs.
+//!
+//! All backends follow `NSUserDefaults`' lookup order: a forced (managed) value wins, then a value
+//! that's been set, then a registered default. Observers are told about changes to that resolved
+//! value - so e.g, removing a key with a registered default reports the default as the new value.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use objc::{class, msg_send, sel, sel_impl};
+use objc::runtime::Object;
+use objc_id::Id;
+
+use crate::defaults::{ChangeHandler, Observation, Observers, Value};
+use crate::defaults::kvo;
+use crate::foundation::{id, nil, to_bool, BOOL, NSString, NSDictionary};
+
+/// The operations a `UserDefaults` store needs to support.
+pub trait DefaultsBackend: fmt::Debug {
+    /// Registers fallback values, returned by `get` for keys that haven't been set.
+    fn register(&mut self, values: HashMap<String, Value>);
+
+    /// Sets the value for a key.
+    fn insert(&mut self, key: &str, value: Value);
+
+    /// Removes the value set for a key. Registered defaults for the key are unaffected.
+    fn remove(&mut self, key: &str);
+
+    /// Returns the value for a key, falling back to any registered default.
+    fn get(&self, key: &str) -> Option<Value>;
+
+    /// Returns whether anything is stored for a key - including values `get` can't represent,
+    /// which it returns `None` for.
+    fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns whether the value for a key is managed by an administrator.
+    fn is_forced_for_key(&self, key: &str) -> bool;
+
+    /// Flushes any pending changes to (and picks up any changes from) persistent storage.
+    fn synchronize(&mut self);
+
+    /// Registers `handler` to be called whenever the value for `key` changes, until the returned
+    /// `Observation` is dropped.
+    fn observe(&self, key: &str, handler: Box<ChangeHandler>) -> Observation;
+}
+
+/// The native backend, wrapping `NSUserDefaults`.
+#[derive(Debug)]
+pub struct NativeDefaults(pub Id<Object>);
+
+impl NativeDefaults {
+    /// Wraps `[NSUserDefaults standardUserDefaults]`.
+    pub fn standard() -> Self {
+        NativeDefaults(unsafe {
+            Id::from_ptr(msg_send![class!(NSUserDefaults), standardUserDefaults])
+        })
+    }
+
+    /// Wraps an `NSUserDefaults` instance for the given suite name.
+    pub fn suite(named: &str) -> Self {
+        let name = NSString::new(named);
+
+        NativeDefaults(unsafe {
+            let alloc: id = msg_send![class!(NSUserDefaults), alloc];
+            Id::from_ptr(msg_send![alloc, initWithSuiteName:name.into_inner()])
+        })
+    }
+}
+
+impl DefaultsBackend for NativeDefaults {
+    fn register(&mut self, values: HashMap<String, Value>) {
+        let dictionary = NSDictionary::from(values);
+
+        unsafe {
+            let _: () = msg_send![&*self.0, registerDefaults:dictionary.into_inner()];
+        }
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        let key = NSString::new(key);
+        let value: id = value.into();
+
+        unsafe {
+            let _: () = msg_send![&*self.0, setObject:value forKey:key];
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        let key = NSString::new(key);
+
+        unsafe {
+            let _: () = msg_send![&*self.0, removeObjectForKey:key.into_inner()];
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        let key = NSString::new(key);
+
+        let result: id = unsafe {
+            msg_send![&*self.0, objectForKey:key.into_inner()]
+        };
+
+        Value::from_objc(result)
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        let key = NSString::new(key);
+
+        let result: id = unsafe {
+            msg_send![&*self.0, objectForKey:key.into_inner()]
+        };
+
+        result != nil
+    }
+
+    fn is_forced_for_key(&self, key: &str) -> bool {
+        let result: BOOL = unsafe {
+            let key = NSString::new(key);
+            msg_send![&*self.0, objectIsForcedForKey:key.into_inner()]
+        };
+
+        to_bool(result)
+    }
+
+    fn synchronize(&mut self) {
+        unsafe {
+            let _: () = msg_send![&*self.0, synchronize];
+        }
+    }
+
+    fn observe(&self, key: &str, handler: Box<ChangeHandler>) -> Observation {
+        kvo::observe(&self.0, key, handler)
+    }
+}
+
+/// A backend that keeps everything in memory. Nothing is persisted.
+///
+/// Forced values, which on a Mac come from configuration profiles, can be simulated with
+/// `force`. Observers are called synchronously, on the thread that made the change.
+#[derive(Debug, Default)]
+pub struct MemoryDefaults {
+    store: Store,
+    observers: Observers
+}
+
+#[derive(Debug, Default)]
+struct Store {
+    registered: HashMap<String, Value>,
+    values: HashMap<String, Value>,
+    forced: HashMap<String, Value>
+}
+
+impl Store {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.forced.get(key)
+            .or_else(|| self.values.get(key))
+            .or_else(|| self.registered.get(key))
+            .cloned()
+    }
+}
+
+impl MemoryDefaults {
+    /// Returns a new, empty store.
+    pub fn new() -> Self {
+        MemoryDefaults::default()
+    }
+
+    /// Returns a store pre-populated with set (not registered) values.
+    pub fn with_values(values: HashMap<String, Value>) -> Self {
+        let mut defaults = MemoryDefaults::default();
+        defaults.store.values = values;
+        defaults
+    }
+
+    /// Forces a key to a value, as an administrator would. Forced values take precedence over
+    /// everything else, and `is_forced_for_key` returns `true` for them.
+    pub fn force<K: Into<String>>(&mut self, key: K, value: Value) {
+        let key = key.into();
+        self.observers.track(&mut self.store, Store::get, |store| {
+            store.forced.insert(key, value);
+        });
+    }
+
+    /// Returns the values that have been set - i.e, what a persistent store would write out.
+    /// Registered defaults and forced values aren't included.
+    pub fn values(&self) -> &HashMap<String, Value> {
+        &self.store.values
+    }
+
+    /// Replaces the values that have been set, leaving registered and forced values alone.
+    pub(crate) fn set_values(&mut self, values: HashMap<String, Value>) {
+        self.observers.track(&mut self.store, Store::get, |store| {
+            store.values = values;
+        });
+    }
+}
+
+impl DefaultsBackend for MemoryDefaults {
+    fn register(&mut self, values: HashMap<String, Value>) {
+        self.observers.track(&mut self.store, Store::get, |store| {
+            store.registered.extend(values);
+        });
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        self.observers.track(&mut self.store, Store::get, |store| {
+            store.values.insert(key.to_string(), value);
+        });
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.observers.track(&mut self.store, Store::get, |store| {
+            store.values.remove(key);
+        });
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        self.store.get(key)
+    }
+
+    fn is_forced_for_key(&self, key: &str) -> bool {
+        self.store.forced.contains_key(key)
+    }
+
+    fn synchronize(&mut self) {}
+
+    fn observe(&self, key: &str, handler: Box<ChangeHandler>) -> Observation {
+        self.observers.add(key, handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::{DefaultsBackend, MemoryDefaults};
+    use crate::defaults::{Change, Value};
+
+    #[test]
+    fn falls_back_to_registered_defaults() {
+        let mut defaults = MemoryDefaults::new();
+
+        let mut registered = HashMap::new();
+        registered.insert("font_size".to_string(), Value::Integer(12));
+        defaults.register(registered);
+        assert_eq!(defaults.get("font_size"), Some(Value::Integer(12)));
+
+        defaults.insert("font_size", Value::Integer(14));
+        assert_eq!(defaults.get("font_size"), Some(Value::Integer(14)));
+
+        defaults.remove("font_size");
+        assert_eq!(defaults.get("font_size"), Some(Value::Integer(12)));
+        assert!(defaults.values().is_empty());
+        assert_eq!(defaults.get("missing"), None);
+    }
+
+    #[test]
+    fn forced_keys_win() {
+        let mut defaults = MemoryDefaults::new();
+        defaults.insert("sync", Value::Bool(true));
+        assert!(!defaults.is_forced_for_key("sync"));
+
+        defaults.force("sync", Value::Bool(false));
+        defaults.insert("sync", Value::Bool(true));
+
+        assert!(defaults.is_forced_for_key("sync"));
+        assert_eq!(defaults.get("sync"), Some(Value::Bool(false)));
+        assert_eq!(defaults.values().get("sync"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn observers_see_the_resolved_value() {
+        let mut defaults = MemoryDefaults::new();
+
+        let mut registered = HashMap::new();
+        registered.insert("font_size".to_string(), Value::Integer(12));
+        defaults.register(registered);
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = changes.clone();
+        let _observation = defaults.observe("font_size", Box::new(move |change: &Change| {
+            recorded.lock().unwrap().push((change.old.clone(), change.new.clone()));
+        }));
+
+        defaults.insert("font_size", Value::Integer(14));
+        defaults.insert("font_size", Value::Integer(14));
+        defaults.remove("font_size");
+        defaults.force("font_size", Value::Integer(20));
+
+        assert_eq!(*changes.lock().unwrap(), vec![
+            (Some(Value::Integer(12)), Some(Value::Integer(14))),
+
+            // Removing the key falls back to the registered default, rather than nothing.
+            (Some(Value::Integer(14)), Some(Value::Integer(12))),
+            (Some(Value::Integer(12)), Some(Value::Integer(20)))
+        ]);
+    }
+}
@@ -0,0 +1,276 @@
+//! A `DefaultsBackend` that persists to a property list file.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::defaults::{ChangeHandler, DefaultsBackend, MemoryDefaults, Observation, Value};
+use crate::defaults::plist;
+
+/// Errors that can occur when reading or writing a `FileDefaults` store.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileDefaultsError {
+    /// The file couldn't be read or written.
+    Io(String),
+
+    /// The file isn't a valid XML property list with a dictionary at its root.
+    Syntax(String)
+}
+
+impl fmt::Display for FileDefaultsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileDefaultsError::Io(error) => write!(f, "Unable to access defaults file: {}", error),
+            FileDefaultsError::Syntax(error) => write!(f, "Unable to parse defaults file: {}", error)
+        }
+    }
+}
+
+impl error::Error for FileDefaultsError {}
+
+/// A backend that keeps values in an XML property list on disk - the same format
+/// `NSUserDefaults` uses in `~/Library/Preferences`, so files can be inspected with `plutil` or
+/// `defaults read`.
+///
+/// Every `insert` and `remove` writes the file straight away. If a write fails, the change is kept
+/// in memory and retried on the next write or `synchronize()` call, and the failure is available
+/// from `last_error()`. `synchronize()` also picks up changes made to the file by anyone else, and
+/// notifies observers of any values that differ.
+///
+/// Registered defaults are never written out, as with `NSUserDefaults`. Dates are stored with
+/// whole-second precision, as that's all property lists support.
+#[derive(Debug)]
+pub struct FileDefaults {
+    path: PathBuf,
+    store: MemoryDefaults,
+    dirty: bool,
+    last_error: Option<FileDefaultsError>
+}
+
+impl FileDefaults {
+    /// Opens the store at `path`. A missing file is treated as an empty store, and will be created
+    /// on the first write; a file that exists but can't be parsed is an error.
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, FileDefaultsError> {
+        let path = path.into();
+        let values = read(&path)?;
+
+        Ok(FileDefaults {
+            path: path,
+            store: MemoryDefaults::with_values(values),
+            dirty: false,
+            last_error: None
+        })
+    }
+
+    /// The path this store reads from and writes to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the error from the most recent write or `synchronize()`, if it failed. This is
+    /// cleared by the next one that succeeds.
+    ///
+    /// `insert`, `remove` and `synchronize()` have no way of returning errors through
+    /// `UserDefaults`, so this is where to look if changes don't seem to be reaching the disk.
+    pub fn last_error(&self) -> Option<&FileDefaultsError> {
+        self.last_error.as_ref()
+    }
+
+    /// Forces a key to a value, as an administrator would. Forced values aren't written to disk.
+    pub fn force<K: Into<String>>(&mut self, key: K, value: Value) {
+        self.store.force(key, value);
+    }
+
+    /// Writes the current values to disk. The file is written to a temporary path first and then
+    /// moved into place, so a crash mid-write won't leave a truncated store behind.
+    pub fn save(&mut self) -> Result<(), FileDefaultsError> {
+        let contents = plist::encode(self.store.values());
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).map_err(|e| FileDefaultsError::Io(e.to_string()))?;
+            }
+        }
+
+        fs::write(&temporary, contents)
+            .and_then(|_| fs::rename(&temporary, &self.path))
+            .map_err(|e| FileDefaultsError::Io(e.to_string()))?;
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Re-reads values from disk, discarding anything in memory that hasn't been written.
+    pub fn reload(&mut self) -> Result<(), FileDefaultsError> {
+        let values = read(&self.path)?;
+        self.store.set_values(values);
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn persist(&mut self) {
+        self.dirty = true;
+
+        // On failure, the change is kept in memory, and the write is retried later.
+        let result = self.save();
+        self.last_error = result.err();
+    }
+}
+
+fn read(path: &Path) -> Result<HashMap<String, Value>, FileDefaultsError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => plist::decode(&contents).map_err(FileDefaultsError::Syntax),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(error) => Err(FileDefaultsError::Io(error.to_string()))
+    }
+}
+
+impl DefaultsBackend for FileDefaults {
+    fn register(&mut self, values: HashMap<String, Value>) {
+        self.store.register(values);
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        self.store.insert(key, value);
+        self.persist();
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.store.remove(key);
+        self.persist();
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        self.store.get(key)
+    }
+
+    fn is_forced_for_key(&self, key: &str) -> bool {
+        self.store.is_forced_for_key(key)
+    }
+
+    fn synchronize(&mut self) {
+        let result = match self.dirty {
+            true => self.save(),
+            false => self.reload()
+        };
+
+        self.last_error = result.err();
+    }
+
+    fn observe(&self, key: &str, handler: Box<ChangeHandler>) -> Observation {
+        self.store.observe(key, handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+
+    use super::{FileDefaults, FileDefaultsError};
+    use crate::defaults::{DefaultsBackend, Value};
+
+    fn temporary_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("cacao-{}-{}.plist", process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn persists_and_reloads() {
+        let path = temporary_path("persists");
+
+        let mut registered = HashMap::new();
+        registered.insert("theme".to_string(), Value::string("light"));
+
+        let mut nested = HashMap::new();
+        nested.insert("width".to_string(), Value::Float(640.5));
+
+        let mut defaults = FileDefaults::open(&path).unwrap();
+        defaults.register(registered);
+        defaults.force("managed", Value::Bool(true));
+        defaults.insert("name", Value::string("Cacao"));
+        defaults.insert("count", Value::Integer(-3));
+        defaults.insert("window", Value::Dictionary(nested.clone()));
+        defaults.insert("recent", Value::Array(vec![Value::string("a"), Value::Bool(false)]));
+        defaults.insert("removed", Value::Integer(1));
+        defaults.remove("removed");
+
+        let reopened = FileDefaults::open(&path).unwrap();
+        assert_eq!(reopened.get("name"), Some(Value::string("Cacao")));
+        assert_eq!(reopened.get("count"), Some(Value::Integer(-3)));
+        assert_eq!(reopened.get("window"), Some(Value::Dictionary(nested)));
+        assert_eq!(reopened.get("recent"), Some(Value::Array(vec![Value::string("a"), Value::Bool(false)])));
+        assert_eq!(reopened.get("removed"), None);
+
+        // Neither registered nor forced values are written out.
+        assert_eq!(reopened.get("theme"), None);
+        assert_eq!(reopened.get("managed"), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn synchronize_picks_up_changes_on_disk() {
+        let path = temporary_path("synchronize");
+
+        let mut first = FileDefaults::open(&path).unwrap();
+        first.insert("count", Value::Integer(1));
+
+        let mut second = FileDefaults::open(&path).unwrap();
+        second.insert("count", Value::Integer(2));
+
+        assert_eq!(first.get("count"), Some(Value::Integer(1)));
+        first.synchronize();
+        assert_eq!(first.get("count"), Some(Value::Integer(2)));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_property_lists() {
+        let path = temporary_path("invalid");
+        fs::write(&path, "not a property list").unwrap();
+
+        match FileDefaults::open(&path) {
+            Err(FileDefaultsError::Syntax(_)) => {},
+            other => panic!("Expected a syntax error, got {:?}", other)
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_last_error() {
+        let path = temporary_path("unwritable");
+        let mut defaults = FileDefaults::open(&path).unwrap();
+        assert_eq!(defaults.last_error(), None);
+
+        // With a directory in the way, the file can't be written.
+        fs::create_dir(&path).unwrap();
+
+        defaults.insert("count", Value::Integer(1));
+        assert!(matches!(defaults.last_error(), Some(FileDefaultsError::Io(_))));
+        assert_eq!(defaults.get("count"), Some(Value::Integer(1)));
+
+        defaults.synchronize();
+        assert!(matches!(defaults.last_error(), Some(FileDefaultsError::Io(_))));
+
+        fs::remove_dir(&path).unwrap();
+        defaults.synchronize();
+        assert_eq!(defaults.last_error(), None);
+
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(".tmp");
+        let _ = fs::remove_file(&temporary);
+        fs::remove_file(&path).unwrap();
+    }
+}
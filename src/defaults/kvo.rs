@@ -0,0 +1,147 @@
+//! Key-Value Observing for `NSUserDefaults`, used by `NativeDefaults::observe`.
+//!
+//! `NSUserDefaults` is KVO-compliant for every key it stores, and - unlike
+//! `NSUserDefaultsDidChangeNotification` - this fires for changes made outside of this process,
+//! e.g by an extension in the same suite, or `defaults write`. Key paths can't contain `.`, so
+//! neither can observed keys.
+
+use std::os::raw::c_void;
+use std::sync::{Arc, Once};
+
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::ShareId;
+
+use crate::defaults::{Change, ChangeHandler, Observation, Value};
+use crate::foundation::{id, is_main_thread, nil, NSString, NSUInteger};
+
+#[cfg(all(feature = "headless", not(target_vendor = "apple")))]
+use crate::headless::dispatch;
+
+pub static DEFAULTS_OBSERVER_PTR: &str = "rstDefaultsObserverPtr";
+
+/// `NSKeyValueObservingOptionNew | NSKeyValueObservingOptionOld`.
+const OBSERVING_OPTIONS: NSUInteger = 0x01 | 0x02;
+
+extern "C" {
+    static NSKeyValueChangeNewKey: id;
+    static NSKeyValueChangeOldKey: id;
+}
+
+/// What the observer object points to: the key it's watching, and the handler to call.
+struct KeyObserver {
+    key: String,
+    handler: Arc<ChangeHandler>
+}
+
+/// Registers `handler` for changes to `key` on an `NSUserDefaults` instance. Dropping the returned
+/// `Observation` removes the KVO registration.
+///
+/// The `KeyObserver` is owned by the observer object, and only freed in its `dealloc` - so a
+/// callback that's already running on another thread when the `Observation` is dropped can still
+/// safely use it.
+pub(crate) fn observe(defaults: &Object, key: &str, handler: Box<ChangeHandler>) -> Observation {
+    let ptr = Arc::into_raw(Arc::new(KeyObserver {
+        key: key.to_string(),
+        handler: Arc::from(handler)
+    }));
+
+    let (defaults, observer) = unsafe {
+        let defaults: ShareId<Object> = ShareId::from_ptr(defaults as *const Object as id);
+
+        let observer: id = msg_send![register_defaults_observer_class(), new];
+        (&mut *observer).set_ivar(DEFAULTS_OBSERVER_PTR, ptr as usize);
+
+        let key_path = NSString::new(key);
+        let _: () = msg_send![&*defaults, addObserver:observer
+            forKeyPath:key_path.into_inner()
+            options:OBSERVING_OPTIONS
+            context:nil];
+
+        (defaults, ShareId::<Object>::from_retained_ptr(observer))
+    };
+
+    let key = key.to_string();
+
+    Observation::new(move || unsafe {
+        let key_path = NSString::new(&key);
+        let _: () = msg_send![&*defaults, removeObserver:&*observer forKeyPath:key_path.into_inner()];
+    })
+}
+
+/// Returns a new reference to the `KeyObserver` an observer object owns.
+fn key_observer(this: &Object) -> Arc<KeyObserver> {
+    unsafe {
+        let ptr: usize = *this.get_ivar(DEFAULTS_OBSERVER_PTR);
+        let ptr = ptr as *const KeyObserver;
+        Arc::increment_strong_count(ptr);
+        Arc::from_raw(ptr)
+    }
+}
+
+/// Called by KVO, on whatever thread made the change. Handlers are always run on the main thread.
+extern fn observe_value(this: &Object, _: Sel, _key_path: id, _object: id, change: id, _context: *mut c_void) {
+    let observer = key_observer(this);
+
+    // Missing values come through as `NSNull`, which `from_objc` maps to `None`.
+    let (old, new) = unsafe {
+        let old: id = msg_send![change, objectForKey:NSKeyValueChangeOldKey];
+        let new: id = msg_send![change, objectForKey:NSKeyValueChangeNewKey];
+        (Value::from_objc(old), Value::from_objc(new))
+    };
+
+    // KVO fires for every write, including ones that store the same value again.
+    if old == new {
+        return;
+    }
+
+    let handler = observer.handler.clone();
+    let change = Change {
+        key: observer.key.clone(),
+        old: old,
+        new: new
+    };
+
+    match is_main_thread() {
+        true => handler(&change),
+        false => dispatch::Queue::main().exec_async(move || handler(&change))
+    }
+}
+
+/// Releases the observer object's reference to its `KeyObserver`. Callbacks that are still running
+/// hold references of their own, so it's freed once the last of them finishes.
+extern fn dealloc(this: &Object, _: Sel) {
+    unsafe {
+        let ptr: usize = *this.get_ivar(DEFAULTS_OBSERVER_PTR);
+
+        if ptr != 0 {
+            drop(Arc::from_raw(ptr as *const KeyObserver));
+        }
+
+        let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+}
+
+/// Injects an `NSObject` subclass that receives KVO callbacks and forwards them to a
+/// `KeyObserver` stored in an ivar, which it owns.
+fn register_defaults_observer_class() -> *const Class {
+    static mut OBSERVER_CLASS: *const Class = 0 as *const Class;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| unsafe {
+        let superclass = class!(NSObject);
+        let mut decl = ClassDecl::new("RSTUserDefaultsObserver", superclass).unwrap();
+
+        decl.add_ivar::<usize>(DEFAULTS_OBSERVER_PTR);
+        decl.add_method(
+            sel!(observeValueForKeyPath:ofObject:change:context:),
+            observe_value as extern fn(&Object, _, id, id, id, *mut c_void)
+        );
+        decl.add_method(sel!(dealloc), dealloc as extern fn(&Object, _));
+
+        OBSERVER_CLASS = decl.register();
+    });
+
+    unsafe { OBSERVER_CLASS }
+}
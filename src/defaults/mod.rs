@@ -1,11 +1,19 @@
 //! Wraps `NSUserDefaults`, providing an interface to fetch and store small amounts of data.
 //!
+//! Storage is pluggable: `NSUserDefaults` is used unless you say otherwise, but `MemoryDefaults`
+//! and `FileDefaults` behave the same way without needing the Objective-C runtime. Keys can be
+//! observed with `UserDefaults::observe`, regardless of the backend.
+//!
 //! In general, this tries to take an approach popularized by `serde_json`'s `Value` struct. In
 //! this case, `Value` handles wrapping types for insertion/retrieval, shepherding between
 //! the Objective-C runtime and your Rust code.
 //!
-//! It currently supports a number of primitive types, as well as a generic `Data` type for custom
-//! usage. Note that the `Data` type is stored internally as an `NSData` instance.
+//! It currently supports a number of primitive types, dates, arrays and dictionaries, as well as a
+//! generic `Data` type for custom usage. Note that the `Data` type is stored internally as an
+//! `NSData` instance.
+//!
+//! With the `serde` feature enabled, `get_as` and `insert_as` map your own types directly onto
+//! these, so structured settings don't need to be hand-encoded into `Data`.
 //!
 //! Do not use this for storing sensitive data - you want the Keychain for that.
 //!
@@ -22,33 +30,51 @@
 //! let mut defaults = UserDefaults::standard();
 //!
 //! defaults.register({
-//!     let map = HashMap::new();
+//!     let mut map = HashMap::new();
 //!     map.insert("test", Value::string("value"));
 //!     map
 //! });
 //!
 //! // Ignore the unwrap() calls, it's a demo ;P
-//! let value = defaults.get("test").unwrap().as_str().unwrap();
-//! assert_eq!(value, "value");
+//! let value = defaults.get("test").unwrap();
+//! assert_eq!(value.as_str().unwrap(), "value");
 //! ```
 
 use std::collections::HashMap;
 
-use objc::{class, msg_send, sel, sel_impl};
-use objc::runtime::Object;
-use objc_id::Id;
+mod value;
+pub use value::{Value, ValueKind};
 
-use crate::foundation::{id, nil, to_bool, YES, NO, BOOL, NSData, NSString, NSDictionary, NSNumber};
+mod backend;
+pub use backend::{DefaultsBackend, MemoryDefaults, NativeDefaults};
 
-mod value;
-pub use value::Value;
+mod observe;
+pub use observe::{Change, ChangeHandler, Observation, Observers};
+
+mod kvo;
+
+pub(crate) mod plist;
+
+mod file;
+pub use file::{FileDefaults, FileDefaultsError};
+
+pub mod schema;
+
+#[cfg(feature = "serde")]
+mod serialization;
+
+#[cfg(feature = "serde")]
+pub use serialization::{date, from_value, to_value, ValueError};
 
 /// Wraps and provides methods for interacting with `NSUserDefaults`, which can be used for storing
 /// pieces of information (preferences, or _defaults_) to persist across application launches.
 ///
 /// This should not be used for sensitive data - use the Keychain for that.
+///
+/// By default this is backed by `NSUserDefaults`, but any `DefaultsBackend` can be swapped in -
+/// e.g, `MemoryDefaults` for tests, or `FileDefaults` for a portable settings file.
 #[derive(Debug)]
-pub struct UserDefaults(pub Id<Object>);
+pub struct UserDefaults(Box<dyn DefaultsBackend>);
 
 impl Default for UserDefaults {
     /// Equivalent to calling `UserDefaults::standard()`.
@@ -71,9 +97,7 @@ impl UserDefaults {
     /// let _ = defaults.get("test");
     /// ```
     pub fn standard() -> Self {
-        UserDefaults(unsafe {
-            Id::from_ptr(msg_send![class!(NSUserDefaults), standardUserDefaults])
-        })
+        UserDefaults::with_backend(NativeDefaults::standard())
     }
 
     /// Returns a user defaults instance for the given suite name. You typically use this to share
@@ -88,12 +112,54 @@ impl UserDefaults {
     /// let _ = defaults.get("test");
     /// ```
     pub fn suite(named: &str) -> Self {
-        let name = NSString::new(named);
+        UserDefaults::with_backend(NativeDefaults::suite(named))
+    }
 
-        UserDefaults(unsafe {
-            let alloc: id = msg_send![class!(NSUserDefaults), alloc];
-            Id::from_ptr(msg_send![alloc, initWithSuiteName:name.into_inner()])
-        })
+    /// Returns a user defaults instance that stores values with the given backend.
+    ///
+    /// ```rust
+    /// use cacao::defaults::{MemoryDefaults, UserDefaults, Value};
+    ///
+    /// let mut backend = MemoryDefaults::new();
+    /// backend.force("managed", Value::Bool(true));
+    ///
+    /// let defaults = UserDefaults::with_backend(backend);
+    /// assert!(defaults.is_forced_for_key("managed"));
+    /// ```
+    pub fn with_backend<B: DefaultsBackend + 'static>(backend: B) -> Self {
+        UserDefaults(Box::new(backend))
+    }
+
+    /// Returns a user defaults instance that only lives in memory. Handy for tests, and anywhere
+    /// the Objective-C runtime isn't available.
+    ///
+    /// ```rust
+    /// use cacao::defaults::{UserDefaults, Value};
+    ///
+    /// let mut defaults = UserDefaults::in_memory();
+    /// defaults.insert("test", Value::Integer(1));
+    /// assert_eq!(defaults.get("test"), Some(Value::Integer(1)));
+    /// ```
+    pub fn in_memory() -> Self {
+        UserDefaults::with_backend(MemoryDefaults::new())
+    }
+
+    /// Returns a user defaults instance that persists to the XML property list at `path`. See
+    /// `FileDefaults` for details.
+    ///
+    /// ```rust,no_run
+    /// use cacao::defaults::{UserDefaults, Value};
+    ///
+    /// let mut defaults = UserDefaults::file("settings.plist").unwrap();
+    /// defaults.insert("test", Value::string("value"));
+    /// ```
+    pub fn file<P: Into<std::path::PathBuf>>(path: P) -> Result<Self, FileDefaultsError> {
+        FileDefaults::open(path).map(UserDefaults::with_backend)
+    }
+
+    /// Returns the backend this instance stores values with.
+    pub fn backend(&self) -> &dyn DefaultsBackend {
+        &*self.0
     }
 
     /// You can use this to register defaults at the beginning of your program. Note that these are
@@ -114,11 +180,9 @@ impl UserDefaults {
     /// });
     /// ```
     pub fn register<K: AsRef<str>>(&mut self, values: HashMap<K, Value>) {
-        let dictionary = NSDictionary::from(values);
-        
-        unsafe {
-            let _: () = msg_send![&*self.0, registerDefaults:dictionary.into_inner()];
-        }
+        self.0.register(values.into_iter().map(|(key, value)| {
+            (key.as_ref().to_string(), value)
+        }).collect());
     }
 
     /// Inserts a value for the specified key. This synchronously updates the backing
@@ -131,12 +195,7 @@ impl UserDefaults {
     /// defaults.insert("test", Value::Bool(true));
     /// ```
     pub fn insert<K: AsRef<str>>(&mut self, key: K, value: Value) {
-        let key = NSString::new(key.as_ref());
-        let value: id = value.into();
-
-        unsafe {
-            let _: () = msg_send![&*self.0, setObject:value forKey:key];
-        }
+        self.0.insert(key.as_ref(), value);
     }
     
     /// Remove the default associated with the key. If the key doesn't exist, this is a noop.
@@ -148,11 +207,7 @@ impl UserDefaults {
     /// defaults.remove("test");
     /// ```
     pub fn remove<K: AsRef<str>>(&mut self, key: K) {
-        let key = NSString::new(key.as_ref());
-
-        unsafe {
-            let _: () = msg_send![&*self.0, removeObjectForKey:key.into_inner()];
-        }
+        self.0.remove(key.as_ref());
     }
 
     /// Returns a `Value` for the given key, from which you can further extract the data you
@@ -169,58 +224,84 @@ impl UserDefaults {
     /// let mut defaults = UserDefaults::standard();
     /// defaults.insert("test", Value::string("value"));
     ///
-    /// let value = defaults.get("test").unwrap().as_str().unwrap();
-    /// assert_eq!(value, "value");
+    /// let value = defaults.get("test").unwrap();
+    /// assert_eq!(value.as_str().unwrap(), "value");
     /// ```
     pub fn get<K: AsRef<str>>(&self, key: K) -> Option<Value> {
-        let key = NSString::new(key.as_ref());
-
-        let result: id = unsafe {
-            msg_send![&*self.0, objectForKey:key.into_inner()]
-        };
-
-        if result == nil {
-            return None;
-        }
-
-        if NSData::is(result) {
-            let data = NSData::wrap(result);
-            return Some(Value::Data(data.into_vec()));
-        }
-
-        if NSString::is(result) {
-            let s = NSString::wrap(result).to_str().to_string();
-            return Some(Value::String(s));
-        }
+        self.0.get(key.as_ref())
+    }
 
-        // This works, but might not be the best approach. We basically need to inspect the
-        // `NSNumber` returned and see what the wrapped encoding type is. `q` and `d` represent
-        // `NSInteger` (platform specific) and `double` (f64) respectively, but conceivably we
-        // might need others.
-        //
-        // BOOL returns as "c", which... something makes me feel weird there, but testing it seems
-        // reliable.
-        //
-        // For context: https://nshipster.com/type-encodings/
-        if NSNumber::is(result) {
-            let number = NSNumber::wrap(result);
-            
-            return match number.objc_type() {
-                "c" => Some(Value::Bool(number.as_bool())),
-                "d" => Some(Value::Float(number.as_f64())),
-                "q" => Some(Value::Integer(number.as_i64())),
+    /// Returns whether anything is stored for the given key, including registered defaults. This
+    /// can be `true` when `get` returns `None`, if what's stored can't be represented as a `Value`.
+    pub fn contains_key<K: AsRef<str>>(&self, key: K) -> bool {
+        self.0.contains_key(key.as_ref())
+    }
 
-                x => {
-                    // Debugging code that should be removed at some point.
-                    #[cfg(debug_assertions)]
-                    println!("Code: {}", x);
+    /// Stores any `Serialize` type for the specified key, as native property list types (see
+    /// `to_value` for how types are mapped). Requires the `serde` feature.
+    ///
+    /// ```rust
+    /// use serde::{Deserialize, Serialize};
+    /// use cacao::defaults::UserDefaults;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Preferences {
+    ///     font_size: u32,
+    ///     recent_files: Vec<String>
+    /// }
+    ///
+    /// let mut defaults = UserDefaults::standard();
+    /// defaults.insert_as("preferences", &Preferences {
+    ///     font_size: 13,
+    ///     recent_files: vec![]
+    /// }).unwrap();
+    ///
+    /// let preferences: Option<Preferences> = defaults.get_as("preferences").unwrap();
+    /// assert_eq!(preferences.unwrap().font_size, 13);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn insert_as<K: AsRef<str>, T: serde::Serialize + ?Sized>(&mut self, key: K, value: &T) -> Result<(), ValueError> {
+        let value = to_value(value)?;
+        self.insert(key, value);
+        Ok(())
+    }
 
-                    None
-                }
-            };
+    /// Returns the value for the specified key, deserialized into any `Deserialize` type. Returns
+    /// `Ok(None)` if nothing is stored for the key, and an error if what's stored doesn't fit `T`.
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn get_as<K: AsRef<str>, T: serde::de::DeserializeOwned>(&self, key: K) -> Result<Option<T>, ValueError> {
+        match self.get(key) {
+            Some(value) => from_value(value).map(Some),
+            None => Ok(None)
         }
+    }
 
-        None
+    /// Calls `handler` whenever the value for `key` changes - whether from this instance, another
+    /// window, an extension sharing the suite, or `defaults write`. The handler is passed the old
+    /// and new values, and stays registered until the returned `Observation` is dropped.
+    ///
+    /// Only the native backend delivers changes on the main thread. `MemoryDefaults`,
+    /// `FileDefaults` (and any other backend built on `Observers`) call handlers synchronously, on
+    /// whichever thread made the change - which may not be the main thread.
+    ///
+    /// ```rust
+    /// use cacao::defaults::{UserDefaults, Value};
+    ///
+    /// let mut defaults = UserDefaults::standard();
+    ///
+    /// let _observation = defaults.observe("font_size", |change| {
+    ///     println!("Font size went from {:?} to {:?}", change.old, change.new);
+    /// });
+    ///
+    /// defaults.insert("font_size", Value::Integer(14));
+    /// ```
+    pub fn observe<K, F>(&self, key: K, handler: F) -> Observation
+    where
+        K: AsRef<str>,
+        F: Fn(&Change) + Send + Sync + 'static
+    {
+        self.0.observe(key.as_ref(), Box::new(handler))
     }
 
     /// Returns a boolean value if the object stored for the specified key is managed by an
@@ -239,12 +320,7 @@ impl UserDefaults {
     /// assert_eq!(value, false);
     /// ```
     pub fn is_forced_for_key<K: AsRef<str>>(&self, key: K) -> bool {
-        let result: BOOL = unsafe {
-            let key = NSString::new(key.as_ref());
-            msg_send![&*self.0, objectIsForcedForKey:key.into_inner()]
-        };
-
-        to_bool(result)
+        self.0.is_forced_for_key(key.as_ref())
     }
 
     /// Blocks for any asynchronous updates to the defaults database and returns.
@@ -258,9 +334,7 @@ impl UserDefaults {
     /// defaults.insert("test", Value::string("value"));
     /// defaults.synchronize();
     /// ```
-    pub fn synchronize(&self) {
-        unsafe {
-            let _: () = msg_send![&*self.0, synchronize];
-        }
+    pub fn synchronize(&mut self) {
+        self.0.synchronize();
     }
 }
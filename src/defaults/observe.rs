@@ -0,0 +1,283 @@
+//! Change observation for `UserDefaults` keys.
+//!
+//! Backends hand out an `Observation` for each handler they register; the handler stays
+//! registered until the `Observation` is dropped. `Observers` is a small registry that pure-Rust
+//! backends (and your own) can use to track handlers and fire them when values change.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::defaults::Value;
+
+/// A change to the value of an observed key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    /// The key that changed.
+    pub key: String,
+
+    /// The value before the change, or `None` if there wasn't one.
+    pub old: Option<Value>,
+
+    /// The value after the change, or `None` if it was removed.
+    pub new: Option<Value>
+}
+
+/// The type of handler that can observe changes.
+pub type ChangeHandler = dyn Fn(&Change) + Send + Sync + 'static;
+
+/// A guard for a registered change handler. Dropping this unregisters the handler; if you want it
+/// to live as long as your program, keep this somewhere that does (e.g, your `AppDelegate`).
+#[must_use = "dropping an Observation unregisters its handler immediately"]
+pub struct Observation(Option<Box<dyn FnOnce()>>);
+
+impl Observation {
+    /// Returns a guard that calls `cancel` when dropped. Backends use this to tear down whatever
+    /// they registered.
+    pub fn new<F: FnOnce() + 'static>(cancel: F) -> Self {
+        Observation(Some(Box::new(cancel)))
+    }
+}
+
+impl fmt::Debug for Observation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observation")
+            .finish()
+    }
+}
+
+impl Drop for Observation {
+    fn drop(&mut self) {
+        if let Some(cancel) = self.0.take() {
+            cancel();
+        }
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: usize,
+    handlers: Vec<(usize, String, Arc<ChangeHandler>)>
+}
+
+/// Tracks change handlers by key.
+///
+/// Handlers are called synchronously, on whatever thread calls `notify`. The registry isn't locked
+/// while they run, so a handler is free to read defaults, or to register or drop observations.
+#[derive(Default)]
+pub struct Observers(Arc<Mutex<Registry>>);
+
+impl Observers {
+    /// Returns a new, empty registry.
+    pub fn new() -> Self {
+        Observers::default()
+    }
+
+    /// Registers `handler` for changes to `key`, until the returned `Observation` is dropped.
+    pub fn add(&self, key: &str, handler: Box<ChangeHandler>) -> Observation {
+        let id = {
+            let mut registry = self.0.lock().unwrap();
+            let id = registry.next_id;
+            registry.next_id += 1;
+            registry.handlers.push((id, key.to_string(), Arc::from(handler)));
+            id
+        };
+
+        let registry = Arc::downgrade(&self.0);
+
+        Observation::new(move || {
+            if let Some(registry) = registry.upgrade() {
+                registry.lock().unwrap().handlers.retain(|(handler_id, _, _)| *handler_id != id);
+            }
+        })
+    }
+
+    /// Returns every key that currently has at least one handler.
+    pub fn keys(&self) -> Vec<String> {
+        let registry = self.0.lock().unwrap();
+        let mut keys: Vec<String> = registry.handlers.iter().map(|(_, key, _)| key.clone()).collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Calls every handler registered for `key`, provided `old` and `new` actually differ.
+    pub fn notify(&self, key: &str, old: Option<Value>, new: Option<Value>) {
+        if old == new {
+            return;
+        }
+
+        let handlers: Vec<Arc<ChangeHandler>> = {
+            let registry = self.0.lock().unwrap();
+            registry.handlers.iter()
+                .filter(|(_, handler_key, _)| handler_key == key)
+                .map(|(_, _, handler)| handler.clone())
+                .collect()
+        };
+
+        let change = Change {
+            key: key.to_string(),
+            old: old,
+            new: new
+        };
+
+        for handler in handlers {
+            handler(&change);
+        }
+    }
+
+    /// Runs `mutate`, then notifies handlers of every observed key whose value (as returned by
+    /// `get`) changed. This saves backends from working out which keys a bulk operation - e.g,
+    /// registering defaults, or reloading from disk - actually touched.
+    pub fn track<S, G, M>(&self, store: &mut S, get: G, mutate: M)
+    where
+        G: Fn(&S, &str) -> Option<Value>,
+        M: FnOnce(&mut S)
+    {
+        let before: Vec<(String, Option<Value>)> = self.keys().into_iter().map(|key| {
+            let value = get(store, &key);
+            (key, value)
+        }).collect();
+
+        mutate(store);
+
+        for (key, old) in before {
+            let new = get(store, &key);
+            self.notify(&key, old, new);
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("keys", &self.keys())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::{Arc, Mutex};
+
+    use super::{Change, Observation, Observers};
+    use crate::defaults::Value;
+
+    /// Returns a handler that records every change it's called with, and the changes themselves.
+    fn recorder() -> (Box<dyn Fn(&Change) + Send + Sync>, Arc<Mutex<Vec<Change>>>) {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = changes.clone();
+
+        (Box::new(move |change: &Change| recorded.lock().unwrap().push(change.clone())), changes)
+    }
+
+    #[test]
+    fn notifies_handlers_for_their_key() {
+        let observers = Observers::new();
+        let (handler, changes) = recorder();
+        let _observation = observers.add("font_size", handler);
+
+        observers.notify("font_size", Some(Value::Integer(12)), Some(Value::Integer(14)));
+        observers.notify("theme", None, Some(Value::string("dark")));
+
+        assert_eq!(*changes.lock().unwrap(), vec![Change {
+            key: "font_size".to_string(),
+            old: Some(Value::Integer(12)),
+            new: Some(Value::Integer(14))
+        }]);
+    }
+
+    #[test]
+    fn skips_unchanged_values() {
+        let observers = Observers::new();
+        let (handler, changes) = recorder();
+        let _observation = observers.add("font_size", handler);
+
+        observers.notify("font_size", Some(Value::Integer(12)), Some(Value::Integer(12)));
+        observers.notify("font_size", None, None);
+
+        assert!(changes.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn dropping_an_observation_unregisters_it() {
+        let observers = Observers::new();
+        let (handler, changes) = recorder();
+        let observation = observers.add("font_size", handler);
+        assert_eq!(observers.keys(), vec!["font_size"]);
+
+        drop(observation);
+        observers.notify("font_size", None, Some(Value::Integer(14)));
+
+        assert!(observers.keys().is_empty());
+        assert!(changes.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn observations_can_outlive_their_registry() {
+        let observers = Observers::new();
+        let observation = observers.add("font_size", Box::new(|_| {}));
+
+        drop(observers);
+        drop(observation);
+    }
+
+    #[test]
+    fn handlers_can_drop_and_register_observations() {
+        thread_local! {
+            static OBSERVATIONS: RefCell<Vec<Observation>> = RefCell::new(Vec::new());
+        }
+
+        let observers = Arc::new(Observers::new());
+        let (late_handler, late_changes) = recorder();
+        let late_handler = Mutex::new(Some(late_handler));
+
+        // The first change drops this handler's own observation, and registers another one.
+        let registry = observers.clone();
+        let observation = observers.add("font_size", Box::new(move |_| {
+            let handler = late_handler.lock().unwrap().take();
+
+            OBSERVATIONS.with(|observations| {
+                let mut observations = observations.borrow_mut();
+                observations.clear();
+
+                if let Some(handler) = handler {
+                    observations.push(registry.add("font_size", handler));
+                }
+            });
+        }));
+
+        OBSERVATIONS.with(|observations| observations.borrow_mut().push(observation));
+
+        observers.notify("font_size", None, Some(Value::Integer(14)));
+        assert!(late_changes.lock().unwrap().is_empty());
+
+        observers.notify("font_size", Some(Value::Integer(14)), Some(Value::Integer(16)));
+        assert_eq!(late_changes.lock().unwrap().len(), 1);
+
+        OBSERVATIONS.with(|observations| observations.borrow_mut().clear());
+        assert!(observers.keys().is_empty());
+    }
+
+    #[test]
+    fn tracks_values_that_change() {
+        let observers = Observers::new();
+        let (handler, changes) = recorder();
+        let _observation = observers.add("count", handler);
+        let _unchanged = observers.add("name", Box::new(|_| panic!("name didn't change")));
+
+        let mut store = vec![("count", 1), ("name", 2)];
+
+        observers.track(&mut store, |store, key| {
+            store.iter().find(|(name, _)| *name == key).map(|(_, value)| Value::Integer(*value))
+        }, |store| {
+            store[0].1 = 5;
+        });
+
+        assert_eq!(*changes.lock().unwrap(), vec![Change {
+            key: "count".to_string(),
+            old: Some(Value::Integer(1)),
+            new: Some(Value::Integer(5))
+        }]);
+    }
+}
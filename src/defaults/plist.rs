@@ -0,0 +1,482 @@
+//! A small reader and writer for XML property lists, as used by `FileDefaults`.
+//!
+//! This handles exactly the subset of the format that `Value` can represent - which is to say,
+//! everything but `<uid>` (which only shows up in keyed archives). Files written by
+//! `PlistBuddy`, `defaults export`, or Xcode's plist editor can be read, and files written here
+//! can be read by all of those.
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::defaults::Value;
+
+const HEADER: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+    "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+    "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+    "<plist version=\"1.0\">\n"
+);
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Writes a dictionary of values out as an XML property list. Keys are sorted, so the same
+/// values always produce the same file.
+pub(crate) fn encode(values: &HashMap<String, Value>) -> String {
+    let mut output = String::from(HEADER);
+    write_dictionary(&mut output, values, 0);
+    output.push_str("\n</plist>\n");
+    output
+}
+
+/// Reads an XML property list whose root is a dictionary.
+pub(crate) fn decode(input: &str) -> Result<HashMap<String, Value>, String> {
+    let mut parser = Parser { tokens: tokenize(input)?, position: 0 };
+
+    match parser.next_element()? {
+        Token::Open(ref name) if name == "plist" => {},
+        token => { return Err(format!("expected <plist>, found {}", token)); }
+    }
+
+    let root = match parser.next_element()? {
+        Token::Open(ref name) if name == "dict" => parser.dictionary()?,
+        Token::Empty(ref name) if name == "dict" => HashMap::new(),
+        token => { return Err(format!("expected a root <dict>, found {}", token)); }
+    };
+
+    parser.close("plist")?;
+    Ok(root)
+}
+
+fn indent(output: &mut String, depth: usize) {
+    for _ in 0..depth {
+        output.push('\t');
+    }
+}
+
+fn escape(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            c => output.push(c)
+        }
+    }
+}
+
+fn write_dictionary(output: &mut String, values: &HashMap<String, Value>, depth: usize) {
+    if values.is_empty() {
+        indent(output, depth);
+        output.push_str("<dict/>");
+        return;
+    }
+
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+
+    indent(output, depth);
+    output.push_str("<dict>\n");
+
+    for key in keys {
+        indent(output, depth + 1);
+        output.push_str("<key>");
+        escape(output, key);
+        output.push_str("</key>\n");
+        write_value(output, &values[key], depth + 1);
+        output.push('\n');
+    }
+
+    indent(output, depth);
+    output.push_str("</dict>");
+}
+
+fn write_value(output: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Dictionary(map) => { write_dictionary(output, map, depth); return; },
+
+        Value::Array(values) if values.is_empty() => {
+            indent(output, depth);
+            output.push_str("<array/>");
+            return;
+        },
+
+        Value::Array(values) => {
+            indent(output, depth);
+            output.push_str("<array>\n");
+
+            for value in values {
+                write_value(output, value, depth + 1);
+                output.push('\n');
+            }
+
+            indent(output, depth);
+            output.push_str("</array>");
+            return;
+        },
+
+        _ => {}
+    }
+
+    indent(output, depth);
+
+    match value {
+        Value::Bool(true) => output.push_str("<true/>"),
+        Value::Bool(false) => output.push_str("<false/>"),
+
+        Value::String(s) => {
+            output.push_str("<string>");
+            escape(output, s);
+            output.push_str("</string>");
+        },
+
+        Value::Integer(i) => output.push_str(&format!("<integer>{}</integer>", i)),
+        Value::UnsignedInteger(u) => output.push_str(&format!("<integer>{}</integer>", u)),
+
+        Value::Float(f) => output.push_str(&format!("<real>{}</real>", match f {
+            f if f.is_nan() => "nan".to_string(),
+            f if f.is_infinite() && *f > 0. => "+infinity".to_string(),
+            f if f.is_infinite() => "-infinity".to_string(),
+            f => format!("{:?}", f)
+        })),
+
+        Value::Data(data) => {
+            output.push_str("<data>");
+            output.push_str(&encode_base64(data));
+            output.push_str("</data>");
+        },
+
+        Value::Date(date) => {
+            output.push_str("<date>");
+            output.push_str(&format_date(*date));
+            output.push_str("</date>");
+        },
+
+        Value::Array(_) | Value::Dictionary(_) => unreachable!()
+    }
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => output.push(BASE64[(triple >> (18 - i * 6)) as usize & 63] as char),
+                false => output.push('=')
+            }
+        }
+    }
+
+    output
+}
+
+fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input.bytes() {
+        let sextet = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => { break; },
+            c if c.is_ascii_whitespace() => { continue; },
+            c => { return Err(format!("invalid character '{}' in <data>", c as char)); }
+        };
+
+        buffer = buffer << 6 | sextet as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Formats a date as ISO 8601 in UTC, which is what property lists use. Property lists only store
+/// whole seconds, so anything finer is dropped.
+fn format_date(date: SystemTime) -> String {
+    let seconds = match date.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(error) => -(error.duration().as_secs_f64().ceil() as i64)
+    };
+
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, time / 3600, time / 60 % 60, time % 60
+    )
+}
+
+fn parse_date(input: &str) -> Result<SystemTime, String> {
+    let error = || format!("invalid <date> '{}'", input);
+    let input = input.trim();
+
+    let bytes = input.as_bytes();
+    if bytes.len() != 20 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' ||
+        bytes[13] != b':' || bytes[16] != b':' || bytes[19] != b'Z' {
+        return Err(error());
+    }
+
+    let field = |range: std::ops::Range<usize>| -> Result<i64, String> {
+        input[range].parse::<i64>().map_err(|_| error())
+    };
+
+    let days = days_from_civil(field(0..4)?, field(5..7)?, field(8..10)?);
+    let seconds = days * 86_400 + field(11..13)? * 3600 + field(14..16)? * 60 + field(17..19)?;
+
+    Ok(match seconds >= 0 {
+        true => UNIX_EPOCH + Duration::from_secs(seconds as u64),
+        false => UNIX_EPOCH - Duration::from_secs(-seconds as u64)
+    })
+}
+
+// Both of these are Howard Hinnant's algorithms for the proleptic Gregorian calendar:
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = match month <= 2 { true => year - 1, false => year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + match month > 2 { true => -3, false => 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = match mp < 10 { true => mp + 3, false => mp - 9 };
+    let year = year_of_era + era * 400 + match month <= 2 { true => 1, false => 0 };
+    (year, month, day)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Open(String),
+    Close(String),
+    Empty(String),
+    Text(String)
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Open(name) => write!(f, "<{}>", name),
+            Token::Close(name) => write!(f, "</{}>", name),
+            Token::Empty(name) => write!(f, "<{}/>", name),
+            Token::Text(text) => write!(f, "text '{}'", text.trim())
+        }
+    }
+}
+
+/// Splits a document into tags and text, dropping the prolog, doctype, comments, and attributes.
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(unescape(&rest[..end])?));
+            rest = &rest[end..];
+            continue;
+        }
+
+        let (terminator, skip) = match rest {
+            r if r.starts_with("<!--") => ("-->", true),
+            r if r.starts_with("<?") => ("?>", true),
+            r if r.starts_with("<!") => (">", true),
+            _ => (">", false)
+        };
+
+        let end = rest.find(terminator).ok_or_else(|| "unterminated tag".to_string())?;
+        let tag = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+
+        if skip {
+            continue;
+        }
+
+        let name = |tag: &str| tag.split_whitespace().next().unwrap_or("").to_string();
+
+        tokens.push(match tag {
+            t if t.starts_with('/') => Token::Close(name(&t[1..])),
+            t if t.ends_with('/') => Token::Empty(name(&t[..t.len() - 1])),
+            t => Token::Open(name(t))
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+
+        let end = rest[start..].find(';').ok_or_else(|| "unterminated entity".to_string())? + start;
+        let entity = &rest[start + 1..end];
+
+        let code = match entity {
+            "amp" => Some('&' as u32),
+            "lt" => Some('<' as u32),
+            "gt" => Some('>' as u32),
+            "quot" => Some('"' as u32),
+            "apos" => Some('\'' as u32),
+            e if e.starts_with("#x") => u32::from_str_radix(&e[2..], 16).ok(),
+            e if e.starts_with('#') => e[1..].parse::<u32>().ok(),
+            _ => None
+        };
+
+        match code.and_then(std::char::from_u32) {
+            Some(c) => output.push(c),
+            None => { return Err(format!("unknown entity '&{};'", entity)); }
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize
+}
+
+impl Parser {
+    /// Returns the next tag, skipping whitespace between elements.
+    fn next_element(&mut self) -> Result<Token, String> {
+        while let Some(token) = self.tokens.get(self.position) {
+            self.position += 1;
+
+            match token {
+                Token::Text(text) if text.trim().is_empty() => {},
+                token => { return Ok(token.clone()); }
+            }
+        }
+
+        Err("unexpected end of file".to_string())
+    }
+
+    /// Returns the text content of the current element (which may be empty), and consumes its
+    /// closing tag.
+    fn text(&mut self, element: &str) -> Result<String, String> {
+        let text = match self.tokens.get(self.position) {
+            Some(Token::Text(text)) => {
+                self.position += 1;
+                text.clone()
+            },
+
+            _ => String::new()
+        };
+
+        self.close(element)?;
+        Ok(text)
+    }
+
+    fn close(&mut self, element: &str) -> Result<(), String> {
+        match self.next_element()? {
+            Token::Close(ref name) if name == element => Ok(()),
+            token => Err(format!("expected </{}>, found {}", element, token))
+        }
+    }
+
+    fn dictionary(&mut self) -> Result<HashMap<String, Value>, String> {
+        let mut map = HashMap::new();
+
+        loop {
+            let key = match self.next_element()? {
+                Token::Close(ref name) if name == "dict" => { return Ok(map); },
+                Token::Open(ref name) if name == "key" => self.text("key")?,
+                token => { return Err(format!("expected <key>, found {}", token)); }
+            };
+
+            let token = self.next_element()?;
+            map.insert(key, self.value(token)?);
+        }
+    }
+
+    fn value(&mut self, token: Token) -> Result<Value, String> {
+        match token {
+            Token::Empty(name) => match name.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "string" => Ok(Value::String(String::new())),
+                "data" => Ok(Value::Data(Vec::new())),
+                "array" => Ok(Value::Array(Vec::new())),
+                "dict" => Ok(Value::Dictionary(HashMap::new())),
+                name => Err(format!("unexpected <{}/>", name))
+            },
+
+            Token::Open(name) => match name.as_str() {
+                "true" => self.close("true").map(|_| Value::Bool(true)),
+                "false" => self.close("false").map(|_| Value::Bool(false)),
+                "string" => self.text("string").map(Value::String),
+                "data" => decode_base64(&self.text("data")?).map(Value::Data),
+                "date" => parse_date(&self.text("date")?).map(Value::Date),
+                "dict" => self.dictionary().map(Value::Dictionary),
+
+                "integer" => {
+                    let text = self.text("integer")?;
+                    text.trim().parse::<i64>()
+                        .map(Value::Integer)
+                        .or_else(|_| text.trim().parse::<u64>().map(Value::UnsignedInteger))
+                        .map_err(|_| format!("invalid <integer> '{}'", text))
+                },
+
+                "real" => {
+                    let text = self.text("real")?;
+
+                    match text.trim() {
+                        "nan" => Ok(Value::Float(f64::NAN)),
+                        "+infinity" | "inf" => Ok(Value::Float(f64::INFINITY)),
+                        "-infinity" | "-inf" => Ok(Value::Float(f64::NEG_INFINITY)),
+                        t => t.parse::<f64>()
+                            .map(Value::Float)
+                            .map_err(|_| format!("invalid <real> '{}'", text))
+                    }
+                },
+
+                "array" => {
+                    let mut values = Vec::new();
+
+                    loop {
+                        match self.next_element()? {
+                            Token::Close(ref name) if name == "array" => { return Ok(Value::Array(values)); },
+                            token => { values.push(self.value(token)?); }
+                        }
+                    }
+                },
+
+                name => Err(format!("unsupported element <{}>", name))
+            },
+
+            token => Err(format!("expected a value, found {}", token))
+        }
+    }
+}
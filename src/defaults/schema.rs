@@ -0,0 +1,697 @@
+//! A versioned schema for preferences, layered over `UserDefaults`.
+//!
+//! A `Schema` declares the keys your app stores - their types, defaults, and any validation - along
+//! with an ordered list of migrations. Call `Schema::apply` once at launch: it runs any
+//! migrations newer than the version recorded in the store, registers the declared defaults, and
+//! returns a `MigrationReport` describing what it did.
+//!
+//! ```rust
+//! use cacao::defaults::{UserDefaults, Value, ValueKind};
+//! use cacao::defaults::schema::{Key, Schema};
+//!
+//! let schema = Schema::new(2)
+//!     .key(Key::new("font_size", ValueKind::Integer)
+//!         .default(Value::Integer(13))
+//!         .validate(|value| match value.as_i64() {
+//!             Some(size) if size >= 8 && size <= 72 => Ok(()),
+//!             _ => Err("must be between 8 and 72".to_string())
+//!         }))
+//!     .key(Key::new("show_sidebar", ValueKind::Bool).default(Value::Bool(true)))
+//!     .migration(2, "Rename fontSize to font_size", |migrator| {
+//!         migrator.rename("fontSize", "font_size");
+//!         Ok(())
+//!     });
+//!
+//! let mut defaults = UserDefaults::in_memory();
+//! defaults.insert("fontSize", Value::Integer(15));
+//!
+//! let report = schema.apply(&mut defaults).unwrap();
+//! assert_eq!(report.applied, vec![2]);
+//! assert_eq!(schema.get(&defaults, "font_size"), Ok(Value::Integer(15)));
+//! ```
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+use crate::defaults::{Change, UserDefaults, Value, ValueKind};
+
+/// The key the schema version is stored under, unless overridden with `Schema::version_key`.
+pub const DEFAULT_VERSION_KEY: &str = "CacaoSchemaVersion";
+
+/// Errors that can occur when applying a schema.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaError {
+    /// The store was written by a newer version of the schema than this one, so migrating it
+    /// would mean guessing. Nothing was changed.
+    NewerVersion {
+        stored: u32,
+        current: u32
+    },
+
+    /// The stored schema version isn't a non-negative integer.
+    InvalidVersion(Value),
+
+    /// A migration returned an error. Migrations before it were applied, and the stored version
+    /// reflects the last one that succeeded.
+    MigrationFailed {
+        version: u32,
+        message: String
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::NewerVersion { stored, current } => {
+                write!(f, "Stored preferences are at schema version {}, newer than {}", stored, current)
+            },
+
+            SchemaError::InvalidVersion(value) => write!(f, "Stored schema version is invalid: {:?}", value),
+
+            SchemaError::MigrationFailed { version, message } => {
+                write!(f, "Migration to schema version {} failed: {}", version, message)
+            }
+        }
+    }
+}
+
+impl error::Error for SchemaError {}
+
+/// Errors that can occur when reading or writing a declared key.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyError {
+    /// The key isn't declared in the schema.
+    Undeclared(String),
+
+    /// Nothing is stored for the key, and it has no default.
+    Missing(String),
+
+    /// Something is stored for the key, but it couldn't be read as a `Value`.
+    Unreadable(String),
+
+    /// The value for the key is of a different type than declared.
+    TypeMismatch {
+        key: String,
+        expected: ValueKind,
+        found: ValueKind
+    },
+
+    /// The value for the key failed the key's validation.
+    Invalid {
+        key: String,
+        message: String
+    }
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyError::Undeclared(key) => write!(f, "\"{}\" is not declared in the schema", key),
+            KeyError::Missing(key) => write!(f, "\"{}\" has no value and no default", key),
+            KeyError::Unreadable(key) => write!(f, "\"{}\" holds a value that can't be read", key),
+
+            KeyError::TypeMismatch { key, expected, found } => {
+                write!(f, "\"{}\" should be a {}, but is a {}", key, expected, found)
+            },
+
+            KeyError::Invalid { key, message } => write!(f, "\"{}\" is invalid: {}", key, message)
+        }
+    }
+}
+
+impl error::Error for KeyError {}
+
+/// The type of a key's validation check.
+type Validator = dyn Fn(&Value) -> Result<(), String>;
+
+/// The type of a migration's body.
+type MigrationFn = dyn Fn(&mut Migrator) -> Result<(), String>;
+
+/// A declared preference key.
+pub struct Key {
+    name: String,
+    kind: ValueKind,
+    default: Option<Value>,
+    validator: Option<Box<Validator>>
+}
+
+impl Key {
+    /// Declares a key holding values of `kind`.
+    pub fn new<S: Into<String>>(name: S, kind: ValueKind) -> Self {
+        Key {
+            name: name.into(),
+            kind: kind,
+            default: None,
+            validator: None
+        }
+    }
+
+    /// Sets the default for this key, which `Schema::apply` registers.
+    pub fn default(mut self, value: Value) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    /// Sets a validation check for this key. It's called with values of the declared type, and
+    /// returns a message describing the problem if the value isn't acceptable.
+    pub fn validate<F: Fn(&Value) -> Result<(), String> + 'static>(mut self, validator: F) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// The name of this key.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type of value this key holds.
+    pub fn kind(&self) -> ValueKind {
+        self.kind
+    }
+
+    /// Checks a value against this key's type and validation. Numbers are converted between
+    /// integer, unsigned integer and float where that's lossless, as neither `NSNumber` nor every
+    /// writer preserves the distinction.
+    pub fn check(&self, value: Value) -> Result<Value, KeyError> {
+        let value = match (self.kind, value) {
+            (ValueKind::Float, Value::Integer(i)) => Value::Float(i as f64),
+            (ValueKind::Float, Value::UnsignedInteger(u)) => Value::Float(u as f64),
+            (ValueKind::Integer, Value::UnsignedInteger(u)) if u <= i64::MAX as u64 => Value::Integer(u as i64),
+            (ValueKind::UnsignedInteger, Value::Integer(i)) if i >= 0 => Value::UnsignedInteger(i as u64),
+            (_, value) => value
+        };
+
+        if value.kind() != self.kind {
+            return Err(KeyError::TypeMismatch {
+                key: self.name.clone(),
+                expected: self.kind,
+                found: value.kind()
+            });
+        }
+
+        if let Some(validator) = &self.validator {
+            if let Err(message) = validator(&value) {
+                return Err(KeyError::Invalid {
+                    key: self.name.clone(),
+                    message: message
+                });
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("default", &self.default)
+            .field("validated", &self.validator.is_some())
+            .finish()
+    }
+}
+
+/// A single migration, bringing the store up to `version`.
+pub struct Migration {
+    version: u32,
+    description: String,
+    run: Box<MigrationFn>
+}
+
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migration")
+            .field("version", &self.version)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// Handed to migrations to read and modify the store. Every modification is recorded for the
+/// `MigrationReport`.
+///
+/// Migrations run before the schema's defaults are registered, so `get` only returns values that
+/// have actually been stored (or registered by something else).
+#[derive(Debug)]
+pub struct Migrator<'a> {
+    defaults: &'a mut UserDefaults,
+    changes: &'a mut Vec<Change>
+}
+
+impl<'a> Migrator<'a> {
+    /// Returns the value stored for `key`.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.defaults.get(key)
+    }
+
+    /// Stores a value for `key`.
+    pub fn insert(&mut self, key: &str, value: Value) {
+        let old = self.defaults.get(key);
+        self.defaults.insert(key, value);
+        self.record(key, old);
+    }
+
+    /// Removes the value stored for `key`.
+    pub fn remove(&mut self, key: &str) {
+        let old = self.defaults.get(key);
+        self.defaults.remove(key);
+        self.record(key, old);
+    }
+
+    /// Moves the value stored for `from` to `to`. Does nothing if there's nothing stored for
+    /// `from`; overwrites whatever's stored for `to` otherwise.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(value) = self.defaults.get(from) {
+            self.remove(from);
+            self.insert(to, value);
+        }
+    }
+
+    /// Replaces the value stored for `key` with the result of `transform`, or removes it if
+    /// `transform` returns `None`. Does nothing if there's nothing stored for `key`.
+    pub fn transform<F: FnOnce(Value) -> Option<Value>>(&mut self, key: &str, transform: F) {
+        if let Some(value) = self.defaults.get(key) {
+            match transform(value) {
+                Some(value) => self.insert(key, value),
+                None => self.remove(key)
+            }
+        }
+    }
+
+    fn record(&mut self, key: &str, old: Option<Value>) {
+        let new = self.defaults.get(key);
+
+        if old != new {
+            self.changes.push(Change {
+                key: key.to_string(),
+                old: old,
+                new: new
+            });
+        }
+    }
+}
+
+/// What `Schema::apply` did.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrationReport {
+    /// The schema version the store was at before applying.
+    pub from_version: u32,
+
+    /// The schema version the store is at now.
+    pub to_version: u32,
+
+    /// The versions of the migrations that ran, in order.
+    pub applied: Vec<u32>,
+
+    /// Every change the migrations made, in order.
+    pub changes: Vec<Change>,
+
+    /// Declared keys whose stored values don't match the schema. These are left as-is; reading
+    /// them through `Schema::get` returns the same error.
+    pub invalid: Vec<KeyError>
+}
+
+impl MigrationReport {
+    /// Returns `true` if applying the schema changed nothing and found nothing wrong.
+    pub fn is_clean(&self) -> bool {
+        self.applied.is_empty() && self.invalid.is_empty()
+    }
+}
+
+/// A declared set of preference keys, and the migrations between versions of it.
+#[derive(Debug)]
+pub struct Schema {
+    version: u32,
+    version_key: String,
+    keys: Vec<Key>,
+    migrations: Vec<Migration>
+}
+
+impl Schema {
+    /// Returns an empty schema at `version`. Stores that have never had a schema applied are
+    /// treated as being at version `0`.
+    pub fn new(version: u32) -> Self {
+        Schema {
+            version: version,
+            version_key: DEFAULT_VERSION_KEY.to_string(),
+            keys: Vec::new(),
+            migrations: Vec::new()
+        }
+    }
+
+    /// Sets the key the schema version is stored under. Defaults to `DEFAULT_VERSION_KEY`.
+    pub fn version_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.version_key = key.into();
+        self
+    }
+
+    /// Declares a key.
+    pub fn key(mut self, key: Key) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Adds a migration that brings the store up to `version`. Migrations run in version order,
+    /// regardless of the order they're added in; versions above the schema's own are ignored.
+    pub fn migration<S, F>(mut self, version: u32, description: S, run: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&mut Migrator) -> Result<(), String> + 'static
+    {
+        self.migrations.push(Migration {
+            version: version,
+            description: description.into(),
+            run: Box::new(run)
+        });
+
+        self.migrations.sort_by_key(|migration| migration.version);
+        self
+    }
+
+    /// The current version of this schema.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the declared key with the given name, if there is one.
+    pub fn declared(&self, name: &str) -> Option<&Key> {
+        self.keys.iter().find(|key| key.name == name)
+    }
+
+    /// Returns the schema version recorded in `defaults`.
+    pub fn stored_version(&self, defaults: &UserDefaults) -> Result<u32, SchemaError> {
+        match defaults.get(&self.version_key) {
+            None => Ok(0),
+            Some(value) => match value.as_u64() {
+                Some(version) if version <= u32::MAX as u64 => Ok(version as u32),
+                _ => Err(SchemaError::InvalidVersion(value))
+            }
+        }
+    }
+
+    /// Brings `defaults` up to this schema: runs any pending migrations (recording the new version
+    /// after each one), registers declared defaults, and checks every declared key.
+    ///
+    /// This is meant to be called once, at launch. Calling it again is harmless - there won't be
+    /// any migrations left to run.
+    pub fn apply(&self, defaults: &mut UserDefaults) -> Result<MigrationReport, SchemaError> {
+        let stored = self.stored_version(defaults)?;
+
+        if stored > self.version {
+            return Err(SchemaError::NewerVersion {
+                stored: stored,
+                current: self.version
+            });
+        }
+
+        let mut report = MigrationReport {
+            from_version: stored,
+            to_version: stored,
+            ..MigrationReport::default()
+        };
+
+        let pending = self.migrations.iter().filter(|migration| {
+            migration.version > stored && migration.version <= self.version
+        });
+
+        for migration in pending {
+            let mut migrator = Migrator {
+                defaults: &mut *defaults,
+                changes: &mut report.changes
+            };
+
+            if let Err(message) = (migration.run)(&mut migrator) {
+                return Err(SchemaError::MigrationFailed {
+                    version: migration.version,
+                    message: message
+                });
+            }
+
+            defaults.insert(&self.version_key, Value::Integer(migration.version as i64));
+            report.applied.push(migration.version);
+            report.to_version = migration.version;
+        }
+
+        if report.to_version != self.version {
+            defaults.insert(&self.version_key, Value::Integer(self.version as i64));
+            report.to_version = self.version;
+        }
+
+        let registered: HashMap<&str, Value> = self.keys.iter().filter_map(|key| {
+            key.default.clone().map(|value| (key.name.as_str(), value))
+        }).collect();
+
+        if !registered.is_empty() {
+            defaults.register(registered);
+        }
+
+        report.invalid = self.keys.iter().filter_map(|key| {
+            match self.get(defaults, &key.name) {
+                Err(KeyError::Missing(_)) | Ok(_) => None,
+                Err(error) => Some(error)
+            }
+        }).collect();
+
+        Ok(report)
+    }
+
+    /// Reads a declared key, checking it against the declaration. Unlike `UserDefaults::get`, this
+    /// tells you _why_ there's no usable value.
+    pub fn get(&self, defaults: &UserDefaults, name: &str) -> Result<Value, KeyError> {
+        let key = self.declared(name).ok_or_else(|| KeyError::Undeclared(name.to_string()))?;
+
+        match defaults.get(name) {
+            Some(value) => key.check(value),
+            None => match defaults.contains_key(name) {
+                true => Err(KeyError::Unreadable(name.to_string())),
+                false => Err(KeyError::Missing(name.to_string()))
+            }
+        }
+    }
+
+    /// Writes a declared key, after checking the value against the declaration.
+    pub fn set(&self, defaults: &mut UserDefaults, name: &str, value: Value) -> Result<(), KeyError> {
+        let key = self.declared(name).ok_or_else(|| KeyError::Undeclared(name.to_string()))?;
+        let value = key.check(value)?;
+        defaults.insert(name, value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use super::{Key, KeyError, Schema, SchemaError, DEFAULT_VERSION_KEY};
+    use crate::defaults::{Change, ChangeHandler, DefaultsBackend, MemoryDefaults, Observation, UserDefaults, Value, ValueKind};
+
+    /// Wraps `MemoryDefaults`, but can't read anything stored under `"opaque"` - like a native
+    /// store holding an object that isn't a property list type.
+    #[derive(Debug, Default)]
+    struct OpaqueDefaults(MemoryDefaults);
+
+    impl DefaultsBackend for OpaqueDefaults {
+        fn register(&mut self, values: HashMap<String, Value>) { self.0.register(values) }
+        fn insert(&mut self, key: &str, value: Value) { self.0.insert(key, value) }
+        fn remove(&mut self, key: &str) { self.0.remove(key) }
+        fn is_forced_for_key(&self, key: &str) -> bool { self.0.is_forced_for_key(key) }
+        fn synchronize(&mut self) { self.0.synchronize() }
+        fn observe(&self, key: &str, handler: Box<ChangeHandler>) -> Observation { self.0.observe(key, handler) }
+
+        fn get(&self, key: &str) -> Option<Value> {
+            match key {
+                "opaque" => None,
+                _ => self.0.get(key)
+            }
+        }
+
+        fn contains_key(&self, key: &str) -> bool {
+            self.0.get(key).is_some()
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::new(3)
+            .key(Key::new("font_size", ValueKind::Integer)
+                .default(Value::Integer(13))
+                .validate(|value| match value.as_i64() {
+                    Some(size) if size >= 8 && size <= 72 => Ok(()),
+                    _ => Err("must be between 8 and 72".to_string())
+                }))
+            .key(Key::new("theme", ValueKind::String).default(Value::string("system")))
+            .key(Key::new("scale", ValueKind::Float))
+            .migration(3, "Drop the legacy theme flag", |migrator| {
+                migrator.transform("dark", |value| match value.as_bool() {
+                    Some(true) => Some(Value::string("dark")),
+                    _ => None
+                });
+
+                migrator.rename("dark", "theme");
+                Ok(())
+            })
+            .migration(2, "Rename fontSize to font_size", |migrator| {
+                migrator.rename("fontSize", "font_size");
+                Ok(())
+            })
+    }
+
+    #[test]
+    fn runs_pending_migrations_in_order() {
+        let mut defaults = UserDefaults::in_memory();
+        defaults.insert("fontSize", Value::Integer(15));
+        defaults.insert("dark", Value::Bool(true));
+
+        let report = schema().apply(&mut defaults).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, 3);
+        assert_eq!(report.applied, vec![2, 3]);
+        assert!(report.invalid.is_empty());
+
+        assert_eq!(report.changes, vec![
+            Change { key: "fontSize".to_string(), old: Some(Value::Integer(15)), new: None },
+            Change { key: "font_size".to_string(), old: None, new: Some(Value::Integer(15)) },
+            Change { key: "dark".to_string(), old: Some(Value::Bool(true)), new: Some(Value::string("dark")) },
+            Change { key: "dark".to_string(), old: Some(Value::string("dark")), new: None },
+            Change { key: "theme".to_string(), old: None, new: Some(Value::string("dark")) }
+        ]);
+
+        assert_eq!(defaults.get(DEFAULT_VERSION_KEY), Some(Value::Integer(3)));
+        assert_eq!(schema().get(&defaults, "font_size"), Ok(Value::Integer(15)));
+        assert_eq!(schema().get(&defaults, "theme"), Ok(Value::string("dark")));
+    }
+
+    #[test]
+    fn migrations_run_once() {
+        let runs = Rc::new(Cell::new(0));
+        let counter = runs.clone();
+
+        let schema = Schema::new(1).migration(1, "Count", move |_| {
+            counter.set(counter.get() + 1);
+            Ok(())
+        });
+
+        let mut defaults = UserDefaults::in_memory();
+        assert_eq!(schema.apply(&mut defaults).unwrap().applied, vec![1]);
+
+        let report = schema.apply(&mut defaults).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.from_version, 1);
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn tracks_versions() {
+        let mut defaults = UserDefaults::in_memory();
+        let schema = Schema::new(4).version_key("version");
+        assert_eq!(schema.stored_version(&defaults), Ok(0));
+
+        // There's nothing to migrate, but the version is still recorded.
+        let report = schema.apply(&mut defaults).unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.to_version, 4);
+        assert_eq!(schema.stored_version(&defaults), Ok(4));
+        assert_eq!(defaults.get(DEFAULT_VERSION_KEY), None);
+
+        // Migrations past the schema's own version are left for later.
+        let schema = Schema::new(5).version_key("version").migration(6, "Future", |_| Err("ran".to_string()));
+        assert_eq!(schema.apply(&mut defaults).unwrap().to_version, 5);
+
+        assert_eq!(Schema::new(2).version_key("version").apply(&mut defaults), Err(SchemaError::NewerVersion {
+            stored: 5,
+            current: 2
+        }));
+
+        defaults.insert("version", Value::string("five"));
+        assert_eq!(schema.stored_version(&defaults), Err(SchemaError::InvalidVersion(Value::string("five"))));
+
+        defaults.insert("version", Value::Integer(-1));
+        assert_eq!(schema.stored_version(&defaults), Err(SchemaError::InvalidVersion(Value::Integer(-1))));
+    }
+
+    #[test]
+    fn stops_at_a_failed_migration() {
+        let schema = Schema::new(3)
+            .migration(1, "Works", |migrator| {
+                migrator.insert("one", Value::Bool(true));
+                Ok(())
+            })
+            .migration(2, "Fails", |_| Err("disk on fire".to_string()))
+            .migration(3, "Never runs", |migrator| {
+                migrator.insert("three", Value::Bool(true));
+                Ok(())
+            });
+
+        let mut defaults = UserDefaults::in_memory();
+        assert_eq!(schema.apply(&mut defaults), Err(SchemaError::MigrationFailed {
+            version: 2,
+            message: "disk on fire".to_string()
+        }));
+
+        assert_eq!(schema.stored_version(&defaults), Ok(1));
+        assert_eq!(defaults.get("one"), Some(Value::Bool(true)));
+        assert_eq!(defaults.get("three"), None);
+    }
+
+    #[test]
+    fn registers_defaults_and_reports_invalid_keys() {
+        let mut defaults = UserDefaults::in_memory();
+        defaults.insert("font_size", Value::Integer(100));
+        defaults.insert("theme", Value::Integer(1));
+
+        let report = schema().apply(&mut defaults).unwrap();
+        assert!(!report.is_clean());
+
+        assert_eq!(report.invalid, vec![
+            KeyError::Invalid { key: "font_size".to_string(), message: "must be between 8 and 72".to_string() },
+            KeyError::TypeMismatch { key: "theme".to_string(), expected: ValueKind::String, found: ValueKind::Integer }
+        ]);
+
+        defaults.remove("font_size");
+        assert_eq!(schema().get(&defaults, "font_size"), Ok(Value::Integer(13)));
+    }
+
+    #[test]
+    fn reports_typed_read_errors() {
+        let schema = schema().key(Key::new("opaque", ValueKind::Data));
+        let mut defaults = UserDefaults::with_backend(OpaqueDefaults::default());
+        schema.apply(&mut defaults).unwrap();
+
+        assert_eq!(schema.get(&defaults, "nope"), Err(KeyError::Undeclared("nope".to_string())));
+        assert_eq!(schema.get(&defaults, "scale"), Err(KeyError::Missing("scale".to_string())));
+
+        defaults.insert("opaque", Value::Data(vec![1]));
+        assert_eq!(schema.get(&defaults, "opaque"), Err(KeyError::Unreadable("opaque".to_string())));
+
+        // Numbers convert where that's lossless.
+        defaults.insert("scale", Value::Integer(2));
+        assert_eq!(schema.get(&defaults, "scale"), Ok(Value::Float(2.)));
+    }
+
+    #[test]
+    fn checks_writes() {
+        let schema = schema();
+        let mut defaults = UserDefaults::in_memory();
+
+        assert_eq!(schema.set(&mut defaults, "font_size", Value::Integer(20)), Ok(()));
+        assert_eq!(defaults.get("font_size"), Some(Value::Integer(20)));
+
+        assert_eq!(schema.set(&mut defaults, "font_size", Value::Integer(2)), Err(KeyError::Invalid {
+            key: "font_size".to_string(),
+            message: "must be between 8 and 72".to_string()
+        }));
+
+        assert_eq!(schema.set(&mut defaults, "font_size", Value::string("big")), Err(KeyError::TypeMismatch {
+            key: "font_size".to_string(),
+            expected: ValueKind::Integer,
+            found: ValueKind::String
+        }));
+
+        assert_eq!(schema.set(&mut defaults, "nope", Value::Bool(true)), Err(KeyError::Undeclared("nope".to_string())));
+        assert_eq!(defaults.get("font_size"), Some(Value::Integer(20)));
+    }
+}
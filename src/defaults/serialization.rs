@@ -0,0 +1,823 @@
+//! Maps Rust types onto `Value`s (and back) using `serde`, so structured settings can be stored as
+//! native property list types rather than hand-encoded bytes.
+//!
+//! Structs and maps become `Value::Dictionary`, sequences and tuples become `Value::Array`, and
+//! primitives map onto their obvious counterparts. Property lists have no notion of `nil`, so
+//! `None` fields are left out of dictionaries entirely, and come back as `None` when
+//! deserializing. Enums follow `serde_json`'s externally tagged layout: unit variants are strings,
+//! and everything else is a single-entry dictionary keyed by the variant name.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use cacao::defaults::{from_value, to_value, Value};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Window {
+//!     width: u32,
+//!     height: u32,
+//!     title: Option<String>
+//! }
+//!
+//! let window = Window { width: 800, height: 600, title: None };
+//! let value = to_value(&window).unwrap();
+//! assert_eq!(value.as_dictionary().unwrap().get("width"), Some(&Value::Integer(800)));
+//!
+//! let back: Window = from_value(value).unwrap();
+//! assert_eq!(back, window);
+//! ```
+//!
+//! `SystemTime` has no serde representation of its own that maps onto a date, so annotate date
+//! fields with `#[serde(with = "cacao::defaults::date")]` to store them as `Value::Date`.
+//!
+//! This module requires the `serde` feature.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serializer};
+
+use crate::defaults::Value;
+
+/// The newtype struct name used to mark dates, so that our serializer can spot them.
+const DATE_TOKEN: &str = "$cacao::defaults::Date";
+
+/// Errors that can occur when converting between Rust types and `Value`s.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueError {
+    /// The type can't be represented as a property list (e.g, `None` on its own, or inside of
+    /// a sequence).
+    Unsupported(&'static str),
+
+    /// A dictionary key didn't serialize to a string (or a number, which is converted).
+    KeyMustBeString,
+
+    /// A custom error from a `Serialize` or `Deserialize` implementation, including type
+    /// mismatches when deserializing.
+    Message(String)
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueError::Unsupported(kind) => write!(f, "{} can't be stored in user defaults", kind),
+            ValueError::KeyMustBeString => write!(f, "Dictionary keys must be strings"),
+            ValueError::Message(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl error::Error for ValueError {}
+
+impl ser::Error for ValueError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        ValueError::Message(message.to_string())
+    }
+}
+
+impl de::Error for ValueError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        ValueError::Message(message.to_string())
+    }
+}
+
+/// Converts any `Serialize` type into a `Value`.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, ValueError> {
+    match value.serialize(ValueSerializer)? {
+        Some(value) => Ok(value),
+        None => Err(ValueError::Unsupported("A nil value"))
+    }
+}
+
+/// Converts a `Value` into any `Deserialize` type.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, ValueError> {
+    T::deserialize(value)
+}
+
+/// Seconds since the Unix epoch, negative for dates before it.
+fn seconds_since_epoch(date: SystemTime) -> f64 {
+    match date.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs_f64(),
+        Err(error) => -error.duration().as_secs_f64()
+    }
+}
+
+/// The inverse of `seconds_since_epoch`.
+fn date_from_seconds(seconds: f64) -> SystemTime {
+    match seconds >= 0. {
+        true => UNIX_EPOCH + Duration::from_secs_f64(seconds),
+        false => UNIX_EPOCH - Duration::from_secs_f64(-seconds)
+    }
+}
+
+/// Serializes and deserializes `SystemTime` fields as `Value::Date`, for use with
+/// `#[serde(with = "cacao::defaults::date")]`.
+///
+/// With other formats (e.g, JSON), dates are written as seconds since the Unix epoch.
+pub mod date {
+    use std::time::SystemTime;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{date_from_seconds, seconds_since_epoch, DATE_TOKEN};
+
+    /// Serializes a `SystemTime` as a date.
+    pub fn serialize<S: Serializer>(date: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(DATE_TOKEN, &seconds_since_epoch(*date))
+    }
+
+    /// Deserializes a `SystemTime` from a date (or a number of seconds since the Unix epoch).
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        f64::deserialize(deserializer).map(date_from_seconds)
+    }
+}
+
+/// Turns Rust values into `Value`s. `Ok(None)` means "nothing to store", which containers use to
+/// skip entries.
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeDictionary;
+    type SerializeStruct = SerializeDictionary;
+    type SerializeStructVariant = SerializeDictionary;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Bool(v))) }
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v as i64))) }
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v as i64))) }
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v as i64))) }
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v))) }
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v as i64))) }
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v as i64))) }
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Integer(v as i64))) }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        match v <= i64::MAX as u64 {
+            true => Ok(Some(Value::Integer(v as i64))),
+            false => Ok(Some(Value::UnsignedInteger(v)))
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Float(v as f64))) }
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Float(v))) }
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::String(v.to_string()))) }
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::String(v.to_string()))) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> { Ok(Some(Value::Data(v.to_vec()))) }
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> { Ok(None) }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> { Err(ValueError::Unsupported("A unit value")) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> { Err(ValueError::Unsupported("A unit struct")) }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(Value::String(variant.to_string())))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value = value.serialize(self)?;
+
+        match (name, value) {
+            (DATE_TOKEN, Some(Value::Float(seconds))) => Ok(Some(Value::Date(date_from_seconds(seconds)))),
+            (_, value) => Ok(value)
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T
+    ) -> Result<Self::Ok, Self::Error> {
+        let mut map = HashMap::new();
+
+        if let Some(value) = value.serialize(self)? {
+            map.insert(variant.to_string(), value);
+        }
+
+        Ok(Some(Value::Dictionary(map)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeArray { variant: None, values: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeArray { variant: Some(variant), values: Vec::with_capacity(len) })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeDictionary { variant: None, map: HashMap::new(), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeDictionary { variant: Some(variant), map: HashMap::new(), key: None })
+    }
+}
+
+/// Wraps `value` in a single-entry dictionary keyed by `variant`, if there is one.
+fn tag(variant: Option<&'static str>, value: Value) -> Value {
+    match variant {
+        Some(variant) => {
+            let mut map = HashMap::new();
+            map.insert(variant.to_string(), value);
+            Value::Dictionary(map)
+        },
+
+        None => value
+    }
+}
+
+/// Collects sequence elements into a `Value::Array`.
+struct SerializeArray {
+    variant: Option<&'static str>,
+    values: Vec<Value>
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        match value.serialize(ValueSerializer)? {
+            Some(value) => {
+                self.values.push(value);
+                Ok(())
+            },
+
+            None => Err(ValueError::Unsupported("A nil value in an array"))
+        }
+    }
+
+    fn finish(self) -> Result<Option<Value>, ValueError> {
+        Ok(Some(tag(self.variant, Value::Array(self.values))))
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> { self.push(value) }
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> { self.push(value) }
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> { self.push(value) }
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> { self.push(value) }
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+/// Collects map entries and struct fields into a `Value::Dictionary`.
+struct SerializeDictionary {
+    variant: Option<&'static str>,
+    map: HashMap<String, Value>,
+    key: Option<String>
+}
+
+impl SerializeDictionary {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), ValueError> {
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.map.insert(key, value);
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<Value>, ValueError> {
+        Ok(Some(tag(self.variant, Value::Dictionary(self.map))))
+    }
+}
+
+impl ser::SerializeMap for SerializeDictionary {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(match key.serialize(ValueSerializer)? {
+            Some(Value::String(key)) => key,
+            Some(Value::Integer(key)) => key.to_string(),
+            Some(Value::UnsignedInteger(key)) => key.to_string(),
+            _ => { return Err(ValueError::KeyMustBeString); }
+        });
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        match self.key.take() {
+            Some(key) => self.insert(key, value),
+            None => Err(ValueError::Message("serialize_value called before serialize_key".to_string()))
+        }
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+impl ser::SerializeStruct for SerializeDictionary {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+impl ser::SerializeStructVariant for SerializeDictionary {
+    type Ok = Option<Value>;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> { self.finish() }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Float(v) => visitor.visit_f64(v),
+            Value::Integer(v) => visitor.visit_i64(v),
+            Value::UnsignedInteger(v) => visitor.visit_u64(v),
+            Value::Data(v) => visitor.visit_byte_buf(v),
+            Value::Date(v) => visitor.visit_f64(seconds_since_epoch(v)),
+            Value::Array(v) => visitor.visit_seq(de::value::SeqDeserializer::new(v.into_iter())),
+            Value::Dictionary(v) => {
+                let entries = v.into_iter().map(|(key, value)| (KeyDeserializer(key), value));
+                visitor.visit_map(de::value::MapDeserializer::new(entries))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+
+            Value::Dictionary(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("map has one entry");
+                visitor.visit_enum(EnumDeserializer { variant: variant, value: value })
+            },
+
+            _ => Err(de::Error::custom("expected a string or a single-entry dictionary for an enum"))
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Deserializes dictionary keys. Keys are always strings, but maps keyed by numbers serialize
+/// their keys as strings - so they're parsed back here when a number is asked for.
+struct KeyDeserializer(String);
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.parse() {
+                    Ok(number) => visitor.$visit(number),
+                    Err(_) => visitor.visit_string(self.0)
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for KeyDeserializer {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16, deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64, deserialize_u8 => visit_u8, deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32, deserialize_u64 => visit_u64
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for KeyDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Deserializes an externally tagged enum variant with data.
+struct EnumDeserializer {
+    variant: String,
+    value: Value
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = ValueError;
+    type Variant = Value;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Value {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+}
+
+impl Serialize for Value {
+    /// Serializes a `Value` into another format (e.g, JSON). Dates are written as seconds since
+    /// the Unix epoch.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Float(v) => serializer.serialize_f64(*v),
+            Value::Integer(v) => serializer.serialize_i64(*v),
+            Value::UnsignedInteger(v) => serializer.serialize_u64(*v),
+            Value::Data(v) => serializer.serialize_bytes(v),
+            Value::Date(v) => date::serialize(v, serializer),
+            Value::Array(v) => v.serialize(serializer),
+            Value::Dictionary(v) => v.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    /// Deserializes a `Value` from another format (e.g, JSON). Integers that fit in an `i64` become
+    /// `Value::Integer`, and larger ones `Value::UnsignedInteger`; `null` isn't supported, as property lists can't store it.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a property list value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> { Ok(Value::Bool(v)) }
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> { Ok(Value::Integer(v)) }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        match v <= i64::MAX as u64 {
+            true => Ok(Value::Integer(v as i64)),
+            false => Ok(Value::UnsignedInteger(v))
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> { Ok(Value::Float(v)) }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> { Ok(Value::String(v.to_string())) }
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> { Ok(Value::String(v)) }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Value, E> { Ok(Value::Data(v.to_vec())) }
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Value, E> { Ok(Value::Data(v)) }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::new();
+
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let mut map = HashMap::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+
+        Ok(Value::Dictionary(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{from_value, to_value, ValueError};
+    use crate::defaults::Value;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Frame {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    enum Mode {
+        Compact,
+        Split(f64),
+        Custom { columns: u8 }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Workspace {
+        name: String,
+        frame: Frame,
+        recent: Vec<String>,
+        zoom: BTreeMap<u32, f64>,
+        modes: Vec<Mode>,
+        subtitle: Option<String>,
+        pinned: Option<bool>,
+        thumbnail: bytes::Bytes,
+
+        #[serde(with = "crate::defaults::date")]
+        opened: SystemTime
+    }
+
+    /// Bytes serialize as a sequence of numbers by default; this wrapper asks for `Value::Data`.
+    mod bytes {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Bytes(pub Vec<u8>);
+
+        impl Serialize for Bytes {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Bytes {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct BytesVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+                    type Value = Bytes;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        write!(f, "bytes")
+                    }
+
+                    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Bytes, E> {
+                        Ok(Bytes(bytes))
+                    }
+                }
+
+                deserializer.deserialize_byte_buf(BytesVisitor)
+            }
+        }
+    }
+
+    fn workspace() -> Workspace {
+        let mut zoom = BTreeMap::new();
+        zoom.insert(1, 1.5);
+        zoom.insert(2, 0.75);
+
+        Workspace {
+            name: "Main".to_string(),
+            frame: Frame { x: -20, y: 40, width: 1280, height: 800 },
+            recent: vec!["a.txt".to_string(), "b.txt".to_string()],
+            zoom: zoom,
+            modes: vec![Mode::Compact, Mode::Split(0.5), Mode::Custom { columns: 3 }],
+            subtitle: None,
+            pinned: Some(true),
+            thumbnail: bytes::Bytes(vec![0, 1, 254, 255]),
+            opened: UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+        }
+    }
+
+    fn dictionary(value: &Value) -> &HashMap<String, Value> {
+        value.as_dictionary().expect("Expected a dictionary")
+    }
+
+    #[test]
+    fn round_trips_nested_structs() {
+        let workspace = workspace();
+        let value = to_value(&workspace).unwrap();
+        let map = dictionary(&value);
+
+        assert_eq!(map["name"], Value::string("Main"));
+        assert_eq!(dictionary(&map["frame"])["x"], Value::Integer(-20));
+        assert_eq!(map["pinned"], Value::Bool(true));
+        assert_eq!(map["thumbnail"], Value::Data(vec![0, 1, 254, 255]));
+        assert_eq!(map["opened"], Value::Date(UNIX_EPOCH + Duration::from_secs(1_600_000_000)));
+
+        // `None` is left out, rather than stored as anything.
+        assert!(!map.contains_key("subtitle"));
+
+        let back: Workspace = from_value(value).unwrap();
+        assert_eq!(back, workspace);
+    }
+
+    #[test]
+    fn round_trips_sequences_and_maps() {
+        let value = to_value(&vec![vec![1, 2], vec![3]]).unwrap();
+        assert_eq!(value, Value::Array(vec![
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            Value::Array(vec![Value::Integer(3)])
+        ]));
+        assert_eq!(from_value::<Vec<Vec<i32>>>(value).unwrap(), vec![vec![1, 2], vec![3]]);
+
+        // Numeric keys are stored as strings, and parsed back.
+        let mut zoom = HashMap::new();
+        zoom.insert(10u64, "ten".to_string());
+        let value = to_value(&zoom).unwrap();
+        assert_eq!(dictionary(&value)["10"], Value::string("ten"));
+        assert_eq!(from_value::<HashMap<u64, String>>(value).unwrap(), zoom);
+
+        let tuple = ("point", 1.5, true);
+        assert_eq!(from_value::<(String, f64, bool)>(to_value(&tuple).unwrap()).unwrap(), ("point".to_string(), 1.5, true));
+    }
+
+    #[test]
+    fn round_trips_enums() {
+        assert_eq!(to_value(&Mode::Compact).unwrap(), Value::string("Compact"));
+
+        let split = to_value(&Mode::Split(0.25)).unwrap();
+        assert_eq!(dictionary(&split)["Split"], Value::Float(0.25));
+
+        for mode in vec![Mode::Compact, Mode::Split(0.25), Mode::Custom { columns: 2 }] {
+            assert_eq!(from_value::<Mode>(to_value(&mode).unwrap()).unwrap(), mode);
+        }
+    }
+
+    #[test]
+    fn round_trips_options() {
+        assert_eq!(to_value(&Some(4)).unwrap(), Value::Integer(4));
+        assert_eq!(from_value::<Option<i32>>(Value::Integer(4)).unwrap(), Some(4));
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Optional {
+            value: Option<i32>
+        }
+
+        let value = to_value(&Optional { value: None }).unwrap();
+        assert_eq!(value, Value::Dictionary(HashMap::new()));
+        assert_eq!(from_value::<Optional>(value).unwrap(), Optional { value: None });
+    }
+
+    #[test]
+    fn round_trips_dates() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Dated {
+            #[serde(with = "crate::defaults::date")]
+            date: SystemTime
+        }
+
+        for date in vec![UNIX_EPOCH, UNIX_EPOCH + Duration::from_millis(1_500), UNIX_EPOCH - Duration::from_secs(86_400)] {
+            let value = to_value(&Dated { date: date }).unwrap();
+            assert_eq!(dictionary(&value)["date"], Value::Date(date));
+            assert_eq!(from_value::<Dated>(value).unwrap(), Dated { date: date });
+        }
+    }
+
+    #[test]
+    fn large_unsigned_integers_stay_unsigned() {
+        assert_eq!(to_value(&7u64).unwrap(), Value::Integer(7));
+        assert_eq!(to_value(&u64::MAX).unwrap(), Value::UnsignedInteger(u64::MAX));
+        assert_eq!(from_value::<u64>(Value::UnsignedInteger(u64::MAX)).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn rejects_unsupported_types() {
+        assert_eq!(to_value(&()), Err(ValueError::Unsupported("A unit value")));
+        assert_eq!(to_value(&None::<i32>), Err(ValueError::Unsupported("A nil value")));
+        assert_eq!(to_value(&vec![Some(1), None]), Err(ValueError::Unsupported("A nil value in an array")));
+
+        #[derive(Serialize)]
+        struct Unit;
+        assert_eq!(to_value(&Unit), Err(ValueError::Unsupported("A unit struct")));
+
+        let mut map = HashMap::new();
+        map.insert(vec![1], 1);
+        assert_eq!(to_value(&map), Err(ValueError::KeyMustBeString));
+
+        match from_value::<Frame>(Value::string("nope")) {
+            Err(ValueError::Message(_)) => {},
+            other => panic!("Expected a type mismatch, got {:?}", other)
+        }
+    }
+}
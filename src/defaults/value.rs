@@ -1,6 +1,10 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::foundation::{id, NSData, NSDictionary, NSString, NSNumber};
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, nil, to_bool, BOOL, NSArray, NSData, NSDictionary, NSString, NSNumber, Number};
 
 /// Represents a Value that can be stored or queried with `UserDefaults`.
 ///
@@ -21,9 +25,70 @@ pub enum Value {
     /// Represents an Integer (`i64`) value.
     Integer(i64),
 
+    /// Represents an unsigned Integer (`u64`) value. Stored numbers only come back as this if they
+    /// were stored as an unsigned 64-bit integer _and_ don't fit in an `i64` - `NSNumber` doesn't
+    /// otherwise keep the distinction.
+    UnsignedInteger(u64),
+
     /// Represents Data (bytes). You can use this to store arbitrary things that aren't supported
     /// above. You're responsible for moving things back and forth to the necessary types.
-    Data(Vec<u8>)
+    Data(Vec<u8>),
+
+    /// Represents a Date, stored as an `NSDate`.
+    Date(SystemTime),
+
+    /// Represents an Array of values, stored as an `NSArray`.
+    Array(Vec<Value>),
+
+    /// Represents a Dictionary of values keyed by strings, stored as an `NSDictionary`.
+    Dictionary(HashMap<String, Value>)
+}
+
+/// The type of a `Value`, without the value itself. Handy for describing what a key should hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    /// A `Value::Bool`.
+    Bool,
+
+    /// A `Value::String`.
+    String,
+
+    /// A `Value::Float`.
+    Float,
+
+    /// A `Value::Integer`.
+    Integer,
+
+    /// A `Value::UnsignedInteger`.
+    UnsignedInteger,
+
+    /// A `Value::Data`.
+    Data,
+
+    /// A `Value::Date`.
+    Date,
+
+    /// A `Value::Array`.
+    Array,
+
+    /// A `Value::Dictionary`.
+    Dictionary
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            ValueKind::Bool => "bool",
+            ValueKind::String => "string",
+            ValueKind::Float => "float",
+            ValueKind::Integer => "integer",
+            ValueKind::UnsignedInteger => "unsigned integer",
+            ValueKind::Data => "data",
+            ValueKind::Date => "date",
+            ValueKind::Array => "array",
+            ValueKind::Dictionary => "dictionary"
+        })
+    }
 }
 
 impl Value {
@@ -32,6 +97,21 @@ impl Value {
         Value::String(value.into())
     }
     
+    /// Returns the type of this value.
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Bool(_) => ValueKind::Bool,
+            Value::String(_) => ValueKind::String,
+            Value::Float(_) => ValueKind::Float,
+            Value::Integer(_) => ValueKind::Integer,
+            Value::UnsignedInteger(_) => ValueKind::UnsignedInteger,
+            Value::Data(_) => ValueKind::Data,
+            Value::Date(_) => ValueKind::Date,
+            Value::Array(_) => ValueKind::Array,
+            Value::Dictionary(_) => ValueKind::Dictionary
+        }
+    }
+
     /// Returns `true` if the value is a boolean value. Returns `false` otherwise.
     pub fn is_boolean(&self) -> bool {
         match self {
@@ -80,10 +160,30 @@ impl Value {
         }
     }
 
-    /// If this is a int, returns it (`i64`). Returns `None` otherwise.
+    /// If this is a int, returns it (`i64`). Unsigned ints that fit are returned too. Returns
+    /// `None` otherwise.
     pub fn as_i64(&self) -> Option<i64> {
         match self {
             Value::Integer(i) => Some(*i as i64),
+            Value::UnsignedInteger(u) if *u <= i64::MAX as u64 => Some(*u as i64),
+            _ => None
+        }
+    }
+
+    /// Returns `true` if the value is an unsigned int. Returns `false` otherwise.
+    pub fn is_unsigned_integer(&self) -> bool {
+        match self {
+            Value::UnsignedInteger(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is an unsigned int (or a non-negative int), returns it (`u64`). Returns `None`
+    /// otherwise.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::UnsignedInteger(u) => Some(*u),
+            Value::Integer(i) if *i >= 0 => Some(*i as u64),
             _ => None
         }
     }
@@ -128,6 +228,127 @@ impl Value {
             _ => None
         }
     }
+
+    /// Returns `true` if the value is a date. Returns `false` otherwise.
+    pub fn is_date(&self) -> bool {
+        match self {
+            Value::Date(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is a date, returns it. Returns `None` otherwise.
+    pub fn as_date(&self) -> Option<SystemTime> {
+        match self {
+            Value::Date(date) => Some(*date),
+            _ => None
+        }
+    }
+
+    /// Returns `true` if the value is an array. Returns `false` otherwise.
+    pub fn is_array(&self) -> bool {
+        match self {
+            Value::Array(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is an array, returns it (`&[Value]`). Returns `None` otherwise.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None
+        }
+    }
+
+    /// Returns `true` if the value is a dictionary. Returns `false` otherwise.
+    pub fn is_dictionary(&self) -> bool {
+        match self {
+            Value::Dictionary(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is a dictionary, returns it. Returns `None` otherwise.
+    pub fn as_dictionary(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Dictionary(map) => Some(map),
+            _ => None
+        }
+    }
+
+    /// Converts an object vended by `NSUserDefaults` into a `Value`. Returns `None` for `nil`, and
+    /// for types that can't be represented (which shouldn't happen with property list types) -
+    /// including arrays and dictionaries holding any, as dropping those entries would lose data
+    /// the next time the collection is written back.
+    pub(crate) fn from_objc(object: id) -> Option<Value> {
+        if object == nil {
+            return None;
+        }
+
+        if NSData::is(object) {
+            let data = NSData::wrap(object);
+            return Some(Value::Data(data.into_vec()));
+        }
+
+        if NSString::is(object) {
+            let s = NSString::wrap(object).to_str().to_string();
+            return Some(Value::String(s));
+        }
+
+        // `NSNumber` reports what it's holding via `objCType`; see `NumberType` for the full
+        // table. Booleans are identified by their class rather than their encoding, as `BOOL` is
+        // `c` on x86_64 and `B` on arm64.
+        //
+        // For context: https://nshipster.com/type-encodings/
+        if NSNumber::is(object) {
+            return NSNumber::wrap(object).value().map(Value::from);
+        }
+
+        if is_kind_of(object, class!(NSDate)) {
+            let interval: f64 = unsafe { msg_send![object, timeIntervalSince1970] };
+
+            return Some(Value::Date(match interval >= 0. {
+                true => UNIX_EPOCH + Duration::from_secs_f64(interval),
+                false => UNIX_EPOCH - Duration::from_secs_f64(-interval)
+            }));
+        }
+
+        if is_kind_of(object, class!(NSArray)) {
+            let array = NSArray::wrap(object);
+
+            return match array.count() {
+                0 => Some(Value::Array(Vec::new())),
+                _ => array.map(Value::from_objc).into_iter().collect::<Option<Vec<Value>>>().map(Value::Array)
+            };
+        }
+
+        if is_kind_of(object, class!(NSDictionary)) {
+            let keys = NSArray::wrap(unsafe { msg_send![object, allKeys] });
+            let mut map = HashMap::new();
+
+            if keys.count() > 0 {
+                for key in keys.map(|key| key) {
+                    if !NSString::is(key) {
+                        return None;
+                    }
+
+                    let value: id = unsafe { msg_send![object, objectForKey:key] };
+                    map.insert(NSString::wrap(key).to_string(), Value::from_objc(value)?);
+                }
+            }
+
+            return Some(Value::Dictionary(map));
+        }
+
+        None
+    }
+}
+
+/// Checks whether an object is an instance of a class (or one of its subclasses).
+fn is_kind_of(object: id, class: &objc::runtime::Class) -> bool {
+    let result: BOOL = unsafe { msg_send![object, isKindOfClass:class] };
+    to_bool(result)
 }
 
 impl From<Value> for id {
@@ -140,7 +361,40 @@ impl From<Value> for id {
             Value::String(s) => NSString::new(&s).into_inner(),
             Value::Float(f) => NSNumber::float(f).into_inner(),
             Value::Integer(i) => NSNumber::integer(i).into_inner(),
-            Value::Data(data) => NSData::new(data).into_inner()
+            Value::UnsignedInteger(u) => NSNumber::unsigned_integer(u).into_inner(),
+            Value::Data(data) => NSData::new(data).into_inner(),
+
+            Value::Date(date) => {
+                let interval = match date.duration_since(UNIX_EPOCH) {
+                    Ok(duration) => duration.as_secs_f64(),
+                    Err(error) => -error.duration().as_secs_f64()
+                };
+
+                unsafe { msg_send![class!(NSDate), dateWithTimeIntervalSince1970:interval] }
+            },
+
+            Value::Array(values) => {
+                let objects: Vec<id> = values.into_iter().map(|value| value.into()).collect();
+                NSArray::new(&objects).into_inner()
+            },
+
+            Value::Dictionary(map) => NSDictionary::from(map).into_inner()
+        }
+    }
+}
+
+impl From<Number> for Value {
+    /// Maps a decoded `NSNumber` onto a `Value`. Single-precision floats are widened, which is
+    /// lossless. Unsigned integers that fit in an `i64` become `Value::Integer`, so a value reads
+    /// back the same however it happened to be stored.
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Bool(b) => Value::Bool(b),
+            Number::Integer(i) => Value::Integer(i),
+            Number::UnsignedInteger(u) if u <= i64::MAX as u64 => Value::Integer(u as i64),
+            Number::UnsignedInteger(u) => Value::UnsignedInteger(u),
+            Number::Float(f) => Value::Float(f as f64),
+            Number::Double(d) => Value::Float(d)
         }
     }
 }
@@ -161,3 +415,88 @@ where
         dictionary
     }
 }
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use objc::{class, msg_send, sel, sel_impl};
+
+    use super::{Value, ValueKind};
+    use crate::foundation::{id, NSArray, NSDictionary, NSNumber, NSString, Number};
+
+    /// Sends a value through its Objective-C counterpart and back.
+    fn round_trip(value: Value) -> Option<Value> {
+        let object: id = value.into();
+        Value::from_objc(object)
+    }
+
+    #[test]
+    fn reports_kinds() {
+        assert_eq!(Value::Bool(true).kind(), ValueKind::Bool);
+        assert_eq!(Value::string("a").kind(), ValueKind::String);
+        assert_eq!(Value::Data(vec![]).kind(), ValueKind::Data);
+        assert_eq!(Value::Date(UNIX_EPOCH).kind(), ValueKind::Date);
+        assert_eq!(Value::Array(vec![]).kind(), ValueKind::Array);
+        assert_eq!(ValueKind::UnsignedInteger.to_string(), "unsigned integer");
+    }
+
+    #[test]
+    fn converts_between_integer_types() {
+        assert_eq!(Value::Integer(-1).as_i64(), Some(-1));
+        assert_eq!(Value::Integer(-1).as_u64(), None);
+        assert_eq!(Value::Integer(7).as_u64(), Some(7));
+        assert_eq!(Value::UnsignedInteger(7).as_i64(), Some(7));
+        assert_eq!(Value::UnsignedInteger(u64::MAX).as_i64(), None);
+        assert_eq!(Value::Float(1.).as_i64(), None);
+    }
+
+    #[test]
+    fn maps_numbers() {
+        let table = [
+            (Number::Bool(true), Value::Bool(true)),
+            (Number::Integer(-3), Value::Integer(-3)),
+            (Number::UnsignedInteger(7), Value::Integer(7)),
+            (Number::UnsignedInteger(i64::MAX as u64), Value::Integer(i64::MAX)),
+            (Number::UnsignedInteger(u64::MAX), Value::UnsignedInteger(u64::MAX)),
+            (Number::Float(0.5), Value::Float(0.5)),
+            (Number::Double(0.1), Value::Float(0.1))
+        ];
+
+        for (number, value) in table.iter() {
+            assert_eq!(Value::from(*number), *value, "{:?}", number);
+        }
+    }
+
+    /// The headless `NSNumber` doesn't keep unsigned integers apart, so those are covered by
+    /// `maps_numbers` instead.
+    #[test]
+    fn round_trips_through_objective_c() {
+        for value in vec![
+            Value::Bool(false),
+            Value::string("Hello"),
+            Value::Integer(-42),
+            Value::Float(2.5),
+            Value::Array(vec![Value::Integer(1), Value::string("two"), Value::Array(vec![Value::Bool(true)])])
+        ] {
+            assert_eq!(round_trip(value.clone()), Some(value));
+        }
+
+        assert_eq!(Value::from_objc(crate::foundation::nil), None);
+        assert_eq!(Value::Date(UNIX_EPOCH + Duration::from_secs(1)).as_date(), Some(UNIX_EPOCH + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn rejects_collections_with_unrepresentable_values() {
+        let unrepresentable: id = unsafe { msg_send![class!(NSObject), new] };
+        assert_eq!(Value::from_objc(unrepresentable), None);
+
+        let array = NSArray::new(&[NSNumber::integer(1).into_inner(), unrepresentable]);
+        assert_eq!(Value::from_objc(array.into_inner()), None);
+
+        let mut dictionary = NSDictionary::new();
+        dictionary.insert(NSString::new("one"), NSNumber::integer(1).into_inner());
+        dictionary.insert(NSString::new("other"), unrepresentable);
+        assert_eq!(Value::from_objc(dictionary.into_inner()), None);
+    }
+}
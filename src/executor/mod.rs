@@ -0,0 +1,310 @@
+//! An async executor that runs on Grand Central Dispatch, alongside the main run loop.
+//!
+//! There are three ways to run a future:
+//!
+//! - `spawn_local` runs a future on the main thread. It doesn't need to be `Send`, so it can hold
+//!   views, windows, and anything else that has to stay on the main thread.
+//! - `spawn` runs a `Send` future on a global (background) queue.
+//! - `main_thread` hands a `Send` future off to the main thread - e.g, to update the UI from
+//!   within a background task - and lets you await its result.
+//!
+//! Each returns a `JoinHandle`, which can be awaited for the future's output (or dropped, if you
+//! don't need it). Completion-handler APIs are bridged with `oneshot`; see e.g
+//! `FileSelectPanel::show_async`.
+//!
+//! ```rust,no_run
+//! use cacao::executor::{main_thread, spawn, spawn_local};
+//!
+//! spawn_local(async {
+//!     let total = spawn(async { (1..=100).sum::<u64>() }).await;
+//!
+//!     main_thread(async move {
+//!         println!("Computed {} in the background", total);
+//!     }).await;
+//! });
+//! ```
+//!
+//! Nothing here blocks: futures are polled from jobs on the queues, so the run loop keeps running
+//! while they wait.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::foundation::is_main_thread;
+
+#[cfg(all(feature = "headless", not(target_vendor = "apple")))]
+use crate::headless::dispatch;
+
+mod oneshot;
+pub use oneshot::{oneshot, JoinHandle, Receiver, Sender};
+
+mod queue;
+pub use queue::{Job, Queue};
+
+mod task;
+use task::{LocalTask, Task};
+
+/// Spawns futures onto a main queue and a background queue.
+///
+/// The free functions in this module use an `Executor` backed by GCD, which is what you'll want
+/// in an app. Creating one over other queues is mostly useful for testing.
+#[derive(Clone)]
+pub struct Executor {
+    main: Arc<dyn Queue>,
+    background: Arc<dyn Queue>
+}
+
+impl Default for Executor {
+    /// Returns an executor over GCD's main queue and its default-priority global queue.
+    fn default() -> Self {
+        Executor::new(
+            dispatch::Queue::main(),
+            dispatch::Queue::global(dispatch::QueuePriority::Default)
+        )
+    }
+}
+
+impl Executor {
+    /// Returns an executor that runs main thread work on `main`, and everything else on
+    /// `background`.
+    pub fn new<M: Queue, B: Queue>(main: M, background: B) -> Self {
+        Executor {
+            main: Arc::new(main),
+            background: Arc::new(background)
+        }
+    }
+
+    /// Runs `future` on the main queue. This must be called on the thread the main queue runs
+    /// jobs on; see `spawn_local`.
+    pub fn spawn_local<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + 'static,
+        T: 'static
+    {
+        let (sender, receiver) = oneshot();
+
+        LocalTask::spawn(self.main.clone(), Box::pin(async move {
+            sender.send(future.await);
+        }));
+
+        JoinHandle(receiver)
+    }
+
+    /// Runs `future` on the background queue.
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static
+    {
+        spawn_on(self.background.clone(), future)
+    }
+
+    /// Runs `future` on the main queue. Unlike `spawn_local`, this can be called from any thread.
+    pub fn main_thread<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static
+    {
+        spawn_on(self.main.clone(), future)
+    }
+}
+
+impl fmt::Debug for Executor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Executor")
+            .finish()
+    }
+}
+
+fn spawn_on<F, T>(queue: Arc<dyn Queue>, future: F) -> JoinHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static
+{
+    let (sender, receiver) = oneshot();
+
+    Task::spawn(queue, Box::pin(async move {
+        sender.send(future.await);
+    }));
+
+    JoinHandle(receiver)
+}
+
+/// Runs `future` on the main thread, as part of the run loop. It doesn't need to be `Send`.
+///
+/// # Panics
+///
+/// Panics if called from any other thread, as the future has to be created where it'll run. To
+/// get work onto the main thread from elsewhere, use `main_thread`.
+pub fn spawn_local<F, T>(future: F) -> JoinHandle<T>
+where
+    F: Future<Output = T> + 'static,
+    T: 'static
+{
+    if !is_main_thread() {
+        panic!("spawn_local() must be called from the main thread; use main_thread() elsewhere.");
+    }
+
+    Executor::default().spawn_local(future)
+}
+
+/// Runs `future` on a global (background) queue.
+pub fn spawn<F, T>(future: F) -> JoinHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static
+{
+    Executor::default().spawn(future)
+}
+
+/// Runs `future` on the main thread, and returns a handle that resolves to its output. This can
+/// be called from any thread.
+pub fn main_thread<F, T>(future: F) -> JoinHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static
+{
+    Executor::default().main_thread(future)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll};
+
+    use super::*;
+
+    /// A queue that holds jobs until the test runs them, on the test's thread.
+    #[derive(Clone, Default)]
+    struct FakeQueue(Arc<Mutex<VecDeque<Job>>>);
+
+    impl Queue for FakeQueue {
+        fn exec(&self, job: Job) {
+            self.0.lock().unwrap().push_back(job);
+        }
+    }
+
+    impl FakeQueue {
+        /// Runs jobs until there are none left, and returns how many ran.
+        fn run(&self) -> usize {
+            let mut count = 0;
+
+            loop {
+                let job = self.0.lock().unwrap().pop_front();
+
+                match job {
+                    Some(job) => { job(); count += 1; },
+                    None => { return count; }
+                }
+            }
+        }
+    }
+
+    /// Returns `Pending` the first time it's polled, waking itself twice - which should only
+    /// schedule one more poll.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            match self.0 {
+                true => Poll::Ready(()),
+
+                false => {
+                    self.0 = true;
+                    cx.waker().wake_by_ref();
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn spawned_futures_only_run_on_their_queue() {
+        let main = FakeQueue::default();
+        let background = FakeQueue::default();
+        let executor = Executor::new(main.clone(), background.clone());
+
+        let ran = Rc::new(Cell::new(false));
+        let flag = ran.clone();
+        let _handle = executor.spawn_local(async move { flag.set(true); });
+
+        assert!(!ran.get());
+        assert_eq!(background.run(), 0);
+        assert_eq!(main.run(), 1);
+        assert!(ran.get());
+    }
+
+    #[test]
+    fn pending_futures_are_polled_once_more_when_woken() {
+        let main = FakeQueue::default();
+        let executor = Executor::new(main.clone(), FakeQueue::default());
+
+        let polls = Rc::new(Cell::new(0));
+        let counter = polls.clone();
+        let _handle = executor.spawn_local(async move {
+            counter.set(counter.get() + 1);
+            YieldOnce(false).await;
+            counter.set(counter.get() + 1);
+        });
+
+        assert_eq!(main.run(), 2);
+        assert_eq!(polls.get(), 2);
+    }
+
+    #[test]
+    fn join_handles_resolve_across_queues() {
+        let main = FakeQueue::default();
+        let background = FakeQueue::default();
+        let executor = Executor::new(main.clone(), background.clone());
+
+        let result = Rc::new(Cell::new(0));
+        let output = result.clone();
+        let inner = executor.clone();
+
+        let _handle = executor.spawn_local(async move {
+            let sum = inner.spawn(async { 2 + 2 }).await;
+            let doubled = inner.main_thread(async move { sum * 2 }).await;
+            output.set(doubled);
+        });
+
+        main.run();
+        assert_eq!(result.get(), 0);
+
+        background.run();
+        main.run();
+        assert_eq!(result.get(), 8);
+    }
+
+    #[test]
+    fn receivers_resolve_to_none_when_the_sender_is_dropped() {
+        let main = FakeQueue::default();
+        let executor = Executor::new(main.clone(), FakeQueue::default());
+        let (sender, receiver) = oneshot::<u8>();
+
+        let result = Rc::new(Cell::new(Some(0)));
+        let output = result.clone();
+        let _handle = executor.spawn_local(async move { output.set(receiver.await); });
+
+        main.run();
+        drop(sender);
+        main.run();
+        assert_eq!(result.get(), None);
+    }
+
+    #[test]
+    fn only_the_first_value_is_sent() {
+        let (sender, _receiver) = oneshot();
+
+        assert!(sender.send(1));
+        assert!(!sender.send(2));
+    }
+}
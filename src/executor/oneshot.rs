@@ -0,0 +1,117 @@
+//! A single-value channel, used to bridge completion handlers (and spawned tasks) to futures.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Shared<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    sent: bool,
+    closed: bool
+}
+
+/// Returns a connected `Sender` and `Receiver`. The receiver resolves once a value is sent, or
+/// once the sender is dropped without sending one.
+pub fn oneshot<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        value: None,
+        waker: None,
+        sent: false,
+        closed: false
+    }));
+
+    (Sender(shared.clone()), Receiver(shared))
+}
+
+/// The sending half of a `oneshot` channel.
+///
+/// `send` takes `&self` so this can live in a completion block that's (technically) callable more
+/// than once; only the first value sent is delivered.
+pub struct Sender<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> Sender<T> {
+    /// Sends `value` to the receiver, waking whatever's waiting on it. Returns `false` (and drops
+    /// `value`) if a value was already sent.
+    pub fn send(&self, value: T) -> bool {
+        let waker = {
+            let mut shared = self.0.lock().unwrap();
+
+            if shared.sent {
+                return false;
+            }
+
+            shared.sent = true;
+            shared.value = Some(value);
+            shared.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+
+        true
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut shared = self.0.lock().unwrap();
+            shared.closed = true;
+            shared.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving half of a `oneshot` channel. This is a future that resolves to the value sent,
+/// or `None` if the sender was dropped without sending one.
+#[must_use = "futures do nothing unless awaited"]
+pub struct Receiver<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> Future for Receiver<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.0.lock().unwrap();
+
+        if let Some(value) = shared.value.take() {
+            return Poll::Ready(Some(value));
+        }
+
+        match shared.closed {
+            true => Poll::Ready(None),
+
+            false => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A future that resolves to the output of a spawned task.
+///
+/// Dropping this doesn't cancel the task; it keeps running, and its output is discarded.
+#[must_use = "dropping a JoinHandle detaches the task, discarding its output"]
+pub struct JoinHandle<T>(pub(crate) Receiver<T>);
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the task was dropped before it completed - which only happens if it panicked.
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.0).poll(cx) {
+            Poll::Ready(Some(value)) => Poll::Ready(value),
+            Poll::Ready(None) => panic!("A spawned task was dropped before it completed."),
+            Poll::Pending => Poll::Pending
+        }
+    }
+}
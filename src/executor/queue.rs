@@ -0,0 +1,22 @@
+//! The queues that the executor schedules work onto.
+
+#[cfg(all(feature = "headless", not(target_vendor = "apple")))]
+use crate::headless::dispatch;
+
+/// A unit of work handed to a `Queue`.
+pub type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Somewhere the executor can schedule work. Out of the box, this is implemented for
+/// `dispatch::Queue` - but anything that runs jobs in order can stand in, which is how the
+/// executor is tested without a run loop.
+pub trait Queue: Send + Sync + 'static {
+    /// Runs `job` at some point after this returns. Implementations must not run `job` before
+    /// returning, as the executor may be holding a lock when it calls this.
+    fn exec(&self, job: Job);
+}
+
+impl Queue for dispatch::Queue {
+    fn exec(&self, job: Job) {
+        self.exec_async(job);
+    }
+}
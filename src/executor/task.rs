@@ -0,0 +1,130 @@
+//! Wake and poll logic for spawned futures.
+//!
+//! Both kinds of task work the same way: waking one schedules a single poll onto its queue (no
+//! matter how many times it's woken before that poll runs), and a task that returns `Pending` is
+//! kept until it's woken again.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Wake, Waker};
+
+use crate::executor::Queue;
+
+type SendFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+type LocalFuture = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+/// A `Send` future, which is polled on whichever thread its queue runs jobs on.
+pub(crate) struct Task {
+    future: Mutex<Option<SendFuture>>,
+    queue: Arc<dyn Queue>,
+    scheduled: AtomicBool
+}
+
+impl Task {
+    /// Schedules the first poll of `future` onto `queue`.
+    pub fn spawn(queue: Arc<dyn Queue>, future: SendFuture) {
+        let task = Arc::new(Task {
+            future: Mutex::new(Some(future)),
+            queue: queue,
+            scheduled: AtomicBool::new(false)
+        });
+
+        task.wake();
+    }
+
+    fn run(self: Arc<Self>) {
+        self.scheduled.store(false, Ordering::SeqCst);
+
+        let mut slot = self.future.lock().unwrap();
+
+        if let Some(mut future) = slot.take() {
+            let waker = Waker::from(self.clone());
+            let mut context = Context::from_waker(&waker);
+
+            if future.as_mut().poll(&mut context).is_pending() {
+                *slot = Some(future);
+            }
+        }
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        if !self.scheduled.swap(true, Ordering::SeqCst) {
+            let queue = self.queue.clone();
+            queue.exec(Box::new(move || self.run()));
+        }
+    }
+}
+
+#[derive(Default)]
+struct LocalTasks {
+    next_id: usize,
+    futures: HashMap<usize, LocalFuture>
+}
+
+thread_local! {
+    /// Futures spawned with `spawn_local`, which can only be polled on the thread they were
+    /// spawned on - i.e, the main thread.
+    static LOCAL_TASKS: RefCell<LocalTasks> = RefCell::new(LocalTasks::default());
+}
+
+/// A handle to a `!Send` future. The future itself stays in `LOCAL_TASKS`; this is just the part
+/// that can cross threads, so that it can be woken from anywhere.
+pub(crate) struct LocalTask {
+    id: usize,
+    queue: Arc<dyn Queue>,
+    scheduled: AtomicBool
+}
+
+impl LocalTask {
+    /// Stores `future` on the current thread, and schedules its first poll onto `queue`. `queue`
+    /// must run its jobs on the current thread, or the future will never be polled.
+    pub fn spawn(queue: Arc<dyn Queue>, future: LocalFuture) {
+        let id = LOCAL_TASKS.with(|tasks| {
+            let mut tasks = tasks.borrow_mut();
+            let id = tasks.next_id;
+            tasks.next_id += 1;
+            tasks.futures.insert(id, future);
+            id
+        });
+
+        let task = Arc::new(LocalTask {
+            id: id,
+            queue: queue,
+            scheduled: AtomicBool::new(false)
+        });
+
+        task.wake();
+    }
+
+    fn run(self: Arc<Self>) {
+        self.scheduled.store(false, Ordering::SeqCst);
+
+        // The future is taken out while it's polled, so that it can spawn (or finish) other local
+        // tasks without the thread local being borrowed.
+        let future = LOCAL_TASKS.with(|tasks| tasks.borrow_mut().futures.remove(&self.id));
+
+        if let Some(mut future) = future {
+            let waker = Waker::from(self.clone());
+            let mut context = Context::from_waker(&waker);
+
+            if future.as_mut().poll(&mut context).is_pending() {
+                LOCAL_TASKS.with(|tasks| tasks.borrow_mut().futures.insert(self.id, future));
+            }
+        }
+    }
+}
+
+impl Wake for LocalTask {
+    fn wake(self: Arc<Self>) {
+        if !self.scheduled.swap(true, Ordering::SeqCst) {
+            let queue = self.queue.clone();
+            queue.exec(Box::new(move || self.run()));
+        }
+    }
+}
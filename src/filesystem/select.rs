@@ -10,6 +10,7 @@ use objc::{class, msg_send, sel, sel_impl};
 use objc::runtime::Object;
 use objc_id::ShareId;
 
+use crate::executor::{oneshot, Receiver};
 use crate::foundation::{id, YES, NO, NSInteger, NSString};
 use crate::filesystem::enums::ModalResponse;
 
@@ -137,6 +138,17 @@ impl FileSelectPanel {
             let _: () = msg_send![&*self.panel, beginWithCompletionHandler:completion.copy()];
         }
     }
+
+    /// Shows the panel as a modal, and returns a future that resolves to the selected paths (which
+    /// are empty if the user cancelled). Awaiting this from `executor::spawn_local` keeps your
+    /// flow linear, rather than split across a completion handler.
+    ///
+    /// This resolves to `None` only if the panel goes away without completing.
+    pub fn show_async(&self) -> Receiver<Vec<PathBuf>> {
+        let (sender, receiver) = oneshot();
+        self.show(move |paths| { sender.send(paths); });
+        receiver
+    }
 }
 
 /// Retrieves the selected URLs from the provided panel.
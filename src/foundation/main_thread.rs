@@ -0,0 +1,62 @@
+//! Compile-time (and, where that's not possible, runtime) checks that UI work happens on the
+//! main thread.
+//!
+//! AppKit and UIKit objects must only be created and mutated on the main thread; doing otherwise
+//! tends to work *most* of the time, and then crashes (or quietly corrupts state) on someone
+//! else's machine. A `MainThreadMarker` is proof that the holder is on the main thread: UI
+//! constructors require one, and the types they return keep it, which makes them `!Send` and
+//! `!Sync` - so a `Window`, `View`, `Label` and so on can't leave the main thread at all, and
+//! their methods can only ever be called there.
+//!
+//! You're handed a marker in `App::dispatch_main_with`, and every UI type can give you one back
+//! via `main_thread_marker()` - which covers delegate callbacks like `did_load`. Elsewhere (e.g,
+//! `main()`, before the app is running), `MainThreadMarker::new()` checks the current thread at
+//! runtime.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{to_bool, BOOL};
+
+/// A zero-sized token that proves the current thread is the main thread.
+///
+/// This is `Copy`, but neither `Send` nor `Sync`, so it can't be moved or shared to another
+/// thread; anything that holds one is pinned to the main thread as well.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MainThreadMarker {
+    _not_send: PhantomData<*mut ()>
+}
+
+impl MainThreadMarker {
+    /// Returns a marker if called on the main thread, or `None` otherwise.
+    pub fn new() -> Option<Self> {
+        match is_main_thread() {
+            true => Some(MainThreadMarker { _not_send: PhantomData }),
+            false => None
+        }
+    }
+
+    /// Returns a marker without checking the current thread.
+    ///
+    /// # Safety
+    ///
+    /// This must only be called on the main thread.
+    pub unsafe fn new_unchecked() -> Self {
+        MainThreadMarker { _not_send: PhantomData }
+    }
+}
+
+impl fmt::Debug for MainThreadMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MainThreadMarker")
+            .finish()
+    }
+}
+
+/// Returns whether the current thread is the main thread.
+pub fn is_main_thread() -> bool {
+    let result: BOOL = unsafe { msg_send![class!(NSThread), isMainThread] };
+    to_bool(result)
+}
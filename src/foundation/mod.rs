@@ -36,8 +36,11 @@ pub use data::NSData;
 mod dictionary;
 pub use dictionary::NSDictionary;
 
+mod main_thread;
+pub use main_thread::{is_main_thread, MainThreadMarker};
+
 mod number;
-pub use number::NSNumber;
+pub use number::{NSNumber, Number, NumberKind, NumberType};
 
 mod string;
 pub use string::NSString;
@@ -2,11 +2,139 @@ use std::ffi::CStr;
 use std::os::raw::c_char;
 
 use objc::{class, msg_send, sel, sel_impl};
-use objc::runtime::Object;
+use objc::runtime::{Class, Object};
 use objc_id::Id;
 
 use crate::foundation::{id, to_bool, BOOL, YES, NO, NSInteger};
 
+/// The scalar types an `NSNumber` can report through `objCType`.
+///
+/// Note that `NSNumber` doesn't promise to keep the type it was created with - e.g, a number made
+/// with `numberWithUnsignedInt:` reports `q` - so this describes how to read the value out, rather
+/// than how it was written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NumberType {
+    /// `c`: a `char`. This is also how `BOOL` is encoded on x86_64.
+    Char,
+
+    /// `C`: an `unsigned char`.
+    UnsignedChar,
+
+    /// `s`: a `short`.
+    Short,
+
+    /// `S`: an `unsigned short`.
+    UnsignedShort,
+
+    /// `i`: an `int`.
+    Int,
+
+    /// `I`: an `unsigned int`.
+    UnsignedInt,
+
+    /// `l`: a `long`. Always 32 bits in type encodings, even on 64-bit platforms.
+    Long,
+
+    /// `L`: an `unsigned long`. Always 32 bits in type encodings, even on 64-bit platforms.
+    UnsignedLong,
+
+    /// `q`: a `long long` (and `NSInteger`, on 64-bit platforms).
+    LongLong,
+
+    /// `Q`: an `unsigned long long` (and `NSUInteger`, on 64-bit platforms).
+    UnsignedLongLong,
+
+    /// `f`: a `float`.
+    Float,
+
+    /// `d`: a `double`.
+    Double,
+
+    /// `B`: a C++ `bool` or C99 `_Bool`. This is how `BOOL` is encoded on arm64.
+    Bool
+}
+
+impl NumberType {
+    /// Maps an `objCType` encoding to a `NumberType`. Returns `None` for anything that isn't a
+    /// scalar (e.g, an `NSValue` wrapping a struct).
+    pub fn from_objc_type(encoding: &str) -> Option<Self> {
+        match encoding {
+            "c" => Some(NumberType::Char),
+            "C" => Some(NumberType::UnsignedChar),
+            "s" => Some(NumberType::Short),
+            "S" => Some(NumberType::UnsignedShort),
+            "i" => Some(NumberType::Int),
+            "I" => Some(NumberType::UnsignedInt),
+            "l" => Some(NumberType::Long),
+            "L" => Some(NumberType::UnsignedLong),
+            "q" => Some(NumberType::LongLong),
+            "Q" => Some(NumberType::UnsignedLongLong),
+            "f" => Some(NumberType::Float),
+            "d" => Some(NumberType::Double),
+            "B" => Some(NumberType::Bool),
+            _ => None
+        }
+    }
+
+    /// Returns the kind of `Number` this type decodes to. `Char` is ambiguous - it's used both for
+    /// `BOOL` and for genuine characters - so the caller says which it is with `is_boolean`.
+    pub fn number_kind(&self, is_boolean: bool) -> NumberKind {
+        match self {
+            NumberType::Char if is_boolean => NumberKind::Bool,
+            NumberType::Bool => NumberKind::Bool,
+
+            NumberType::Char | NumberType::Short | NumberType::Int | NumberType::Long |
+            NumberType::LongLong => NumberKind::Integer,
+
+            // These all fit in an `i64`, so there's nothing gained by keeping them unsigned.
+            NumberType::UnsignedChar | NumberType::UnsignedShort | NumberType::UnsignedInt |
+            NumberType::UnsignedLong => NumberKind::Integer,
+
+            NumberType::UnsignedLongLong => NumberKind::UnsignedInteger,
+            NumberType::Float => NumberKind::Float,
+            NumberType::Double => NumberKind::Double
+        }
+    }
+}
+
+/// The variants of `Number`, without their values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NumberKind {
+    /// A `Number::Bool`.
+    Bool,
+
+    /// A `Number::Integer`.
+    Integer,
+
+    /// A `Number::UnsignedInteger`.
+    UnsignedInteger,
+
+    /// A `Number::Float`.
+    Float,
+
+    /// A `Number::Double`.
+    Double
+}
+
+/// The decoded value of an `NSNumber`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Number {
+    /// A boolean.
+    Bool(bool),
+
+    /// A signed integer, or an unsigned integer of 32 bits or less.
+    Integer(i64),
+
+    /// An unsigned 64-bit integer.
+    UnsignedInteger(u64),
+
+    /// A single-precision float.
+    Float(f32),
+
+    /// A double-precision float.
+    Double(f64)
+}
+
 /// Wrapper for a retained `NSNumber` object.
 ///
 /// In general we strive to avoid using this in the codebase, but it's a requirement for moving
@@ -40,6 +168,13 @@ impl NSNumber {
         })
     }
 
+    /// Constructs a `numberWithUnsignedLongLong` instance of `NSNumber` and retains it.
+    pub fn unsigned_integer(value: u64) -> Self {
+        NSNumber(unsafe {
+            Id::from_ptr(msg_send![class!(NSNumber), numberWithUnsignedLongLong:value])
+        })
+    }
+
     /// Constructs a `numberWithDouble` instance of `NSNumber` and retains it.
     pub fn float(value: f64) -> Self {
         NSNumber(unsafe {
@@ -60,6 +195,46 @@ impl NSNumber {
         }
     }
 
+    /// Returns the type this number reports through `objCType`, or `None` if it isn't a scalar.
+    pub fn number_type(&self) -> Option<NumberType> {
+        NumberType::from_objc_type(self.objc_type())
+    }
+
+    /// Returns whether this number is one of the shared `kCFBooleanTrue`/`kCFBooleanFalse`
+    /// instances - i.e, whether it was created as a boolean, regardless of how it's encoded.
+    pub fn is_boolean(&self) -> bool {
+        unsafe {
+            let boolean: id = msg_send![class!(NSNumber), numberWithBool:YES];
+            let boolean_class: *const Class = msg_send![boolean, class];
+            let class: *const Class = msg_send![&*self.0, class];
+            class == boolean_class
+        }
+    }
+
+    /// Decodes the underlying value, using the reported `objCType` to pick the right accessor.
+    /// Returns `None` if the number isn't a scalar.
+    pub fn value(&self) -> Option<Number> {
+        let kind = self.number_type()?.number_kind(self.is_boolean());
+
+        Some(match kind {
+            NumberKind::Bool => Number::Bool(self.as_bool()),
+
+            NumberKind::Integer => Number::Integer(unsafe {
+                let i: i64 = msg_send![&*self.0, longLongValue];
+                i
+            }),
+
+            NumberKind::UnsignedInteger => Number::UnsignedInteger(self.as_u64()),
+
+            NumberKind::Float => Number::Float(unsafe {
+                let f: f32 = msg_send![&*self.0, floatValue];
+                f
+            }),
+
+            NumberKind::Double => Number::Double(self.as_f64())
+        })
+    }
+
     /// Pulls the underlying `NSInteger` value out and passes it back as an `i64`.
     ///
     /// Note that this _does not check_ if the underlying type is actually this. You are
@@ -71,6 +246,16 @@ impl NSNumber {
         }
     }
 
+    /// Pulls the underlying `unsigned long long` value out and passes it back as a `u64`.
+    ///
+    /// Note that this _does not check_ if the underlying type is actually this. You are
+    /// responsible for doing so via the `objc_type()` method.
+    pub fn as_u64(&self) -> u64 {
+        unsafe {
+            msg_send![&*self.0, unsignedLongLongValue]
+        }
+    }
+
     /// Pulls the underlying `double` value out and passes it back as an `f64`.
     ///
     /// Note that this _does not check_ if the underlying type is actually this. You are
@@ -107,3 +292,46 @@ impl NSNumber {
         &mut *self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{NumberKind, NumberType};
+
+    #[test]
+    fn decodes_every_scalar_encoding() {
+        let table = [
+            ("c", NumberType::Char, NumberKind::Integer),
+            ("C", NumberType::UnsignedChar, NumberKind::Integer),
+            ("s", NumberType::Short, NumberKind::Integer),
+            ("S", NumberType::UnsignedShort, NumberKind::Integer),
+            ("i", NumberType::Int, NumberKind::Integer),
+            ("I", NumberType::UnsignedInt, NumberKind::Integer),
+            ("l", NumberType::Long, NumberKind::Integer),
+            ("L", NumberType::UnsignedLong, NumberKind::Integer),
+            ("q", NumberType::LongLong, NumberKind::Integer),
+            ("Q", NumberType::UnsignedLongLong, NumberKind::UnsignedInteger),
+            ("f", NumberType::Float, NumberKind::Float),
+            ("d", NumberType::Double, NumberKind::Double),
+            ("B", NumberType::Bool, NumberKind::Bool)
+        ];
+
+        for (encoding, number_type, kind) in table.iter() {
+            assert_eq!(NumberType::from_objc_type(encoding), Some(*number_type), "{}", encoding);
+            assert_eq!(number_type.number_kind(false), *kind, "{}", encoding);
+        }
+    }
+
+    #[test]
+    fn booleans_decode_as_bool_regardless_of_encoding() {
+        assert_eq!(NumberType::Char.number_kind(true), NumberKind::Bool);
+        assert_eq!(NumberType::Bool.number_kind(true), NumberKind::Bool);
+        assert_eq!(NumberType::Bool.number_kind(false), NumberKind::Bool);
+    }
+
+    #[test]
+    fn rejects_non_scalar_encodings() {
+        for encoding in ["", "v", "@", "*", "{CGPoint=dd}", "qq"].iter() {
+            assert_eq!(NumberType::from_objc_type(encoding), None, "{}", encoding);
+        }
+    }
+}
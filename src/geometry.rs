@@ -3,7 +3,8 @@
 use core_graphics::geometry::{CGRect, CGPoint, CGSize};
 
 /// A struct that represents a box - top, left, width and height.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect {
     /// Distance from the top, in points.
     pub top: f64,
@@ -54,3 +55,37 @@ impl From<CGRect> for Rect {
         }
     }
 }
+
+/// Insets from each edge of a box, in points. These are directional: `leading` and `trailing`
+/// follow the layout direction, in the same way the `leading` and `trailing` anchors do.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EdgeInsets {
+    /// Inset from the top edge, in points.
+    pub top: f64,
+
+    /// Inset from the leading edge, in points.
+    pub leading: f64,
+
+    /// Inset from the bottom edge, in points.
+    pub bottom: f64,
+
+    /// Inset from the trailing edge, in points.
+    pub trailing: f64
+}
+
+impl EdgeInsets {
+    /// Returns a new `EdgeInsets` initialized with the values specified.
+    pub fn new(top: f64, leading: f64, bottom: f64, trailing: f64) -> Self {
+        EdgeInsets { top: top, leading: leading, bottom: bottom, trailing: trailing }
+    }
+
+    /// Returns `EdgeInsets` with the same inset on every edge.
+    pub fn uniform(inset: f64) -> Self {
+        EdgeInsets::new(inset, inset, inset, inset)
+    }
+
+    /// Returns zero'd out `EdgeInsets`.
+    pub fn zero() -> Self {
+        EdgeInsets::default()
+    }
+}
@@ -0,0 +1,215 @@
+//! A container that lays its children out in a grid of rows and columns.
+//!
+//! Useful for things like keypads and preference forms, where you'd otherwise be nesting rows of
+//! views and pinning anchors by hand.
+//!
+//! ```rust,no_run
+//! use cacao::button::Button;
+//! use cacao::foundation::MainThreadMarker;
+//! use cacao::gridview::GridView;
+//! use cacao::layout::{GridCell, GridTrack};
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let keypad = GridView::new(vec![GridTrack::Fraction(1.); 4], vec![GridTrack::Fraction(1.); 5], mtm);
+//! keypad.set_gaps(1., 1.);
+//!
+//! let zero = Button::new("0", mtm);
+//! keypad.add_subview_in(&zero, GridCell::new(4, 0).span(1, 2));
+//! ```
+//!
+//! Tracks are backed by layout guides, and the constraints are generated by
+//! `layout::GridLayout`. If you just need the frame of a cell for a given size, use
+//! `GridLayout::cell_rect`, which doesn't need a view at all.
+
+use std::cell::RefCell;
+
+use objc::{class, msg_send, sel, sel_impl};
+use objc::runtime::Object;
+use objc_id::ShareId;
+
+use crate::color::Color;
+use crate::foundation::{id, MainThreadMarker};
+use crate::geometry::EdgeInsets;
+use crate::layout::{
+    Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension, LayoutConstraint, LayoutItem,
+    GridCell, GridLayout, GridTrack
+};
+use crate::stackview::layout_item;
+use crate::view::View;
+
+/// A container view that positions its subviews in grid cells.
+#[derive(Debug)]
+pub struct GridView {
+    /// The container view that subviews are added to.
+    pub view: View,
+
+    /// A pointer to the Objective-C runtime top layout constraint.
+    pub top: LayoutAnchorY,
+
+    /// A pointer to the Objective-C runtime leading layout constraint.
+    pub leading: LayoutAnchorX,
+
+    /// A pointer to the Objective-C runtime trailing layout constraint.
+    pub trailing: LayoutAnchorX,
+
+    /// A pointer to the Objective-C runtime bottom layout constraint.
+    pub bottom: LayoutAnchorY,
+
+    /// A pointer to the Objective-C runtime width layout constraint.
+    pub width: LayoutAnchorDimension,
+
+    /// A pointer to the Objective-C runtime height layout constraint.
+    pub height: LayoutAnchorDimension,
+
+    /// A pointer to the Objective-C runtime center X layout constraint.
+    pub center_x: LayoutAnchorX,
+
+    /// A pointer to the Objective-C runtime center Y layout constraint.
+    pub center_y: LayoutAnchorY,
+
+    layout: RefCell<GridLayout>,
+    column_guides: RefCell<Vec<ShareId<Object>>>,
+    row_guides: RefCell<Vec<ShareId<Object>>>,
+    cells: RefCell<Vec<(ShareId<Object>, GridCell)>>,
+    constraints: RefCell<Vec<LayoutConstraint>>
+}
+
+impl GridView {
+    /// Returns a new, empty `GridView` with the given column and row tracks.
+    pub fn new(columns: Vec<GridTrack>, rows: Vec<GridTrack>, mtm: MainThreadMarker) -> Self {
+        let view = View::new(mtm);
+
+        let grid = GridView {
+            top: view.top.clone(),
+            leading: view.leading.clone(),
+            trailing: view.trailing.clone(),
+            bottom: view.bottom.clone(),
+            width: view.width.clone(),
+            height: view.height.clone(),
+            center_x: view.center_x.clone(),
+            center_y: view.center_y.clone(),
+            view: view,
+            layout: RefCell::new(GridLayout::new(columns, rows)),
+            column_guides: RefCell::new(Vec::new()),
+            row_guides: RefCell::new(Vec::new()),
+            cells: RefCell::new(Vec::new()),
+            constraints: RefCell::new(Vec::new())
+        };
+
+        grid.rebuild_guides();
+        grid
+    }
+
+    /// Returns the current layout configuration.
+    pub fn grid_layout(&self) -> GridLayout {
+        self.layout.borrow().clone()
+    }
+
+    /// Replaces the layout configuration wholesale, and re-lays out the grid.
+    pub fn set_grid_layout(&self, layout: GridLayout) {
+        *self.layout.borrow_mut() = layout;
+        self.rebuild_guides();
+    }
+
+    /// Replaces the column and row tracks.
+    pub fn set_tracks(&self, columns: Vec<GridTrack>, rows: Vec<GridTrack>) {
+        {
+            let mut layout = self.layout.borrow_mut();
+            layout.columns = columns;
+            layout.rows = rows;
+        }
+
+        self.rebuild_guides();
+    }
+
+    /// Sets the space between columns and between rows, in points.
+    pub fn set_gaps<F: Into<f64>>(&self, column_gap: F, row_gap: F) {
+        {
+            let mut layout = self.layout.borrow_mut();
+            layout.column_gap = column_gap.into();
+            layout.row_gap = row_gap.into();
+        }
+
+        self.relayout();
+    }
+
+    /// Sets the insets from the edges of this view.
+    pub fn set_insets(&self, insets: EdgeInsets) {
+        self.layout.borrow_mut().insets = insets;
+        self.relayout();
+    }
+
+    /// Sets the background color of the container.
+    pub fn set_background_color<C: AsRef<Color>>(&self, color: C) {
+        self.view.set_background_color(color);
+    }
+
+    /// Adds a view to the grid, occupying the given cell (and any span it has).
+    pub fn add_subview_in<V: Layout>(&self, view: &V, cell: GridCell) {
+        self.view.add_subview(view);
+        self.cells.borrow_mut().push((view.get_backing_node(), cell));
+        self.relayout();
+    }
+
+    /// Creates a layout guide for every track, replacing any existing ones.
+    fn rebuild_guides(&self) {
+        let container = self.view.get_backing_node();
+        let layout = self.layout.borrow();
+
+        let make_guides = |count: usize| -> Vec<ShareId<Object>> {
+            (0..count).map(|_| unsafe {
+                let guide: id = msg_send![class!(NSLayoutGuide), new];
+                let _: () = msg_send![&*container, addLayoutGuide:guide];
+                ShareId::from_ptr(guide)
+            }).collect()
+        };
+
+        let columns = make_guides(layout.columns.len());
+        let rows = make_guides(layout.rows.len());
+        drop(layout);
+
+        LayoutConstraint::deactivate(&self.constraints.borrow_mut().split_off(0));
+
+        let old_columns = std::mem::replace(&mut *self.column_guides.borrow_mut(), columns);
+        let old_rows = std::mem::replace(&mut *self.row_guides.borrow_mut(), rows);
+
+        for guide in old_columns.iter().chain(old_rows.iter()) {
+            unsafe {
+                let _: () = msg_send![&*container, removeLayoutGuide:&**guide];
+            }
+        }
+
+        self.relayout();
+    }
+
+    /// Regenerates and activates the constraints for the current configuration.
+    fn relayout(&self) {
+        let container = layout_item(&self.view.get_backing_node());
+        let columns: Vec<LayoutItem> = self.column_guides.borrow().iter().map(layout_item).collect();
+        let rows: Vec<LayoutItem> = self.row_guides.borrow().iter().map(layout_item).collect();
+        let cells: Vec<(LayoutItem, GridCell)> = self.cells.borrow().iter().map(|(node, cell)| {
+            (layout_item(node), *cell)
+        }).collect();
+
+        let constraints: Vec<LayoutConstraint> = self.layout.borrow()
+            .constraints(container, &columns, &rows, &cells)
+            .into_iter()
+            .map(LayoutConstraint::from_record)
+            .collect();
+
+        let mut active = self.constraints.borrow_mut();
+        LayoutConstraint::deactivate(&active);
+        LayoutConstraint::activate(&constraints);
+        *active = constraints;
+    }
+}
+
+impl Layout for GridView {
+    fn get_backing_node(&self) -> ShareId<Object> {
+        self.view.get_backing_node()
+    }
+
+    fn add_subview<V: Layout>(&self, view: &V) {
+        self.view.add_subview(view);
+    }
+}
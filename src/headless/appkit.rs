@@ -0,0 +1,1473 @@
+//! The fake AppKit (and Foundation) behaviour: what an object does with a message when its class
+//! didn't declare a method for it.
+//!
+//! Most of this is recording - setters store what they were given in the object's record, and
+//! getters hand it back - with just enough real behaviour on top (view trees, windows, anchors
+//! and constraints, strings, arrays and numbers) for cacao's wrappers to work as they do on a Mac.
+//! Anything not listed here is recorded as unhandled and returns `nil`.
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_void};
+
+use core_graphics::base::CGFloat;
+use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+use lazy_static::lazy_static;
+
+use objc::runtime::{Imp, Sel};
+use objc::{msg_send, sel, sel_impl};
+
+use crate::color::{ColorValue, Rgba};
+use crate::foundation::{id, nil, BOOL, NO, YES, NSInteger, NSUInteger};
+use crate::geometry::Rect;
+use crate::headless::blocks;
+use crate::headless::runtime::{self, MockClass};
+use crate::headless::store::{Payload, Property, Store, STORE};
+use crate::layout::{AnchorRecord, ConstraintRecord, LayoutAttribute, LayoutItem, LayoutRelation};
+
+/// Casts a typed implementation to an `Imp`.
+macro_rules! imp {
+    ($f:expr, fn($($arg:ty),*) $(-> $ret:ty)?) => {
+        unsafe { mem::transmute::<extern fn($($arg),*) $(-> $ret)?, Imp>($f) }
+    };
+}
+
+/// Which table a builtin is registered in: methods on instances, or methods on the class itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Instance,
+    Class
+}
+
+#[derive(Default)]
+struct Builtins(HashMap<(&'static str, Kind, &'static str), Imp>);
+
+// `Imp`s here are all plain Rust functions.
+unsafe impl Send for Builtins {}
+unsafe impl Sync for Builtins {}
+
+impl Builtins {
+    fn add(&mut self, class: &'static str, kind: Kind, selectors: &[&'static str], imp: Imp) {
+        for selector in selectors {
+            self.0.insert((class, kind, *selector), imp);
+        }
+    }
+}
+
+lazy_static! {
+    static ref BUILTINS: Builtins = builtins();
+}
+
+/// The superclass each faked class has on macOS. Classes not listed here inherit from
+/// `NSObject`.
+pub(crate) fn superclass_name(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "NSView" | "NSWindow" | "NSViewController" | "NSWindowController" | "NSApplication" => "NSResponder",
+        "NSControl" | "NSScrollView" | "NSStackView" | "NSProgressIndicator" | "NSVisualEffectView" |
+        "NSTableRowView" | "NSClipView" => "NSView",
+        "NSTextField" | "NSButton" | "NSImageView" | "NSTableView" | "NSSwitch" | "NSSlider" => "NSControl",
+        "NSOutlineView" => "NSTableView",
+        "NSPanel" => "NSWindow",
+        "NSMutableString" => "NSString",
+        "NSMutableArray" => "NSArray",
+        "NSMutableDictionary" => "NSDictionary",
+        "NSNumber" => "NSValue",
+        "__NSCFBoolean" => "NSNumber",
+        "NSLayoutXAxisAnchor" | "NSLayoutYAxisAnchor" | "NSLayoutDimension" => "NSLayoutAnchor",
+        "NSMutableAttributedString" => "NSAttributedString",
+        _ => return None
+    })
+}
+
+/// Returns the builtin implementation of `selector` for `class`, if there is one.
+pub(crate) fn builtin(class: &'static MockClass, selector: &str) -> Option<Imp> {
+    let mut current = Some(class);
+
+    while let Some(c) = current {
+        let kind = match c.is_meta() {
+            true => Kind::Class,
+            false => Kind::Instance
+        };
+
+        if let Some(imp) = BUILTINS.0.get(&(c.name(), kind, selector)) {
+            return Some(*imp);
+        }
+
+        current = c.superclass();
+    }
+
+    // There are far too many system colors to list, but they all work the same way.
+    if class.is_meta() && class.is_kind_of("NSColor") && !selector.contains(':') {
+        return Some(imp!(system_color, fn(id, Sel) -> id));
+    }
+
+    None
+}
+
+/// The implementation used for anything without one.
+pub(crate) fn unhandled() -> Imp {
+    imp!(record_unhandled, fn(id, Sel) -> id)
+}
+
+/// The implementation used for messages to nil.
+pub(crate) fn nil_receiver() -> Imp {
+    imp!(return_nil, fn(id, Sel) -> id)
+}
+
+fn builtins() -> Builtins {
+    use Kind::{Class, Instance};
+
+    let mut b = Builtins::default();
+
+    // NSObject
+    b.add("NSObject", Class, &["alloc"], imp!(alloc, fn(id, Sel) -> id));
+    b.add("NSObject", Class, &["allocWithZone:"], imp!(alloc_with_zone, fn(id, Sel, *mut c_void) -> id));
+    b.add("NSObject", Class, &["new"], imp!(new, fn(id, Sel) -> id));
+    b.add("NSObject", Class, &["class"], imp!(return_self, fn(id, Sel) -> id));
+    b.add("NSObject", Instance, &["init", "self", "autorelease", "cell"], imp!(return_self, fn(id, Sel) -> id));
+    b.add("NSObject", Instance, &["class"], imp!(class, fn(id, Sel) -> id));
+    b.add("NSObject", Instance, &["retain"], imp!(retain, fn(id, Sel) -> id));
+    b.add("NSObject", Instance, &["release"], imp!(release, fn(id, Sel)));
+    b.add("NSObject", Instance, &["dealloc"], imp!(no_op, fn(id, Sel)));
+    b.add("NSObject", Instance, &["isKindOfClass:"], imp!(is_kind_of_class, fn(id, Sel, id) -> BOOL));
+    b.add("NSObject", Instance, &["isMemberOfClass:"], imp!(is_member_of_class, fn(id, Sel, id) -> BOOL));
+    b.add("NSObject", Instance, &["respondsToSelector:"], imp!(responds_to_selector, fn(id, Sel, Sel) -> BOOL));
+    b.add("NSObject", Instance, &["isEqual:"], imp!(is_equal, fn(id, Sel, id) -> BOOL));
+    b.add("NSObject", Instance, &["hash"], imp!(hash, fn(id, Sel) -> NSUInteger));
+
+    // NSString
+    b.add("NSString", Class, &["stringWithUTF8String:"], imp!(string_with_utf8_string, fn(id, Sel, *const c_char) -> id));
+    b.add("NSString", Instance, &["initWithBytes:length:encoding:"], imp!(string_init_with_bytes, fn(id, Sel, *const u8, NSUInteger, NSUInteger) -> id));
+    b.add("NSString", Instance, &["UTF8String"], imp!(string_utf8, fn(id, Sel) -> *const c_char));
+    b.add("NSString", Instance, &["lengthOfBytesUsingEncoding:"], imp!(string_byte_length, fn(id, Sel, NSUInteger) -> NSUInteger));
+    b.add("NSString", Instance, &["length"], imp!(string_length, fn(id, Sel) -> NSUInteger));
+    b.add("NSString", Instance, &["isEqualToString:"], imp!(string_is_equal, fn(id, Sel, id) -> BOOL));
+
+    // NSArray
+    b.add("NSArray", Class, &["array"], imp!(array, fn(id, Sel) -> id));
+    b.add("NSArray", Class, &["arrayWithObjects:count:"], imp!(array_with_objects, fn(id, Sel, *const id, NSUInteger) -> id));
+    b.add("NSArray", Instance, &["count"], imp!(array_count, fn(id, Sel) -> NSUInteger));
+    b.add("NSArray", Instance, &["objectAtIndex:"], imp!(array_object_at_index, fn(id, Sel, NSUInteger) -> id));
+    b.add("NSMutableArray", Instance, &["addObject:"], imp!(array_add_object, fn(id, Sel, id)));
+
+    // NSDictionary
+    b.add("NSDictionary", Instance, &["objectForKey:"], imp!(dictionary_object_for_key, fn(id, Sel, id) -> id));
+    b.add("NSDictionary", Instance, &["allKeys"], imp!(dictionary_all_keys, fn(id, Sel) -> id));
+    b.add("NSMutableDictionary", Instance, &["setObject:forKey:"], imp!(dictionary_set_object, fn(id, Sel, id, id)));
+
+    // NSValue - only rects are boxed, as that's all `userInfo` dictionaries need.
+    b.add("NSValue", Class, &["valueWithRect:", "valueWithCGRect:"], imp!(value_with_rect, fn(id, Sel, CGRect) -> id));
+    b.add("NSValue", Instance, &["objCType"], imp!(value_objc_type, fn(id, Sel) -> *const c_char));
+    b.add("NSValue", Instance, &["getValue:size:"], imp!(value_get_value, fn(id, Sel, *mut c_void, NSUInteger)));
+
+    // NSNumber
+    b.add("NSNumber", Class, &["numberWithBool:"], imp!(number_with_bool, fn(id, Sel, BOOL) -> id));
+    b.add("NSNumber", Class, &["numberWithInteger:", "numberWithLongLong:"], imp!(number_with_integer, fn(id, Sel, NSInteger) -> id));
+    b.add("NSNumber", Class, &["numberWithUnsignedLongLong:", "numberWithUnsignedInteger:"], imp!(number_with_unsigned_integer, fn(id, Sel, u64) -> id));
+    b.add("NSNumber", Class, &["numberWithDouble:"], imp!(number_with_double, fn(id, Sel, f64) -> id));
+    b.add("NSNumber", Instance, &["boolValue"], imp!(number_bool_value, fn(id, Sel) -> BOOL));
+    b.add("NSNumber", Instance, &["integerValue", "longLongValue"], imp!(number_integer_value, fn(id, Sel) -> NSInteger));
+    b.add("NSNumber", Instance, &["unsignedLongLongValue"], imp!(number_unsigned_value, fn(id, Sel) -> u64));
+    b.add("NSNumber", Instance, &["doubleValue"], imp!(number_double_value, fn(id, Sel) -> f64));
+    b.add("NSNumber", Instance, &["floatValue"], imp!(number_float_value, fn(id, Sel) -> f32));
+    b.add("NSNumber", Instance, &["objCType"], imp!(number_objc_type, fn(id, Sel) -> *const c_char));
+
+    // NSThread - every thread is the main thread, as far as tests are concerned.
+    b.add("NSThread", Class, &["isMainThread"], imp!(yes, fn(id, Sel) -> BOOL));
+    b.add("NSThread", Instance, &["isMainThread"], imp!(yes, fn(id, Sel) -> BOOL));
+
+    // NSColor, NSFont and CALayer
+    b.add("NSColor", Class, &[
+        "colorWithSRGBRed:green:blue:alpha:", "colorWithCalibratedRed:green:blue:alpha:",
+        "colorWithRed:green:blue:alpha:", "colorWithDisplayP3Red:green:blue:alpha:"
+    ], imp!(color_with_components, fn(id, Sel, CGFloat, CGFloat, CGFloat, CGFloat) -> id));
+    b.add("NSColor", Class, &["colorWithCalibratedHue:saturation:brightness:alpha:"], imp!(color_with_hsb, fn(id, Sel, CGFloat, CGFloat, CGFloat, CGFloat) -> id));
+    b.add("NSColor", Class, &["colorWithCalibratedWhite:alpha:"], imp!(color_with_white, fn(id, Sel, CGFloat, CGFloat) -> id));
+    b.add("NSColor", Class, &["colorWithCGColor:"], imp!(color_with_cg_color, fn(id, Sel, id) -> id));
+    b.add("NSColor", Instance, &["CGColor"], imp!(return_self, fn(id, Sel) -> id));
+    b.add("NSColor", Instance, &["colorUsingColorSpace:"], imp!(color_using_color_space, fn(id, Sel, id) -> id));
+    b.add("NSColor", Instance, &["redComponent", "greenComponent", "blueComponent", "alphaComponent"], imp!(color_component, fn(id, Sel) -> CGFloat));
+    b.add("NSColor", Instance, &["description"], imp!(color_description, fn(id, Sel) -> id));
+    b.add("NSColorSpace", Class, &["sRGBColorSpace"], imp!(return_self, fn(id, Sel) -> id));
+    b.add("NSFont", Class, &["systemFontOfSize:", "boldSystemFontOfSize:", "labelFontOfSize:"], imp!(font_of_size, fn(id, Sel, CGFloat) -> id));
+    b.add("NSFont", Class, &["systemFontOfSize:weight:"], imp!(font_of_size_and_weight, fn(id, Sel, CGFloat, CGFloat) -> id));
+    b.add("NSFont", Class, &["labelFontSize", "systemFontSize"], imp!(default_font_size, fn(id, Sel) -> CGFloat));
+    b.add("NSFont", Instance, &["pointSize"], imp!(get_float, fn(id, Sel) -> CGFloat));
+    b.add("CALayer", Instance, &["setBackgroundColor:", "setBorderColor:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("CALayer", Instance, &["setCornerRadius:", "setBorderWidth:"], imp!(set_float, fn(id, Sel, CGFloat)));
+    b.add("CALayer", Instance, &["setMasksToBounds:"], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("CALayer", Instance, &["backgroundColor", "borderColor"], imp!(get_object, fn(id, Sel) -> id));
+
+    // NSView
+    b.add("NSView", Instance, &["init"], imp!(view_init, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["initWithFrame:"], imp!(view_init_with_frame, fn(id, Sel, CGRect) -> id));
+    b.add("NSView", Instance, &["setFrame:"], imp!(set_rect, fn(id, Sel, CGRect)));
+    b.add("NSView", Instance, &["frame"], imp!(get_rect, fn(id, Sel) -> CGRect));
+    b.add("NSView", Instance, &["layer"], imp!(view_layer, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["addSubview:"], imp!(view_add_subview, fn(id, Sel, id)));
+    b.add("NSView", Instance, &["removeFromSuperview"], imp!(view_remove_from_superview, fn(id, Sel)));
+    b.add("NSView", Instance, &["superview"], imp!(view_superview, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["subviews"], imp!(view_subviews, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["window"], imp!(view_window, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["constraints"], imp!(view_constraints, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &[
+        "setTranslatesAutoresizingMaskIntoConstraints:", "setWantsLayer:", "setHidden:",
+        "setNeedsDisplay:", "setNeedsLayout:"
+    ], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSView", Instance, &["isHidden", "translatesAutoresizingMaskIntoConstraints", "wantsLayer"], imp!(get_bool, fn(id, Sel) -> BOOL));
+    b.add("NSView", Instance, &["setAlphaValue:"], imp!(set_float, fn(id, Sel, CGFloat)));
+    b.add("NSView", Instance, &["alphaValue"], imp!(get_float, fn(id, Sel) -> CGFloat));
+    b.add("NSView", Instance, &["registerForDraggedTypes:", "setToolTip:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSView", Instance, &["toolTip"], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["layout", "layoutSubtreeIfNeeded", "display"], imp!(no_op, fn(id, Sel)));
+    b.add("NSView", Instance, &["setNextKeyView:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSView", Instance, &["nextKeyView"], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &[
+        "leadingAnchor", "trailingAnchor", "leftAnchor", "rightAnchor", "topAnchor", "bottomAnchor",
+        "widthAnchor", "heightAnchor", "centerXAnchor", "centerYAnchor", "firstBaselineAnchor",
+        "lastBaselineAnchor"
+    ], imp!(view_anchor, fn(id, Sel) -> id));
+
+    // NSControl, NSTextField and NSButton
+    b.add("NSControl", Instance, &["init"], imp!(control_init, fn(id, Sel) -> id));
+    b.add("NSControl", Instance, &[
+        "setStringValue:", "setFont:", "setTextColor:", "setBackgroundColor:", "setPlaceholderString:",
+        "setTarget:", "setTitle:", "setKeyEquivalent:", "setAttributedTitle:", "setImage:"
+    ], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSControl", Instance, &[
+        "stringValue", "font", "textColor", "backgroundColor", "placeholderString", "target", "title",
+        "keyEquivalent", "attributedTitle", "image"
+    ], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSControl", Instance, &[
+        "setAlignment:", "setLineBreakMode:", "setBezelStyle:", "setFocusRingType:", "setButtonType:",
+        "setState:", "setMaximumNumberOfLines:"
+    ], imp!(set_integer, fn(id, Sel, NSInteger)));
+    b.add("NSControl", Instance, &["alignment", "lineBreakMode", "bezelStyle", "state"], imp!(get_integer, fn(id, Sel) -> NSInteger));
+    b.add("NSControl", Instance, &[
+        "setEnabled:", "setEditable:", "setSelectable:", "setBezeled:", "setBordered:",
+        "setDrawsBackground:", "highlight:"
+    ], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSControl", Instance, &["isEnabled", "isEditable", "isSelectable", "isBezeled", "isBordered"], imp!(get_bool, fn(id, Sel) -> BOOL));
+    b.add("NSControl", Instance, &["setAction:"], imp!(set_selector, fn(id, Sel, Sel)));
+    b.add("NSTextField", Class, &["labelWithString:", "textFieldWithString:"], imp!(text_field_with_string, fn(id, Sel, id) -> id));
+    b.add("NSButton", Class, &["buttonWithTitle:target:action:"], imp!(button_with_title, fn(id, Sel, id, id, Sel) -> id));
+
+    // NSScrollView and NSTableView
+    b.add("NSScrollView", Instance, &["setDocumentView:"], imp!(scroll_view_set_document_view, fn(id, Sel, id)));
+    b.add("NSScrollView", Instance, &["documentView"], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSScrollView", Instance, &["setHasVerticalScroller:", "setHasHorizontalScroller:", "setDrawsBackground:"], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSScrollView", Instance, &["setBorderType:", "setHorizontalScrollElasticity:", "setVerticalScrollElasticity:"], imp!(set_integer, fn(id, Sel, NSInteger)));
+    b.add("NSTableView", Instance, &["setDelegate:", "setDataSource:", "setHeaderView:", "addTableColumn:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSTableView", Instance, &[
+        "setUsesAutomaticRowHeights:", "setFloatsGroupRows:", "setAllowsMultipleSelection:",
+        "setAllowsEmptySelection:"
+    ], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSTableView", Instance, &["setSelectionHighlightStyle:", "setColumnAutoresizingStyle:"], imp!(set_integer, fn(id, Sel, NSInteger)));
+    b.add("NSTableView", Instance, &["setRowHeight:"], imp!(set_float, fn(id, Sel, CGFloat)));
+    b.add("NSTableView", Instance, &["setIntercellSpacing:"], imp!(set_size, fn(id, Sel, CGSize)));
+    b.add("NSTableView", Instance, &["reloadData", "beginUpdates", "endUpdates"], imp!(no_op, fn(id, Sel)));
+
+    // NSWindow
+    b.add("NSWindow", Class, &["setAllowsAutomaticWindowTabbing:"], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSWindow", Instance, &["initWithContentRect:styleMask:backing:defer:"], imp!(window_init, fn(id, Sel, CGRect, NSUInteger, NSUInteger, BOOL) -> id));
+    b.add("NSWindow", Instance, &["setContentView:"], imp!(window_set_content_view, fn(id, Sel, id)));
+    b.add("NSWindow", Instance, &["setContentViewController:"], imp!(window_set_content_view_controller, fn(id, Sel, id)));
+    b.add("NSWindow", Instance, &[
+        "setTitle:", "setDelegate:", "setToolbar:", "setFrameAutosaveName:", "setBackgroundColor:",
+        "setAppearance:"
+    ], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSWindow", Instance, &[
+        "contentView", "contentViewController", "title", "delegate", "toolbar", "backgroundColor",
+        "attachedSheet"
+    ], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSWindow", Instance, &[
+        "setReleasedWhenClosed:", "setRestorable:", "setMovableByWindowBackground:",
+        "setTitlebarAppearsTransparent:", "setShowsToolbarButton:", "setExcludedFromWindowsMenu:",
+        "setOpaque:"
+    ], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSWindow", Instance, &[
+        "isVisible", "isKeyWindow", "isMainWindow", "isMiniaturized", "isOpaque", "isOnActiveSpace",
+        "isMovableByWindowBackground"
+    ], imp!(get_bool, fn(id, Sel) -> BOOL));
+    b.add("NSWindow", Instance, &["canBecomeKeyWindow", "canBecomeMainWindow"], imp!(yes, fn(id, Sel) -> BOOL));
+    b.add("NSWindow", Instance, &["setToolbarStyle:", "setTitleVisibility:", "setStyleMask:"], imp!(set_integer, fn(id, Sel, NSInteger)));
+    b.add("NSWindow", Instance, &["styleMask"], imp!(get_integer, fn(id, Sel) -> NSInteger));
+    b.add("NSWindow", Instance, &["setContentMinSize:", "setContentMaxSize:", "setContentSize:"], imp!(set_size, fn(id, Sel, CGSize)));
+    b.add("NSWindow", Instance, &["frame"], imp!(get_rect, fn(id, Sel) -> CGRect));
+    b.add("NSWindow", Instance, &["backingScaleFactor"], imp!(get_float, fn(id, Sel) -> CGFloat));
+    b.add("NSWindow", Instance, &[
+        "makeKeyAndOrderFront:", "orderFront:", "orderOut:", "miniaturize:", "deminiaturize:",
+        "toggleFullScreen:", "toggleToolbarShown:"
+    ], imp!(window_order, fn(id, Sel, id)));
+    b.add("NSWindow", Instance, &["close", "makeKeyWindow", "miniaturize", "deminiaturize"], imp!(window_state, fn(id, Sel)));
+    b.add("NSWindow", Instance, &["beginSheet:completionHandler:"], imp!(window_begin_sheet, fn(id, Sel, id, *mut c_void)));
+    b.add("NSWindow", Instance, &["endSheet:"], imp!(window_end_sheet, fn(id, Sel, id)));
+
+    // NSViewController and NSWindowController
+    b.add("NSApplication", Class, &["sharedApplication"], imp!(shared_application, fn(id, Sel) -> id));
+    b.add("NSViewController", Instance, &["setView:", "setTitle:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSViewController", Instance, &["view", "title"], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSWindowController", Instance, &["initWithWindow:"], imp!(window_controller_init, fn(id, Sel, id) -> id));
+    b.add("NSWindowController", Instance, &["setWindow:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSWindowController", Instance, &["window"], imp!(get_object, fn(id, Sel) -> id));
+
+    // Layout direction
+    b.add("NSView", Instance, &["setUserInterfaceLayoutDirection:"], imp!(set_integer, fn(id, Sel, NSInteger)));
+    b.add("NSView", Instance, &["userInterfaceLayoutDirection"], imp!(get_integer, fn(id, Sel) -> NSInteger));
+    b.add("NSApplication", Instance, &["userInterfaceLayoutDirection"], imp!(get_integer, fn(id, Sel) -> NSInteger));
+
+    // Localization. There's no app bundle, so lookups go to catalogs (or the English text).
+    b.add("NSBundle", Class, &["mainBundle"], imp!(return_nil, fn(id, Sel) -> id));
+    b.add("NSLocale", Class, &["preferredLanguages"], imp!(preferred_languages, fn(id, Sel) -> id));
+
+    // Accessibility
+    b.add("NSView", Instance, &[
+        "setAccessibilityLabel:", "setAccessibilityHelp:", "setAccessibilityRole:", "setAccessibilityValue:",
+        "setAccessibilityIdentifier:", "setAccessibilityCustomActions:", "setAccessibilityChildrenInNavigationOrder:"
+    ], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSView", Instance, &[
+        "accessibilityLabel", "accessibilityHelp", "accessibilityRole", "accessibilityValue",
+        "accessibilityIdentifier", "accessibilityCustomActions", "accessibilityChildrenInNavigationOrder"
+    ], imp!(get_object, fn(id, Sel) -> id));
+    b.add("NSView", Instance, &["setAccessibilityElement:"], imp!(set_bool, fn(id, Sel, BOOL)));
+    b.add("NSView", Instance, &["isAccessibilityElement"], imp!(get_bool, fn(id, Sel) -> BOOL));
+    b.add("NSAccessibilityCustomAction", Instance, &["initWithName:handler:"], imp!(custom_action_init, fn(id, Sel, id, *const c_void) -> id));
+    b.add("NSAccessibilityCustomAction", Instance, &["name"], imp!(get_object, fn(id, Sel) -> id));
+
+    // Anchors and constraints
+    b.add("NSLayoutAnchor", Instance, &[
+        "constraintEqualToAnchor:", "constraintGreaterThanOrEqualToAnchor:",
+        "constraintLessThanOrEqualToAnchor:"
+    ], imp!(anchor_constraint_to_anchor, fn(id, Sel, id) -> id));
+    b.add("NSLayoutAnchor", Instance, &[
+        "constraintEqualToAnchor:constant:", "constraintGreaterThanOrEqualToAnchor:constant:",
+        "constraintLessThanOrEqualToAnchor:constant:", "constraintEqualToAnchor:multiplier:",
+        "constraintGreaterThanOrEqualToAnchor:multiplier:", "constraintLessThanOrEqualToAnchor:multiplier:"
+    ], imp!(anchor_constraint_to_anchor_with, fn(id, Sel, id, CGFloat) -> id));
+    b.add("NSLayoutDimension", Instance, &[
+        "constraintEqualToConstant:", "constraintGreaterThanOrEqualToConstant:",
+        "constraintLessThanOrEqualToConstant:"
+    ], imp!(anchor_constraint_to_constant, fn(id, Sel, CGFloat) -> id));
+    b.add("NSLayoutConstraint", Class, &["constraintWithItem:attribute:relatedBy:toItem:attribute:multiplier:constant:"],
+        imp!(constraint_with_item, fn(id, Sel, id, NSInteger, NSInteger, id, NSInteger, CGFloat, CGFloat) -> id));
+    b.add("NSLayoutConstraint", Class, &["activateConstraints:", "deactivateConstraints:"], imp!(constraint_activate_all, fn(id, Sel, id)));
+    b.add("NSLayoutConstraint", Instance, &["setActive:"], imp!(constraint_set_active, fn(id, Sel, BOOL)));
+    b.add("NSLayoutConstraint", Instance, &["isActive"], imp!(constraint_is_active, fn(id, Sel) -> BOOL));
+    b.add("NSLayoutConstraint", Instance, &["setConstant:"], imp!(constraint_set_constant, fn(id, Sel, CGFloat)));
+    b.add("NSLayoutConstraint", Instance, &["constant"], imp!(constraint_constant, fn(id, Sel) -> CGFloat));
+    b.add("NSLayoutConstraint", Instance, &["setPriority:"], imp!(constraint_set_priority, fn(id, Sel, f32)));
+    b.add("NSLayoutConstraint", Instance, &["priority"], imp!(constraint_priority, fn(id, Sel) -> f32));
+    b.add("NSLayoutConstraint", Instance, &["firstItem", "secondItem"], imp!(constraint_item, fn(id, Sel) -> id));
+    b.add("NSLayoutConstraint", Instance, &["firstAttribute", "secondAttribute", "relation"], imp!(constraint_attribute, fn(id, Sel) -> NSInteger));
+    b.add("NSLayoutConstraint", Instance, &["multiplier"], imp!(constraint_multiplier, fn(id, Sel) -> CGFloat));
+    b.add("NSLayoutConstraint", Instance, &["setIdentifier:"], imp!(set_object, fn(id, Sel, id)));
+    b.add("NSLayoutConstraint", Instance, &["identifier"], imp!(get_object, fn(id, Sel) -> id));
+
+    b
+}
+
+/// Returns the property key for an accessor: `setStringValue:` and `stringValue` both map to
+/// `stringValue`, and `setHidden:` and `isHidden` both map to `hidden`. Anything else is just
+/// the selector, minus its trailing colon.
+pub(crate) fn property_key(selector: &str) -> String {
+    let name = selector.trim_end_matches(':');
+
+    for prefix in &["set", "is"] {
+        if let Some(rest) = name.strip_prefix(prefix) {
+            let mut chars = rest.chars();
+
+            if let Some(first) = chars.next() {
+                if first.is_ascii_uppercase() {
+                    return first.to_ascii_lowercase().to_string() + chars.as_str();
+                }
+            }
+        }
+    }
+
+    name.to_string()
+}
+
+fn key(sel: Sel) -> String {
+    property_key(runtime::selector_name(sel))
+}
+
+fn to_bool(value: BOOL) -> bool {
+    value != NO
+}
+
+fn from_bool(value: bool) -> BOOL {
+    match value {
+        true => YES,
+        false => NO
+    }
+}
+
+/// Allocates and records an instance of `class`. The caller must be holding the store's lock.
+fn instance_of(store: &mut Store, class: &'static MockClass) -> id {
+    let object = runtime::allocate(class);
+    store.insert(object);
+    object
+}
+
+/// Allocates and records an instance of the class called `name`.
+fn instance_named(store: &mut Store, name: &str) -> id {
+    instance_of(store, runtime::class_named(name))
+}
+
+/// Allocates and records an `NSString`.
+fn new_string(store: &mut Store, value: &str) -> id {
+    let string = instance_named(store, "NSString");
+    store.set_payload(string as usize, Payload::String(c_string(value)));
+    string
+}
+
+fn c_string(value: &str) -> CString {
+    CString::new(value.replace('\0', "")).unwrap()
+}
+
+/// Returns the contents of `object`, if it's a string.
+pub(crate) fn string_value(store: &Store, object: usize) -> Option<String> {
+    match store.payload(object) {
+        Payload::String(s) => Some(s.to_string_lossy().into_owned()),
+        _ => None
+    }
+}
+
+fn class_receiver(this: id) -> &'static MockClass {
+    unsafe { &*(this as *const MockClass) }
+}
+
+extern fn record_unhandled(this: id, sel: Sel) -> id {
+    let class = runtime::class_of(this);
+    let prefix = match class.is_meta() {
+        true => "+",
+        false => "-"
+    };
+
+    let message = format!("{}[{} {}]", prefix, class.name(), runtime::selector_name(sel));
+    STORE.lock().unwrap().unhandled.insert(message);
+    nil
+}
+
+extern fn no_op(_this: id, _sel: Sel) {}
+
+extern fn yes(_this: id, _sel: Sel) -> BOOL {
+    YES
+}
+
+extern fn return_nil(_this: id, _sel: Sel) -> id {
+    nil
+}
+
+extern fn return_self(this: id, _sel: Sel) -> id {
+    this
+}
+
+extern fn alloc(this: id, _sel: Sel) -> id {
+    instance_of(&mut STORE.lock().unwrap(), class_receiver(this))
+}
+
+extern fn alloc_with_zone(this: id, sel: Sel, _zone: *mut c_void) -> id {
+    alloc(this, sel)
+}
+
+extern fn new(this: id, sel: Sel) -> id {
+    let object = alloc(this, sel);
+    unsafe { msg_send![object, init] }
+}
+
+extern fn class(this: id, _sel: Sel) -> id {
+    runtime::class_of(this) as *const MockClass as id
+}
+
+extern fn retain(this: id, _sel: Sel) -> id {
+    STORE.lock().unwrap().retain(this);
+    this
+}
+
+extern fn release(this: id, _sel: Sel) {
+    STORE.lock().unwrap().release(this);
+}
+
+extern fn is_kind_of_class(this: id, _sel: Sel, class: id) -> BOOL {
+    let mut current = Some(runtime::class_of(this));
+
+    while let Some(c) = current {
+        if c as *const MockClass as id == class {
+            return YES;
+        }
+
+        current = c.superclass();
+    }
+
+    NO
+}
+
+extern fn is_member_of_class(this: id, _sel: Sel, class: id) -> BOOL {
+    from_bool(runtime::class_of(this) as *const MockClass as id == class)
+}
+
+extern fn responds_to_selector(this: id, _sel: Sel, selector: Sel) -> BOOL {
+    from_bool(runtime::responds_to(runtime::class_of(this), selector))
+}
+
+extern fn is_equal(this: id, _sel: Sel, other: id) -> BOOL {
+    let store = STORE.lock().unwrap();
+
+    match (string_value(&store, this as usize), string_value(&store, other as usize)) {
+        (Some(a), Some(b)) => from_bool(a == b),
+        _ => from_bool(this == other)
+    }
+}
+
+extern fn hash(this: id, _sel: Sel) -> NSUInteger {
+    this as NSUInteger
+}
+
+extern fn get_bool(this: id, sel: Sel) -> BOOL {
+    match STORE.lock().unwrap().property(this as usize, &key(sel)) {
+        Some(Property::Bool(value)) => from_bool(*value),
+        _ => NO
+    }
+}
+
+extern fn set_bool(this: id, sel: Sel, value: BOOL) {
+    STORE.lock().unwrap().set(this as usize, &key(sel), Property::Bool(to_bool(value)));
+}
+
+extern fn get_integer(this: id, sel: Sel) -> NSInteger {
+    match STORE.lock().unwrap().property(this as usize, &key(sel)) {
+        Some(Property::Integer(value)) => *value as NSInteger,
+        _ => 0
+    }
+}
+
+extern fn set_integer(this: id, sel: Sel, value: NSInteger) {
+    STORE.lock().unwrap().set(this as usize, &key(sel), Property::Integer(value as i64));
+}
+
+extern fn get_float(this: id, sel: Sel) -> CGFloat {
+    match STORE.lock().unwrap().property(this as usize, &key(sel)) {
+        Some(Property::Float(value)) => *value as CGFloat,
+        _ => 0.
+    }
+}
+
+extern fn set_float(this: id, sel: Sel, value: CGFloat) {
+    STORE.lock().unwrap().set(this as usize, &key(sel), Property::Float(value as f64));
+}
+
+extern fn get_object(this: id, sel: Sel) -> id {
+    match STORE.lock().unwrap().property(this as usize, &key(sel)) {
+        Some(Property::Object(value)) => *value as id,
+        _ => nil
+    }
+}
+
+extern fn set_object(this: id, sel: Sel, value: id) {
+    STORE.lock().unwrap().set(this as usize, &key(sel), Property::Object(value as usize));
+}
+
+extern fn set_selector(this: id, sel: Sel, value: Sel) {
+    let name = match value.as_ptr().is_null() {
+        true => String::new(),
+        false => runtime::selector_name(value).to_string()
+    };
+
+    STORE.lock().unwrap().set(this as usize, &key(sel), Property::Selector(name));
+}
+
+extern fn set_size(this: id, sel: Sel, value: CGSize) {
+    let size = Property::Size(value.width as f64, value.height as f64);
+    STORE.lock().unwrap().set(this as usize, &key(sel), size);
+}
+
+extern fn get_rect(this: id, sel: Sel) -> CGRect {
+    match STORE.lock().unwrap().property(this as usize, &key(sel)) {
+        Some(Property::Rect(rect)) => CGRect::new(
+            &CGPoint::new(rect.left, rect.top),
+            &CGSize::new(rect.width, rect.height)
+        ),
+
+        _ => CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(0., 0.))
+    }
+}
+
+extern fn set_rect(this: id, sel: Sel, value: CGRect) {
+    STORE.lock().unwrap().set(this as usize, &key(sel), Property::Rect(rect_from(value)));
+}
+
+fn rect_from(rect: CGRect) -> Rect {
+    Rect::new(rect.origin.y as f64, rect.origin.x as f64, rect.size.width as f64, rect.size.height as f64)
+}
+
+extern fn string_with_utf8_string(this: id, _sel: Sel, bytes: *const c_char) -> id {
+    let mut store = STORE.lock().unwrap();
+    let string = instance_of(&mut store, class_receiver(this));
+
+    if !bytes.is_null() {
+        let value = unsafe { CStr::from_ptr(bytes) }.to_owned();
+        store.set_payload(string as usize, Payload::String(value));
+    }
+
+    string
+}
+
+extern fn string_init_with_bytes(this: id, _sel: Sel, bytes: *const u8, length: NSUInteger, _encoding: NSUInteger) -> id {
+    let value = match bytes.is_null() {
+        true => String::new(),
+        false => String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(bytes, length as usize) }).into_owned()
+    };
+
+    STORE.lock().unwrap().set_payload(this as usize, Payload::String(c_string(&value)));
+    this
+}
+
+extern fn string_utf8(this: id, _sel: Sel) -> *const c_char {
+    let store = STORE.lock().unwrap();
+
+    // Records are never removed, so the pointer stays valid for as long as the string does.
+    match store.payload(this as usize) {
+        Payload::String(s) => s.as_ptr(),
+        _ => b"\0".as_ptr() as *const c_char
+    }
+}
+
+extern fn string_byte_length(this: id, _sel: Sel, _encoding: NSUInteger) -> NSUInteger {
+    match STORE.lock().unwrap().payload(this as usize) {
+        Payload::String(s) => s.as_bytes().len() as NSUInteger,
+        _ => 0
+    }
+}
+
+extern fn string_length(this: id, _sel: Sel) -> NSUInteger {
+    let store = STORE.lock().unwrap();
+    string_value(&store, this as usize).map_or(0, |s| s.encode_utf16().count() as NSUInteger)
+}
+
+extern fn string_is_equal(this: id, _sel: Sel, other: id) -> BOOL {
+    let store = STORE.lock().unwrap();
+    from_bool(string_value(&store, this as usize) == string_value(&store, other as usize))
+}
+
+extern fn array(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+    let array = instance_of(&mut store, class_receiver(this));
+    store.set_payload(array as usize, Payload::Array(Vec::new()));
+    array
+}
+
+extern fn array_with_objects(this: id, _sel: Sel, objects: *const id, count: NSUInteger) -> id {
+    let items = match objects.is_null() {
+        true => Vec::new(),
+        false => unsafe { std::slice::from_raw_parts(objects, count as usize) }.iter().map(|o| *o as usize).collect()
+    };
+
+    let mut store = STORE.lock().unwrap();
+    let array = instance_of(&mut store, class_receiver(this));
+    store.set_payload(array as usize, Payload::Array(items));
+    array
+}
+
+extern fn array_count(this: id, _sel: Sel) -> NSUInteger {
+    match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Array(items) => items.len() as NSUInteger,
+        _ => 0
+    }
+}
+
+extern fn array_object_at_index(this: id, _sel: Sel, index: NSUInteger) -> id {
+    match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Array(items) => items.get(index as usize).map_or(nil, |item| *item as id),
+        _ => nil
+    }
+}
+
+extern fn array_add_object(this: id, _sel: Sel, object: id) {
+    let mut store = STORE.lock().unwrap();
+
+    let mut items = match store.payload(this as usize) {
+        Payload::Array(items) => items.clone(),
+        _ => Vec::new()
+    };
+
+    items.push(object as usize);
+    store.set_payload(this as usize, Payload::Array(items));
+}
+
+extern fn dictionary_object_for_key(this: id, _sel: Sel, key: id) -> id {
+    let store = STORE.lock().unwrap();
+
+    match string_value(&store, key as usize).and_then(|key| store.property(this as usize, &key)) {
+        Some(Property::Object(value)) => *value as id,
+        _ => nil
+    }
+}
+
+/// Dictionaries keep their entries as properties, so they show up when inspected. Only string
+/// keys are supported.
+extern fn dictionary_set_object(this: id, _sel: Sel, object: id, key: id) {
+    let mut store = STORE.lock().unwrap();
+
+    if let Some(key) = string_value(&store, key as usize) {
+        store.set(this as usize, &key, Property::Object(object as usize));
+    }
+}
+
+/// Returns a new array of the keys `setObject:forKey:` stored, in no particular order (like the
+/// real thing).
+extern fn dictionary_all_keys(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    let keys: Vec<String> = match store.get(this as usize) {
+        Some(record) => record.properties.iter()
+            .filter(|(_, value)| match value {
+                Property::Object(_) => true,
+                _ => false
+            })
+            .map(|(key, _)| key.clone())
+            .collect(),
+
+        None => Vec::new()
+    };
+
+    let items = keys.iter().map(|key| new_string(&mut store, key) as usize).collect();
+    let array = instance_named(&mut store, "NSArray");
+    store.set_payload(array as usize, Payload::Array(items));
+    array
+}
+
+extern fn value_with_rect(_this: id, _sel: Sel, rect: CGRect) -> id {
+    let mut store = STORE.lock().unwrap();
+    let value = instance_named(&mut store, "NSValue");
+    store.set_payload(value as usize, Payload::Value(Property::Rect(rect_from(rect))));
+    value
+}
+
+extern fn value_objc_type(this: id, _sel: Sel) -> *const c_char {
+    let encoding: &'static [u8] = match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Value(Property::Rect(_)) => b"{CGRect={CGPoint=dd}{CGSize=dd}}\0",
+        _ => b"v\0"
+    };
+
+    encoding.as_ptr() as *const c_char
+}
+
+extern fn value_get_value(this: id, _sel: Sel, buffer: *mut c_void, size: NSUInteger) {
+    let rect = match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Value(Property::Rect(rect)) => *rect,
+        _ => { return; }
+    };
+
+    if buffer.is_null() || (size as usize) < mem::size_of::<CGRect>() {
+        return;
+    }
+
+    let rect = CGRect::new(&CGPoint::new(rect.left, rect.top), &CGSize::new(rect.width, rect.height));
+    unsafe { *(buffer as *mut CGRect) = rect; }
+}
+
+fn new_number(class: &str, value: Property) -> id {
+    let mut store = STORE.lock().unwrap();
+    let number = instance_named(&mut store, class);
+    store.set_payload(number as usize, Payload::Number(value));
+    number
+}
+
+extern fn number_with_bool(_this: id, _sel: Sel, value: BOOL) -> id {
+    // Like the real thing, booleans are a distinct subclass - which is how `NSNumber` tells them
+    // apart from integers.
+    new_number("__NSCFBoolean", Property::Bool(to_bool(value)))
+}
+
+extern fn number_with_integer(_this: id, _sel: Sel, value: NSInteger) -> id {
+    new_number("NSNumber", Property::Integer(value as i64))
+}
+
+extern fn number_with_unsigned_integer(_this: id, _sel: Sel, value: u64) -> id {
+    new_number("NSNumber", Property::Integer(value as i64))
+}
+
+extern fn number_with_double(_this: id, _sel: Sel, value: f64) -> id {
+    new_number("NSNumber", Property::Float(value))
+}
+
+fn number_value(this: id) -> f64 {
+    match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Number(Property::Bool(value)) => match value {
+            true => 1.,
+            false => 0.
+        },
+
+        Payload::Number(Property::Integer(value)) => *value as f64,
+        Payload::Number(Property::Float(value)) => *value,
+        _ => 0.
+    }
+}
+
+extern fn number_bool_value(this: id, _sel: Sel) -> BOOL {
+    from_bool(number_value(this) != 0.)
+}
+
+extern fn number_integer_value(this: id, _sel: Sel) -> NSInteger {
+    match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Number(Property::Integer(value)) => *value as NSInteger,
+        _ => number_value(this) as NSInteger
+    }
+}
+
+extern fn number_unsigned_value(this: id, sel: Sel) -> u64 {
+    number_integer_value(this, sel) as u64
+}
+
+extern fn number_double_value(this: id, _sel: Sel) -> f64 {
+    number_value(this)
+}
+
+extern fn number_float_value(this: id, _sel: Sel) -> f32 {
+    number_value(this) as f32
+}
+
+extern fn number_objc_type(this: id, _sel: Sel) -> *const c_char {
+    let encoding: &'static [u8] = match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Number(Property::Bool(_)) => b"c\0",
+        Payload::Number(Property::Float(_)) => b"d\0",
+        _ => b"q\0"
+    };
+
+    encoding.as_ptr() as *const c_char
+}
+
+extern fn system_color(this: id, sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+    let color = instance_of(&mut store, class_receiver(this));
+    store.set_payload(color as usize, Payload::SystemColor(runtime::selector_name(sel).to_string()));
+    color
+}
+
+extern fn color_with_components(this: id, sel: Sel, red: CGFloat, green: CGFloat, blue: CGFloat, alpha: CGFloat) -> id {
+    let rgba = Rgba::new(red as f64, green as f64, blue as f64, alpha as f64);
+    let value = match runtime::selector_name(sel).starts_with("colorWithDisplayP3") {
+        true => ColorValue::DisplayP3(rgba),
+        false => ColorValue::Srgb(rgba)
+    };
+
+    let mut store = STORE.lock().unwrap();
+    let color = instance_of(&mut store, class_receiver(this));
+    store.set_payload(color as usize, Payload::Color(value));
+    color
+}
+
+extern fn color_with_hsb(this: id, _sel: Sel, hue: CGFloat, saturation: CGFloat, brightness: CGFloat, alpha: CGFloat) -> id {
+    let value = ColorValue::Hsb {
+        hue: hue as f64 * 360.,
+        saturation: saturation as f64,
+        brightness: brightness as f64,
+        alpha: alpha as f64
+    };
+
+    let mut store = STORE.lock().unwrap();
+    let color = instance_of(&mut store, class_receiver(this));
+    store.set_payload(color as usize, Payload::Color(value));
+    color
+}
+
+extern fn color_with_white(this: id, _sel: Sel, white: CGFloat, alpha: CGFloat) -> id {
+    let value = ColorValue::Gray {
+        white: white as f64,
+        alpha: alpha as f64
+    };
+
+    let mut store = STORE.lock().unwrap();
+    let color = instance_of(&mut store, class_receiver(this));
+    store.set_payload(color as usize, Payload::Color(value));
+    color
+}
+
+extern fn color_with_cg_color(_this: id, _sel: Sel, color: id) -> id {
+    // `CGColor` hands back the color itself, so there's nothing to convert.
+    color
+}
+
+extern fn color_using_color_space(this: id, _sel: Sel, _space: id) -> id {
+    // System colors depend on an appearance, which there isn't one of here.
+    match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Color(_) => this,
+        _ => nil
+    }
+}
+
+extern fn color_component(this: id, sel: Sel) -> CGFloat {
+    let rgba = match STORE.lock().unwrap().payload(this as usize) {
+        Payload::Color(color) => color.to_srgb(),
+        _ => return 0.
+    };
+
+    let component = match runtime::selector_name(sel) {
+        "redComponent" => rgba.red,
+        "greenComponent" => rgba.green,
+        "blueComponent" => rgba.blue,
+        _ => rgba.alpha
+    };
+
+    component as CGFloat
+}
+
+extern fn color_description(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    let description = match store.payload(this as usize) {
+        Payload::SystemColor(name) => name.clone(),
+        _ => runtime::class_of(this).name().to_string()
+    };
+
+    new_string(&mut store, &description)
+}
+
+extern fn font_of_size(this: id, sel: Sel, size: CGFloat) -> id {
+    let mut store = STORE.lock().unwrap();
+    let font = instance_of(&mut store, class_receiver(this));
+    store.set(font as usize, "pointSize", Property::Float(size as f64));
+    store.set(font as usize, "bold", Property::Bool(runtime::selector_name(sel).starts_with("bold")));
+    font
+}
+
+extern fn font_of_size_and_weight(this: id, sel: Sel, size: CGFloat, weight: CGFloat) -> id {
+    let font = font_of_size(this, sel, size);
+    STORE.lock().unwrap().set(font as usize, "weight", Property::Float(weight as f64));
+    font
+}
+
+extern fn default_font_size(_this: id, _sel: Sel) -> CGFloat {
+    13.
+}
+
+extern fn view_init(this: id, sel: Sel) -> id {
+    view_init_with_frame(this, sel, CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(0., 0.)))
+}
+
+extern fn view_init_with_frame(this: id, _sel: Sel, frame: CGRect) -> id {
+    let mut store = STORE.lock().unwrap();
+    store.set(this as usize, "frame", Property::Rect(rect_from(frame)));
+    store.set(this as usize, "alphaValue", Property::Float(1.));
+    store.set(this as usize, "hidden", Property::Bool(false));
+    store.set(this as usize, "translatesAutoresizingMaskIntoConstraints", Property::Bool(true));
+    this
+}
+
+extern fn control_init(this: id, sel: Sel) -> id {
+    view_init(this, sel);
+    STORE.lock().unwrap().set(this as usize, "enabled", Property::Bool(true));
+    this
+}
+
+extern fn view_layer(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    if let Some(Property::Object(layer)) = store.property(this as usize, "layer") {
+        return *layer as id;
+    }
+
+    let layer = instance_named(&mut store, "CALayer");
+    store.set(this as usize, "layer", Property::Object(layer as usize));
+    layer
+}
+
+extern fn view_add_subview(this: id, _sel: Sel, view: id) {
+    STORE.lock().unwrap().add_subview(this as usize, view as usize);
+}
+
+extern fn view_remove_from_superview(this: id, _sel: Sel) {
+    STORE.lock().unwrap().remove_from_superview(this as usize);
+}
+
+extern fn view_superview(this: id, _sel: Sel) -> id {
+    STORE.lock().unwrap().get(this as usize).and_then(|record| record.superview).map_or(nil, |view| view as id)
+}
+
+extern fn view_subviews(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+    let subviews = store.get(this as usize).map_or(Vec::new(), |record| record.subviews.clone());
+
+    let array = instance_named(&mut store, "NSArray");
+    store.set_payload(array as usize, Payload::Array(subviews));
+    array
+}
+
+extern fn view_window(this: id, _sel: Sel) -> id {
+    let store = STORE.lock().unwrap();
+    let mut current = Some(this as usize);
+
+    while let Some(view) = current {
+        if let Some(Property::Object(window)) = store.property(view, "window") {
+            return *window as id;
+        }
+
+        current = store.get(view).and_then(|record| record.superview);
+    }
+
+    nil
+}
+
+/// Returns `view` and each of its superviews, nearest first.
+fn ancestors(store: &Store, view: usize) -> Vec<usize> {
+    let mut ancestors = vec![view];
+
+    while let Some(superview) = store.get(*ancestors.last().unwrap()).and_then(|record| record.superview) {
+        ancestors.push(superview);
+    }
+
+    ancestors
+}
+
+/// Returns the active constraints installed on `this`: like AppKit, a constraint is installed on
+/// the nearest common ancestor of the views it relates.
+extern fn view_constraints(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    let installed: Vec<usize> = store.active_constraints.iter().cloned().filter(|constraint| {
+        let record = match store.payload(*constraint) {
+            Payload::Constraint(record) => *record,
+            _ => return false
+        };
+
+        let first = ancestors(&store, record.first.item.0);
+        let owner = match record.second {
+            Some(second) => {
+                let second = ancestors(&store, second.item.0);
+                first.into_iter().find(|view| second.contains(view))
+            },
+
+            None => Some(record.first.item.0)
+        };
+
+        owner == Some(this as usize)
+    }).collect();
+
+    let array = instance_named(&mut store, "NSArray");
+    store.set_payload(array as usize, Payload::Array(installed));
+    array
+}
+
+extern fn view_anchor(this: id, sel: Sel) -> id {
+    let (attribute, class) = match runtime::selector_name(sel) {
+        "leadingAnchor" => (LayoutAttribute::Leading, "NSLayoutXAxisAnchor"),
+        "trailingAnchor" => (LayoutAttribute::Trailing, "NSLayoutXAxisAnchor"),
+        "leftAnchor" => (LayoutAttribute::Left, "NSLayoutXAxisAnchor"),
+        "rightAnchor" => (LayoutAttribute::Right, "NSLayoutXAxisAnchor"),
+        "centerXAnchor" => (LayoutAttribute::CenterX, "NSLayoutXAxisAnchor"),
+        "topAnchor" => (LayoutAttribute::Top, "NSLayoutYAxisAnchor"),
+        "bottomAnchor" => (LayoutAttribute::Bottom, "NSLayoutYAxisAnchor"),
+        "centerYAnchor" => (LayoutAttribute::CenterY, "NSLayoutYAxisAnchor"),
+        "firstBaselineAnchor" => (LayoutAttribute::FirstBaseline, "NSLayoutYAxisAnchor"),
+        "lastBaselineAnchor" => (LayoutAttribute::LastBaseline, "NSLayoutYAxisAnchor"),
+        "widthAnchor" => (LayoutAttribute::Width, "NSLayoutDimension"),
+        _ => (LayoutAttribute::Height, "NSLayoutDimension")
+    };
+
+    let mut store = STORE.lock().unwrap();
+    let anchor = instance_named(&mut store, class);
+    let record = AnchorRecord::new(LayoutItem(this as usize), attribute);
+    store.set_payload(anchor as usize, Payload::Anchor(record));
+    anchor
+}
+
+extern fn text_field_with_string(this: id, sel: Sel, string: id) -> id {
+    let field: id = unsafe { msg_send![this, new] };
+    let is_label = runtime::selector_name(sel) == "labelWithString:";
+
+    let mut store = STORE.lock().unwrap();
+    store.set(field as usize, "stringValue", Property::Object(string as usize));
+    store.set(field as usize, "editable", Property::Bool(!is_label));
+    store.set(field as usize, "selectable", Property::Bool(!is_label));
+    store.set(field as usize, "bezeled", Property::Bool(!is_label));
+    store.set(field as usize, "drawsBackground", Property::Bool(!is_label));
+    field
+}
+
+extern fn button_with_title(this: id, _sel: Sel, title: id, target: id, action: Sel) -> id {
+    let button: id = unsafe { msg_send![this, new] };
+
+    let mut store = STORE.lock().unwrap();
+    store.set(button as usize, "title", Property::Object(title as usize));
+    store.set(button as usize, "target", Property::Object(target as usize));
+    store.set(button as usize, "bordered", Property::Bool(true));
+    drop(store);
+
+    set_selector(button, sel!(setAction:), action);
+    button
+}
+
+extern fn scroll_view_set_document_view(this: id, sel: Sel, view: id) {
+    let mut store = STORE.lock().unwrap();
+
+    if let Some(Property::Object(previous)) = store.property(this as usize, "documentView").cloned() {
+        store.remove_from_superview(previous);
+    }
+
+    store.set(this as usize, &key(sel), Property::Object(view as usize));
+
+    if view != nil {
+        store.add_subview(this as usize, view as usize);
+    }
+}
+
+extern fn window_init(this: id, _sel: Sel, rect: CGRect, style: NSUInteger, _backing: NSUInteger, _defer: BOOL) -> id {
+    {
+        let mut store = STORE.lock().unwrap();
+        store.set(this as usize, "frame", Property::Rect(rect_from(rect)));
+        store.set(this as usize, "styleMask", Property::Integer(style as i64));
+        store.set(this as usize, "backingScaleFactor", Property::Float(1.));
+        store.set(this as usize, "visible", Property::Bool(false));
+        store.windows.push(this as usize);
+    }
+
+    // Windows come with a content view, which is created (and so recorded) like any other view.
+    let content_view: id = unsafe { msg_send![runtime::class_named("NSView") as *const MockClass as id, new] };
+    window_set_content_view(this, sel!(setContentView:), content_view);
+    this
+}
+
+extern fn window_set_content_view(this: id, _sel: Sel, view: id) {
+    let mut store = STORE.lock().unwrap();
+
+    if let Some(Property::Object(previous)) = store.property(this as usize, "contentView").cloned() {
+        store.set(previous, "window", Property::Object(0));
+    }
+
+    store.set(this as usize, "contentView", Property::Object(view as usize));
+
+    if view != nil {
+        store.remove_from_superview(view as usize);
+        store.set(view as usize, "window", Property::Object(this as usize));
+    }
+}
+
+extern fn window_set_content_view_controller(this: id, sel: Sel, controller: id) {
+    let view = {
+        let mut store = STORE.lock().unwrap();
+        store.set(this as usize, &key(sel), Property::Object(controller as usize));
+
+        match store.property(controller as usize, "view") {
+            Some(Property::Object(view)) => *view as id,
+            _ => nil
+        }
+    };
+
+    if view != nil {
+        window_set_content_view(this, sel!(setContentView:), view);
+    }
+}
+
+extern fn window_order(this: id, sel: Sel, _sender: id) {
+    window_state(this, sel);
+}
+
+extern fn window_state(this: id, sel: Sel) {
+    let mut store = STORE.lock().unwrap();
+    let window = this as usize;
+
+    let is_full_screen = match store.property(window, "fullScreen") {
+        Some(Property::Bool(value)) => *value,
+        _ => false
+    };
+
+    let changes: &[(&str, bool)] = match runtime::selector_name(sel) {
+        "makeKeyAndOrderFront:" => &[("visible", true), ("keyWindow", true), ("mainWindow", true)],
+        "orderFront:" => &[("visible", true)],
+        "orderOut:" | "close" => &[("visible", false), ("keyWindow", false), ("mainWindow", false)],
+        "makeKeyWindow" => &[("keyWindow", true)],
+        "miniaturize:" | "miniaturize" => &[("miniaturized", true)],
+        "deminiaturize:" | "deminiaturize" => &[("miniaturized", false)],
+        "toggleFullScreen:" => match is_full_screen {
+            true => &[("fullScreen", false)],
+            false => &[("fullScreen", true)]
+        },
+        _ => &[]
+    };
+
+    for (key, value) in changes {
+        store.set(window, key, Property::Bool(*value));
+    }
+}
+
+extern fn window_begin_sheet(this: id, _sel: Sel, sheet: id, _completion: *mut c_void) {
+    let mut store = STORE.lock().unwrap();
+    store.set(this as usize, "attachedSheet", Property::Object(sheet as usize));
+    store.set(sheet as usize, "visible", Property::Bool(true));
+}
+
+extern fn window_end_sheet(this: id, _sel: Sel, sheet: id) {
+    let mut store = STORE.lock().unwrap();
+    store.set(this as usize, "attachedSheet", Property::Object(0));
+    store.set(sheet as usize, "visible", Property::Bool(false));
+}
+
+extern fn window_controller_init(this: id, _sel: Sel, window: id) -> id {
+    STORE.lock().unwrap().set(this as usize, "window", Property::Object(window as usize));
+    this
+}
+
+extern fn shared_application(this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    match store.application {
+        Some(application) => application as id,
+        None => {
+            let application = instance_of(&mut store, class_receiver(this));
+            store.application = Some(application as usize);
+            application
+        }
+    }
+}
+
+extern fn preferred_languages(_this: id, _sel: Sel) -> id {
+    let mut store = STORE.lock().unwrap();
+    let english = new_string(&mut store, "en");
+    let languages = instance_named(&mut store, "NSArray");
+    store.set_payload(languages as usize, Payload::Array(vec![english as usize]));
+    languages
+}
+
+/// Custom actions hold on to a copy of their handler, kept as the `handler` property; see
+/// `headless::perform_accessibility_action`.
+extern fn custom_action_init(this: id, _sel: Sel, name: id, handler: *const c_void) -> id {
+    let handler = unsafe { blocks::_Block_copy(handler) };
+
+    let mut store = STORE.lock().unwrap();
+    store.set(this as usize, "name", Property::Object(name as usize));
+    store.set(this as usize, "handler", Property::Object(handler as usize));
+    this
+}
+
+/// Returns the relation a constraint-building selector describes.
+fn relation(selector: &str) -> LayoutRelation {
+    match selector {
+        s if s.starts_with("constraintGreaterThanOrEqual") => LayoutRelation::GreaterThanOrEqual,
+        s if s.starts_with("constraintLessThanOrEqual") => LayoutRelation::LessThanOrEqual,
+        _ => LayoutRelation::Equal
+    }
+}
+
+fn new_constraint(store: &mut Store, record: ConstraintRecord) -> id {
+    let constraint = instance_named(store, "NSLayoutConstraint");
+    store.set_payload(constraint as usize, Payload::Constraint(record));
+    constraint
+}
+
+fn anchor_record(store: &Store, anchor: id) -> Option<AnchorRecord> {
+    match store.payload(anchor as usize) {
+        Payload::Anchor(record) => Some(*record),
+        _ => None
+    }
+}
+
+extern fn anchor_constraint_to_anchor(this: id, sel: Sel, other: id) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    match anchor_record(&store, this) {
+        Some(first) => {
+            let second = anchor_record(&store, other);
+            let record = ConstraintRecord::new(first, relation(runtime::selector_name(sel)), second);
+            new_constraint(&mut store, record)
+        },
+
+        None => nil
+    }
+}
+
+extern fn anchor_constraint_to_anchor_with(this: id, sel: Sel, other: id, value: CGFloat) -> id {
+    let constraint = anchor_constraint_to_anchor(this, sel, other);
+    let is_multiplier = runtime::selector_name(sel).ends_with("multiplier:");
+
+    let mut store = STORE.lock().unwrap();
+
+    if let Some(record) = store.get_mut(constraint as usize) {
+        if let Payload::Constraint(c) = &mut record.payload {
+            match is_multiplier {
+                true => { c.multiplier = value as f64; },
+                false => { c.offset = value as f64; }
+            }
+        }
+    }
+
+    constraint
+}
+
+extern fn anchor_constraint_to_constant(this: id, sel: Sel, value: CGFloat) -> id {
+    let mut store = STORE.lock().unwrap();
+
+    match anchor_record(&store, this) {
+        Some(first) => {
+            let mut record = ConstraintRecord::new(first, relation(runtime::selector_name(sel)), None);
+            record.offset = value as f64;
+            new_constraint(&mut store, record)
+        },
+
+        None => nil
+    }
+}
+
+extern fn constraint_with_item(
+    _this: id,
+    _sel: Sel,
+    first_item: id,
+    first_attribute: NSInteger,
+    relation: NSInteger,
+    second_item: id,
+    second_attribute: NSInteger,
+    multiplier: CGFloat,
+    constant: CGFloat
+) -> id {
+    let first = AnchorRecord::new(LayoutItem(first_item as usize), LayoutAttribute::from(first_attribute));
+    let second = match second_item == nil {
+        true => None,
+        false => Some(AnchorRecord::new(LayoutItem(second_item as usize), LayoutAttribute::from(second_attribute)))
+    };
+
+    let mut record = ConstraintRecord::new(first, LayoutRelation::from(relation), second);
+    record.multiplier = multiplier as f64;
+    record.offset = constant as f64;
+
+    new_constraint(&mut STORE.lock().unwrap(), record)
+}
+
+extern fn constraint_activate_all(_this: id, sel: Sel, constraints: id) {
+    let active = runtime::selector_name(sel) == "activateConstraints:";
+    let mut store = STORE.lock().unwrap();
+
+    let constraints = match store.payload(constraints as usize) {
+        Payload::Array(items) => items.clone(),
+        _ => Vec::new()
+    };
+
+    for constraint in constraints {
+        store.set_active(constraint, active);
+    }
+}
+
+extern fn constraint_set_active(this: id, _sel: Sel, active: BOOL) {
+    STORE.lock().unwrap().set_active(this as usize, to_bool(active));
+}
+
+extern fn constraint_is_active(this: id, _sel: Sel) -> BOOL {
+    from_bool(STORE.lock().unwrap().active_constraints.contains(&(this as usize)))
+}
+
+/// Runs `f` on the record of the constraint `this`, if it is one.
+fn with_constraint<R, F: FnOnce(&mut ConstraintRecord) -> R>(this: id, f: F) -> Option<R> {
+    let mut store = STORE.lock().unwrap();
+
+    match store.get_mut(this as usize).map(|record| &mut record.payload) {
+        Some(Payload::Constraint(record)) => Some(f(record)),
+        _ => None
+    }
+}
+
+extern fn constraint_item(this: id, sel: Sel) -> id {
+    let is_first = runtime::selector_name(sel) == "firstItem";
+
+    with_constraint(this, |record| match is_first {
+        true => Some(record.first.item.0 as id),
+        false => record.second.map(|anchor| anchor.item.0 as id)
+    }).flatten().unwrap_or(nil)
+}
+
+extern fn constraint_attribute(this: id, sel: Sel) -> NSInteger {
+    let selector = runtime::selector_name(sel);
+
+    with_constraint(this, |record| match selector {
+        "firstAttribute" => record.first.attribute.into(),
+        "secondAttribute" => record.second.map_or(LayoutAttribute::NotAnAttribute, |anchor| anchor.attribute).into(),
+        _ => record.relation.into()
+    }).unwrap_or(0)
+}
+
+extern fn constraint_multiplier(this: id, _sel: Sel) -> CGFloat {
+    with_constraint(this, |record| record.multiplier as CGFloat).unwrap_or(1.)
+}
+
+extern fn constraint_set_constant(this: id, _sel: Sel, constant: CGFloat) {
+    with_constraint(this, |record| record.offset = constant as f64);
+}
+
+extern fn constraint_constant(this: id, _sel: Sel) -> CGFloat {
+    with_constraint(this, |record| record.offset as CGFloat).unwrap_or(0.)
+}
+
+extern fn constraint_set_priority(this: id, _sel: Sel, priority: f32) {
+    with_constraint(this, |record| record.priority = priority as f64);
+}
+
+extern fn constraint_priority(this: id, _sel: Sel) -> f32 {
+    with_constraint(this, |record| record.priority as f32).unwrap_or(0.)
+}
+
+// AppKit's accessibility functions. Posting just records the notification.
+
+#[no_mangle]
+pub extern "C" fn NSAccessibilityPostNotification(element: id, notification: id) {
+    NSAccessibilityPostNotificationWithUserInfo(element, notification, nil);
+}
+
+#[no_mangle]
+pub extern "C" fn NSAccessibilityPostNotificationWithUserInfo(element: id, notification: id, user_info: id) {
+    let post = (element as usize, notification as usize, user_info as usize);
+    STORE.lock().unwrap().accessibility_notifications.push(post);
+}
+
+// The string constants cacao links against, with the values AppKit and Foundation give them.
+// These are filled in by `initialize_constants`, before the first message is sent.
+
+#[no_mangle]
+pub static mut NSForegroundColorAttributeName: id = 0 as id;
+
+#[no_mangle]
+pub static mut NSKeyValueChangeNewKey: id = 0 as id;
+
+#[no_mangle]
+pub static mut NSKeyValueChangeOldKey: id = 0 as id;
+
+#[no_mangle]
+pub static mut NSAppearanceNameAqua: id = 0 as id;
+
+#[no_mangle]
+pub static mut NSAppearanceNameAccessibilityHighContrastAqua: id = 0 as id;
+
+#[no_mangle]
+pub static mut NSAppearanceNameDarkAqua: id = 0 as id;
+
+#[no_mangle]
+pub static mut NSAppearanceNameAccessibilityHighContrastDarkAqua: id = 0 as id;
+
+/// Creates the strings behind the exported constants. This must only be called once, and not
+/// while the store's lock is held.
+pub(crate) fn initialize_constants() {
+    let mut store = STORE.lock().unwrap();
+
+    unsafe {
+        NSForegroundColorAttributeName = new_string(&mut store, "NSColor");
+        NSKeyValueChangeNewKey = new_string(&mut store, "new");
+        NSKeyValueChangeOldKey = new_string(&mut store, "old");
+        NSAppearanceNameAqua = new_string(&mut store, "NSAppearanceNameAqua");
+        NSAppearanceNameAccessibilityHighContrastAqua = new_string(&mut store, "NSAppearanceNameAccessibilityHighContrastAqua");
+        NSAppearanceNameDarkAqua = new_string(&mut store, "NSAppearanceNameDarkAqua");
+        NSAppearanceNameAccessibilityHighContrastDarkAqua = new_string(&mut store, "NSAppearanceNameAccessibilityHighContrastDarkAqua");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::property_key;
+
+    #[test]
+    fn accessors_share_a_property_key() {
+        assert_eq!(property_key("setStringValue:"), "stringValue");
+        assert_eq!(property_key("stringValue"), "stringValue");
+        assert_eq!(property_key("setHidden:"), "hidden");
+        assert_eq!(property_key("isHidden"), "hidden");
+        assert_eq!(property_key("settings"), "settings");
+        assert_eq!(property_key("registerForDraggedTypes:"), "registerForDraggedTypes");
+    }
+}
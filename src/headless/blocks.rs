@@ -0,0 +1,95 @@
+//! The handful of blocks runtime symbols the `block` crate links against.
+//!
+//! Stack blocks are copied to the heap with `_Block_copy` (running their copy helper), and freed
+//! by `_Block_release` once their reference count - kept in the header's reserved field, as there
+//! is nowhere else to put it - drops to zero.
+
+use std::mem;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+use crate::foundation::BOOL;
+
+/// Set on blocks that have copy and dispose helpers in their descriptor.
+const BLOCK_HAS_COPY_DISPOSE: c_int = 1 << 25;
+
+/// Set on blocks that live on the heap, and so are reference counted.
+const BLOCK_NEEDS_FREE: c_int = 1 << 24;
+
+#[repr(C)]
+struct BlockHeader {
+    isa: *const c_void,
+    flags: c_int,
+    reserved: c_int,
+    invoke: *const c_void,
+    descriptor: *const BlockDescriptor
+}
+
+#[repr(C)]
+struct BlockDescriptor {
+    reserved: usize,
+    size: usize,
+    copy_helper: unsafe extern "C" fn(*mut c_void, *const c_void),
+    dispose_helper: unsafe extern "C" fn(*mut c_void)
+}
+
+/// Blocks only need these to be distinct addresses; they're never messaged.
+#[no_mangle]
+pub static _NSConcreteStackBlock: [usize; 32] = [0; 32];
+
+#[no_mangle]
+pub static _NSConcreteMallocBlock: [usize; 32] = [0; 32];
+
+#[no_mangle]
+pub unsafe extern "C" fn _Block_copy(block: *const c_void) -> *mut c_void {
+    if block.is_null() {
+        return ptr::null_mut();
+    }
+
+    let header = block as *mut BlockHeader;
+
+    if (*header).flags & BLOCK_NEEDS_FREE != 0 {
+        (*header).reserved += 1;
+        return block as *mut c_void;
+    }
+
+    let size = (*(*header).descriptor).size;
+    let copy = libc::malloc(size) as *mut BlockHeader;
+    ptr::copy_nonoverlapping(block as *const u8, copy as *mut u8, size);
+
+    (*copy).isa = &_NSConcreteMallocBlock as *const _ as *const c_void;
+    (*copy).flags |= BLOCK_NEEDS_FREE;
+    (*copy).reserved = 1;
+
+    if (*copy).flags & BLOCK_HAS_COPY_DISPOSE != 0 {
+        ((*(*copy).descriptor).copy_helper)(copy as *mut c_void, block);
+    }
+
+    copy as *mut c_void
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _Block_release(block: *const c_void) {
+    let header = block as *mut BlockHeader;
+
+    if header.is_null() || (*header).flags & BLOCK_NEEDS_FREE == 0 {
+        return;
+    }
+
+    (*header).reserved -= 1;
+
+    if (*header).reserved == 0 {
+        if (*header).flags & BLOCK_HAS_COPY_DISPOSE != 0 {
+            ((*(*header).descriptor).dispose_helper)(header as *mut c_void);
+        }
+
+        libc::free(header as *mut c_void);
+    }
+}
+
+/// Calls `block`, which must take no arguments and return a `BOOL`.
+pub(crate) unsafe fn invoke_returning_bool(block: *const c_void) -> BOOL {
+    let header = block as *const BlockHeader;
+    let invoke: unsafe extern "C" fn(*const c_void) -> BOOL = mem::transmute((*header).invoke);
+    invoke(block)
+}
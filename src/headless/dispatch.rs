@@ -0,0 +1,68 @@
+//! Stands in for the `dispatch` crate, as there's no libdispatch to link against off Apple
+//! platforms. Only what cacao itself uses is here.
+//!
+//! Every thread counts as the main thread in the headless runtime, so the main queue is simply a
+//! serial queue, run by a thread of its own. Global queues run each job on a new thread.
+
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+use lazy_static::lazy_static;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+lazy_static! {
+    static ref MAIN_QUEUE: Mutex<Sender<Job>> = {
+        let (sender, receiver) = channel::<Job>();
+
+        thread::spawn(move || {
+            for job in receiver {
+                job();
+            }
+        });
+
+        Mutex::new(sender)
+    };
+}
+
+/// Mirrors `dispatch::QueuePriority`, as far as cacao uses it. Priorities have no effect.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QueuePriority {
+    Default
+}
+
+/// Mirrors `dispatch::Queue`.
+#[derive(Clone, Debug)]
+pub struct Queue {
+    serial: bool
+}
+
+impl Queue {
+    /// Returns the main queue.
+    pub fn main() -> Self {
+        Queue { serial: true }
+    }
+
+    /// Returns a global (concurrent) queue.
+    pub fn global(_priority: QueuePriority) -> Self {
+        Queue { serial: false }
+    }
+
+    /// Runs `work` at some point after this returns - in order with everything else on the main
+    /// queue, or straight away on a thread of its own on a global one.
+    pub fn exec_async<F>(&self, work: F)
+    where
+        F: FnOnce() + Send + 'static
+    {
+        match self.serial {
+            true => {
+                let _ = MAIN_QUEUE.lock().unwrap().send(Box::new(work));
+            },
+
+            false => {
+                thread::spawn(work);
+            }
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! A fake Objective-C runtime, for exercising cacao's widgets where AppKit doesn't exist.
+//!
+//! Every control in cacao talks to the runtime through `msg_send!`, which off Apple platforms is
+//! just a call to `objc_msg_lookup` followed by a call to whatever implementation it returns. With
+//! the `headless` feature enabled on a non-Apple target, this module provides those runtime
+//! functions itself (along with the few blocks runtime symbols the `block` crate needs), and
+//! answers messages with an in-process fake of the parts of `NSObject`, `NSView`, `NSWindow` and
+//! friends that cacao uses.
+//!
+//! The fake doesn't draw anything or run an event loop; it records. Property setters store what
+//! they were given, views keep track of their subviews, and constraints remember what they relate
+//! and whether they're active. Tests can then build a window, call `set_text`,
+//! `set_background_color`, `add_subview` and so on, and assert on the result:
+//!
+//! ```rust,no_run
+//! use cacao::foundation::MainThreadMarker;
+//! use cacao::headless::{self, Value};
+//! use cacao::layout::Layout;
+//! use cacao::text::Label;
+//! use cacao::view::View;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let view = View::new(mtm);
+//! let label = Label::new(mtm);
+//! label.set_text("Hello");
+//! view.add_subview(&label);
+//!
+//! let tree = headless::inspect(&view);
+//! assert_eq!(tree.children[0].text(), Some("Hello"));
+//! ```
+//!
+//! Messages the fake doesn't know about return `nil` (or zero), and are recorded; see
+//! `unhandled_selectors()`. Objects are never freed, so anything you held on to can still be
+//! inspected after it's dropped.
+//!
+//! Every thread counts as the main thread, and all state is global - so tests that look at the
+//! global lists (`windows()`, `constraints()`) should filter them down to the objects they
+//! created, as other tests may be running at the same time.
+//!
+//! Beyond the runtime, nothing else from the system is needed. Off Apple platforms cacao only
+//! uses the geometry types from `core-graphics` (so `Image::draw` and `Color::cg_color`, which
+//! need Core Graphics proper, aren't available), and GCD queues are stood in for by threads -
+//! the main queue being a serial queue with a thread of its own.
+
+use std::collections::BTreeMap;
+use std::os::raw::c_void;
+
+use objc::{class, msg_send, sel, sel_impl};
+use objc::runtime::Object;
+use objc_id::ShareId;
+
+use crate::color::ColorValue;
+use crate::foundation::{id, NSInteger, NO};
+use crate::geometry::Rect;
+use crate::layout::{ConstraintRecord, Layout, LayoutDirection, LayoutItem};
+
+#[cfg(feature = "macos")]
+use crate::macos::window::Window;
+
+mod appkit;
+mod blocks;
+pub(crate) mod dispatch;
+mod runtime;
+mod store;
+
+use appkit::string_value;
+use store::{Payload, Property, Store, STORE};
+
+/// Identifies an object created through the headless runtime. This is the object's address, so
+/// it matches the `LayoutItem`s in constraint records.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(pub usize);
+
+impl ObjectId {
+    /// Returns the id of an Objective-C object.
+    pub fn of(object: &Object) -> Self {
+        ObjectId(object as *const Object as usize)
+    }
+}
+
+impl From<ObjectId> for LayoutItem {
+    fn from(id: ObjectId) -> Self {
+        LayoutItem(id.0)
+    }
+}
+
+/// A recorded property value. Objects that carry a value of their own (strings, numbers, colors)
+/// are resolved to it; anything else is referred to by id.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// The property was set to `nil`.
+    Nil,
+
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Rect(Rect),
+
+    /// A size, as `(width, height)`.
+    Size(f64, f64),
+
+    /// A color built from components.
+    Color(ColorValue),
+
+    /// A system color, named by the `NSColor` class method that returned it (e.g, `labelColor`).
+    SystemColor(String),
+
+    /// A selector, by name (e.g, an action).
+    Selector(String),
+
+    /// Any other object.
+    Object(ObjectId)
+}
+
+impl Value {
+    /// Returns the string this value holds, if it's a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None
+        }
+    }
+
+    /// Returns the boolean this value holds, if it's a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    /// Returns the number this value holds, if it's an integer or a float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    /// Resolves a recorded property, looking into objects that carry a value of their own.
+    fn resolve(store: &Store, property: &Property) -> Self {
+        match property {
+            Property::Bool(value) => Value::Bool(*value),
+            Property::Integer(value) => Value::Integer(*value),
+            Property::Float(value) => Value::Float(*value),
+            Property::Rect(rect) => Value::Rect(*rect),
+            Property::Size(width, height) => Value::Size(*width, *height),
+            Property::Selector(name) => Value::Selector(name.clone()),
+            Property::Object(0) => Value::Nil,
+            Property::Object(object) => Value::object(store, *object)
+        }
+    }
+
+    fn object(store: &Store, object: usize) -> Self {
+        match store.payload(object) {
+            Payload::String(_) => Value::String(string_value(store, object).unwrap_or_default()),
+            Payload::Number(number) => Value::resolve(store, number),
+            Payload::Color(color) => Value::Color(*color),
+            Payload::SystemColor(name) => Value::SystemColor(name.clone()),
+            _ => Value::Object(ObjectId(object))
+        }
+    }
+}
+
+/// A constraint that was created through the headless runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constraint {
+    /// The id of the underlying `NSLayoutConstraint`.
+    pub id: ObjectId,
+
+    /// What the constraint relates.
+    pub record: ConstraintRecord,
+
+    /// The identifier set on the constraint, if any.
+    pub identifier: Option<String>,
+
+    /// Whether the constraint is currently active.
+    pub active: bool
+}
+
+/// A snapshot of an object and everything recorded about it - and, for views and windows, the
+/// same for everything beneath it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    /// The object this node describes.
+    pub id: ObjectId,
+
+    /// The object's class name. For cacao's own subclasses, this is the registered name (e.g,
+    /// `RSTView`).
+    pub class_name: String,
+
+    /// The names of the class's superclasses, nearest first.
+    pub superclasses: Vec<String>,
+
+    /// Every property that was set, keyed by property name (e.g, `stringValue`, `hidden`).
+    pub properties: BTreeMap<String, Value>,
+
+    /// Subviews, in order. For a window, this holds the content view.
+    pub children: Vec<Node>,
+
+    /// Active constraints whose left hand side is this object.
+    pub constraints: Vec<Constraint>
+}
+
+impl Node {
+    /// Returns the property `key`, if it was set.
+    pub fn property(&self, key: &str) -> Option<&Value> {
+        self.properties.get(key)
+    }
+
+    /// Whether this node's class is, or inherits from, `class_name`.
+    pub fn is_kind_of(&self, class_name: &str) -> bool {
+        self.class_name == class_name || self.superclasses.iter().any(|c| c == class_name)
+    }
+
+    /// The text this node displays: its string value, or failing that, its title.
+    pub fn text(&self) -> Option<&str> {
+        self.property("stringValue")
+            .or_else(|| self.property("title"))
+            .and_then(Value::as_str)
+    }
+
+    /// The background color that was set on this node, whether on the view itself or on its
+    /// layer.
+    pub fn background_color(&self) -> Option<&Value> {
+        self.property("backgroundColor")
+    }
+
+    /// The frame that was last set on this node.
+    pub fn frame(&self) -> Option<Rect> {
+        match self.property("frame") {
+            Some(Value::Rect(rect)) => Some(*rect),
+            _ => None
+        }
+    }
+
+    /// Whether this node is hidden.
+    pub fn is_hidden(&self) -> bool {
+        self.property("hidden").and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    /// Returns every node beneath this one, depth first.
+    pub fn descendants(&self) -> Vec<&Node> {
+        let mut nodes = Vec::new();
+
+        for child in &self.children {
+            nodes.push(child);
+            nodes.extend(child.descendants());
+        }
+
+        nodes
+    }
+
+    /// Returns the first node at or beneath this one that matches `predicate`.
+    pub fn find<F: Fn(&Node) -> bool>(&self, predicate: F) -> Option<&Node> {
+        match predicate(self) {
+            true => Some(self),
+            false => self.descendants().into_iter().find(|node| predicate(node))
+        }
+    }
+}
+
+/// Takes a snapshot of `view` and its subviews.
+pub fn inspect<L: Layout>(view: &L) -> Node {
+    let node = view.get_backing_node();
+    inspect_object(&node)
+}
+
+/// Takes a snapshot of `window`; its only child is the content view.
+#[cfg(feature = "macos")]
+pub fn inspect_window<T>(window: &Window<T>) -> Node {
+    inspect_object(&window.objc)
+}
+
+/// Takes a snapshot of any object created through the headless runtime.
+pub fn inspect_object(object: &ShareId<Object>) -> Node {
+    let store = STORE.lock().unwrap();
+    node(&store, ObjectId::of(object))
+}
+
+fn node(store: &Store, id: ObjectId) -> Node {
+    let object = id.0 as *const Object;
+    let class = runtime::class_of(object);
+
+    let mut properties: BTreeMap<String, Value> = store.get(id.0)
+        .map(|record| record.properties.iter()
+            .map(|(key, value)| (key.clone(), Value::resolve(store, value)))
+            .collect()
+        )
+        .unwrap_or_default();
+
+    // `View` keeps its background color in an ivar (and draws it in `updateLayer`), while most
+    // other controls set it on their layer; either way, it's reported as `backgroundColor`.
+    if !properties.contains_key("backgroundColor") {
+        let color = runtime::ivar_value(object, crate::view::BACKGROUND_COLOR)
+            .filter(|color| *color != 0)
+            .map(|color| Value::object(store, color))
+            .or_else(|| match store.property(id.0, "layer") {
+                Some(Property::Object(layer)) => store.property(*layer, "backgroundColor")
+                    .map(|color| Value::resolve(store, color)),
+                _ => None
+            });
+
+        if let Some(color) = color {
+            properties.insert("backgroundColor".to_string(), color);
+        }
+    }
+
+    let children = match store.get(id.0) {
+        Some(record) if class.is_kind_of("NSWindow") => match record.properties.get("contentView") {
+            Some(Property::Object(view)) if *view != 0 => vec![node(store, ObjectId(*view))],
+            _ => Vec::new()
+        },
+
+        Some(record) => record.subviews.iter().map(|view| node(store, ObjectId(*view))).collect(),
+        None => Vec::new()
+    };
+
+    let mut superclasses = Vec::new();
+    let mut current = class.superclass();
+
+    while let Some(superclass) = current {
+        superclasses.push(superclass.name().to_string());
+        current = superclass.superclass();
+    }
+
+    Node {
+        id: id,
+        class_name: class.name().to_string(),
+        superclasses: superclasses,
+        properties: properties,
+        children: children,
+        constraints: active_constraints(store)
+            .filter(|constraint| constraint.record.first.item == LayoutItem::from(id))
+            .collect()
+    }
+}
+
+fn constraint(store: &Store, id: usize) -> Option<Constraint> {
+    match store.payload(id) {
+        Payload::Constraint(record) => Some(Constraint {
+            id: ObjectId(id),
+            record: *record,
+            identifier: match store.property(id, "identifier") {
+                Some(Property::Object(identifier)) => string_value(store, *identifier),
+                _ => None
+            },
+            active: store.active_constraints.contains(&id)
+        }),
+
+        _ => None
+    }
+}
+
+fn active_constraints<'a>(store: &'a Store) -> impl Iterator<Item = Constraint> + 'a {
+    store.active_constraints.iter().filter_map(move |id| constraint(store, *id))
+}
+
+/// Returns every active constraint, in the order they were activated.
+pub fn constraints() -> Vec<Constraint> {
+    let store = STORE.lock().unwrap();
+    active_constraints(&store).collect()
+}
+
+/// Returns the ids of every window that's been created, in order.
+pub fn windows() -> Vec<ObjectId> {
+    STORE.lock().unwrap().windows.iter().map(|window| ObjectId(*window)).collect()
+}
+
+/// An accessibility notification that was posted through `NSAccessibilityPostNotification` (or
+/// its `WithUserInfo` variant).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityPost {
+    /// The element the notification was posted for.
+    pub element: ObjectId,
+
+    /// The notification's name (e.g, `AXValueChanged`).
+    pub name: String,
+
+    /// The user info posted along with it, keyed by name (e.g, `AXAnnouncementKey`).
+    pub user_info: BTreeMap<String, Value>
+}
+
+/// Returns every accessibility notification that's been posted, in order.
+pub fn accessibility_notifications() -> Vec<AccessibilityPost> {
+    let store = STORE.lock().unwrap();
+
+    store.accessibility_notifications.iter().map(|(element, name, user_info)| AccessibilityPost {
+        element: ObjectId(*element),
+        name: string_value(&store, *name).unwrap_or_default(),
+        user_info: store.get(*user_info)
+            .map(|record| record.properties.iter()
+                .map(|(key, value)| (key.clone(), Value::resolve(&store, value)))
+                .collect()
+            )
+            .unwrap_or_default()
+    }).collect()
+}
+
+/// Performs the custom accessibility action called `name` on `object`, as VoiceOver would, and
+/// returns what its handler returned. Returns `None` if `object` has no such action.
+pub fn perform_accessibility_action(object: ObjectId, name: &str) -> Option<bool> {
+    let handler = {
+        let store = STORE.lock().unwrap();
+
+        let actions = match store.property(object.0, "accessibilityCustomActions") {
+            Some(Property::Object(actions)) => match store.payload(*actions) {
+                Payload::Array(actions) => actions.clone(),
+                _ => Vec::new()
+            },
+
+            _ => Vec::new()
+        };
+
+        actions.into_iter().find_map(|action| {
+            let action_name = match store.property(action, "name") {
+                Some(Property::Object(action_name)) => string_value(&store, *action_name),
+                _ => None
+            };
+
+            match (action_name, store.property(action, "handler")) {
+                (Some(action_name), Some(Property::Object(handler))) if action_name == name => Some(*handler),
+                _ => None
+            }
+        })?
+    };
+
+    // The handler is free to message objects, so it's called without holding the store.
+    let result = unsafe { blocks::invoke_returning_bool(handler as *const c_void) };
+    Some(result != NO)
+}
+
+/// Sets the layout direction the app reports - as if it were running in Arabic or Hebrew, say.
+/// Like the rest of the fake's state, this is global.
+pub fn set_application_layout_direction(direction: LayoutDirection) {
+    let application: id = unsafe { msg_send![class!(NSApplication), sharedApplication] };
+    let direction = Property::Integer(NSInteger::from(direction) as i64);
+    STORE.lock().unwrap().set(application as usize, "userInterfaceLayoutDirection", direction);
+}
+
+/// Returns every message that was sent without an implementation to answer it, formatted as
+/// `-[Class selector]` (or `+[Class selector]`, for class methods). Handy for finding out why a
+/// widget doesn't behave as expected.
+pub fn unhandled_selectors() -> Vec<String> {
+    STORE.lock().unwrap().unhandled.iter().cloned().collect()
+}
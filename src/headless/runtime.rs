@@ -0,0 +1,508 @@
+//! The Objective-C runtime functions that the `objc` crate links against, implemented in Rust.
+//!
+//! Off Apple platforms, `objc` sends a message by asking the runtime for an implementation
+//! (`objc_msg_lookup`) and then calling it directly - which means a runtime can be provided
+//! without any assembly trampolines. Classes, selectors and objects here are plain heap
+//! allocations:
+//!
+//! - A selector is a pointer to an interned, nul-terminated name.
+//! - A class is a `MockClass`, which (like any object) starts with a pointer to its metaclass.
+//! - An object is a zeroed block of memory, starting with a pointer to its class and followed by
+//!   the ivars its class (and superclasses) declared.
+//!
+//! Objects are never freed, even once their retain count hits zero, so that anything a test held
+//! on to can still be inspected afterwards.
+
+use std::alloc::{alloc_zeroed, Layout as AllocLayout};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+
+use lazy_static::lazy_static;
+
+use objc::runtime::{Class, Imp, Ivar, Method, Object, Protocol, Sel, BOOL, NO, YES};
+
+use crate::headless::appkit;
+use crate::headless::store::STORE;
+
+lazy_static! {
+    /// Interned selector names, keyed by name. The values are addresses of leaked `CString`s.
+    static ref SELECTORS: Mutex<HashMap<String, usize>> = Mutex::new(HashMap::new());
+
+    /// Every class, keyed by name. The values are addresses of leaked `MockClass`es.
+    static ref CLASSES: Mutex<HashMap<String, usize>> = Mutex::new(HashMap::new());
+}
+
+/// Guards creating the string constants AppKit and Foundation export; see `objc_msg_lookup`.
+static CONSTANTS: Once = Once::new();
+
+/// The alignment every object is allocated with.
+const OBJECT_ALIGNMENT: usize = 16;
+
+/// A class (or metaclass). The layout starts with an `isa` pointer, so a class can be messaged
+/// like any other object.
+#[repr(C)]
+pub(crate) struct MockClass {
+    isa: *const MockClass,
+    superclass: *const MockClass,
+    name: CString,
+    is_meta: bool,
+    methods: Mutex<HashMap<usize, Imp>>,
+    ivars: Mutex<Vec<&'static MockIvar>>,
+    instance_size: AtomicUsize
+}
+
+/// An instance variable declared on a class.
+pub(crate) struct MockIvar {
+    name: CString,
+    types: CString,
+    offset: isize
+}
+
+/// The layout `objc` uses for messages to `super`.
+#[repr(C)]
+pub struct Super {
+    receiver: *mut Object,
+    superclass: *const Class
+}
+
+impl MockClass {
+    /// The name of this class.
+    pub fn name(&self) -> &str {
+        self.name.to_str().unwrap_or("")
+    }
+
+    /// Whether this is a metaclass - i.e, whether its instances are classes.
+    pub fn is_meta(&self) -> bool {
+        self.is_meta
+    }
+
+    /// This class's superclass, if it's not a root class.
+    pub fn superclass(&self) -> Option<&'static MockClass> {
+        unsafe { self.superclass.as_ref() }
+    }
+
+    /// Whether this class is, or inherits from, a class called `name`. Metaclasses only match
+    /// other metaclasses.
+    pub fn is_kind_of(&self, name: &str) -> bool {
+        let mut current = Some(self);
+
+        while let Some(class) = current {
+            if class.name() == name && class.is_meta == self.is_meta {
+                return true;
+            }
+
+            current = class.superclass();
+        }
+
+        false
+    }
+
+    /// Returns the ivar called `name`, searching superclasses as well.
+    fn ivar(&self, name: &str) -> Option<&'static MockIvar> {
+        let mut current = Some(self);
+
+        while let Some(class) = current {
+            let ivars = class.ivars.lock().unwrap();
+
+            if let Some(ivar) = ivars.iter().find(|ivar| ivar.name.to_bytes() == name.as_bytes()) {
+                return Some(*ivar);
+            }
+
+            current = class.superclass();
+        }
+
+        None
+    }
+
+    /// Returns a method that was added (by `ClassDecl`, typically) to this class or one of its
+    /// superclasses.
+    fn declared_method(&self, sel: Sel) -> Option<Imp> {
+        let mut current = Some(self);
+
+        while let Some(class) = current {
+            if let Some(imp) = class.methods.lock().unwrap().get(&(sel.as_ptr() as usize)) {
+                return Some(*imp);
+            }
+
+            current = class.superclass();
+        }
+
+        None
+    }
+}
+
+impl fmt::Debug for MockClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockClass")
+            .field("name", &self.name())
+            .field("is_meta", &self.is_meta)
+            .finish()
+    }
+}
+
+/// Returns the class of `object`, which may be a class itself (in which case this returns its
+/// metaclass).
+pub(crate) fn class_of(object: *const Object) -> &'static MockClass {
+    unsafe { &**(object as *const *const MockClass) }
+}
+
+/// Returns the class called `name`, creating it if it doesn't exist yet. AppKit classes get the
+/// superclass they'd have on a Mac; anything else is assumed to inherit from `NSObject`, so that
+/// `class!()` never fails.
+pub(crate) fn class_named(name: &str) -> &'static MockClass {
+    if let Some(class) = registered_class(name) {
+        return class;
+    }
+
+    // Superclasses are created first, without holding the lock, as that's recursive.
+    let superclass = match name {
+        "NSObject" => None,
+        name => Some(class_named(appkit::superclass_name(name).unwrap_or("NSObject")))
+    };
+
+    let mut classes = CLASSES.lock().unwrap();
+
+    if let Some(class) = classes.get(name) {
+        return unsafe { &*(*class as *const MockClass) };
+    }
+
+    let class = allocate_class(superclass, name, 0);
+    classes.insert(name.to_string(), class as *const MockClass as usize);
+    class
+}
+
+/// Returns the class called `name`, if it's been registered.
+fn registered_class(name: &str) -> Option<&'static MockClass> {
+    CLASSES.lock().unwrap().get(name).map(|class| unsafe { &*(*class as *const MockClass) })
+}
+
+/// Allocates (but doesn't register) a class and its metaclass.
+fn allocate_class(superclass: Option<&'static MockClass>, name: &str, extra_bytes: usize) -> &'static MockClass {
+    let name = CString::new(name).unwrap();
+
+    // The root metaclass inherits from the root class, so that classes respond to `NSObject`'s
+    // instance methods (`isKindOfClass:`, `respondsToSelector:`, and so on).
+    let (meta_superclass, instance_size) = match superclass {
+        Some(superclass) => (superclass.isa, superclass.instance_size.load(Ordering::SeqCst)),
+        None => (ptr::null(), mem::size_of::<usize>())
+    };
+
+    let metaclass: &'static mut MockClass = Box::leak(Box::new(MockClass {
+        isa: ptr::null(),
+        superclass: meta_superclass,
+        name: name.clone(),
+        is_meta: true,
+        methods: Mutex::new(HashMap::new()),
+        ivars: Mutex::new(Vec::new()),
+        instance_size: AtomicUsize::new(0)
+    }));
+
+    let class: &'static MockClass = Box::leak(Box::new(MockClass {
+        isa: &*metaclass,
+        superclass: superclass.map_or(ptr::null(), |superclass| superclass as *const MockClass),
+        name: name,
+        is_meta: false,
+        methods: Mutex::new(HashMap::new()),
+        ivars: Mutex::new(Vec::new()),
+        instance_size: AtomicUsize::new(instance_size + extra_bytes)
+    }));
+
+    let isa: *const MockClass = metaclass;
+    metaclass.isa = isa;
+
+    if superclass.is_none() {
+        metaclass.superclass = class;
+    }
+
+    class
+}
+
+/// Makes `class` available by name.
+fn register_class(class: &'static MockClass) {
+    CLASSES.lock().unwrap().insert(class.name().to_string(), class as *const MockClass as usize);
+}
+
+/// Allocates a zeroed instance of `class`. This doesn't record anything about it; see
+/// `Store::insert`.
+pub(crate) fn allocate(class: &'static MockClass) -> *mut Object {
+    let size = class.instance_size.load(Ordering::SeqCst);
+    let layout = AllocLayout::from_size_align(size, OBJECT_ALIGNMENT).unwrap();
+
+    unsafe {
+        let object = alloc_zeroed(layout) as *mut *const MockClass;
+        *object = class;
+        object as *mut Object
+    }
+}
+
+/// Reads the pointer-sized ivar `name` from `object`, if its class declared one.
+pub(crate) fn ivar_value(object: *const Object, name: &str) -> Option<usize> {
+    let ivar = class_of(object).ivar(name)?;
+
+    match ivar.types.to_bytes() {
+        b"@" | b"^v" | b"Q" | b"L" | b"q" => unsafe {
+            Some(*((object as *const u8).offset(ivar.offset) as *const usize))
+        },
+
+        _ => None
+    }
+}
+
+/// Returns the interned selector for `name`.
+pub(crate) fn selector(name: &str) -> Sel {
+    let mut selectors = SELECTORS.lock().unwrap();
+
+    let address = *selectors.entry(name.to_string()).or_insert_with(|| {
+        CString::new(name).unwrap().into_raw() as usize
+    });
+
+    unsafe { Sel::from_ptr(address as *const c_void) }
+}
+
+/// Returns the name of `sel`.
+pub(crate) fn selector_name(sel: Sel) -> &'static str {
+    unsafe { CStr::from_ptr(sel.as_ptr() as *const c_char).to_str().unwrap_or("") }
+}
+
+/// Finds the implementation `class` uses for `sel`: methods declared on the class (or a
+/// superclass) win, then the fake AppKit behaviour, and finally a handler that records the
+/// message as unhandled and returns `nil`.
+pub(crate) fn lookup(class: &'static MockClass, sel: Sel) -> Imp {
+    class.declared_method(sel)
+        .or_else(|| appkit::builtin(class, selector_name(sel)))
+        .unwrap_or_else(appkit::unhandled)
+}
+
+/// Whether `class` has an implementation for `sel`, other than the unhandled fallback.
+pub(crate) fn responds_to(class: &'static MockClass, sel: Sel) -> bool {
+    class.declared_method(sel).is_some() || appkit::builtin(class, selector_name(sel)).is_some()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_msg_lookup(receiver: *mut Object, op: Sel) -> Imp {
+    // Anything reading an exported constant has sent a message first.
+    CONSTANTS.call_once(appkit::initialize_constants);
+
+    // As with the real runtime, messages to nil do nothing and return nil (or zero).
+    if receiver.is_null() {
+        return appkit::nil_receiver();
+    }
+
+    lookup(class_of(receiver), op)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_msg_lookup_super(sup: *const Super, sel: Sel) -> Imp {
+    lookup(&*((*sup).superclass as *const MockClass), sel)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sel_registerName(name: *const c_char) -> Sel {
+    selector(CStr::from_ptr(name).to_str().unwrap_or(""))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sel_getName(sel: Sel) -> *const c_char {
+    sel.as_ptr() as *const c_char
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_getClass(name: *const c_char) -> *const Class {
+    class_named(CStr::from_ptr(name).to_str().unwrap_or("")) as *const MockClass as *const Class
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_allocateClassPair(superclass: *const Class, name: *const c_char, extra_bytes: usize) -> *mut Class {
+    let name = CStr::from_ptr(name).to_str().unwrap_or("");
+
+    if registered_class(name).is_some() {
+        return ptr::null_mut();
+    }
+
+    let superclass = (superclass as *const MockClass).as_ref();
+    allocate_class(superclass, name, extra_bytes) as *const MockClass as *mut Class
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_registerClassPair(cls: *mut Class) {
+    register_class(&*(cls as *const MockClass));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_disposeClassPair(_cls: *mut Class) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_getName(cls: *const Class) -> *const c_char {
+    (*(cls as *const MockClass)).name.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_getSuperclass(cls: *const Class) -> *const Class {
+    (*(cls as *const MockClass)).superclass as *const Class
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_getInstanceSize(cls: *const Class) -> usize {
+    (*(cls as *const MockClass)).instance_size.load(Ordering::SeqCst)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_getInstanceMethod(_cls: *const Class, _sel: Sel) -> *const Method {
+    ptr::null()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_addMethod(cls: *mut Class, name: Sel, imp: Imp, _types: *const c_char) -> BOOL {
+    let class = &*(cls as *const MockClass);
+    class.methods.lock().unwrap().insert(name.as_ptr() as usize, imp);
+    YES
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_addIvar(cls: *mut Class, name: *const c_char, size: usize, alignment: u8, types: *const c_char) -> BOOL {
+    let class = &*(cls as *const MockClass);
+    let name = CStr::from_ptr(name);
+
+    if class.ivar(name.to_str().unwrap_or("")).is_some() {
+        return NO;
+    }
+
+    let alignment = 1usize << alignment;
+    let offset = (class.instance_size.load(Ordering::SeqCst) + alignment - 1) / alignment * alignment;
+    class.instance_size.store(offset + size, Ordering::SeqCst);
+
+    class.ivars.lock().unwrap().push(Box::leak(Box::new(MockIvar {
+        name: name.to_owned(),
+        types: CStr::from_ptr(types).to_owned(),
+        offset: offset as isize
+    })));
+
+    YES
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_getInstanceVariable(cls: *const Class, name: *const c_char) -> *const Ivar {
+    let class = &*(cls as *const MockClass);
+
+    match class.ivar(CStr::from_ptr(name).to_str().unwrap_or("")) {
+        Some(ivar) => ivar as *const MockIvar as *const Ivar,
+        None => ptr::null()
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_addProtocol(_cls: *mut Class, _proto: *const Protocol) -> BOOL {
+    YES
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_conformsToProtocol(_cls: *const Class, _proto: *const Protocol) -> BOOL {
+    NO
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_getProtocol(_name: *const c_char) -> *const Protocol {
+    ptr::null()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ivar_getName(ivar: *const Ivar) -> *const c_char {
+    (*(ivar as *const MockIvar)).name.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ivar_getOffset(ivar: *const Ivar) -> isize {
+    (*(ivar as *const MockIvar)).offset
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ivar_getTypeEncoding(ivar: *const Ivar) -> *const c_char {
+    (*(ivar as *const MockIvar)).types.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn class_createInstance(cls: *const Class, _extra_bytes: usize) -> *mut Object {
+    let class = &*(cls as *const MockClass);
+    let object = allocate(class);
+    STORE.lock().unwrap().insert(object);
+    object
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn object_dispose(obj: *mut Object) -> *mut Object {
+    obj
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn object_getClass(obj: *const Object) -> *const Class {
+    match obj.is_null() {
+        true => ptr::null(),
+        false => class_of(obj) as *const MockClass as *const Class
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_retain(obj: *mut Object) -> *mut Object {
+    STORE.lock().unwrap().retain(obj);
+    obj
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_release(obj: *mut Object) {
+    STORE.lock().unwrap().release(obj);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_autorelease(obj: *mut Object) -> *mut Object {
+    obj
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_autoreleasePoolPush() -> *mut c_void {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_autoreleasePoolPop(_context: *mut c_void) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_initWeak(location: *mut *mut Object, obj: *mut Object) -> *mut Object {
+    *location = obj;
+    obj
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_loadWeakRetained(location: *mut *mut Object) -> *mut Object {
+    objc_retain(*location)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_copyWeak(to: *mut *mut Object, from: *mut *mut Object) {
+    *to = *from;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_destroyWeak(location: *mut *mut Object) {
+    *location = ptr::null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_setAssociatedObject(obj: *mut Object, key: *const c_void, value: *mut Object, _policy: usize) {
+    let mut store = STORE.lock().unwrap();
+
+    match value.is_null() {
+        true => { store.associated.remove(&(obj as usize, key as usize)); },
+        false => { store.associated.insert((obj as usize, key as usize), value as usize); }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn objc_getAssociatedObject(obj: *mut Object, key: *const c_void) -> *mut Object {
+    STORE.lock().unwrap().associated.get(&(obj as usize, key as usize)).map_or(ptr::null_mut(), |value| *value as *mut Object)
+}
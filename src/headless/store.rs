@@ -0,0 +1,179 @@
+//! Everything the fake AppKit classes have recorded, keyed by object address.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ffi::CString;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use objc::runtime::Object;
+
+use crate::color::ColorValue;
+use crate::geometry::Rect;
+use crate::layout::{AnchorRecord, ConstraintRecord};
+
+lazy_static! {
+    pub(crate) static ref STORE: Mutex<Store> = Mutex::new(Store::default());
+}
+
+/// A property value, as it was set. Objects are kept as addresses, and resolved into strings,
+/// colors and so on when they're inspected.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Property {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Rect(Rect),
+    Size(f64, f64),
+    Object(usize),
+    Selector(String)
+}
+
+/// Data that certain kinds of objects carry, beyond their properties.
+#[derive(Clone, Debug)]
+pub(crate) enum Payload {
+    None,
+    String(CString),
+    Array(Vec<usize>),
+    Number(Property),
+    Value(Property),
+    Color(ColorValue),
+    SystemColor(String),
+    Anchor(AnchorRecord),
+    Constraint(ConstraintRecord)
+}
+
+/// Everything recorded about a single object.
+#[derive(Debug)]
+pub(crate) struct ObjectRecord {
+    pub retain_count: usize,
+    pub properties: BTreeMap<String, Property>,
+    pub payload: Payload,
+    pub superview: Option<usize>,
+    pub subviews: Vec<usize>
+}
+
+/// The recorded state of every object allocated through the headless runtime.
+#[derive(Debug, Default)]
+pub(crate) struct Store {
+    pub objects: HashMap<usize, ObjectRecord>,
+
+    /// Windows, in the order they were created.
+    pub windows: Vec<usize>,
+
+    /// Constraints that are currently active, in the order they were activated.
+    pub active_constraints: Vec<usize>,
+
+    /// Selectors that were sent but had no implementation, formatted as `-[Class selector]`.
+    pub unhandled: BTreeSet<String>,
+
+    /// Accessibility notifications that were posted, as `(element, name, user info)`; the user
+    /// info is `0` if there wasn't any.
+    pub accessibility_notifications: Vec<(usize, usize, usize)>,
+
+    /// The shared `NSApplication`, once something has asked for it.
+    pub application: Option<usize>,
+
+    /// Associated objects, keyed by `(object, key)`.
+    pub associated: HashMap<(usize, usize), usize>
+}
+
+// Records only ever hold addresses and leaked classes, and are only touched behind the `Mutex`.
+unsafe impl Send for Store {}
+
+impl Store {
+    /// Starts recording `object`.
+    pub fn insert(&mut self, object: *mut Object) {
+        self.objects.insert(object as usize, ObjectRecord {
+            retain_count: 1,
+            properties: BTreeMap::new(),
+            payload: Payload::None,
+            superview: None,
+            subviews: Vec::new()
+        });
+    }
+
+    /// Returns the record for `object`, if there is one. Classes (and anything allocated outside
+    /// of the runtime) have none.
+    pub fn get(&self, object: usize) -> Option<&ObjectRecord> {
+        self.objects.get(&object)
+    }
+
+    /// Returns the record for `object`, if there is one.
+    pub fn get_mut(&mut self, object: usize) -> Option<&mut ObjectRecord> {
+        self.objects.get_mut(&object)
+    }
+
+    /// Sets the property `key` on `object`.
+    pub fn set(&mut self, object: usize, key: &str, value: Property) {
+        if let Some(record) = self.objects.get_mut(&object) {
+            record.properties.insert(key.to_string(), value);
+        }
+    }
+
+    /// Returns the property `key` on `object`.
+    pub fn property(&self, object: usize, key: &str) -> Option<&Property> {
+        self.objects.get(&object).and_then(|record| record.properties.get(key))
+    }
+
+    /// Returns the payload of `object`, or `Payload::None` if it isn't recorded.
+    pub fn payload(&self, object: usize) -> &Payload {
+        match self.objects.get(&object) {
+            Some(record) => &record.payload,
+            None => &Payload::None
+        }
+    }
+
+    /// Sets the payload of `object`.
+    pub fn set_payload(&mut self, object: usize, payload: Payload) {
+        if let Some(record) = self.objects.get_mut(&object) {
+            record.payload = payload;
+        }
+    }
+
+    /// Moves `view` into `superview`, removing it from wherever it was before.
+    pub fn add_subview(&mut self, superview: usize, view: usize) {
+        self.remove_from_superview(view);
+
+        if let Some(record) = self.objects.get_mut(&view) {
+            record.superview = Some(superview);
+        }
+
+        if let Some(record) = self.objects.get_mut(&superview) {
+            record.subviews.push(view);
+        }
+    }
+
+    /// Removes `view` from its superview, if it has one.
+    pub fn remove_from_superview(&mut self, view: usize) {
+        let superview = match self.objects.get_mut(&view) {
+            Some(record) => record.superview.take(),
+            None => None
+        };
+
+        if let Some(record) = superview.and_then(|superview| self.objects.get_mut(&superview)) {
+            record.subviews.retain(|subview| *subview != view);
+        }
+    }
+
+    /// Activates or deactivates `constraint`.
+    pub fn set_active(&mut self, constraint: usize, active: bool) {
+        self.active_constraints.retain(|c| *c != constraint);
+
+        if active {
+            self.active_constraints.push(constraint);
+        }
+    }
+
+    pub fn retain(&mut self, object: *mut Object) {
+        if let Some(record) = self.objects.get_mut(&(object as usize)) {
+            record.retain_count += 1;
+        }
+    }
+
+    pub fn release(&mut self, object: *mut Object) {
+        if let Some(record) = self.objects.get_mut(&(object as usize)) {
+            record.retain_count = record.retain_count.saturating_sub(1);
+        }
+    }
+}
@@ -9,9 +9,12 @@ use core_graphics::{
     base::{CGFloat},
     geometry::{CGRect, CGPoint, CGSize}
 };
+
+// Off Apple platforms, only the `core-graphics` geometry types are available.
+#[cfg(target_vendor = "apple")]
 use core_graphics::context::{CGContext, CGContextRef};
 
-use crate::foundation::{id, YES, NO, NSString};
+use crate::foundation::{id, BOOL, YES, NO, NSString};
 use crate::utils::os;
 use super::icons::*;
 
@@ -132,7 +135,44 @@ impl Image {
         })
     }
 
+    /// Returns a copy of this image, flipped horizontally - e.g, to point a "back" arrow the other
+    /// way in right-to-left layouts. `ImageView::set_directional_image` does this for you.
+    pub fn mirrored(&self) -> Self {
+        #[cfg(feature = "macos")]
+        {
+            let source = self.0.clone();
+            let size: CGSize = unsafe { msg_send![&*self.0, size] };
+            let is_template: BOOL = unsafe { msg_send![&*self.0, isTemplate] };
+
+            let block = ConcreteBlock::new(move |destination: CGRect| unsafe {
+                let _: () = msg_send![class!(NSGraphicsContext), saveGraphicsState];
+
+                let transform: id = msg_send![class!(NSAffineTransform), transform];
+                let _: () = msg_send![transform, translateXBy:(destination.origin.x * 2. + destination.size.width) yBy:0. as CGFloat];
+                let _: () = msg_send![transform, scaleXBy:-1. as CGFloat yBy:1. as CGFloat];
+                let _: () = msg_send![transform, concat];
+                let _: () = msg_send![&*source, drawInRect:destination];
+
+                let _: () = msg_send![class!(NSGraphicsContext), restoreGraphicsState];
+                YES
+            });
+            let block = block.copy();
+
+            Image(unsafe {
+                let img: id = msg_send![class!(NSImage), imageWithSize:size flipped:NO drawingHandler:block];
+                let _: () = msg_send![img, setTemplate:is_template];
+                ShareId::from_ptr(img)
+            })
+        }
+
+        #[cfg(feature = "ios")]
+        Image(unsafe {
+            ShareId::from_ptr(msg_send![&*self.0, imageWithHorizontallyFlippedOrientation])
+        })
+    }
+
     /// Draw a custom image and get it back as a returned `Image`.
+    #[cfg(target_vendor = "apple")]
     pub fn draw<F>(config: DrawConfig, handler: F) -> Self
     where
         F: Fn(CGRect, &CGContextRef) -> bool + 'static
@@ -2,14 +2,14 @@ use objc_id::ShareId;
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSArray, NSString};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSArray, NSString};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::register_image_view_class;
 
 #[cfg(target_os = "ios")]
@@ -30,7 +30,7 @@ fn allocate_view(registration_fn: fn() -> *const Class) -> id {
         let view: id = msg_send![registration_fn(), new];
         let _: () = msg_send![view, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let _: () = msg_send![view, setWantsLayer:YES];
 
         view 
@@ -45,6 +45,8 @@ pub struct ImageView {
     /// A pointer to the Objective-C runtime view controller.
     pub objc: ShareId<Object>,
 
+    mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -71,29 +73,37 @@ pub struct ImageView {
 }
 
 impl Default for ImageView {
+    /// Returns a new `ImageView`. This can't take a `MainThreadMarker`, so it checks the current
+    /// thread at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        ImageView::new()
+        ImageView::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl ImageView {
     /// Returns a default `View`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let view = allocate_view(register_image_view_class);
 
         ImageView {
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         }
     }
 
+    /// Returns a marker for the main thread, which this image view is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Call this to set the background color for the backing layer.
     pub fn set_background_color(&self, color: Color) {
         let bg = color.into_platform_specific_color();
@@ -110,6 +120,16 @@ impl ImageView {
             let _: () = msg_send![&*self.objc, setImage:&*image.0];
         }
     }
+
+    /// Sets an image that points one way or the other (a "back" arrow, say), mirroring it if this
+    /// view lays out right-to-left. As with `set_image`, this happens once; call it again if the
+    /// layout direction changes.
+    pub fn set_directional_image(&self, image: &Image) {
+        match self.layout_direction().is_right_to_left() {
+            true => self.set_image(&image.mirrored()),
+            false => self.set_image(image)
+        }
+    }
 }
 
 impl Layout for ImageView {
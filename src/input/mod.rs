@@ -44,15 +44,15 @@ use objc_id::ShareId;
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSArray, NSInteger, NSString};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSArray, NSInteger, NSString};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 use crate::text::{Font, TextAlign};
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::{register_view_class, register_view_class_with_delegate};
 
 #[cfg(target_os = "ios")]
@@ -76,7 +76,7 @@ fn allocate_view(registration_fn: fn() -> *const Class) -> id {
 
         let _: () = msg_send![view, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let _: () = msg_send![view, setWantsLayer:YES];
 
         view 
@@ -93,6 +93,9 @@ pub struct TextField<T = ()> {
     /// A pointer to the delegate for this view.
     pub delegate: Option<Box<T>>,
 
+    /// Proof that this text field was created on the main thread, which keeps it there.
+    pub(crate) mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -119,26 +122,29 @@ pub struct TextField<T = ()> {
 }
 
 impl Default for TextField {
+    /// Returns a new `TextField`. This can't take a `MainThreadMarker`, so it checks the current
+    /// thread at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        TextField::new()
+        TextField::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl TextField {
     /// Returns a default `TextField`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let view = allocate_view(register_view_class);
 
         TextField {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         }
     }
@@ -147,7 +153,7 @@ impl TextField {
 impl<T> TextField<T> where T: TextFieldDelegate + 'static {
     /// Initializes a new TextField with a given `TextFieldDelegate`. This enables you to respond to events
     /// and customize the view as a module, similar to class-based systems.
-    pub fn with(delegate: T) -> TextField<T> {
+    pub fn with(delegate: T, mtm: MainThreadMarker) -> TextField<T> {
         let delegate = Box::new(delegate);
         
         let label = allocate_view(register_view_class_with_delegate::<T>);
@@ -160,14 +166,15 @@ impl<T> TextField<T> where T: TextFieldDelegate + 'static {
 
         let mut label = TextField {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![label, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![label, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![label, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![label, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![label, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![label, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![label, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![label, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(label),
+            leading: LayoutAnchorX::leading(label),
+            trailing: LayoutAnchorX::trailing(label),
+            bottom: LayoutAnchorY::bottom(label),
+            width: LayoutAnchorDimension::width(label),
+            height: LayoutAnchorDimension::height(label),
+            center_x: LayoutAnchorX::center_x(label),
+            center_y: LayoutAnchorY::center_y(label),
             objc: unsafe { ShareId::from_ptr(label) },
         };
 
@@ -185,6 +192,7 @@ impl<T> TextField<T> {
     pub(crate) fn clone_as_handle(&self) -> TextField {
         TextField {
             delegate: None,
+            mtm: self.mtm,
             top: self.top.clone(),
             leading: self.leading.clone(),
             trailing: self.trailing.clone(),
@@ -197,6 +205,11 @@ impl<T> TextField<T> {
         }
     }
 
+    /// Returns a marker for the main thread, which this text field is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Grabs the value from the textfield and returns it as an owned String.
     pub fn get_value(&self) -> String {
         let value = NSString::wrap(unsafe {
@@ -226,7 +239,12 @@ impl<T> TextField<T> {
         }
     }
 
+    /// Sets the alignment of the text. `TextAlign::Natural` is resolved against this control's
+    /// layout direction when this is called, so it follows any direction forced on it (or on a
+    /// view containing it) - see `Layout::set_layout_direction`.
     pub fn set_text_alignment(&self, alignment: TextAlign) {
+        let alignment = alignment.resolve(self.layout_direction());
+
         unsafe {
             let alignment: NSInteger = alignment.into();
             let _: () = msg_send![&*self.objc, setAlignment:alignment];
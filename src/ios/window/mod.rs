@@ -7,6 +7,7 @@ use objc_id::Id;
 
 use crate::foundation::id;
 use crate::geometry::Rect;
+use crate::layout::direction::{self, LayoutDirection};
 use crate::ios::Scene;
 use crate::utils::Controller;
 
@@ -36,6 +37,18 @@ impl Window {
         }
     }
 
+    /// Returns the direction this window lays out in: its own override if it has one, or the
+    /// app's.
+    pub fn layout_direction(&self) -> LayoutDirection {
+        direction::effective_layout_direction(&*self.0 as *const Object as id)
+    }
+
+    /// Forces everything in this window to lay out in `direction` - or with `None`, goes back to
+    /// following the app. Views inside of it can still override this for themselves.
+    pub fn set_layout_direction(&self, layout_direction: Option<LayoutDirection>) {
+        direction::set_layout_direction_override(&*self.0 as *const Object as id, layout_direction);
+    }
+
     pub fn show(&self) {
         unsafe {
             let _: () = msg_send![&*self.0, makeKeyAndVisible];
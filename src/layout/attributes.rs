@@ -26,7 +26,7 @@ impl From<NSInteger> for LayoutConstraintOrientation {
 }
 
 /// Represents a relation between layout constraints. Used mostly internally.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutRelation {
     /// Relation is less than or equal to another specified relation.
     LessThanOrEqual,
@@ -43,6 +43,17 @@ pub enum LayoutRelation {
     Unknown(NSInteger)
 }
 
+impl From<LayoutRelation> for NSInteger {
+    fn from(relation: LayoutRelation) -> Self {
+        match relation {
+            LayoutRelation::LessThanOrEqual => -1,
+            LayoutRelation::Equal => 0,
+            LayoutRelation::GreaterThanOrEqual => 1,
+            LayoutRelation::Unknown(i) => i
+        }
+    }
+}
+
 impl From<NSInteger> for LayoutRelation {
     fn from(i: NSInteger) -> Self {
         match i {
@@ -59,7 +70,7 @@ impl From<NSInteger> for LayoutRelation {
 /// Note that this only covers attributes that are shared across platforms. In general, this is enough
 /// to build apps that work everywhere - but if you need to specify something else, you can handle
 /// it yourself with the `Unknown` variant.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutAttribute {
     /// The left side of the object’s alignment rectangle.
     Left,
@@ -111,6 +122,27 @@ pub enum LayoutAttribute {
     Unknown(NSInteger)
 }
 
+impl From<LayoutAttribute> for NSInteger {
+    fn from(attribute: LayoutAttribute) -> Self {
+        match attribute {
+            LayoutAttribute::Left => 1,
+            LayoutAttribute::Right => 2,
+            LayoutAttribute::Top => 3,
+            LayoutAttribute::Bottom => 4,
+            LayoutAttribute::Leading => 5,
+            LayoutAttribute::Trailing => 6,
+            LayoutAttribute::Width => 7,
+            LayoutAttribute::Height => 8,
+            LayoutAttribute::CenterX => 9,
+            LayoutAttribute::CenterY => 10,
+            LayoutAttribute::LastBaseline => 11,
+            LayoutAttribute::FirstBaseline => 12,
+            LayoutAttribute::NotAnAttribute => 0,
+            LayoutAttribute::Unknown(i) => i
+        }
+    }
+}
+
 impl From<NSInteger> for LayoutAttribute {
     fn from(i: NSInteger) -> Self {
         match i {
@@ -206,9 +238,36 @@ impl From<NSUInteger> for LayoutFormat {
 }
 
 /// Specifies layout priority.
-#[derive(Debug)]
+///
+/// Constraints below `Required` are optional, and may be broken by the system in order to satisfy
+/// higher priority constraints. This converts into an `f64`, which is what `LayoutConstraint`
+/// stores.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutPriority {
+    /// A required constraint (`1000`). This is the default for new constraints.
     Required,
+
+    /// The priority a button resists compressing its content with (`750`).
     High,
-    Low
+
+    /// The priority a button hugs its contents horizontally with (`250`).
+    Low,
+
+    /// The priority with which a view wants to conform to a target size when fitting (`50`).
+    FittingSizeCompression,
+
+    /// Any other priority, from `1` to `1000`.
+    Custom(f64)
+}
+
+impl From<LayoutPriority> for f64 {
+    fn from(priority: LayoutPriority) -> Self {
+        match priority {
+            LayoutPriority::Required => 1000.,
+            LayoutPriority::High => 750.,
+            LayoutPriority::Low => 250.,
+            LayoutPriority::FittingSizeCompression => 50.,
+            LayoutPriority::Custom(priority) => priority
+        }
+    }
 }
@@ -8,7 +8,10 @@ use objc::{class, msg_send, sel, sel_impl};
 use objc::runtime::Object;
 use objc_id::ShareId;
 
-use crate::foundation::id;
+use crate::foundation::{id, nil, to_bool, BOOL, YES, NO, NSInteger, NSString};
+use crate::layout::attributes::LayoutRelation;
+use crate::layout::diagnostics::{self, LayoutDiagnostic};
+use crate::layout::record::{AnchorRecord, ConstraintRecord, REQUIRED_PRIORITY};
 
 /// A wrapper for `NSLayoutConstraint`. This both acts as a central path through which to activate
 /// constraints, as well as a wrapper for layout constraints that are not axis bound (e.g, width or
@@ -19,6 +22,15 @@ pub struct LayoutConstraint {
     /// be valid.
     pub constraint: ShareId<Object>,
 
+    /// The anchor on the left hand side of this constraint.
+    pub first: AnchorRecord,
+
+    /// The relation between the two sides of this constraint.
+    pub relation: LayoutRelation,
+
+    /// The anchor on the right hand side of this constraint, if it's not against a constant.
+    pub second: Option<AnchorRecord>,
+
     /// The offset used in computing this constraint.
     pub offset: f64,
 
@@ -27,16 +39,42 @@ pub struct LayoutConstraint {
 
     /// The priority used in computing this constraint.
     pub priority: f64,
+
+    /// An optional identifier, useful for finding this constraint in AppKit/UIKit logging.
+    pub identifier: Option<String>
 }
 
 impl LayoutConstraint {
-    /// An internal method for wrapping existing constraints.
-    pub(crate) fn new(object: id) -> Self {
+    /// An internal method for wrapping existing constraints, along with a record of what they
+    /// were built from.
+    pub(crate) fn new(
+        object: id,
+        first: AnchorRecord,
+        relation: LayoutRelation,
+        second: Option<AnchorRecord>
+    ) -> Self {
         LayoutConstraint {
             constraint: unsafe { ShareId::from_ptr(object) },
+            first: first,
+            relation: relation,
+            second: second,
             offset: 0.0,
-            multiplier: 0.0,
-            priority: 0.0
+            multiplier: 1.0,
+            priority: REQUIRED_PRIORITY,
+            identifier: None
+        }
+    }
+
+    /// Returns a plain, runtime-independent record of this constraint. This is what the
+    /// `solver` module works against.
+    pub fn record(&self) -> ConstraintRecord {
+        ConstraintRecord {
+            first: self.first,
+            relation: self.relation,
+            second: self.second,
+            multiplier: self.multiplier,
+            offset: self.offset,
+            priority: self.priority
         }
     }
 
@@ -50,13 +88,125 @@ impl LayoutConstraint {
         }
 
         LayoutConstraint {
-            constraint: self.constraint,
             offset: offset,
-            multiplier: self.multiplier,
-            priority: self.priority
+            ..self
+        }
+    }
+
+    /// An internal method for building a new underlying constraint from a record. The items in
+    /// the record must be the addresses of live views - i.e, they must have come from anchors or
+    /// `Layout::get_backing_node()`.
+    pub(crate) fn from_record(record: ConstraintRecord) -> Self {
+        let constraint: id = unsafe {
+            let first_item = record.first.item.0 as id;
+            let first_attribute: NSInteger = record.first.attribute.into();
+            let relation: NSInteger = record.relation.into();
+
+            let (second_item, second_attribute): (id, NSInteger) = match record.second {
+                Some(anchor) => (anchor.item.0 as id, anchor.attribute.into()),
+                None => (nil, 0)
+            };
+
+            let m = record.multiplier as CGFloat;
+            let c = record.offset as CGFloat;
+
+            let constraint: id = msg_send![class!(NSLayoutConstraint), constraintWithItem:first_item
+                attribute:first_attribute
+                relatedBy:relation
+                toItem:second_item
+                attribute:second_attribute
+                multiplier:m
+                constant:c
+            ];
+
+            let priority = record.priority as f32;
+            let _: () = msg_send![constraint, setPriority:priority];
+            constraint
+        };
+
+        let mut constraint = LayoutConstraint::new(constraint, record.first, record.relation, record.second);
+        constraint.offset = record.offset;
+        constraint.multiplier = record.multiplier;
+        constraint.priority = record.priority;
+        constraint
+    }
+
+    /// Sets the multiplier for this constraint.
+    ///
+    /// `NSLayoutConstraint` doesn't allow changing the multiplier after creation, so this builds
+    /// a new underlying constraint from the recorded anchors, carrying over the offset, priority
+    /// and identifier. If this constraint is already active, the new one replaces it.
+    pub fn multiplier<F: Into<f64>>(self, multiplier: F) -> Self {
+        let mut record = self.record();
+        record.multiplier = multiplier.into();
+
+        let constraint = LayoutConstraint::from_record(record);
+        let constraint = match &self.identifier {
+            Some(identifier) => constraint.identifier(identifier),
+            None => constraint
+        };
+
+        if self.is_active() {
+            self.set_active(false);
+            constraint.set_active(true);
+        }
+
+        constraint
+    }
+
+    /// Sets the priority for this constraint. Accepts a `LayoutPriority` or a raw value from `1`
+    /// to `1000`.
+    ///
+    /// Note that the system doesn't allow changing a constraint from required to optional (or
+    /// vice versa) once it's been activated, so call this before activating it.
+    pub fn priority<P: Into<f64>>(self, priority: P) -> Self {
+        let priority: f64 = priority.into();
+
+        unsafe {
+            let p = priority as f32;
+            let _: () = msg_send![&*self.constraint, setPriority:p];
+        }
+
+        LayoutConstraint {
+            priority: priority,
+            ..self
+        }
+    }
+
+    /// Sets an identifier on this constraint. This shows up in the system's logging when
+    /// constraints can't be satisfied, which can make debugging far less painful.
+    pub fn identifier(self, identifier: &str) -> Self {
+        unsafe {
+            let value = NSString::new(identifier);
+            let _: () = msg_send![&*self.constraint, setIdentifier:value];
+        }
+
+        LayoutConstraint {
+            identifier: Some(identifier.to_string()),
+            ..self
         }
     }
 
+    /// Activates or deactivates this constraint on its own. For batches, prefer
+    /// `LayoutConstraint::activate` and `LayoutConstraint::deactivate`.
+    pub fn set_active(&self, active: bool) {
+        unsafe {
+            let _: () = msg_send![&*self.constraint, setActive:match active {
+                true => YES,
+                false => NO
+            }];
+        }
+    }
+
+    /// Returns whether this constraint is currently active.
+    pub fn is_active(&self) -> bool {
+        let result: BOOL = unsafe {
+            msg_send![&*self.constraint, isActive]
+        };
+
+        to_bool(result)
+    }
+
     /// Call this with your batch of constraints to activate them.
     // If you're astute, you'll note that, yes... this is kind of hacking around some
     // borrowing rules with how objc_id::Id/objc_id::ShareId works. In this case, to
@@ -75,4 +225,105 @@ impl LayoutConstraint {
             let _: () = msg_send![class!(NSLayoutConstraint), activateConstraints:constraints];
         }
     }
+
+    /// Call this with your batch of constraints to deactivate them.
+    pub fn deactivate(constraints: &[LayoutConstraint]) {
+        unsafe {
+            let ids: Vec<&Object> = constraints.into_iter().map(|constraint| {
+                &*constraint.constraint
+            }).collect();
+
+            let constraints: id = msg_send![class!(NSArray), arrayWithObjects:ids.as_ptr() count:ids.len()];
+            let _: () = msg_send![class!(NSLayoutConstraint), deactivateConstraints:constraints];
+        }
+    }
+
+    /// Inspects a batch of constraints without activating them, returning any conflicts,
+    /// priority collisions or underconstrained views that were found. This works entirely off of
+    /// the recorded constraints, so it doesn't need a live view hierarchy.
+    ///
+    /// See the `diagnostics` module for more information.
+    pub fn validate(constraints: &[LayoutConstraint]) -> Result<(), Vec<LayoutDiagnostic>> {
+        let records: Vec<ConstraintRecord> = constraints.iter().map(|c| c.record()).collect();
+        diagnostics::validate(&records)
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::LayoutConstraint;
+    use crate::foundation::MainThreadMarker;
+    use crate::layout::{LayoutAttribute, LayoutPriority, LayoutRelation};
+    use crate::view::View;
+
+    #[test]
+    fn rebuilds_with_a_multiplier() {
+        let mtm = MainThreadMarker::new().unwrap();
+        let parent = View::new(mtm);
+        let child = View::new(mtm);
+
+        let constraint = child.width.constraint_greater_than_or_equal_to(&parent.width)
+            .offset(8.)
+            .priority(LayoutPriority::High)
+            .identifier("child-width");
+
+        let original = constraint.record();
+        let rebuilt = constraint.clone().multiplier(0.5);
+        let record = rebuilt.record();
+
+        assert_eq!(record.first, original.first);
+        assert_eq!(record.relation, LayoutRelation::GreaterThanOrEqual);
+        assert_eq!(record.second, original.second);
+        assert_eq!(record.first.attribute, LayoutAttribute::Width);
+        assert_eq!(record.multiplier, 0.5);
+        assert_eq!(record.offset, 8.);
+        assert_eq!(record.priority, original.priority);
+        assert!(!record.is_required());
+        assert_eq!(rebuilt.identifier.as_deref(), Some("child-width"));
+
+        // The underlying constraint is a new one.
+        assert!(!std::ptr::eq(&*rebuilt.constraint, &*constraint.constraint));
+    }
+
+    #[test]
+    fn keeps_priority_and_identifier() {
+        let mtm = MainThreadMarker::new().unwrap();
+        let view = View::new(mtm);
+
+        let constraint = view.width.constraint_equal_to_constant(100.);
+        assert!(constraint.record().is_required());
+
+        let constraint = constraint.priority(250.).identifier("width");
+        assert_eq!(constraint.record().priority, 250.);
+        assert_eq!(constraint.record().offset, 100.);
+        assert_eq!(constraint.identifier.as_deref(), Some("width"));
+    }
+
+    #[test]
+    fn tracks_activation() {
+        let mtm = MainThreadMarker::new().unwrap();
+        let parent = View::new(mtm);
+        let child = View::new(mtm);
+
+        let top = child.top.constraint_equal_to(&parent.top);
+        let width = child.width.constraint_equal_to(&parent.width);
+        assert!(!top.is_active());
+
+        top.set_active(true);
+        assert!(top.is_active());
+
+        top.set_active(false);
+        assert!(!top.is_active());
+
+        LayoutConstraint::activate(&[top.clone(), width.clone()]);
+        assert!(top.is_active() && width.is_active());
+
+        // Changing the multiplier of an active constraint swaps the new one in.
+        let half = width.clone().multiplier(0.5);
+        assert!(half.is_active());
+        assert!(!width.is_active());
+
+        LayoutConstraint::deactivate(&[top.clone(), half.clone()]);
+        assert!(!top.is_active() && !half.is_active());
+    }
 }
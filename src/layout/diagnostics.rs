@@ -0,0 +1,398 @@
+//! Diagnostics for batches of layout constraints.
+//!
+//! AppKit will happily accept a set of constraints that can't be satisfied, and let you know about
+//! it by logging to the console at runtime. This module runs a batch of `ConstraintRecord`s through
+//! the `solver` ahead of time and reports problems as data instead:
+//!
+//! - Items whose position or size isn't fully determined.
+//! - Required constraints that can't be satisfied, along with the (minimal) set of constraints
+//!   they conflict with.
+//! - Optional constraints at the same priority that can't both be satisfied, where the system
+//!   would have to pick one arbitrarily.
+//!
+//! Items that never appear on the left hand side of a constraint are treated as roots - they're
+//! assumed to be positioned by something else (e.g, a window's content view), and are never
+//! reported as underconstrained. If you know a root's frame, pass it to `diagnose_in` (or
+//! `validate_in`): it's then required, just like the constraints themselves, so anything that
+//! doesn't fit inside it is reported as a conflict. Roots without a known frame are only weakly
+//! held in place, and simply take on whatever size the constraints ask for.
+//!
+//! ```rust,no_run
+//! use cacao::foundation::MainThreadMarker;
+//! use cacao::layout::LayoutConstraint;
+//! use cacao::view::View;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let content = View::new(mtm);
+//! let child = View::new(mtm);
+//!
+//! let constraints = [
+//!     child.top.constraint_equal_to(&content.top),
+//!     child.leading.constraint_equal_to(&content.leading)
+//! ];
+//!
+//! // Child has no width or height, so this reports it as underconstrained.
+//! if let Err(diagnostics) = LayoutConstraint::validate(&constraints) {
+//!     for diagnostic in diagnostics {
+//!         println!("{}", diagnostic);
+//!     }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error;
+use std::fmt;
+
+use crate::geometry::Rect;
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem, REQUIRED_PRIORITY};
+use crate::layout::solver::{LayoutSolver, SolverError};
+
+/// A problem found in a batch of constraints.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutDiagnostic {
+    /// The item's position or size isn't fully determined. `attributes` lists which of `Left`,
+    /// `Top`, `Width` and `Height` are ambiguous.
+    Underconstrained {
+        item: LayoutItem,
+        attributes: Vec<LayoutAttribute>
+    },
+
+    /// A required constraint can't be satisfied. `conflicts_with` is a minimal set of
+    /// constraints from the same batch that, together with `constraint`, can't be satisfied.
+    Conflict {
+        constraint: ConstraintRecord,
+        conflicts_with: Vec<ConstraintRecord>
+    },
+
+    /// Two optional constraints share a priority and can each be satisfied on their own, but not
+    /// together - so the result depends on which one the system decides to break.
+    PriorityCollision {
+        first: ConstraintRecord,
+        second: ConstraintRecord,
+        priority: f64
+    },
+
+    /// The constraint couldn't be modeled at all (e.g, it uses an `Unknown` attribute).
+    Unsupported {
+        constraint: ConstraintRecord,
+        error: SolverError
+    }
+}
+
+impl fmt::Display for LayoutDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutDiagnostic::Underconstrained { item, attributes } => {
+                write!(f, "Item {:?} is underconstrained: ambiguous {:?}", item, attributes)
+            },
+
+            LayoutDiagnostic::Conflict { constraint, conflicts_with } => {
+                write!(f, "Required constraint {:?} conflicts with {:?}", constraint, conflicts_with)
+            },
+
+            LayoutDiagnostic::PriorityCollision { first, second, priority } => {
+                write!(f, "Constraints {:?} and {:?} collide at priority {}", first, second, priority)
+            },
+
+            LayoutDiagnostic::Unsupported { constraint, error } => {
+                write!(f, "Constraint {:?} is unsupported: {}", constraint, error)
+            }
+        }
+    }
+}
+
+impl error::Error for LayoutDiagnostic {}
+
+/// Returns the items that only ever appear on the right hand side of a constraint.
+fn roots(records: &[ConstraintRecord]) -> BTreeSet<LayoutItem> {
+    let constrained: BTreeSet<LayoutItem> = records.iter().map(|r| r.first.item).collect();
+
+    records.iter()
+        .flat_map(|r| r.items())
+        .filter(|item| !constrained.contains(item))
+        .collect()
+}
+
+/// Builds a solver with the roots held in place, so they don't read as ambiguous: pinned to their
+/// frame if it's known, and weakly held at the origin otherwise.
+fn base_solver(roots: &BTreeSet<LayoutItem>, frames: &BTreeMap<LayoutItem, Rect>) -> LayoutSolver {
+    let mut solver = LayoutSolver::new();
+
+    for (item, frame) in frames {
+        // Nothing else is in the solver yet, so a single frame can't conflict.
+        let _ = solver.set_frame(*item, *frame);
+    }
+
+    for item in roots.iter().filter(|item| !frames.contains_key(item)) {
+        for attribute in [
+            LayoutAttribute::Left,
+            LayoutAttribute::Top,
+            LayoutAttribute::Width,
+            LayoutAttribute::Height
+        ].iter() {
+            let mut record = ConstraintRecord::new(AnchorRecord::new(*item, *attribute), LayoutRelation::Equal, None);
+            record.priority = 1.;
+
+            // Optional constraints can't be unsatisfiable, so this can't fail.
+            let _ = solver.add_constraint(&record);
+        }
+    }
+
+    solver
+}
+
+/// Whether `records` can all be satisfied, treating every one of them as required.
+fn satisfiable(base: &LayoutSolver, records: &[ConstraintRecord]) -> bool {
+    let mut solver = base.clone();
+
+    records.iter().all(|record| {
+        let mut record = *record;
+        record.priority = REQUIRED_PRIORITY;
+        solver.add_constraint(&record).is_ok()
+    })
+}
+
+/// Narrows `accepted` down to a minimal set that still conflicts with `constraint`.
+fn conflict_set(base: &LayoutSolver, accepted: &[ConstraintRecord], constraint: ConstraintRecord) -> Vec<ConstraintRecord> {
+    let mut core: Vec<ConstraintRecord> = accepted.to_vec();
+    let mut index = 0;
+
+    while index < core.len() {
+        let mut candidate = core.clone();
+        candidate.remove(index);
+        candidate.push(constraint);
+
+        if satisfiable(base, &candidate) {
+            index += 1;
+        } else {
+            core.remove(index);
+        }
+    }
+
+    core
+}
+
+/// Inspects a batch of constraint records and returns every problem found. An empty result
+/// means the batch is satisfiable and fully determines every non-root item.
+pub fn diagnose(records: &[ConstraintRecord]) -> Vec<LayoutDiagnostic> {
+    diagnose_in(records, &BTreeMap::new())
+}
+
+/// Like `diagnose`, but with the frames of some items (typically the roots) known up front. These
+/// are required, so constraints that don't fit inside them are reported as conflicts; items with
+/// a frame are never reported as underconstrained.
+pub fn diagnose_in(records: &[ConstraintRecord], frames: &BTreeMap<LayoutItem, Rect>) -> Vec<LayoutDiagnostic> {
+    let mut roots = roots(records);
+    roots.extend(frames.keys().cloned());
+    let base = base_solver(&roots, frames);
+
+    let mut diagnostics = Vec::new();
+    let mut solver = base.clone();
+    let mut accepted: Vec<ConstraintRecord> = Vec::new();
+    let mut optional: Vec<ConstraintRecord> = Vec::new();
+
+    for record in records.iter().filter(|r| r.is_required()) {
+        match solver.add_constraint(record) {
+            Ok(()) => { accepted.push(*record); },
+
+            Err(SolverError::UnsatisfiableConstraint(_)) => {
+                diagnostics.push(LayoutDiagnostic::Conflict {
+                    constraint: *record,
+                    conflicts_with: conflict_set(&base, &accepted, *record)
+                });
+            },
+
+            Err(error) => {
+                diagnostics.push(LayoutDiagnostic::Unsupported { constraint: *record, error });
+            }
+        }
+    }
+
+    // Optional constraints are checked against the required ones that made it in.
+    let mut required = base.clone();
+    let _ = required.add_constraints(&accepted);
+
+    for record in records.iter().filter(|r| !r.is_required()) {
+        match solver.add_constraint(record) {
+            Ok(()) => {
+                let collisions: Vec<ConstraintRecord> = match satisfiable(&required, &[*record]) {
+                    true => optional.iter().filter(|other| {
+                        other.priority == record.priority &&
+                            satisfiable(&required, &[**other]) &&
+                            !satisfiable(&required, &[**other, *record])
+                    }).cloned().collect(),
+
+                    false => Vec::new()
+                };
+
+                for other in collisions {
+                    diagnostics.push(LayoutDiagnostic::PriorityCollision {
+                        first: other,
+                        second: *record,
+                        priority: record.priority
+                    });
+                }
+
+                optional.push(*record);
+            },
+
+            Err(error) => {
+                diagnostics.push(LayoutDiagnostic::Unsupported { constraint: *record, error });
+            }
+        }
+    }
+
+    let mut items: BTreeSet<LayoutItem> = records.iter().flat_map(|r| r.items()).collect();
+    items.retain(|item| !roots.contains(item));
+
+    for item in items {
+        let attributes = solver.ambiguous_attributes(item);
+
+        if !attributes.is_empty() {
+            diagnostics.push(LayoutDiagnostic::Underconstrained { item, attributes });
+        }
+    }
+
+    diagnostics
+}
+
+/// Like `diagnose`, but returns `Ok(())` if nothing was found.
+pub fn validate(records: &[ConstraintRecord]) -> Result<(), Vec<LayoutDiagnostic>> {
+    validate_in(records, &BTreeMap::new())
+}
+
+/// Like `diagnose_in`, but returns `Ok(())` if nothing was found.
+pub fn validate_in(records: &[ConstraintRecord], frames: &BTreeMap<LayoutItem, Rect>) -> Result<(), Vec<LayoutDiagnostic>> {
+    let diagnostics = diagnose_in(records, frames);
+
+    match diagnostics.is_empty() {
+        true => Ok(()),
+        false => Err(diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{diagnose, diagnose_in, validate, validate_in, LayoutDiagnostic};
+    use crate::geometry::Rect;
+    use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+    use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+    use crate::layout::solver::SolverError;
+
+    const ROOT: LayoutItem = LayoutItem(1);
+    const CHILD: LayoutItem = LayoutItem(2);
+
+    /// `CHILD.attribute <relation> ROOT.attribute + offset`, or against a constant if `to_root`
+    /// is `false`.
+    fn record(attribute: LayoutAttribute, relation: LayoutRelation, to_root: bool, offset: f64) -> ConstraintRecord {
+        let second = match to_root {
+            true => Some(AnchorRecord::new(ROOT, attribute)),
+            false => None
+        };
+
+        let mut record = ConstraintRecord::new(AnchorRecord::new(CHILD, attribute), relation, second);
+        record.offset = offset;
+        record
+    }
+
+    fn pinned() -> Vec<ConstraintRecord> {
+        vec![
+            record(LayoutAttribute::Top, LayoutRelation::Equal, true, 0.),
+            record(LayoutAttribute::Left, LayoutRelation::Equal, true, 0.),
+            record(LayoutAttribute::Width, LayoutRelation::Equal, false, 100.),
+            record(LayoutAttribute::Height, LayoutRelation::Equal, false, 50.)
+        ]
+    }
+
+    #[test]
+    fn accepts_a_complete_layout() {
+        assert_eq!(validate(&pinned()), Ok(()));
+    }
+
+    #[test]
+    fn reports_underconstrained_items() {
+        let records = &pinned()[..2];
+
+        assert_eq!(diagnose(records), vec![LayoutDiagnostic::Underconstrained {
+            item: CHILD,
+            attributes: vec![LayoutAttribute::Width, LayoutAttribute::Height]
+        }]);
+    }
+
+    #[test]
+    fn reports_conflicts_with_a_minimal_set() {
+        // Without a known frame, the root would just move, so this pins to a constant.
+        let mut records = pinned();
+        records[1] = record(LayoutAttribute::Left, LayoutRelation::Equal, false, 20.);
+        records.push(record(LayoutAttribute::Right, LayoutRelation::Equal, false, 150.));
+
+        // Only the left edge and the width are to blame, not the top edge or the height.
+        assert_eq!(diagnose(&records), vec![LayoutDiagnostic::Conflict {
+            constraint: records[4],
+            conflicts_with: vec![records[1], records[2]]
+        }]);
+    }
+
+    #[test]
+    fn reports_priority_collisions() {
+        let mut records = pinned();
+        records.remove(2);
+
+        let mut narrow = record(LayoutAttribute::Width, LayoutRelation::Equal, false, 100.);
+        narrow.priority = 500.;
+
+        let mut wide = record(LayoutAttribute::Width, LayoutRelation::Equal, false, 200.);
+        wide.priority = 500.;
+
+        // At a different priority, there's a clear winner.
+        let mut weak = record(LayoutAttribute::Width, LayoutRelation::Equal, false, 300.);
+        weak.priority = 250.;
+
+        records.extend_from_slice(&[narrow, wide, weak]);
+
+        assert_eq!(diagnose(&records), vec![LayoutDiagnostic::PriorityCollision {
+            first: narrow,
+            second: wide,
+            priority: 500.
+        }]);
+    }
+
+    #[test]
+    fn reports_unsupported_constraints() {
+        let mut records = pinned();
+        records.push(record(LayoutAttribute::Unknown(99), LayoutRelation::Equal, false, 0.));
+
+        assert_eq!(diagnose(&records), vec![LayoutDiagnostic::Unsupported {
+            constraint: records[4],
+            error: SolverError::UnsupportedAttribute(LayoutAttribute::Unknown(99))
+        }]);
+    }
+
+    #[test]
+    fn roots_take_any_size_without_a_frame() {
+        let mut records = pinned();
+        records.push(record(LayoutAttribute::Right, LayoutRelation::Equal, true, 0.));
+
+        assert_eq!(validate(&records), Ok(()));
+    }
+
+    #[test]
+    fn reports_conflicts_with_a_root_frame() {
+        let mut frames = BTreeMap::new();
+        frames.insert(ROOT, Rect::new(0., 0., 80., 200.));
+
+        let mut records = pinned();
+        assert_eq!(validate_in(&records, &frames), Ok(()));
+
+        // Pinned to both edges of an 80pt wide root, the child can't also be 100pt wide.
+        records.push(record(LayoutAttribute::Right, LayoutRelation::Equal, true, 0.));
+
+        assert_eq!(diagnose_in(&records, &frames), vec![LayoutDiagnostic::Conflict {
+            constraint: records[4],
+            conflicts_with: vec![records[1], records[2]]
+        }]);
+    }
+}
@@ -8,39 +8,90 @@ use objc::runtime::Object;
 use objc_id::ShareId;
 
 use crate::foundation::id;
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
 use crate::layout::constraint::LayoutConstraint;
+use crate::layout::record::{AnchorRecord, LayoutItem};
 
 /// A wrapper for `NSLayoutAnchor`. You should never be creating this yourself - it's more of a
 /// factory/helper for creating `LayoutConstraint` objects based on your views.
 #[derive(Clone, Debug, Default)]
-pub struct LayoutAnchorDimension(pub Option<ShareId<Object>>);
+pub struct LayoutAnchorDimension(
+    /// A shared pointer to the underlying anchor. This is `None` for uninitialized anchors.
+    pub Option<ShareId<Object>>,
+
+    /// The item and attribute this anchor represents, for the Rust-side constraint model.
+    Option<AnchorRecord>
+);
 
 impl LayoutAnchorDimension {
     /// An internal method for wrapping existing anchors.
-    pub(crate) fn new(object: id) -> Self {
-        LayoutAnchorDimension(Some(unsafe {
-            ShareId::from_ptr(object)
-        }))
+    fn new(view: id, attribute: LayoutAttribute) -> Self {
+        let anchor: id = unsafe {
+            match attribute {
+                LayoutAttribute::Height => msg_send![view, heightAnchor],
+                _ => msg_send![view, widthAnchor]
+            }
+        };
+
+        LayoutAnchorDimension(
+            Some(unsafe { ShareId::from_ptr(anchor) }),
+            Some(AnchorRecord::new(LayoutItem(view as usize), attribute))
+        )
     }
 
-    /// Return a constraint equal to a constant value.
-    pub fn constraint_equal_to_constant(&self, constant: f64) -> LayoutConstraint {
-        match &self.0 {
-            Some(from) => LayoutConstraint::new(unsafe {
-                let value = constant as CGFloat;
-                msg_send![*from, constraintEqualToConstant:value]
-            }),
+    /// Wraps the `widthAnchor` of the given view.
+    pub(crate) fn width(view: id) -> Self {
+        Self::new(view, LayoutAttribute::Width)
+    }
+
+    /// Wraps the `heightAnchor` of the given view.
+    pub(crate) fn height(view: id) -> Self {
+        Self::new(view, LayoutAttribute::Height)
+    }
+
+    /// An internal method for building constraints against a constant value.
+    fn constraint_to_constant(&self, relation: LayoutRelation, constant: f64) -> LayoutConstraint {
+        match (&self.0, self.1) {
+            (Some(from), Some(first)) => {
+                let mut constraint = LayoutConstraint::new(unsafe {
+                    let value = constant as CGFloat;
+
+                    match relation {
+                        LayoutRelation::LessThanOrEqual => msg_send![*from, constraintLessThanOrEqualToConstant:value],
+                        LayoutRelation::GreaterThanOrEqual => msg_send![*from, constraintGreaterThanOrEqualToConstant:value],
+                        _ => msg_send![*from, constraintEqualToConstant:value]
+                    }
+                }, first, relation, None);
+
+                constraint.offset = constant;
+                constraint
+            },
 
             _ => { panic!("Attempted to create constraints with an uninitialized anchor!"); }
         }
     }
 
+    /// Return a constraint equal to a constant value.
+    pub fn constraint_equal_to_constant(&self, constant: f64) -> LayoutConstraint {
+        self.constraint_to_constant(LayoutRelation::Equal, constant)
+    }
+
+    /// Return a constraint greater than or equal to a constant value.
+    pub fn constraint_greater_than_or_equal_to_constant(&self, constant: f64) -> LayoutConstraint {
+        self.constraint_to_constant(LayoutRelation::GreaterThanOrEqual, constant)
+    }
+
+    /// Return a constraint less than or equal to a constant value.
+    pub fn constraint_less_than_or_equal_to_constant(&self, constant: f64) -> LayoutConstraint {
+        self.constraint_to_constant(LayoutRelation::LessThanOrEqual, constant)
+    }
+
     /// Return a constraint equal to another dimension anchor.
     pub fn constraint_equal_to(&self, anchor_to: &LayoutAnchorDimension) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::Equal, second),
 
             _ => { panic!("Attempted to create constraints with an uninitialized anchor!"); }
         }
@@ -48,10 +99,10 @@ impl LayoutAnchorDimension {
 
     /// Return a constraint greater than or equal to another dimension anchor.
     pub fn constraint_greater_than_or_equal_to(&self, anchor_to: &LayoutAnchorDimension) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintGreaterThanOrEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::GreaterThanOrEqual, second),
 
             _ => { panic!("Attempted to create constraints with an uninitialized anchor!"); }
         }
@@ -59,10 +110,10 @@ impl LayoutAnchorDimension {
 
     /// Return a constraint less than or equal to another dimension anchor.
     pub fn constraint_less_than_or_equal_to(&self, anchor_to: &LayoutAnchorDimension) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintLessThanOrEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::LessThanOrEqual, second),
 
             _ => { panic!("Attempted to create constraints with an uninitialized anchor!"); }
         }
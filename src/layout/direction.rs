@@ -0,0 +1,303 @@
+//! Right-to-left support: which way views lay out, and resolving leading, trailing and natural
+//! alignment against it.
+//!
+//! The user interface layout direction comes from the app's localization (Arabic and Hebrew lay
+//! out right-to-left, for instance), but can be forced for a view - and everything inside of it -
+//! with `Layout::set_layout_direction`, or for a whole window. Which override wins is decided by
+//! `resolve_layout_direction`, which (like the rest of the pure half of this module) doesn't touch
+//! the Objective-C runtime:
+//!
+//! ```rust
+//! use cacao::layout::{resolve_layout_direction, LayoutDirection};
+//!
+//! // A view with no override of its own, inside a container that's been forced right-to-left, in
+//! // a left-to-right app.
+//! let direction = resolve_layout_direction(
+//!     vec![None, Some(LayoutDirection::RightToLeft), None],
+//!     LayoutDirection::LeftToRight
+//! );
+//!
+//! assert_eq!(direction, LayoutDirection::RightToLeft);
+//! ```
+
+#[cfg(feature = "macos")]
+use std::os::raw::c_void;
+
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, NSInteger};
+use crate::geometry::Rect;
+use crate::layout::attributes::LayoutAttribute;
+
+#[cfg(feature = "macos")]
+use crate::foundation::{nil, NSNumber, NSUInteger};
+
+/// The direction a view lays out in. Mirrors `NSUserInterfaceLayoutDirection` and
+/// `UIUserInterfaceLayoutDirection`, which share values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum LayoutDirection {
+    /// Leading is left, and trailing is right.
+    #[default]
+    LeftToRight,
+
+    /// Leading is right, and trailing is left.
+    RightToLeft
+}
+
+impl From<NSInteger> for LayoutDirection {
+    fn from(direction: NSInteger) -> Self {
+        match direction {
+            1 => LayoutDirection::RightToLeft,
+            _ => LayoutDirection::LeftToRight
+        }
+    }
+}
+
+impl From<LayoutDirection> for NSInteger {
+    fn from(direction: LayoutDirection) -> Self {
+        match direction {
+            LayoutDirection::LeftToRight => 0,
+            LayoutDirection::RightToLeft => 1
+        }
+    }
+}
+
+impl LayoutDirection {
+    /// Returns whether this is `RightToLeft`.
+    pub fn is_right_to_left(&self) -> bool {
+        *self == LayoutDirection::RightToLeft
+    }
+
+    /// Resolves `Leading` and `Trailing` to `Left` or `Right`. Every other attribute is returned
+    /// as-is.
+    pub fn resolve_attribute(&self, attribute: LayoutAttribute) -> LayoutAttribute {
+        match (attribute, self) {
+            (LayoutAttribute::Leading, LayoutDirection::LeftToRight) => LayoutAttribute::Left,
+            (LayoutAttribute::Leading, LayoutDirection::RightToLeft) => LayoutAttribute::Right,
+            (LayoutAttribute::Trailing, LayoutDirection::LeftToRight) => LayoutAttribute::Right,
+            (LayoutAttribute::Trailing, LayoutDirection::RightToLeft) => LayoutAttribute::Left,
+            (attribute, _) => attribute
+        }
+    }
+
+    /// For `RightToLeft`, mirrors `rect` horizontally inside of `bounds` - so a frame worked out
+    /// from the leading edge ends up the same distance from the trailing one. `LeftToRight`
+    /// returns `rect` untouched.
+    pub fn mirror(&self, rect: Rect, bounds: Rect) -> Rect {
+        match self {
+            LayoutDirection::LeftToRight => rect,
+
+            LayoutDirection::RightToLeft => Rect::new(
+                rect.top,
+                bounds.left + (bounds.left + bounds.width) - (rect.left + rect.width),
+                rect.width,
+                rect.height
+            )
+        }
+    }
+}
+
+/// Works out the direction a view lays out in, given the overrides that apply to it - its own
+/// first, then each of its superviews', then its window's - and the direction of the app. The
+/// nearest override wins; if there are none, the view follows the app.
+pub fn resolve_layout_direction<I>(overrides: I, application: LayoutDirection) -> LayoutDirection
+where
+    I: IntoIterator<Item = Option<LayoutDirection>>
+{
+    overrides.into_iter().flatten().next().unwrap_or(application)
+}
+
+/// `NSView` has no notion of an unset direction, so on macOS overrides are kept alongside the
+/// view (or window) as an associated object, keyed by the address of this.
+#[cfg(feature = "macos")]
+static OVERRIDE_KEY: u8 = 0;
+
+/// `OBJC_ASSOCIATION_RETAIN_NONATOMIC`.
+#[cfg(feature = "macos")]
+const RETAIN_NONATOMIC: usize = 1;
+
+#[cfg(feature = "macos")]
+extern "C" {
+    fn objc_setAssociatedObject(object: id, key: *const c_void, value: id, policy: usize);
+    fn objc_getAssociatedObject(object: id, key: *const c_void) -> id;
+}
+
+/// Returns the direction of the app, which comes from the language it's running in.
+pub fn application_layout_direction() -> LayoutDirection {
+    unsafe {
+        #[cfg(feature = "macos")]
+        let application: id = msg_send![class!(NSApplication), sharedApplication];
+
+        #[cfg(feature = "ios")]
+        let application: id = msg_send![class!(UIApplication), sharedApplication];
+
+        let direction: NSInteger = msg_send![application, userInterfaceLayoutDirection];
+        LayoutDirection::from(direction)
+    }
+}
+
+/// Returns the override set on a view or window, if any.
+pub(crate) fn layout_direction_override(node: id) -> Option<LayoutDirection> {
+    #[cfg(feature = "macos")]
+    {
+        let value = unsafe { objc_getAssociatedObject(node, &OVERRIDE_KEY as *const u8 as *const c_void) };
+
+        match value.is_null() {
+            true => None,
+            false => Some(LayoutDirection::from(NSNumber::wrap(value).as_i64() as NSInteger))
+        }
+    }
+
+    // `UISemanticContentAttribute` - `.forceLeftToRight` and `.forceRightToLeft` are the only
+    // ones that force a direction.
+    #[cfg(feature = "ios")]
+    {
+        let attribute: NSInteger = unsafe { msg_send![node, semanticContentAttribute] };
+
+        match attribute {
+            3 => Some(LayoutDirection::LeftToRight),
+            4 => Some(LayoutDirection::RightToLeft),
+            _ => None
+        }
+    }
+}
+
+/// Sets (or with `None`, clears) the override on a view or window. On macOS, follow this with
+/// `apply_layout_direction` for the view(s) affected.
+pub(crate) fn set_layout_direction_override(node: id, direction: Option<LayoutDirection>) {
+    #[cfg(feature = "macos")]
+    unsafe {
+        let key = &OVERRIDE_KEY as *const u8 as *const c_void;
+
+        match direction {
+            Some(direction) => {
+                let value = NSNumber::integer(NSInteger::from(direction));
+                objc_setAssociatedObject(node, key, &*value.0 as *const _ as id, RETAIN_NONATOMIC);
+            },
+
+            None => objc_setAssociatedObject(node, key, nil, RETAIN_NONATOMIC)
+        }
+    }
+
+    #[cfg(feature = "ios")]
+    unsafe {
+        let attribute: NSInteger = match direction {
+            Some(LayoutDirection::LeftToRight) => 3,
+            Some(LayoutDirection::RightToLeft) => 4,
+            None => 0
+        };
+
+        let _: () = msg_send![node, setSemanticContentAttribute:attribute];
+    }
+}
+
+/// Returns the direction `view` lays out in, walking up through its superviews (and, on macOS,
+/// its window) for overrides.
+pub(crate) fn effective_layout_direction(view: id) -> LayoutDirection {
+    let mut overrides = Vec::new();
+    let mut current = view;
+
+    while !current.is_null() {
+        overrides.push(layout_direction_override(current));
+        current = unsafe { msg_send![current, superview] };
+    }
+
+    // On iOS, windows are views, and were picked up above.
+    #[cfg(feature = "macos")]
+    {
+        let window: id = unsafe { msg_send![view, window] };
+
+        if !window.is_null() {
+            overrides.push(layout_direction_override(window));
+        }
+    }
+
+    resolve_layout_direction(overrides, application_layout_direction())
+}
+
+/// Sets `userInterfaceLayoutDirection` on `view` and its subviews, each according to its resolved
+/// direction. UIKit does this on its own with `semanticContentAttribute`.
+#[cfg(feature = "macos")]
+pub(crate) fn apply_layout_direction(view: id) {
+    fn apply(view: id, direction: LayoutDirection) {
+        let direction = layout_direction_override(view).unwrap_or(direction);
+
+        unsafe {
+            let _: () = msg_send![view, setUserInterfaceLayoutDirection:NSInteger::from(direction)];
+        }
+
+        unsafe {
+            let subviews: id = msg_send![view, subviews];
+
+            if subviews == nil {
+                return;
+            }
+
+            let count: NSUInteger = msg_send![subviews, count];
+
+            for index in 0..count {
+                apply(msg_send![subviews, objectAtIndex:index], direction);
+            }
+        }
+    }
+
+    apply(view, effective_layout_direction(view));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_layout_direction, LayoutDirection};
+    use crate::geometry::Rect;
+    use crate::layout::{AnchorRecord, ConstraintRecord, LayoutAttribute, LayoutItem, LayoutRelation};
+    use crate::layout::{LayoutSolver, StackAxis, StackLayout};
+
+    #[test]
+    fn the_nearest_override_wins() {
+        use LayoutDirection::{LeftToRight, RightToLeft};
+
+        assert_eq!(resolve_layout_direction(vec![None, None], RightToLeft), RightToLeft);
+        assert_eq!(resolve_layout_direction(vec![None, Some(LeftToRight), Some(RightToLeft)], RightToLeft), LeftToRight);
+        assert_eq!(resolve_layout_direction(vec![Some(RightToLeft), Some(LeftToRight)], LeftToRight), RightToLeft);
+        assert_eq!(resolve_layout_direction(Vec::new(), LeftToRight), LeftToRight);
+    }
+
+    #[test]
+    fn resolves_leading_and_trailing() {
+        let rtl = LayoutDirection::RightToLeft;
+
+        assert_eq!(rtl.resolve_attribute(LayoutAttribute::Leading), LayoutAttribute::Right);
+        assert_eq!(rtl.resolve_attribute(LayoutAttribute::Trailing), LayoutAttribute::Left);
+        assert_eq!(rtl.resolve_attribute(LayoutAttribute::Top), LayoutAttribute::Top);
+        assert_eq!(LayoutDirection::LeftToRight.resolve_attribute(LayoutAttribute::Leading), LayoutAttribute::Left);
+    }
+
+    #[test]
+    fn mirrors_within_bounds() {
+        let bounds = Rect::new(0., 10., 100., 50.);
+        let rect = Rect::new(5., 20., 30., 10.);
+
+        assert_eq!(LayoutDirection::RightToLeft.mirror(rect, bounds), Rect::new(5., 70., 30., 10.));
+        assert_eq!(LayoutDirection::LeftToRight.mirror(rect, bounds), rect);
+    }
+
+    #[test]
+    fn the_solver_lays_stacks_out_from_the_right() {
+        let container = LayoutItem(1);
+        let children = [LayoutItem(2), LayoutItem(3)];
+
+        let mut solver = LayoutSolver::new();
+        solver.set_layout_direction(LayoutDirection::RightToLeft);
+        solver.set_frame(container, Rect::new(0., 0., 100., 20.)).unwrap();
+
+        let mut width = ConstraintRecord::new(AnchorRecord::new(children[0], LayoutAttribute::Width), LayoutRelation::Equal, None);
+        width.offset = 30.;
+        solver.add_constraint(&width).unwrap();
+
+        let mut layout = StackLayout::new(StackAxis::Horizontal);
+        layout.spacing = 10.;
+        solver.add_constraints(&layout.constraints(container, &children)).unwrap();
+
+        assert_eq!(solver.frame(children[0]).map(|frame| frame.left), Some(70.));
+        assert_eq!(solver.frame(children[1]).map(|frame| frame.width), Some(60.));
+    }
+}
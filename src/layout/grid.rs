@@ -0,0 +1,424 @@
+//! Cell geometry and constraint generation for grid layouts.
+//!
+//! This is the pure half of `GridView`. A `GridLayout` describes a set of column and row tracks -
+//! a fixed number of points, a fraction of whatever space is left over, or just enough to fit
+//! what's in them - along with the gaps between them and insets from the container's edges.
+//!
+//! From there you can either compute the frame for a cell directly (`cell_rect`), or generate the
+//! constraints that position views in cells (`constraints`). The latter uses one layout guide per
+//! track, so that fractional tracks can be expressed with plain two-anchor constraints.
+
+use crate::geometry::{EdgeInsets, Rect};
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+
+/// How a single row or column is sized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GridTrack {
+    /// A fixed size, in points.
+    Fixed(f64),
+
+    /// A share of the space left over once fixed tracks and gaps are accounted for. A track of
+    /// `Fraction(2.)` is twice the size of one of `Fraction(1.)`.
+    Fraction(f64),
+
+    /// Just large enough for the views in it. Views that end in an auto track need a size of
+    /// their own (e.g, a width constraint for a column), and the track fits the largest of them.
+    Auto
+}
+
+/// The priority auto tracks hug their content with (`NSLayoutPriorityDefaultLow`).
+const AUTO_HUGGING_PRIORITY: f64 = 250.;
+
+/// A cell (or range of cells) in a grid, by zero-based row and column.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GridCell {
+    /// The row this cell starts in.
+    pub row: usize,
+
+    /// The column this cell starts in.
+    pub column: usize,
+
+    /// How many rows this cell covers. Always at least one.
+    pub row_span: usize,
+
+    /// How many columns this cell covers. Always at least one.
+    pub column_span: usize
+}
+
+impl GridCell {
+    /// Returns a cell covering a single row and column.
+    pub fn new(row: usize, column: usize) -> Self {
+        GridCell { row: row, column: column, row_span: 1, column_span: 1 }
+    }
+
+    /// Returns this cell, spanning the given number of rows and columns.
+    pub fn span(self, rows: usize, columns: usize) -> Self {
+        GridCell {
+            row_span: rows.max(1),
+            column_span: columns.max(1),
+            ..self
+        }
+    }
+}
+
+/// Resolves a list of tracks into `(offset, size)` pairs, given the space available (after
+/// insets) and the gap between tracks. Auto tracks have nothing to fit, and resolve to zero; see
+/// `resolve_tracks_fitting`.
+pub fn resolve_tracks(tracks: &[GridTrack], available: f64, gap: f64) -> Vec<(f64, f64)> {
+    resolve_tracks_fitting(tracks, &[], available, gap)
+}
+
+/// Like `resolve_tracks`, but with the size of the content in each track - which auto tracks
+/// resolve to. `content` is indexed by track; tracks past the end of it have no content.
+pub fn resolve_tracks_fitting(tracks: &[GridTrack], content: &[f64], available: f64, gap: f64) -> Vec<(f64, f64)> {
+    let gaps = gap * (tracks.len().max(1) - 1) as f64;
+    let content_size = |index: usize| content.get(index).cloned().unwrap_or(0.);
+
+    let fixed: f64 = tracks.iter().enumerate().map(|(index, track)| match track {
+        GridTrack::Fixed(size) => *size,
+        GridTrack::Fraction(_) => 0.,
+        GridTrack::Auto => content_size(index)
+    }).sum();
+
+    let fractions: f64 = tracks.iter().map(|track| match track {
+        GridTrack::Fraction(fraction) if *fraction > 0. => *fraction,
+        _ => 0.
+    }).sum();
+
+    let remaining = (available - gaps - fixed).max(0.);
+    let mut offset = 0.;
+
+    tracks.iter().enumerate().map(|(index, track)| {
+        let size = match track {
+            GridTrack::Fixed(size) => *size,
+            GridTrack::Fraction(fraction) if *fraction > 0. => remaining * fraction / fractions,
+            GridTrack::Fraction(_) => 0.,
+            GridTrack::Auto => content_size(index)
+        };
+
+        let resolved = (offset, size);
+        offset += size + gap;
+        resolved
+    }).collect()
+}
+
+/// Describes a grid's tracks, gaps and insets.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GridLayout {
+    /// Column tracks, from leading to trailing.
+    pub columns: Vec<GridTrack>,
+
+    /// Row tracks, from top to bottom.
+    pub rows: Vec<GridTrack>,
+
+    /// Space between adjacent columns, in points.
+    pub column_gap: f64,
+
+    /// Space between adjacent rows, in points.
+    pub row_gap: f64,
+
+    /// Insets from the container's edges.
+    pub insets: EdgeInsets
+}
+
+impl GridLayout {
+    /// Returns a new `GridLayout` with the given tracks, and no gaps or insets.
+    pub fn new(columns: Vec<GridTrack>, rows: Vec<GridTrack>) -> Self {
+        GridLayout {
+            columns: columns,
+            rows: rows,
+            ..GridLayout::default()
+        }
+    }
+
+    /// Returns a grid of `columns` by `rows` equally sized tracks.
+    pub fn uniform(columns: usize, rows: usize) -> Self {
+        GridLayout::new(vec![GridTrack::Fraction(1.); columns], vec![GridTrack::Fraction(1.); rows])
+    }
+
+    /// Computes the frame of a cell inside of `bounds`. Returns `None` if the cell (including its
+    /// span) falls outside of the grid.
+    ///
+    /// Columns are laid out left to right; for a right-to-left layout, pass the result through
+    /// `LayoutDirection::mirror`. Auto tracks resolve to zero; see `cell_rect_fitting`.
+    pub fn cell_rect(&self, bounds: Rect, cell: GridCell) -> Option<Rect> {
+        self.cell_rect_fitting(bounds, cell, &[], &[])
+    }
+
+    /// Like `cell_rect`, but with the size of the content in each column and row, which auto
+    /// tracks are sized to. Both are indexed by track.
+    pub fn cell_rect_fitting(&self, bounds: Rect, cell: GridCell, column_content: &[f64], row_content: &[f64]) -> Option<Rect> {
+        let last_column = cell.column + cell.column_span.max(1) - 1;
+        let last_row = cell.row + cell.row_span.max(1) - 1;
+
+        if last_column >= self.columns.len() || last_row >= self.rows.len() {
+            return None;
+        }
+
+        let width = bounds.width - self.insets.leading - self.insets.trailing;
+        let height = bounds.height - self.insets.top - self.insets.bottom;
+        let columns = resolve_tracks_fitting(&self.columns, column_content, width, self.column_gap);
+        let rows = resolve_tracks_fitting(&self.rows, row_content, height, self.row_gap);
+
+        let (left, _) = columns[cell.column];
+        let (top, _) = rows[cell.row];
+        let right = columns[last_column].0 + columns[last_column].1;
+        let bottom = rows[last_row].0 + rows[last_row].1;
+
+        Some(Rect::new(
+            bounds.top + self.insets.top + top,
+            bounds.left + self.insets.leading + left,
+            right - left,
+            bottom - top
+        ))
+    }
+
+    /// Generates constraints for a set of guides along one axis.
+    fn track_constraints(
+        tracks: &[GridTrack],
+        guides: &[LayoutItem],
+        container: LayoutItem,
+        gap: f64,
+        insets: (f64, f64),
+        main: (LayoutAttribute, LayoutAttribute, LayoutAttribute),
+        cross: (LayoutAttribute, LayoutAttribute)
+    ) -> Vec<ConstraintRecord> {
+        let (start, end, size) = main;
+        let mut records = Vec::new();
+
+        let (first, last) = match (guides.first(), guides.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => { return records; }
+        };
+
+        records.push(relate(first, start, LayoutRelation::Equal, Some((container, start)), insets.0));
+
+        for pair in guides.windows(2) {
+            records.push(relate(pair[1], start, LayoutRelation::Equal, Some((pair[0], end)), gap));
+        }
+
+        // With nothing flexible to absorb extra space, fixed tracks just need to fit.
+        let flexible = tracks.iter().any(|track| matches!(track, GridTrack::Fraction(f) if *f > 0.));
+
+        let end_relation = match flexible {
+            true => LayoutRelation::Equal,
+            false => LayoutRelation::LessThanOrEqual
+        };
+
+        records.push(relate(last, end, end_relation, Some((container, end)), -insets.1));
+
+        let mut basis: Option<(LayoutItem, f64)> = None;
+
+        for (guide, track) in guides.iter().zip(tracks.iter()) {
+            match track {
+                GridTrack::Fixed(points) => {
+                    records.push(relate(*guide, size, LayoutRelation::Equal, None, *points));
+                },
+
+                GridTrack::Fraction(fraction) if *fraction <= 0. => {
+                    records.push(relate(*guide, size, LayoutRelation::Equal, None, 0.));
+                },
+
+                // Views push the track open (see `constraints`), and it otherwise shrinks to fit.
+                GridTrack::Auto => {
+                    let mut hugging = relate(*guide, size, LayoutRelation::Equal, None, 0.);
+                    hugging.priority = AUTO_HUGGING_PRIORITY;
+                    records.push(relate(*guide, size, LayoutRelation::GreaterThanOrEqual, None, 0.));
+                    records.push(hugging);
+                },
+
+                GridTrack::Fraction(fraction) => match basis {
+                    Some((basis_guide, basis_fraction)) => {
+                        let mut record = relate(*guide, size, LayoutRelation::Equal, Some((basis_guide, size)), 0.);
+                        record.multiplier = fraction / basis_fraction;
+                        records.push(record);
+                    },
+
+                    None => {
+                        records.push(relate(*guide, size, LayoutRelation::GreaterThanOrEqual, None, 0.));
+                        basis = Some((*guide, *fraction));
+                    }
+                }
+            }
+
+            // Guides span the container on the other axis, so they're never ambiguous.
+            records.push(relate(*guide, cross.0, LayoutRelation::Equal, Some((container, cross.0)), 0.));
+            records.push(relate(*guide, cross.1, LayoutRelation::Equal, Some((container, cross.1)), 0.));
+        }
+
+        records
+    }
+
+    /// Generates the constraints that position views in cells.
+    ///
+    /// `column_guides` and `row_guides` are items used to represent each track; there must be
+    /// one per column and row, respectively. Views whose cells fall outside of the grid are
+    /// skipped. Views ending in an auto track fit inside of it, rather than filling it.
+    pub fn constraints(
+        &self,
+        container: LayoutItem,
+        column_guides: &[LayoutItem],
+        row_guides: &[LayoutItem],
+        cells: &[(LayoutItem, GridCell)]
+    ) -> Vec<ConstraintRecord> {
+        let mut records = Self::track_constraints(
+            &self.columns,
+            column_guides,
+            container,
+            self.column_gap,
+            (self.insets.leading, self.insets.trailing),
+            (LayoutAttribute::Leading, LayoutAttribute::Trailing, LayoutAttribute::Width),
+            (LayoutAttribute::Top, LayoutAttribute::Height)
+        );
+
+        records.extend(Self::track_constraints(
+            &self.rows,
+            row_guides,
+            container,
+            self.row_gap,
+            (self.insets.top, self.insets.bottom),
+            (LayoutAttribute::Top, LayoutAttribute::Bottom, LayoutAttribute::Height),
+            (LayoutAttribute::Leading, LayoutAttribute::Width)
+        ));
+
+        for (item, cell) in cells {
+            let last_column = cell.column + cell.column_span.max(1) - 1;
+            let last_row = cell.row + cell.row_span.max(1) - 1;
+
+            if last_column >= column_guides.len() || last_row >= row_guides.len() {
+                continue;
+            }
+
+            let fits = |tracks: &[GridTrack], index: usize| match tracks.get(index) {
+                Some(GridTrack::Auto) => LayoutRelation::LessThanOrEqual,
+                _ => LayoutRelation::Equal
+            };
+
+            let item = *item;
+            records.push(relate(item, LayoutAttribute::Leading, LayoutRelation::Equal, Some((column_guides[cell.column], LayoutAttribute::Leading)), 0.));
+            records.push(relate(item, LayoutAttribute::Trailing, fits(&self.columns, last_column), Some((column_guides[last_column], LayoutAttribute::Trailing)), 0.));
+            records.push(relate(item, LayoutAttribute::Top, LayoutRelation::Equal, Some((row_guides[cell.row], LayoutAttribute::Top)), 0.));
+            records.push(relate(item, LayoutAttribute::Bottom, fits(&self.rows, last_row), Some((row_guides[last_row], LayoutAttribute::Bottom)), 0.));
+        }
+
+        records
+    }
+}
+
+/// Returns a record for `first <relation> second + offset`, or `first <relation> offset`.
+fn relate(
+    first: LayoutItem,
+    attribute: LayoutAttribute,
+    relation: LayoutRelation,
+    second: Option<(LayoutItem, LayoutAttribute)>,
+    offset: f64
+) -> ConstraintRecord {
+    let mut record = ConstraintRecord::new(
+        AnchorRecord::new(first, attribute),
+        relation,
+        second.map(|(item, attribute)| AnchorRecord::new(item, attribute))
+    );
+
+    record.offset = offset;
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_tracks, resolve_tracks_fitting, GridCell, GridLayout, GridTrack};
+    use crate::geometry::{EdgeInsets, Rect};
+    use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+    use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+    use crate::layout::solver::LayoutSolver;
+
+    #[test]
+    fn resolves_fixed_and_fractional_tracks() {
+        let tracks = [GridTrack::Fixed(50.), GridTrack::Fraction(1.), GridTrack::Fraction(3.)];
+        assert_eq!(resolve_tracks(&tracks, 270., 10.), vec![(0., 50.), (60., 50.), (120., 150.)]);
+
+        // Fractions get whatever's left, which may be nothing.
+        assert_eq!(resolve_tracks(&tracks, 40., 10.), vec![(0., 50.), (60., 0.), (70., 0.)]);
+        assert_eq!(resolve_tracks(&[GridTrack::Fraction(0.), GridTrack::Fraction(1.)], 100., 0.), vec![(0., 0.), (0., 100.)]);
+    }
+
+    #[test]
+    fn resolves_auto_tracks_to_their_content() {
+        let tracks = [GridTrack::Auto, GridTrack::Fraction(1.), GridTrack::Auto];
+
+        assert_eq!(resolve_tracks_fitting(&tracks, &[40.], 100., 10.), vec![(0., 40.), (50., 40.), (100., 0.)]);
+        assert_eq!(resolve_tracks(&tracks, 100., 10.), vec![(0., 0.), (10., 80.), (100., 0.)]);
+    }
+
+    #[test]
+    fn computes_cell_rects() {
+        let mut layout = GridLayout::uniform(2, 2);
+        layout.column_gap = 10.;
+        layout.row_gap = 10.;
+        layout.insets = EdgeInsets::uniform(5.);
+
+        let bounds = Rect::new(0., 0., 210., 110.);
+
+        assert_eq!(layout.cell_rect(bounds, GridCell::new(0, 0)), Some(Rect::new(5., 5., 95., 45.)));
+        assert_eq!(layout.cell_rect(bounds, GridCell::new(1, 1)), Some(Rect::new(60., 110., 95., 45.)));
+        assert_eq!(layout.cell_rect(bounds, GridCell::new(0, 0).span(2, 2)), Some(Rect::new(5., 5., 200., 100.)));
+        assert_eq!(layout.cell_rect(bounds, GridCell::new(0, 1).span(1, 2)), None);
+        assert_eq!(layout.cell_rect(bounds, GridCell::new(2, 0)), None);
+    }
+
+    #[test]
+    fn constraints_match_cell_rects() {
+        let container = LayoutItem(1);
+        let columns = [LayoutItem(10), LayoutItem(11), LayoutItem(12)];
+        let rows = [LayoutItem(20), LayoutItem(21)];
+        let (keypad, display, badge) = (LayoutItem(30), LayoutItem(31), LayoutItem(32));
+
+        let mut layout = GridLayout::new(
+            vec![GridTrack::Fixed(50.), GridTrack::Fraction(1.), GridTrack::Auto],
+            vec![GridTrack::Fraction(1.), GridTrack::Fraction(1.)]
+        );
+        layout.column_gap = 10.;
+
+        let cells = [
+            (keypad, GridCell::new(0, 0)),
+            (display, GridCell::new(0, 1).span(2, 1)),
+            (badge, GridCell::new(1, 2))
+        ];
+
+        let bounds = Rect::new(0., 0., 300., 100.);
+        let mut solver = LayoutSolver::new();
+        solver.set_frame(container, bounds).unwrap();
+
+        // The badge is what sizes the auto column.
+        let mut width = ConstraintRecord::new(AnchorRecord::new(badge, LayoutAttribute::Width), LayoutRelation::Equal, None);
+        width.offset = 40.;
+        solver.add_constraint(&width).unwrap();
+
+        solver.add_constraints(&layout.constraints(container, &columns, &rows, &cells)).unwrap();
+        assert!(solver.ambiguous_items().is_empty());
+
+        for (item, cell) in cells.iter() {
+            let expected = layout.cell_rect_fitting(bounds, *cell, &[0., 0., 40.], &[]);
+            assert_eq!(solver.frame(*item), expected, "{:?}", cell);
+        }
+
+        assert_eq!(solver.frame(display), Some(Rect::new(0., 60., 190., 100.)));
+        assert_eq!(solver.frame(badge), Some(Rect::new(50., 260., 40., 50.)));
+    }
+
+    #[test]
+    fn fixed_tracks_only_need_to_fit() {
+        let container = LayoutItem(1);
+        let columns = [LayoutItem(10)];
+        let rows = [LayoutItem(20)];
+        let cell = LayoutItem(30);
+
+        let layout = GridLayout::new(vec![GridTrack::Fixed(50.)], vec![GridTrack::Fixed(20.)]);
+
+        let mut solver = LayoutSolver::new();
+        solver.set_frame(container, Rect::new(0., 0., 300., 100.)).unwrap();
+        solver.add_constraints(&layout.constraints(container, &columns, &rows, &[(cell, GridCell::new(0, 0))])).unwrap();
+
+        assert_eq!(solver.frame(cell), Some(Rect::new(0., 0., 50., 20.)));
+    }
+}
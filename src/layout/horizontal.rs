@@ -7,27 +7,59 @@ use objc::runtime::Object;
 use objc_id::ShareId;
 
 use crate::foundation::id;
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
 use crate::layout::constraint::LayoutConstraint;
+use crate::layout::record::{AnchorRecord, LayoutItem};
 
 /// A wrapper for `NSLayoutAnchor`. You should never be creating this yourself - it's more of a
 /// factory/helper for creating `LayoutConstraint` objects based on your views.
 #[derive(Clone, Debug, Default)]
-pub struct LayoutAnchorX(pub Option<ShareId<Object>>);
+pub struct LayoutAnchorX(
+    /// A shared pointer to the underlying anchor. This is `None` for uninitialized anchors.
+    pub Option<ShareId<Object>>,
+
+    /// The item and attribute this anchor represents, for the Rust-side constraint model.
+    Option<AnchorRecord>
+);
 
 impl LayoutAnchorX {
     /// An internal method for wrapping existing anchors.
-    pub(crate) fn new(object: id) -> Self {
-        LayoutAnchorX(Some(unsafe {
-            ShareId::from_ptr(object)
-        }))
+    fn new(view: id, attribute: LayoutAttribute) -> Self {
+        let anchor: id = unsafe {
+            match attribute {
+                LayoutAttribute::Trailing => msg_send![view, trailingAnchor],
+                LayoutAttribute::CenterX => msg_send![view, centerXAnchor],
+                _ => msg_send![view, leadingAnchor]
+            }
+        };
+
+        LayoutAnchorX(
+            Some(unsafe { ShareId::from_ptr(anchor) }),
+            Some(AnchorRecord::new(LayoutItem(view as usize), attribute))
+        )
+    }
+
+    /// Wraps the `leadingAnchor` of the given view.
+    pub(crate) fn leading(view: id) -> Self {
+        Self::new(view, LayoutAttribute::Leading)
+    }
+
+    /// Wraps the `trailingAnchor` of the given view.
+    pub(crate) fn trailing(view: id) -> Self {
+        Self::new(view, LayoutAttribute::Trailing)
+    }
+
+    /// Wraps the `centerXAnchor` of the given view.
+    pub(crate) fn center_x(view: id) -> Self {
+        Self::new(view, LayoutAttribute::CenterX)
     }
 
     /// Return a constraint equal to another horizontal anchor.
     pub fn constraint_equal_to(&self, anchor_to: &LayoutAnchorX) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::Equal, second),
 
             _ => { panic!("Attempted to create horizontal constraints with an uninitialized anchor!"); }
         }
@@ -35,10 +67,10 @@ impl LayoutAnchorX {
 
     /// Return a constraint greater than or equal to another horizontal anchor.
     pub fn constraint_greater_than_or_equal_to(&self, anchor_to: &LayoutAnchorX) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintGreaterThanOrEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::GreaterThanOrEqual, second),
 
             _ => { panic!("Attempted to create horizontal constraints with an uninitialized anchor!"); }
         }
@@ -46,10 +78,10 @@ impl LayoutAnchorX {
 
     /// Return a constraint less than or equal to another horizontal anchor.
     pub fn constraint_less_than_or_equal_to(&self, anchor_to: &LayoutAnchorX) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintLessThanOrEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::LessThanOrEqual, second),
 
             _ => { panic!("Attempted to create horizontal constraints with an uninitialized anchor!"); }
         }
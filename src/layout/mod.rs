@@ -16,6 +16,21 @@ pub use traits::Layout;
 pub mod constraint;
 pub use constraint::LayoutConstraint;
 
+pub mod record;
+pub use record::{AnchorRecord, ConstraintRecord, LayoutItem};
+
+pub mod solver;
+pub use solver::{LayoutSolver, SolverError};
+
+pub mod diagnostics;
+pub use diagnostics::LayoutDiagnostic;
+
+pub mod grid;
+pub use grid::{GridCell, GridLayout, GridTrack};
+
+pub mod stack;
+pub use stack::{StackAlignment, StackAxis, StackDistribution, StackLayout};
+
 pub mod dimension;
 pub use dimension::LayoutAnchorDimension;
 
@@ -24,3 +39,6 @@ pub use horizontal::LayoutAnchorX;
 
 pub mod vertical;
 pub use vertical::LayoutAnchorY;
+
+pub mod direction;
+pub use direction::{application_layout_direction, resolve_layout_direction, LayoutDirection};
@@ -0,0 +1,84 @@
+//! A Rust-side record of the constraints built through the anchor types.
+//!
+//! Every `LayoutConstraint` carries a plain description of what it was built from - which item
+//! and attribute sit on either side, the relation between them, and the multiplier, offset and
+//! priority involved. None of this touches the Objective-C runtime, which means you can hand
+//! these to the `solver` module (or build them yourself) and reason about a layout without AppKit
+//! or UIKit being around.
+
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+
+/// The priority AutoLayout treats as required (`NSLayoutPriorityRequired`).
+pub const REQUIRED_PRIORITY: f64 = 1000.;
+
+/// An opaque identifier for an item (typically a view) that participates in layout.
+///
+/// For constraints built from real views, this is the address of the backing node. When
+/// building records by hand (e.g, in tests), any unique value will do.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LayoutItem(pub usize);
+
+/// Records a single anchor: an attribute on a given item.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AnchorRecord {
+    /// The item this anchor belongs to.
+    pub item: LayoutItem,
+
+    /// The attribute this anchor represents (e.g, `Top`, `Width`).
+    pub attribute: LayoutAttribute
+}
+
+impl AnchorRecord {
+    /// Returns a new `AnchorRecord` for the given item and attribute.
+    pub fn new(item: LayoutItem, attribute: LayoutAttribute) -> Self {
+        AnchorRecord { item, attribute }
+    }
+}
+
+/// Records a constraint of the form `first <relation> second * multiplier + offset`.
+///
+/// If `second` is `None`, the constraint is against a constant: `first <relation> offset`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConstraintRecord {
+    /// The anchor on the left hand side of the relation.
+    pub first: AnchorRecord,
+
+    /// How the two sides relate to one another.
+    pub relation: LayoutRelation,
+
+    /// The anchor on the right hand side of the relation, if any.
+    pub second: Option<AnchorRecord>,
+
+    /// The multiplier applied to the `second` anchor.
+    pub multiplier: f64,
+
+    /// The constant added to the right hand side.
+    pub offset: f64,
+
+    /// The priority of this constraint. `REQUIRED_PRIORITY` (1000) and above is required.
+    pub priority: f64
+}
+
+impl ConstraintRecord {
+    /// Returns a required record relating two anchors, with a multiplier of `1` and no offset.
+    pub fn new(first: AnchorRecord, relation: LayoutRelation, second: Option<AnchorRecord>) -> Self {
+        ConstraintRecord {
+            first,
+            relation,
+            second,
+            multiplier: 1.,
+            offset: 0.,
+            priority: REQUIRED_PRIORITY
+        }
+    }
+
+    /// Whether this record must be satisfied for a layout to be valid.
+    pub fn is_required(&self) -> bool {
+        self.priority >= REQUIRED_PRIORITY
+    }
+
+    /// Returns every item this record references.
+    pub fn items(&self) -> impl Iterator<Item = LayoutItem> {
+        std::iter::once(self.first.item).chain(self.second.map(|anchor| anchor.item))
+    }
+}
@@ -0,0 +1,446 @@
+//! A pure-Rust constraint solver for `LayoutConstraint` records.
+//!
+//! AppKit and UIKit solve AutoLayout constraints with the Cassowary algorithm. This module
+//! implements the same algorithm in Rust, operating on the `ConstraintRecord` values that every
+//! `LayoutConstraint` carries. This is useful for testing layouts without a Mac (e.g, in CI), and
+//! for catching conflicting or ambiguous constraints before they ever reach the system.
+//!
+//! Each item is modeled with four variables (`left`, `top`, `width`, `height`) in a flipped,
+//! top-left coordinate space - the same one `View` uses. Leading and trailing follow the solver's
+//! `LayoutDirection` (left-to-right, unless set otherwise), and baselines are treated as the
+//! top/bottom edges, as there's no text to measure.
+//!
+//! ```rust
+//! use cacao::geometry::Rect;
+//! use cacao::layout::{LayoutAttribute, LayoutRelation};
+//! use cacao::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+//! use cacao::layout::solver::LayoutSolver;
+//!
+//! let root = LayoutItem(1);
+//! let child = LayoutItem(2);
+//!
+//! let mut solver = LayoutSolver::new();
+//! solver.set_frame(root, Rect::new(0., 0., 400., 300.)).unwrap();
+//!
+//! let mut top = ConstraintRecord::new(
+//!     AnchorRecord::new(child, LayoutAttribute::Top),
+//!     LayoutRelation::Equal,
+//!     Some(AnchorRecord::new(root, LayoutAttribute::Top))
+//! );
+//! top.offset = 16.;
+//! solver.add_constraint(&top).unwrap();
+//! ```
+//!
+//! Priorities below `REQUIRED_PRIORITY` are mapped onto an exponential weight scale, so that a
+//! higher priority constraint generally wins out over several lower priority ones, much like it
+//! does in AutoLayout.
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+
+use crate::geometry::Rect;
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+use crate::layout::direction::LayoutDirection;
+use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem, REQUIRED_PRIORITY};
+
+mod simplex;
+use simplex::{Expression, Relation, Simplex, Variable, REQUIRED};
+
+/// Errors that can occur when adding constraints to a `LayoutSolver`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolverError {
+    /// A required constraint conflicts with the constraints already in the solver.
+    UnsatisfiableConstraint(ConstraintRecord),
+
+    /// The constraint uses an attribute the solver can't model (`Unknown`, or
+    /// `NotAnAttribute` on the left hand side).
+    UnsupportedAttribute(LayoutAttribute),
+
+    /// The constraint uses a relation the solver can't model (`Unknown`).
+    UnsupportedRelation(LayoutRelation)
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolverError::UnsatisfiableConstraint(record) => {
+                write!(f, "Unable to satisfy required constraint: {:?}", record)
+            },
+
+            SolverError::UnsupportedAttribute(attribute) => {
+                write!(f, "Unsupported layout attribute: {:?}", attribute)
+            },
+
+            SolverError::UnsupportedRelation(relation) => {
+                write!(f, "Unsupported layout relation: {:?}", relation)
+            }
+        }
+    }
+}
+
+impl error::Error for SolverError {}
+
+/// The variables backing a single item.
+#[derive(Copy, Clone, Debug)]
+struct ItemVariables {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable
+}
+
+/// Solves a set of constraint records into frames.
+///
+/// Constraints are added incrementally; the solution is always up to date, so you can query
+/// frames at any point.
+#[derive(Clone, Debug, Default)]
+pub struct LayoutSolver {
+    simplex: Simplex,
+    items: BTreeMap<LayoutItem, ItemVariables>,
+    direction: LayoutDirection
+}
+
+impl LayoutSolver {
+    /// Returns a new, empty solver.
+    pub fn new() -> Self {
+        LayoutSolver::default()
+    }
+
+    /// Sets the direction leading and trailing constraints are solved in. This applies to
+    /// constraints added afterwards, so set it before adding any.
+    pub fn set_layout_direction(&mut self, direction: LayoutDirection) {
+        self.direction = direction;
+    }
+
+    /// Maps a layout priority onto a solver strength. Required priorities map to the required
+    /// strength; everything else is spread across `1.0..1_000_000.0`.
+    fn strength(priority: f64) -> f64 {
+        if priority >= REQUIRED_PRIORITY {
+            return REQUIRED;
+        }
+
+        let clamped = priority.max(0.) / REQUIRED_PRIORITY;
+        10f64.powf(clamped * 6.)
+    }
+
+    fn variables(&mut self, item: LayoutItem) -> ItemVariables {
+        if let Some(variables) = self.items.get(&item) {
+            return *variables;
+        }
+
+        let variables = ItemVariables {
+            left: self.simplex.new_variable(),
+            top: self.simplex.new_variable(),
+            width: self.simplex.new_variable(),
+            height: self.simplex.new_variable()
+        };
+
+        self.items.insert(item, variables);
+        variables
+    }
+
+    /// Builds the expression for a given anchor.
+    fn expression(&mut self, anchor: AnchorRecord) -> Result<Expression, SolverError> {
+        let v = self.variables(anchor.item);
+        let left = Expression::variable(v.left);
+        let top = Expression::variable(v.top);
+        let width = Expression::variable(v.width);
+        let height = Expression::variable(v.height);
+
+        // Leading and trailing are measured from the leading edge, so right-to-left they're
+        // modeled with x mirrored (-x). That way offsets and inequalities on them flip, as they do
+        // in AutoLayout: `leading = other.trailing + 8` puts 8 points to the left of `other`.
+        let mirrored = self.direction.is_right_to_left();
+
+        Ok(match anchor.attribute {
+            LayoutAttribute::Left => left,
+            LayoutAttribute::Right => left.add(&width, 1.),
+            LayoutAttribute::Leading if mirrored => Expression::constant(0.).add(&left, -1.).add(&width, -1.),
+            LayoutAttribute::Trailing if mirrored => Expression::constant(0.).add(&left, -1.),
+            LayoutAttribute::Leading => left,
+            LayoutAttribute::Trailing => left.add(&width, 1.),
+            LayoutAttribute::Top | LayoutAttribute::FirstBaseline => top,
+            LayoutAttribute::Bottom | LayoutAttribute::LastBaseline => top.add(&height, 1.),
+            LayoutAttribute::CenterX => left.add(&width, 0.5),
+            LayoutAttribute::CenterY => top.add(&height, 0.5),
+            LayoutAttribute::Width => width,
+            LayoutAttribute::Height => height,
+            attribute => { return Err(SolverError::UnsupportedAttribute(attribute)); }
+        })
+    }
+
+    /// Adds a constraint record to the solver.
+    ///
+    /// If the record is required and can't be satisfied alongside what's already been added,
+    /// this returns `SolverError::UnsatisfiableConstraint` and leaves the solver unchanged.
+    pub fn add_constraint(&mut self, record: &ConstraintRecord) -> Result<(), SolverError> {
+        let relation = match record.relation {
+            LayoutRelation::LessThanOrEqual => Relation::LessThanOrEqual,
+            LayoutRelation::Equal => Relation::Equal,
+            LayoutRelation::GreaterThanOrEqual => Relation::GreaterThanOrEqual,
+            relation => { return Err(SolverError::UnsupportedRelation(relation)); }
+        };
+
+        let first = self.expression(record.first)?;
+        let second = match record.second {
+            Some(anchor) if anchor.attribute != LayoutAttribute::NotAnAttribute => self.expression(anchor)?,
+            _ => Expression::constant(0.)
+        };
+
+        // first <relation> second * multiplier + offset  =>  first - (second * multiplier + offset) <relation> 0
+        let expression = first.add(&second, -record.multiplier).plus(-record.offset);
+        let strength = Self::strength(record.priority);
+
+        // The simplex can be left in a half-updated state when a required constraint fails, so
+        // try it against a copy first.
+        if strength >= REQUIRED {
+            let mut attempt = self.simplex.clone();
+            attempt.add_constraint(&expression, relation, strength)
+                .map_err(|_| SolverError::UnsatisfiableConstraint(*record))?;
+            self.simplex = attempt;
+            return Ok(());
+        }
+
+        self.simplex.add_constraint(&expression, relation, strength)
+            .map_err(|_| SolverError::UnsatisfiableConstraint(*record))
+    }
+
+    /// Adds a batch of constraint records, stopping at the first error.
+    pub fn add_constraints(&mut self, records: &[ConstraintRecord]) -> Result<(), SolverError> {
+        for record in records {
+            self.add_constraint(record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pins an item to a fixed frame. This is typically used for the root view (e.g, a window's
+    /// content view), which everything else is laid out relative to.
+    pub fn set_frame(&mut self, item: LayoutItem, frame: Rect) -> Result<(), SolverError> {
+        let pins = [
+            (LayoutAttribute::Left, frame.left),
+            (LayoutAttribute::Top, frame.top),
+            (LayoutAttribute::Width, frame.width),
+            (LayoutAttribute::Height, frame.height)
+        ];
+
+        for (attribute, value) in pins.iter() {
+            let mut record = ConstraintRecord::new(AnchorRecord::new(item, *attribute), LayoutRelation::Equal, None);
+            record.offset = *value;
+            self.add_constraint(&record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the solved frame for an item, if the solver knows about it.
+    pub fn frame(&self, item: LayoutItem) -> Option<Rect> {
+        self.items.get(&item).map(|v| Rect::new(
+            self.simplex.value(v.top),
+            self.simplex.value(v.left),
+            self.simplex.value(v.width),
+            self.simplex.value(v.height)
+        ))
+    }
+
+    /// Returns the solved frames for every item the solver knows about.
+    pub fn frames(&self) -> BTreeMap<LayoutItem, Rect> {
+        self.items.keys().filter_map(|item| {
+            self.frame(*item).map(|frame| (*item, frame))
+        }).collect()
+    }
+
+    /// Returns the attributes of an item that the current constraints don't fully determine,
+    /// out of `Left`, `Top`, `Width` and `Height`. An empty result means the item is fully
+    /// determined (or unknown to the solver).
+    pub fn ambiguous_attributes(&self, item: LayoutItem) -> Vec<LayoutAttribute> {
+        match self.items.get(&item) {
+            Some(v) => [
+                (LayoutAttribute::Left, v.left),
+                (LayoutAttribute::Top, v.top),
+                (LayoutAttribute::Width, v.width),
+                (LayoutAttribute::Height, v.height)
+            ].iter().filter(|(_, variable)| {
+                self.simplex.is_ambiguous(*variable)
+            }).map(|(attribute, _)| *attribute).collect(),
+
+            None => Vec::new()
+        }
+    }
+
+    /// Returns the items that the current constraints don't fully determine - i.e, at least one
+    /// of their edges or dimensions could change without affecting the solution.
+    pub fn ambiguous_items(&self) -> Vec<LayoutItem> {
+        self.items.keys().filter(|item| {
+            !self.ambiguous_attributes(**item).is_empty()
+        }).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LayoutSolver, SolverError};
+    use crate::geometry::Rect;
+    use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+    use crate::layout::direction::LayoutDirection;
+    use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+
+    const ROOT: LayoutItem = LayoutItem(1);
+    const CHILD: LayoutItem = LayoutItem(2);
+
+    /// `item.attribute <relation> other.attribute + offset`, or against a constant if `other` is
+    /// `None`.
+    fn record(
+        item: LayoutItem,
+        attribute: LayoutAttribute,
+        relation: LayoutRelation,
+        other: Option<(LayoutItem, LayoutAttribute)>,
+        offset: f64
+    ) -> ConstraintRecord {
+        let mut record = ConstraintRecord::new(
+            AnchorRecord::new(item, attribute),
+            relation,
+            other.map(|(item, attribute)| AnchorRecord::new(item, attribute))
+        );
+
+        record.offset = offset;
+        record
+    }
+
+    fn solver() -> LayoutSolver {
+        let mut solver = LayoutSolver::new();
+        solver.set_frame(ROOT, Rect::new(0., 0., 400., 300.)).unwrap();
+        solver
+    }
+
+    #[test]
+    fn solves_frames() {
+        let mut solver = solver();
+
+        solver.add_constraints(&[
+            record(CHILD, LayoutAttribute::Top, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Top)), 16.),
+            record(CHILD, LayoutAttribute::Leading, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Leading)), 20.),
+            record(CHILD, LayoutAttribute::Trailing, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Trailing)), -20.),
+            record(CHILD, LayoutAttribute::Height, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Height)), -32.)
+        ]).unwrap();
+
+        assert_eq!(solver.frame(CHILD), Some(Rect::new(16., 20., 360., 268.)));
+        assert_eq!(solver.frame(LayoutItem(3)), None);
+        assert!(solver.ambiguous_items().is_empty());
+    }
+
+    #[test]
+    fn applies_multipliers_and_centers() {
+        let mut solver = solver();
+
+        let mut width = record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Width)), 0.);
+        width.multiplier = 0.5;
+
+        solver.add_constraints(&[
+            width,
+            record(CHILD, LayoutAttribute::Height, LayoutRelation::Equal, None, 50.),
+            record(CHILD, LayoutAttribute::CenterX, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::CenterX)), 0.),
+            record(CHILD, LayoutAttribute::CenterY, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::CenterY)), 0.)
+        ]).unwrap();
+
+        assert_eq!(solver.frame(CHILD), Some(Rect::new(125., 100., 200., 50.)));
+    }
+
+    #[test]
+    fn rejects_conflicting_required_constraints() {
+        let mut solver = solver();
+        solver.add_constraint(&record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 100.)).unwrap();
+
+        let conflict = record(CHILD, LayoutAttribute::Width, LayoutRelation::GreaterThanOrEqual, None, 150.);
+        assert_eq!(solver.add_constraint(&conflict), Err(SolverError::UnsatisfiableConstraint(conflict)));
+
+        // The solver is left as it was.
+        assert_eq!(solver.frame(CHILD).map(|frame| frame.width), Some(100.));
+    }
+
+    #[test]
+    fn rejects_unsupported_attributes_and_relations() {
+        let mut solver = solver();
+
+        let attribute = record(CHILD, LayoutAttribute::NotAnAttribute, LayoutRelation::Equal, None, 0.);
+        assert_eq!(solver.add_constraint(&attribute), Err(SolverError::UnsupportedAttribute(LayoutAttribute::NotAnAttribute)));
+
+        let relation = record(CHILD, LayoutAttribute::Width, LayoutRelation::Unknown(7), None, 0.);
+        assert_eq!(solver.add_constraint(&relation), Err(SolverError::UnsupportedRelation(LayoutRelation::Unknown(7))));
+    }
+
+    #[test]
+    fn higher_priorities_win() {
+        let mut solver = solver();
+
+        let mut low = record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 100.);
+        low.priority = 250.;
+
+        let mut high = record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 200.);
+        high.priority = 750.;
+
+        let mut other_low = record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 120.);
+        other_low.priority = 250.;
+
+        solver.add_constraints(&[low, high, other_low]).unwrap();
+        assert_eq!(solver.frame(CHILD).map(|frame| frame.width), Some(200.));
+
+        // Required constraints always win.
+        solver.add_constraint(&record(CHILD, LayoutAttribute::Width, LayoutRelation::LessThanOrEqual, None, 150.)).unwrap();
+        assert_eq!(solver.frame(CHILD).map(|frame| frame.width), Some(150.));
+    }
+
+    #[test]
+    fn reports_ambiguous_attributes() {
+        let mut solver = solver();
+
+        solver.add_constraints(&[
+            record(CHILD, LayoutAttribute::Top, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Top)), 0.),
+            record(CHILD, LayoutAttribute::Height, LayoutRelation::Equal, None, 20.),
+            record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 100.)
+        ]).unwrap();
+
+        assert_eq!(solver.ambiguous_attributes(CHILD), vec![LayoutAttribute::Left]);
+        assert_eq!(solver.ambiguous_items(), vec![CHILD]);
+        assert!(solver.ambiguous_attributes(ROOT).is_empty());
+
+        solver.add_constraint(&record(CHILD, LayoutAttribute::Left, LayoutRelation::GreaterThanOrEqual, Some((ROOT, LayoutAttribute::Left)), 0.)).unwrap();
+        assert_eq!(solver.ambiguous_attributes(CHILD), vec![LayoutAttribute::Left]);
+
+        solver.add_constraint(&record(CHILD, LayoutAttribute::Right, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Right)), 0.)).unwrap();
+        assert!(solver.ambiguous_items().is_empty());
+    }
+
+    #[test]
+    fn resolves_leading_and_trailing_right_to_left() {
+        let sibling = LayoutItem(3);
+
+        let mut solver = LayoutSolver::new();
+        solver.set_layout_direction(LayoutDirection::RightToLeft);
+        solver.set_frame(ROOT, Rect::new(0., 0., 400., 300.)).unwrap();
+
+        solver.add_constraints(&[
+            record(CHILD, LayoutAttribute::Leading, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Leading)), 20.),
+            record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 100.),
+            record(sibling, LayoutAttribute::Leading, LayoutRelation::Equal, Some((CHILD, LayoutAttribute::Trailing)), 8.),
+            record(sibling, LayoutAttribute::Trailing, LayoutRelation::LessThanOrEqual, Some((ROOT, LayoutAttribute::Trailing)), 0.),
+            record(sibling, LayoutAttribute::Width, LayoutRelation::Equal, None, 50.)
+        ]).unwrap();
+
+        // Leading is the right edge, and offsets move items towards the left.
+        assert_eq!(solver.frame(CHILD).map(|frame| frame.left), Some(280.));
+        assert_eq!(solver.frame(sibling).map(|frame| frame.left), Some(222.));
+
+        // Left and right aren't affected.
+        let mut left = LayoutSolver::new();
+        left.set_layout_direction(LayoutDirection::RightToLeft);
+        left.set_frame(ROOT, Rect::new(0., 0., 400., 300.)).unwrap();
+        left.add_constraints(&[
+            record(CHILD, LayoutAttribute::Left, LayoutRelation::Equal, Some((ROOT, LayoutAttribute::Left)), 20.),
+            record(CHILD, LayoutAttribute::Width, LayoutRelation::Equal, None, 100.)
+        ]).unwrap();
+
+        assert_eq!(left.frame(CHILD).map(|frame| frame.left), Some(20.));
+    }
+}
@@ -0,0 +1,434 @@
+//! An incremental Cassowary simplex solver.
+//!
+//! This is a fairly direct port of the algorithm described in "The Cassowary Linear Arithmetic
+//! Constraint Solving Algorithm" (Badros, Borning, Stuckey), following the same structure as the
+//! widely used Kiwi implementation. It's trimmed down to what layout needs: constraints are added
+//! one at a time, and the values of variables can be read back at any point. Edit variables and
+//! constraint removal aren't supported - build a new solver instead.
+
+use std::collections::BTreeMap;
+
+/// The strength used for required constraints. Anything at or above this must be satisfied.
+pub(crate) const REQUIRED: f64 = 1_001_001_000.;
+
+const EPSILON: f64 = 1.0e-8;
+
+fn near_zero(value: f64) -> bool {
+    value.abs() < EPSILON
+}
+
+/// A variable in the linear system. These are handed out by `Simplex::new_variable()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Variable(usize);
+
+/// The relation between an expression and zero.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Relation {
+    LessThanOrEqual,
+    Equal,
+    GreaterThanOrEqual
+}
+
+/// A linear expression: a sum of weighted variables, plus a constant.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Expression {
+    pub terms: Vec<(Variable, f64)>,
+    pub constant: f64
+}
+
+impl Expression {
+    /// Returns an expression holding just a constant.
+    pub fn constant(constant: f64) -> Self {
+        Expression { terms: Vec::new(), constant }
+    }
+
+    /// Returns an expression holding a single variable.
+    pub fn variable(variable: Variable) -> Self {
+        Expression { terms: vec![(variable, 1.)], constant: 0. }
+    }
+
+    /// Adds `other * coefficient` into this expression.
+    pub fn add(mut self, other: &Expression, coefficient: f64) -> Self {
+        for (variable, c) in &other.terms {
+            self.terms.push((*variable, c * coefficient));
+        }
+
+        self.constant += other.constant * coefficient;
+        self
+    }
+
+    /// Adds a constant to this expression.
+    pub fn plus(mut self, constant: f64) -> Self {
+        self.constant += constant;
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolKind {
+    External,
+    Slack,
+    Error,
+    Dummy
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Symbol(usize, SymbolKind);
+
+impl Symbol {
+    fn is_restricted(&self) -> bool {
+        self.1 != SymbolKind::External
+    }
+}
+
+/// The symbols a constraint introduced into the tableau.
+#[derive(Copy, Clone, Debug)]
+struct Tag {
+    marker: Symbol,
+    other: Option<Symbol>
+}
+
+#[derive(Clone, Debug, Default)]
+struct Row {
+    cells: BTreeMap<Symbol, f64>,
+    constant: f64
+}
+
+impl Row {
+    fn new(constant: f64) -> Self {
+        Row { cells: BTreeMap::new(), constant }
+    }
+
+    fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        self.cells.get(&symbol).cloned().unwrap_or(0.)
+    }
+
+    fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64) {
+        let value = self.coefficient_for(symbol) + coefficient;
+
+        if near_zero(value) {
+            self.cells.remove(&symbol);
+        } else {
+            self.cells.insert(symbol, value);
+        }
+    }
+
+    fn insert_row(&mut self, other: &Row, coefficient: f64) {
+        self.constant += other.constant * coefficient;
+
+        for (symbol, c) in &other.cells {
+            self.insert_symbol(*symbol, c * coefficient);
+        }
+    }
+
+    fn reverse_sign(&mut self) {
+        self.constant = -self.constant;
+
+        for c in self.cells.values_mut() {
+            *c = -*c;
+        }
+    }
+
+    /// Solves the row for `symbol`, which must be present in it. The symbol is removed.
+    fn solve_for(&mut self, symbol: Symbol) {
+        let coefficient = -1. / self.cells.remove(&symbol).unwrap_or(1.);
+        self.constant *= coefficient;
+
+        for c in self.cells.values_mut() {
+            *c *= coefficient;
+        }
+    }
+
+    /// Solves the row `lhs = self` for `rhs`.
+    fn solve_for_symbols(&mut self, lhs: Symbol, rhs: Symbol) {
+        self.insert_symbol(lhs, -1.);
+        self.solve_for(rhs);
+    }
+
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        if let Some(coefficient) = self.cells.remove(&symbol) {
+            self.insert_row(row, coefficient);
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Objective {
+    Primary,
+    Artificial
+}
+
+/// Returned when a required constraint conflicts with those already in the solver.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Unsatisfiable;
+
+/// The solver itself.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Simplex {
+    next_id: usize,
+    variables: BTreeMap<Variable, Symbol>,
+    rows: BTreeMap<Symbol, Row>,
+    objective: Row,
+    artificial: Option<Row>
+}
+
+impl Simplex {
+    fn symbol(&mut self, kind: SymbolKind) -> Symbol {
+        self.next_id += 1;
+        Symbol(self.next_id, kind)
+    }
+
+    /// Creates a new, unconstrained variable.
+    pub fn new_variable(&mut self) -> Variable {
+        let symbol = self.symbol(SymbolKind::External);
+        let variable = Variable(symbol.0);
+        self.variables.insert(variable, symbol);
+        variable
+    }
+
+    /// Returns the current value of `variable`.
+    pub fn value(&self, variable: Variable) -> f64 {
+        self.variables.get(&variable)
+            .and_then(|symbol| self.rows.get(symbol))
+            .map(|row| row.constant)
+            .unwrap_or(0.)
+    }
+
+    /// Whether `variable` could move without violating a constraint or making the solution any
+    /// worse. In layout terms, this means the variable is underconstrained.
+    pub fn is_ambiguous(&self, variable: Variable) -> bool {
+        let symbol = match self.variables.get(&variable) {
+            Some(symbol) => *symbol,
+            None => { return true; }
+        };
+
+        match self.rows.get(&symbol) {
+            Some(row) => row.cells.keys().any(|s| self.is_free(*s)),
+            None => true
+        }
+    }
+
+    /// A non-basic symbol is free if it's unrestricted, or if it's a slack/error symbol that
+    /// the objective doesn't care about.
+    fn is_free(&self, symbol: Symbol) -> bool {
+        match symbol.1 {
+            SymbolKind::External => true,
+            SymbolKind::Slack | SymbolKind::Error => near_zero(self.objective.coefficient_for(symbol)),
+            SymbolKind::Dummy => false
+        }
+    }
+
+    /// Adds the constraint `expression <relation> 0` with the given strength.
+    pub fn add_constraint(
+        &mut self,
+        expression: &Expression,
+        relation: Relation,
+        strength: f64
+    ) -> Result<(), Unsatisfiable> {
+        let (mut row, tag) = self.create_row(expression, relation, strength);
+        let mut subject = self.choose_subject(&row, tag);
+
+        if subject.is_none() && row.cells.keys().all(|s| s.1 == SymbolKind::Dummy) {
+            if !near_zero(row.constant) {
+                return Err(Unsatisfiable);
+            }
+
+            subject = Some(tag.marker);
+        }
+
+        match subject {
+            Some(subject) => {
+                row.solve_for(subject);
+                self.substitute(subject, &row);
+                self.rows.insert(subject, row);
+            },
+
+            None => {
+                if !self.add_with_artificial_variable(row)? {
+                    return Err(Unsatisfiable);
+                }
+            }
+        }
+
+        self.optimize(Objective::Primary)
+    }
+
+    fn create_row(&mut self, expression: &Expression, relation: Relation, strength: f64) -> (Row, Tag) {
+        let mut row = Row::new(expression.constant);
+
+        for (variable, coefficient) in &expression.terms {
+            if near_zero(*coefficient) {
+                continue;
+            }
+
+            let symbol = match self.variables.get(variable) {
+                Some(symbol) => *symbol,
+                None => { continue; }
+            };
+
+            match self.rows.get(&symbol) {
+                Some(basic) => {
+                    let basic = basic.clone();
+                    row.insert_row(&basic, *coefficient);
+                },
+
+                None => { row.insert_symbol(symbol, *coefficient); }
+            }
+        }
+
+        let tag = match relation {
+            Relation::LessThanOrEqual | Relation::GreaterThanOrEqual => {
+                let coefficient = match relation {
+                    Relation::LessThanOrEqual => 1.,
+                    _ => -1.
+                };
+
+                let slack = self.symbol(SymbolKind::Slack);
+                row.insert_symbol(slack, coefficient);
+
+                if strength < REQUIRED {
+                    let error = self.symbol(SymbolKind::Error);
+                    row.insert_symbol(error, -coefficient);
+                    self.objective.insert_symbol(error, strength);
+                    Tag { marker: slack, other: Some(error) }
+                } else {
+                    Tag { marker: slack, other: None }
+                }
+            },
+
+            Relation::Equal => {
+                if strength < REQUIRED {
+                    let plus = self.symbol(SymbolKind::Error);
+                    let minus = self.symbol(SymbolKind::Error);
+                    row.insert_symbol(plus, -1.);
+                    row.insert_symbol(minus, 1.);
+                    self.objective.insert_symbol(plus, strength);
+                    self.objective.insert_symbol(minus, strength);
+                    Tag { marker: plus, other: Some(minus) }
+                } else {
+                    let dummy = self.symbol(SymbolKind::Dummy);
+                    row.insert_symbol(dummy, 1.);
+                    Tag { marker: dummy, other: None }
+                }
+            }
+        };
+
+        if row.constant < 0. {
+            row.reverse_sign();
+        }
+
+        (row, tag)
+    }
+
+    fn choose_subject(&self, row: &Row, tag: Tag) -> Option<Symbol> {
+        if let Some(symbol) = row.cells.keys().find(|s| s.1 == SymbolKind::External) {
+            return Some(*symbol);
+        }
+
+        let candidates = std::iter::once(tag.marker).chain(tag.other);
+        for symbol in candidates {
+            let pivotable = symbol.1 == SymbolKind::Slack || symbol.1 == SymbolKind::Error;
+            if pivotable && row.coefficient_for(symbol) < 0. {
+                return Some(symbol);
+            }
+        }
+
+        None
+    }
+
+    fn add_with_artificial_variable(&mut self, row: Row) -> Result<bool, Unsatisfiable> {
+        let artificial = self.symbol(SymbolKind::Slack);
+        self.rows.insert(artificial, row.clone());
+        self.artificial = Some(row);
+
+        self.optimize(Objective::Artificial)?;
+        let success = self.artificial.take().map(|row| near_zero(row.constant)).unwrap_or(false);
+
+        if let Some(mut row) = self.rows.remove(&artificial) {
+            if row.cells.is_empty() {
+                return Ok(success);
+            }
+
+            let entering = match row.cells.keys().find(|s| s.1 == SymbolKind::Slack || s.1 == SymbolKind::Error) {
+                Some(symbol) => *symbol,
+                None => { return Ok(false); }
+            };
+
+            row.solve_for_symbols(artificial, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+
+        for row in self.rows.values_mut() {
+            row.cells.remove(&artificial);
+        }
+
+        self.objective.cells.remove(&artificial);
+        Ok(success)
+    }
+
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        for basic in self.rows.values_mut() {
+            basic.substitute(symbol, row);
+        }
+
+        self.objective.substitute(symbol, row);
+
+        if let Some(artificial) = &mut self.artificial {
+            artificial.substitute(symbol, row);
+        }
+    }
+
+    fn optimize(&mut self, objective: Objective) -> Result<(), Unsatisfiable> {
+        loop {
+            let entering = {
+                let row = match objective {
+                    Objective::Primary => &self.objective,
+                    Objective::Artificial => match &self.artificial {
+                        Some(row) => row,
+                        None => { return Ok(()); }
+                    }
+                };
+
+                row.cells.iter()
+                    .find(|(symbol, coefficient)| symbol.1 != SymbolKind::Dummy && **coefficient < 0.)
+                    .map(|(symbol, _)| *symbol)
+            };
+
+            let entering = match entering {
+                Some(symbol) => symbol,
+                None => { return Ok(()); }
+            };
+
+            let mut ratio = f64::MAX;
+            let mut leaving = None;
+
+            for (symbol, row) in &self.rows {
+                if !symbol.is_restricted() {
+                    continue;
+                }
+
+                let coefficient = row.coefficient_for(entering);
+                if coefficient < 0. {
+                    let r = -row.constant / coefficient;
+                    if r < ratio {
+                        ratio = r;
+                        leaving = Some(*symbol);
+                    }
+                }
+            }
+
+            // An unbounded objective means the constraint set itself is broken; surface it the
+            // same way as any other conflict.
+            let leaving = leaving.ok_or(Unsatisfiable)?;
+            let mut row = match self.rows.remove(&leaving) {
+                Some(row) => row,
+                None => { return Err(Unsatisfiable); }
+            };
+
+            row.solve_for_symbols(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Constraint generation for stacked (row/column) layouts.
+//!
+//! This is the pure half of `StackView`: given a container and an ordered list of children, it
+//! produces the `ConstraintRecord`s needed to lay them out along an axis. Nothing here touches the
+//! Objective-C runtime, so you can feed the result straight into the `solver` to check a layout.
+
+use crate::geometry::EdgeInsets;
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+
+/// The axis children are stacked along.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum StackAxis {
+    /// Children are laid out in a row, from leading to trailing.
+    Horizontal,
+
+    /// Children are laid out in a column, from top to bottom.
+    #[default]
+    Vertical
+}
+
+/// How children are positioned on the axis perpendicular to the stack.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum StackAlignment {
+    /// Children stretch to fill the container.
+    #[default]
+    Fill,
+
+    /// Children sit against the top (horizontal stacks) or leading (vertical stacks) edge.
+    Start,
+
+    /// Children are centered.
+    Center,
+
+    /// Children sit against the bottom (horizontal stacks) or trailing (vertical stacks) edge.
+    End
+}
+
+/// How children are sized and positioned along the stack axis.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum StackDistribution {
+    /// Children fill the container; their sizes come from their own constraints.
+    #[default]
+    Fill,
+
+    /// Children fill the container, and are all given the same size.
+    FillEqually,
+
+    /// Children are packed against the start of the container, leaving any extra space at the end.
+    Start,
+
+    /// Children are packed against the end of the container, leaving any extra space at the start.
+    End
+}
+
+/// The attributes used for one axis of a stack.
+struct AxisAttributes {
+    start: LayoutAttribute,
+    end: LayoutAttribute,
+    center: LayoutAttribute,
+    size: LayoutAttribute
+}
+
+impl StackAxis {
+    fn main(&self) -> AxisAttributes {
+        match self {
+            StackAxis::Horizontal => AxisAttributes {
+                start: LayoutAttribute::Leading,
+                end: LayoutAttribute::Trailing,
+                center: LayoutAttribute::CenterX,
+                size: LayoutAttribute::Width
+            },
+
+            StackAxis::Vertical => AxisAttributes {
+                start: LayoutAttribute::Top,
+                end: LayoutAttribute::Bottom,
+                center: LayoutAttribute::CenterY,
+                size: LayoutAttribute::Height
+            }
+        }
+    }
+
+    fn perpendicular(&self) -> StackAxis {
+        match self {
+            StackAxis::Horizontal => StackAxis::Vertical,
+            StackAxis::Vertical => StackAxis::Horizontal
+        }
+    }
+}
+
+/// Returns a record for `first <relation> second + offset`.
+fn relate(
+    first: LayoutItem,
+    first_attribute: LayoutAttribute,
+    relation: LayoutRelation,
+    second: LayoutItem,
+    second_attribute: LayoutAttribute,
+    offset: f64
+) -> ConstraintRecord {
+    let mut record = ConstraintRecord::new(
+        AnchorRecord::new(first, first_attribute),
+        relation,
+        Some(AnchorRecord::new(second, second_attribute))
+    );
+
+    record.offset = offset;
+    record
+}
+
+/// Describes how a stack lays out its children. The `constraints` method does the actual work.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StackLayout {
+    /// The axis to stack along.
+    pub axis: StackAxis,
+
+    /// Space between adjacent children, in points.
+    pub spacing: f64,
+
+    /// How children are positioned on the perpendicular axis.
+    pub alignment: StackAlignment,
+
+    /// How children are sized and positioned along the stack axis.
+    pub distribution: StackDistribution,
+
+    /// Insets from the container's edges.
+    pub insets: EdgeInsets
+}
+
+impl StackLayout {
+    /// Returns a `StackLayout` for the given axis, with no spacing or insets.
+    pub fn new(axis: StackAxis) -> Self {
+        StackLayout {
+            axis: axis,
+            ..StackLayout::default()
+        }
+    }
+
+    /// The insets at the start and end of the given axis.
+    fn insets_for(&self, axis: StackAxis) -> (f64, f64) {
+        match axis {
+            StackAxis::Horizontal => (self.insets.leading, self.insets.trailing),
+            StackAxis::Vertical => (self.insets.top, self.insets.bottom)
+        }
+    }
+
+    /// Generates the constraints that lay `children` out, in order, inside of `container`.
+    pub fn constraints(&self, container: LayoutItem, children: &[LayoutItem]) -> Vec<ConstraintRecord> {
+        let mut records = Vec::new();
+
+        let main = self.axis.main();
+        let (main_start, main_end) = self.insets_for(self.axis);
+
+        let cross = self.axis.perpendicular().main();
+        let (cross_start, cross_end) = self.insets_for(self.axis.perpendicular());
+
+        let (first, last) = match (children.first(), children.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => { return records; }
+        };
+
+        // Along the stack axis: pin the ends, then chain each child to the one before it.
+        let start_relation = match self.distribution {
+            StackDistribution::End => LayoutRelation::GreaterThanOrEqual,
+            _ => LayoutRelation::Equal
+        };
+
+        let end_relation = match self.distribution {
+            StackDistribution::Start => LayoutRelation::LessThanOrEqual,
+            _ => LayoutRelation::Equal
+        };
+
+        records.push(relate(first, main.start, start_relation, container, main.start, main_start));
+
+        for pair in children.windows(2) {
+            records.push(relate(pair[1], main.start, LayoutRelation::Equal, pair[0], main.end, self.spacing));
+        }
+
+        records.push(relate(last, main.end, end_relation, container, main.end, -main_end));
+
+        if self.distribution == StackDistribution::FillEqually {
+            for child in children.iter().skip(1) {
+                records.push(relate(*child, main.size, LayoutRelation::Equal, first, main.size, 0.));
+            }
+        }
+
+        // Across the stack axis, each child is positioned on its own.
+        for child in children {
+            let child = *child;
+
+            match self.alignment {
+                StackAlignment::Fill => {
+                    records.push(relate(child, cross.start, LayoutRelation::Equal, container, cross.start, cross_start));
+                    records.push(relate(child, cross.end, LayoutRelation::Equal, container, cross.end, -cross_end));
+                },
+
+                StackAlignment::Start => {
+                    records.push(relate(child, cross.start, LayoutRelation::Equal, container, cross.start, cross_start));
+                    records.push(relate(child, cross.end, LayoutRelation::LessThanOrEqual, container, cross.end, -cross_end));
+                },
+
+                StackAlignment::Center => {
+                    let offset = (cross_start - cross_end) / 2.;
+                    records.push(relate(child, cross.center, LayoutRelation::Equal, container, cross.center, offset));
+                    records.push(relate(child, cross.start, LayoutRelation::GreaterThanOrEqual, container, cross.start, cross_start));
+                },
+
+                StackAlignment::End => {
+                    records.push(relate(child, cross.start, LayoutRelation::GreaterThanOrEqual, container, cross.start, cross_start));
+                    records.push(relate(child, cross.end, LayoutRelation::Equal, container, cross.end, -cross_end));
+                }
+            }
+        }
+
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StackAlignment, StackAxis, StackDistribution, StackLayout};
+    use crate::geometry::{EdgeInsets, Rect};
+    use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
+    use crate::layout::record::{AnchorRecord, ConstraintRecord, LayoutItem};
+    use crate::layout::solver::LayoutSolver;
+
+    const CONTAINER: LayoutItem = LayoutItem(1);
+    const CHILDREN: [LayoutItem; 2] = [LayoutItem(2), LayoutItem(3)];
+
+    /// Lays the children out in a 200x100 container, with each child given the sizes listed for
+    /// it, and returns their frames.
+    fn solve(layout: StackLayout, sizes: &[&[(LayoutAttribute, f64)]]) -> Vec<Rect> {
+        let mut solver = LayoutSolver::new();
+        solver.set_frame(CONTAINER, Rect::new(0., 0., 200., 100.)).unwrap();
+
+        for (child, sizes) in CHILDREN.iter().zip(sizes.iter()) {
+            for (attribute, size) in sizes.iter() {
+                let mut record = ConstraintRecord::new(AnchorRecord::new(*child, *attribute), LayoutRelation::Equal, None);
+                record.offset = *size;
+                solver.add_constraint(&record).unwrap();
+            }
+        }
+
+        solver.add_constraints(&layout.constraints(CONTAINER, &CHILDREN)).unwrap();
+        assert!(solver.ambiguous_items().is_empty());
+
+        CHILDREN.iter().map(|child| solver.frame(*child).unwrap()).collect()
+    }
+
+    #[test]
+    fn fills_a_column() {
+        let mut layout = StackLayout::new(StackAxis::Vertical);
+        layout.spacing = 10.;
+        layout.insets = EdgeInsets::uniform(5.);
+
+        assert_eq!(solve(layout, &[&[(LayoutAttribute::Height, 30.)], &[]]), vec![
+            Rect::new(5., 5., 190., 30.),
+            Rect::new(45., 5., 190., 50.)
+        ]);
+    }
+
+    #[test]
+    fn fills_a_row_equally() {
+        let mut layout = StackLayout::new(StackAxis::Horizontal);
+        layout.spacing = 10.;
+        layout.distribution = StackDistribution::FillEqually;
+
+        assert_eq!(solve(layout, &[&[], &[]]), vec![
+            Rect::new(0., 0., 95., 100.),
+            Rect::new(0., 105., 95., 100.)
+        ]);
+    }
+
+    #[test]
+    fn packs_against_either_end() {
+        let widths: &[&[(LayoutAttribute, f64)]] = &[&[(LayoutAttribute::Width, 40.)], &[(LayoutAttribute::Width, 50.)]];
+
+        let mut layout = StackLayout::new(StackAxis::Horizontal);
+        layout.spacing = 10.;
+
+        layout.distribution = StackDistribution::Start;
+        let lefts: Vec<f64> = solve(layout, widths).iter().map(|frame| frame.left).collect();
+        assert_eq!(lefts, vec![0., 50.]);
+
+        layout.distribution = StackDistribution::End;
+        let lefts: Vec<f64> = solve(layout, widths).iter().map(|frame| frame.left).collect();
+        assert_eq!(lefts, vec![100., 150.]);
+    }
+
+    #[test]
+    fn aligns_across_a_row() {
+        let heights: &[&[(LayoutAttribute, f64)]] = &[&[(LayoutAttribute::Height, 20.)], &[(LayoutAttribute::Height, 40.)]];
+        let mut layout = StackLayout::new(StackAxis::Horizontal);
+        layout.distribution = StackDistribution::FillEqually;
+
+        let alignments = [
+            (StackAlignment::Start, [0., 0.]),
+            (StackAlignment::Center, [40., 30.]),
+            (StackAlignment::End, [80., 60.])
+        ];
+
+        for (alignment, tops) in alignments.iter() {
+            layout.alignment = *alignment;
+            let frames = solve(layout, heights);
+            assert_eq!([frames[0].top, frames[1].top], *tops, "{:?}", alignment);
+        }
+
+        // Centering accounts for uneven insets.
+        layout.alignment = StackAlignment::Center;
+        layout.insets = EdgeInsets::new(10., 0., 0., 0.);
+        assert_eq!(solve(layout, heights)[0].top, 45.);
+    }
+
+    #[test]
+    fn aligns_across_a_column() {
+        let widths: &[&[(LayoutAttribute, f64)]] = &[&[(LayoutAttribute::Width, 50.)], &[(LayoutAttribute::Width, 100.)]];
+        let mut layout = StackLayout::new(StackAxis::Vertical);
+        layout.distribution = StackDistribution::FillEqually;
+
+        layout.alignment = StackAlignment::Start;
+        let lefts: Vec<f64> = solve(layout, widths).iter().map(|frame| frame.left).collect();
+        assert_eq!(lefts, vec![0., 0.]);
+
+        layout.alignment = StackAlignment::End;
+        let lefts: Vec<f64> = solve(layout, widths).iter().map(|frame| frame.left).collect();
+        assert_eq!(lefts, vec![150., 100.]);
+    }
+
+    #[test]
+    fn empty_stacks_have_no_constraints() {
+        assert!(StackLayout::new(StackAxis::Horizontal).constraints(CONTAINER, &[]).is_empty());
+    }
+}
@@ -4,6 +4,9 @@
 use objc::runtime::Object;
 use objc_id::ShareId;
 
+use crate::foundation::id;
+use crate::layout::direction::{self, LayoutDirection};
+
 /// A trait that view wrappers must conform to. Enables managing the subview tree.
 pub trait Layout {
     /// Returns a reference to the backing Objective-C layer. This is optional, as we try to keep
@@ -13,4 +16,32 @@ pub trait Layout {
 
     /// This trait should implement adding a view to the subview tree for a given view.
     fn add_subview<V: Layout>(&self, _view: &V);
+
+    /// Returns the direction this view lays out in: its own override if it has one, or the
+    /// nearest one set on a superview or its window, or failing those, the app's.
+    fn layout_direction(&self) -> LayoutDirection {
+        let node = self.get_backing_node();
+        direction::effective_layout_direction(&*node as *const Object as id)
+    }
+
+    /// Returns the direction this view has been forced to lay out in, if any.
+    fn layout_direction_override(&self) -> Option<LayoutDirection> {
+        let node = self.get_backing_node();
+        direction::layout_direction_override(&*node as *const Object as id)
+    }
+
+    /// Forces this view, and everything inside of it, to lay out in `direction` - or with `None`,
+    /// goes back to inheriting it. Leading and trailing anchors follow this, as do stack views and
+    /// natural text alignment.
+    ///
+    /// On macOS, subviews added after this is called pick up the direction the next time it's
+    /// set; set it once the hierarchy is built.
+    fn set_layout_direction(&self, direction: Option<LayoutDirection>) {
+        let node = self.get_backing_node();
+        let node = &*node as *const Object as id;
+        direction::set_layout_direction_override(node, direction);
+
+        #[cfg(feature = "macos")]
+        direction::apply_layout_direction(node);
+    }
 }
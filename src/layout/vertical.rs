@@ -7,28 +7,59 @@ use objc::runtime::Object;
 use objc_id::ShareId;
 
 use crate::foundation::id;
+use crate::layout::attributes::{LayoutAttribute, LayoutRelation};
 use crate::layout::constraint::LayoutConstraint;
+use crate::layout::record::{AnchorRecord, LayoutItem};
 
 /// A wrapper for `NSLayoutAnchor`. You should never be creating this yourself - it's more of a
 /// factory/helper for creating `LayoutConstraint` objects based on your views.
 #[derive(Clone, Debug, Default)]
-pub struct LayoutAnchorY(pub Option<ShareId<Object>>);
+pub struct LayoutAnchorY(
+    /// A shared pointer to the underlying anchor. This is `None` for uninitialized anchors.
+    pub Option<ShareId<Object>>,
+
+    /// The item and attribute this anchor represents, for the Rust-side constraint model.
+    Option<AnchorRecord>
+);
 
 impl LayoutAnchorY {
-    /// An internal method for wrapping existing constraints.
-    pub(crate) fn new(object: id) -> Self {
-        LayoutAnchorY(Some(unsafe {
-            ShareId::from_ptr(object)
-        }))
+    /// An internal method for wrapping existing anchors.
+    fn new(view: id, attribute: LayoutAttribute) -> Self {
+        let anchor: id = unsafe {
+            match attribute {
+                LayoutAttribute::Bottom => msg_send![view, bottomAnchor],
+                LayoutAttribute::CenterY => msg_send![view, centerYAnchor],
+                _ => msg_send![view, topAnchor]
+            }
+        };
+
+        LayoutAnchorY(
+            Some(unsafe { ShareId::from_ptr(anchor) }),
+            Some(AnchorRecord::new(LayoutItem(view as usize), attribute))
+        )
+    }
+
+    /// Wraps the `topAnchor` of the given view.
+    pub(crate) fn top(view: id) -> Self {
+        Self::new(view, LayoutAttribute::Top)
+    }
+
+    /// Wraps the `bottomAnchor` of the given view.
+    pub(crate) fn bottom(view: id) -> Self {
+        Self::new(view, LayoutAttribute::Bottom)
+    }
+
+    /// Wraps the `centerYAnchor` of the given view.
+    pub(crate) fn center_y(view: id) -> Self {
+        Self::new(view, LayoutAttribute::CenterY)
     }
 
     /// Return a constraint equal to another vertical anchor.
     pub fn constraint_equal_to(&self, anchor_to: &LayoutAnchorY) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
-                let b: id = msg_send![*from, constraintEqualToAnchor:&**to];
-                b
-            }),
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
+                msg_send![*from, constraintEqualToAnchor:&**to]
+            }, first, LayoutRelation::Equal, second),
 
             _ => { panic!("Attempted to create vertical constraints with an uninitialized anchor!"); }
         }
@@ -36,10 +67,10 @@ impl LayoutAnchorY {
 
     /// Return a constraint greater than or equal to another vertical anchor.
     pub fn constraint_greater_than_or_equal_to(&self, anchor_to: &LayoutAnchorY) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintGreaterThanOrEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::GreaterThanOrEqual, second),
 
             _ => { panic!("Attempted to create vertical constraints with an uninitialized anchor!"); }
         }
@@ -47,10 +78,10 @@ impl LayoutAnchorY {
 
     /// Return a constraint less than or equal to another vertical anchor.
     pub fn constraint_less_than_or_equal_to(&self, anchor_to: &LayoutAnchorY) -> LayoutConstraint {
-        match (&self.0, &anchor_to.0) {
-            (Some(from), Some(to)) => LayoutConstraint::new(unsafe {
+        match (&self.0, &anchor_to.0, self.1, anchor_to.1) {
+            (Some(from), Some(to), Some(first), second) => LayoutConstraint::new(unsafe {
                 msg_send![*from, constraintLessThanOrEqualToAnchor:&**to]
-            }),
+            }, first, LayoutRelation::LessThanOrEqual, second),
 
             _ => { panic!("Attempted to create vertical constraints with an uninitialized anchor!"); }
         }
@@ -59,6 +59,11 @@
 //!
 //! - **cloudkit**: Links `CloudKit.framework` and provides some wrappers around CloudKit
 //! functionality. Currently not feature complete.
+//! - **fluent**: Lets localization catalogs load Fluent (`.ftl`) files, alongside `.strings` and
+//! `.stringsdict` ones. See the `localization` module.
+//! - **headless**: On non-Apple targets, provides the Objective-C runtime in-process, along with
+//! a fake of the AppKit classes cacao uses - so widgets can be built and inspected in tests. See
+//! the `headless` module. Implies `macos`, since it fakes AppKit.
 //! - **user-notifications**: Links `UserNotifications.framework` and provides functionality for
 //! emitting notifications on macOS and iOS. Note that this _requires_ your application be
 //! code-signed, and will not work without it.
@@ -85,32 +90,45 @@ pub mod macos;
 #[cfg(feature = "ios")]
 pub mod ios;
 
+pub mod accessibility;
 pub mod button;
 
 #[cfg(feature = "cloudkit")]
 pub mod cloudkit;
 
 pub mod color;
+pub mod debug;
 pub mod dragdrop;
 pub mod error;
 pub mod events;
+pub mod executor;
 pub mod defaults;
 pub mod filesystem;
 pub mod foundation;
 pub mod geometry;
+pub mod gridview;
+
+#[cfg(all(feature = "headless", not(target_vendor = "apple")))]
+pub mod headless;
+
 pub mod image;
 pub mod input;
 pub(crate) mod invoker;
 pub mod layout;
 pub mod listview;
+pub mod localization;
 pub mod networking;
 pub mod notification_center;
 pub mod pasteboard;
 pub mod progress;
 pub mod scrollview;
+pub mod stackview;
 pub mod switch;
 pub mod text;
 
+#[cfg(feature = "theme")]
+pub mod theme;
+
 #[cfg(feature = "quicklook")]
 pub mod quicklook;
 
@@ -48,17 +48,17 @@ use objc_id::ShareId;
 use objc::runtime::{Class, Object};
 use objc::{class, msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSArray, NSString, NSUInteger};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSArray, NSString, NSUInteger};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 use crate::pasteboard::PasteboardType;
 use crate::scrollview::ScrollView;
 use crate::utils::CGSize;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::{register_listview_class, register_listview_class_with_delegate};
 
 #[cfg(target_os = "ios")]
@@ -146,7 +146,7 @@ fn common_init(class: *const Class) -> id {
         let _: () = msg_send![tableview, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
         // Let's... make NSTableView into UITableView-ish.
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         {
             let _: () = msg_send![tableview, setWantsLayer:YES];
             let _: () = msg_send![tableview, setUsesAutomaticRowHeights:YES];
@@ -182,12 +182,15 @@ pub struct ListView<T = ()> {
 
     /// On macOS, we need to manage the NSScrollView ourselves. It's a bit
     /// more old school like that...
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", feature = "headless"))]
     pub scrollview: ScrollView,
 
     /// A pointer to the delegate for this view.
     pub delegate: Option<Box<T>>,
 
+    /// Proof that this list view was created on the main thread, which keeps it there.
+    pub(crate) mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -214,20 +217,22 @@ pub struct ListView<T = ()> {
 }
 
 impl Default for ListView {
+    /// Returns a new `ListView`. This can't take a `MainThreadMarker`, so it checks the current
+    /// thread at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        ListView::new()
+        ListView::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl ListView {
     /// Returns a default `View`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let class = register_listview_class();
         let view = common_init(class);
         
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let scrollview = {
-            let sview = ScrollView::new();
+            let sview = ScrollView::new(mtm);
             
             unsafe {
                 let _: () = msg_send![&*sview.objc, setDocumentView:view];
@@ -237,8 +242,8 @@ impl ListView {
         };
 
         // For macOS, we need to use the NSScrollView anchor points, not the NSTableView.
-        #[cfg(target_os = "macos")]
-        let anchor_view = &*scrollview.objc;
+        #[cfg(any(target_os = "macos", feature = "headless"))]
+        let anchor_view: id = unsafe { msg_send![&*scrollview.objc, self] };
         
         #[cfg(target_os = "ios")]
         let anchor_view = view;
@@ -246,17 +251,18 @@ impl ListView {
         ListView {
             cell_factory: CellFactory::new(),
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![anchor_view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![anchor_view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![anchor_view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![anchor_view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![anchor_view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![anchor_view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![anchor_view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![anchor_view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(anchor_view),
+            leading: LayoutAnchorX::leading(anchor_view),
+            trailing: LayoutAnchorX::trailing(anchor_view),
+            bottom: LayoutAnchorY::bottom(anchor_view),
+            width: LayoutAnchorDimension::width(anchor_view),
+            height: LayoutAnchorDimension::height(anchor_view),
+            center_x: LayoutAnchorX::center_x(anchor_view),
+            center_y: LayoutAnchorY::center_y(anchor_view),
             objc: unsafe { ShareId::from_ptr(view) },
 
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", feature = "headless"))]
             scrollview: scrollview
         }
     }
@@ -265,7 +271,7 @@ impl ListView {
 impl<T> ListView<T> where T: ListViewDelegate + 'static {
     /// Initializes a new View with a given `ViewDelegate`. This enables you to respond to events
     /// and customize the view as a module, similar to class-based systems.
-    pub fn with(delegate: T) -> ListView<T> {
+    pub fn with(delegate: T, mtm: MainThreadMarker) -> ListView<T> {
         let class = register_listview_class_with_delegate::<T>(&delegate);
         let view = common_init(class);
         let mut delegate = Box::new(delegate);
@@ -282,9 +288,9 @@ impl<T> ListView<T> where T: ListViewDelegate + 'static {
             let _: () = msg_send![view, setDataSource:view];
         };
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let scrollview = {
-            let sview = ScrollView::new();
+            let sview = ScrollView::new(mtm);
             
             unsafe {
                 let _: () = msg_send![&*sview.objc, setDocumentView:view];
@@ -294,8 +300,8 @@ impl<T> ListView<T> where T: ListViewDelegate + 'static {
         };
 
         // For macOS, we need to use the NSScrollView anchor points, not the NSTableView.
-        #[cfg(target_os = "macos")]
-        let anchor_view = &*scrollview.objc;
+        #[cfg(any(target_os = "macos", feature = "headless"))]
+        let anchor_view: id = unsafe { msg_send![&*scrollview.objc, self] };
         
         #[cfg(target_os = "ios")]
         let anchor_view = view;
@@ -303,17 +309,18 @@ impl<T> ListView<T> where T: ListViewDelegate + 'static {
         let mut view = ListView {
             cell_factory: cell,
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![anchor_view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![anchor_view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![anchor_view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![anchor_view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![anchor_view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![anchor_view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![anchor_view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![anchor_view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(anchor_view),
+            leading: LayoutAnchorX::leading(anchor_view),
+            trailing: LayoutAnchorX::trailing(anchor_view),
+            bottom: LayoutAnchorY::bottom(anchor_view),
+            width: LayoutAnchorDimension::width(anchor_view),
+            height: LayoutAnchorDimension::height(anchor_view),
+            center_x: LayoutAnchorX::center_x(anchor_view),
+            center_y: LayoutAnchorY::center_y(anchor_view),
             objc: unsafe { ShareId::from_ptr(view) },
             
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", feature = "headless"))]
             scrollview: scrollview
         };
 
@@ -332,6 +339,7 @@ impl<T> ListView<T> {
         ListView {
             cell_factory: CellFactory::new(),
             delegate: None,
+            mtm: self.mtm,
             top: self.top.clone(),
             leading: self.leading.clone(),
             trailing: self.trailing.clone(),
@@ -342,7 +350,7 @@ impl<T> ListView<T> {
             center_y: self.center_y.clone(),
             objc: self.objc.clone(),
 
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", feature = "headless"))]
             scrollview: self.scrollview.clone_as_handle()
         }
     }
@@ -359,22 +367,27 @@ impl<T> ListView<T> {
 
     /// Dequeue a reusable cell. If one is not in the queue, will create and cache one for reuse.
     pub fn dequeue<R: ViewDelegate + 'static>(&self, identifier: &'static str) -> ListViewRow<R> {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let key = NSString::new(identifier).into_inner();
             let cell: id = msg_send![&*self.objc, makeViewWithIdentifier:key owner:nil];
             
             if cell != nil {
-                ListViewRow::from_cached(cell)
+                ListViewRow::from_cached(cell, self.mtm)
             } else {
                 let delegate: Box<R> = self.cell_factory.get(identifier);
-                let view = ListViewRow::with_boxed(delegate);
+                let view = ListViewRow::with_boxed(delegate, self.mtm);
                 view.set_identifier(identifier);
                 view
             }
         }
     }
 
+    /// Returns a marker for the main thread, which this list view is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Call this to set the background color for the backing layer.
     pub fn set_background_color(&self, color: Color) {
         let bg = color.into_platform_specific_color();
@@ -387,7 +400,7 @@ impl<T> ListView<T> {
     }
 
     pub fn perform_batch_updates<F: Fn(ListView)>(&self, update: F) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe { 
             let _: () = msg_send![&*self.objc, beginUpdates];
            
@@ -399,7 +412,7 @@ impl<T> ListView<T> {
     }
 
     pub fn insert_rows<I: IntoIterator<Item = usize>>(&self, indexes: I, animation: RowAnimation) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let index_set: id = msg_send![class!(NSMutableIndexSet), new];
             
@@ -418,7 +431,7 @@ impl<T> ListView<T> {
     }
 
     pub fn reload_rows(&self, indexes: &[usize]) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let index_set: id = msg_send![class!(NSMutableIndexSet), new];
             
@@ -436,7 +449,7 @@ impl<T> ListView<T> {
     }
 
     pub fn remove_rows<I: IntoIterator<Item = usize>>(&self, indexes: I, animations: RowAnimation) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let index_set: id = msg_send![class!(NSMutableIndexSet), new];
             
@@ -468,7 +481,7 @@ impl<T> ListView<T> {
     ///
     /// It can make some scrolling situations much smoother.
     pub fn set_uses_automatic_row_heights(&self, uses: bool) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let _: () = msg_send![&*self.objc, setUsesAutomaticRowHeights:match uses {
                 true => YES,
@@ -481,7 +494,7 @@ impl<T> ListView<T> {
     /// background colors automatically. If you set this, you possibly want
     /// to hard-set a row height as well.
     pub fn set_uses_alternating_backgrounds(&self, uses: bool) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let _: () = msg_send![&*self.objc, setUsesAlternatingRowBackgroundColors:match uses {
                 true => YES,
@@ -492,7 +505,7 @@ impl<T> ListView<T> {
 
     /// End actions for a row. API subject to change.
     pub fn set_row_actions_visible(&self, visible: bool) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let _: () = msg_send![&*self.objc, setRowActionsVisible:match visible {
                 true => YES,
@@ -525,7 +538,7 @@ impl<T> ListView<T> {
 impl<T> Layout for ListView<T> {
     /// On macOS, this returns the NSScrollView, not the NSTableView.
     fn get_backing_node(&self) -> ShareId<Object> {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let val = self.scrollview.objc.clone();
 
         #[cfg(target_os = "ios")]
@@ -538,7 +551,7 @@ impl<T> Layout for ListView<T> {
         let backing_node = view.get_backing_node();
 
         unsafe {
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", feature = "headless"))]
             let _: () = msg_send![&*self.scrollview.objc, addSubview:backing_node];
             
             #[cfg(target_os = "ios")]
@@ -48,16 +48,16 @@ use objc_id::{Id, ShareId};
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSArray, NSString};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSArray, NSString};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 use crate::pasteboard::PasteboardType;
 use crate::view::ViewDelegate;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::{register_listview_row_class, register_listview_row_class_with_delegate};
 
 #[cfg(target_os = "ios")]
@@ -74,7 +74,7 @@ fn allocate_view(registration_fn: fn() -> *const Class) -> id {
         let view: id = msg_send![registration_fn(), new];
         let _: () = msg_send![view, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let _: () = msg_send![view, setWantsLayer:YES];
 
         view 
@@ -92,6 +92,9 @@ pub struct ListViewRow<T = ()> {
     /// A pointer to the delegate for this view.
     pub delegate: Option<Box<T>>,
 
+    /// Proof that this row was created on the main thread, which keeps it there.
+    pub(crate) mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -118,26 +121,29 @@ pub struct ListViewRow<T = ()> {
 }
 
 impl Default for ListViewRow {
+    /// Returns a new `ListViewRow`. This can't take a `MainThreadMarker`, so it checks the current
+    /// thread at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        ListViewRow::new()
+        ListViewRow::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl ListViewRow {
     /// Returns a default `View`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let view = allocate_view(register_listview_row_class);
 
         ListViewRow {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: Rc::new(RefCell::new(unsafe { Id::from_ptr(view) })),
         }
     }
@@ -154,7 +160,7 @@ impl<T> ListViewRow<T> where T: ViewDelegate + 'static {
     /// - It takes ownership of the returned row in row_for_item
     /// - When it takes ownership, it "forgets" the pointer - and the `dealloc` method on the
     /// backing view cell will clean it up whenever it's dropped.
-    pub(crate) fn from_cached(view: id) -> ListViewRow<T> {
+    pub(crate) fn from_cached(view: id, mtm: MainThreadMarker) -> ListViewRow<T> {
         // @TODO: Make this better.
         let delegate = unsafe {
             let ptr: usize = *(&*view).get_ivar(LISTVIEW_ROW_DELEGATE_PTR);
@@ -165,28 +171,29 @@ impl<T> ListViewRow<T> where T: ViewDelegate + 'static {
 
         let view = ListViewRow {
             delegate: Some(delegate),
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: Rc::new(RefCell::new(unsafe { Id::from_ptr(view) })),
         };
 
         view
     }
 
-    pub fn with(delegate: T) -> ListViewRow<T> {
+    pub fn with(delegate: T, mtm: MainThreadMarker) -> ListViewRow<T> {
         let delegate = Box::new(delegate);
-        Self::with_boxed(delegate)
+        Self::with_boxed(delegate, mtm)
     }
 
     /// Initializes a new View with a given `ViewDelegate`. This enables you to respond to events
     /// and customize the view as a module, similar to class-based systems.
-    pub fn with_boxed(mut delegate: Box<T>) -> ListViewRow<T> { 
+    pub fn with_boxed(mut delegate: Box<T>, mtm: MainThreadMarker) -> ListViewRow<T> { 
         let view = allocate_view(register_listview_row_class_with_delegate::<T>);
         unsafe {
             //let view: id = msg_send![register_view_class_with_delegate::<T>(), new];
@@ -197,14 +204,15 @@ impl<T> ListViewRow<T> where T: ViewDelegate + 'static {
 
         let mut view = ListViewRow {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: Rc::new(RefCell::new(unsafe { Id::from_ptr(view) })),
         };
 
@@ -223,6 +231,7 @@ impl<T> ListViewRow<T> where T: ViewDelegate + 'static {
 
         ListViewRow {
             delegate: None,
+            mtm: self.mtm,
             top: self.top.clone(),
             leading: self.leading.clone(),
             trailing: self.trailing.clone(),
@@ -250,6 +259,7 @@ impl<T> ListViewRow<T> {
     pub(crate) fn clone_as_handle(&self) -> crate::view::View {
         crate::view::View {
             delegate: None,
+            mtm: self.mtm,
             top: self.top.clone(),
             leading: self.leading.clone(),
             trailing: self.trailing.clone(),
@@ -272,6 +282,11 @@ impl<T> ListViewRow<T> {
         }
     }
 
+    /// Returns a marker for the main thread, which this row is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Call this to set the background color for the backing layer.
     pub fn set_background_color(&self, color: Color) {
         let bg = color.into_platform_specific_color();
@@ -0,0 +1,105 @@
+//! Lookups in the main bundle's `.lproj` tables, by way of `NSBundle`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, NSString};
+use crate::localization::stringsdict::{parse_stringsdict, PluralEntry};
+use crate::localization::strings::decode;
+use crate::localization::LocalizationError;
+
+/// Passed as the default value to `localizedStringForKey:value:table:`, so a missing key can be
+/// told apart from one that's translated to itself.
+const MISSING: &str = "\u{1}cacao.missing";
+
+fn main_bundle() -> id {
+    unsafe { msg_send![class!(NSBundle), mainBundle] }
+}
+
+/// Looks `key` up in the main bundle's `table`, the way `NSLocalizedString` does. Returns `None`
+/// if the table doesn't have it.
+pub(crate) fn localized_string(table: &str, key: &str) -> Option<String> {
+    let bundle = main_bundle();
+
+    if bundle.is_null() {
+        return None;
+    }
+
+    let key = NSString::new(key);
+    let missing = NSString::new(MISSING);
+    let table = NSString::new(table);
+
+    let value: id = unsafe {
+        msg_send![bundle, localizedStringForKey:key.into_inner() value:missing.into_inner() table:table.into_inner()]
+    };
+
+    match value.is_null() {
+        true => None,
+        false => Some(NSString::wrap(value).to_string()).filter(|value| value != MISSING)
+    }
+}
+
+/// Returns the localization the main bundle picked for the user (e.g, `de`), if it has any.
+pub(crate) fn language() -> Option<String> {
+    let bundle = main_bundle();
+
+    if bundle.is_null() {
+        return None;
+    }
+
+    unsafe {
+        let localizations: id = msg_send![bundle, preferredLocalizations];
+        let language: id = msg_send![localizations, firstObject];
+
+        match language.is_null() {
+            true => None,
+            false => Some(NSString::wrap(language).to_string())
+        }
+    }
+}
+
+/// Reads and parses the `.stringsdict` file for `table`, from whichever `.lproj` the main bundle
+/// picks for the user. Returns `Ok(None)` if there isn't one.
+///
+/// `NSBundle` only applies these through `+[NSString localizedStringWithFormat:]`, which is
+/// variadic (and so can't be called safely from Rust) - so they're read and applied here instead.
+pub(crate) fn plurals(table: &str) -> Result<Option<HashMap<String, PluralEntry>>, LocalizationError> {
+    let bundle = main_bundle();
+
+    if bundle.is_null() {
+        return Ok(None);
+    }
+
+    let path = unsafe {
+        let name = NSString::new(table);
+        let kind = NSString::new("stringsdict");
+        let path: id = msg_send![bundle, pathForResource:name.into_inner() ofType:kind.into_inner()];
+
+        match path.is_null() {
+            true => { return Ok(None); },
+            false => PathBuf::from(NSString::wrap(path).to_string())
+        }
+    };
+
+    let contents = fs::read(&path).map_err(|error| LocalizationError::Io(format!("{}: {}", path.display(), error)))?;
+
+    decode(&contents)
+        .and_then(|source| parse_stringsdict(&source))
+        .map(Some)
+        .map_err(|error| error.in_file(&path))
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::{language, localized_string, plurals};
+
+    #[test]
+    fn finds_nothing_without_a_main_bundle() {
+        assert_eq!(localized_string("Localizable", "Inbox"), None);
+        assert_eq!(language(), None);
+        assert_eq!(plurals("Localizable"), Ok(None));
+    }
+}
@@ -0,0 +1,269 @@
+//! Translations for a single language, loaded from files rather than the app bundle.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::localization::strings::{decode, parse_strings};
+use crate::localization::stringsdict::{parse_stringsdict, PluralEntry};
+use crate::localization::LocalizationError;
+
+#[cfg(feature = "fluent")]
+use crate::localization::fluent::FluentMessages;
+
+/// The default table, as with `NSLocalizedString`.
+pub const DEFAULT_TABLE: &str = "Localizable";
+
+/// The files `Catalog::load` picks up.
+#[cfg(feature = "fluent")]
+const EXTENSIONS: &[&str] = &["strings", "stringsdict", "ftl"];
+
+#[cfg(not(feature = "fluent"))]
+const EXTENSIONS: &[&str] = &["strings", "stringsdict"];
+
+/// The strings and plural rules in one table.
+#[derive(Clone, Debug, Default)]
+struct Table {
+    strings: HashMap<String, String>,
+    plurals: HashMap<String, PluralEntry>
+}
+
+/// Every translation for one language: `.strings` and `.stringsdict` tables, and (with the
+/// `fluent` feature) Fluent messages.
+///
+/// As in an `.lproj` directory, strings live in named tables - `Localizable` unless otherwise
+/// specified. Fluent has no notion of tables, so Fluent messages are found whichever table is
+/// asked for.
+pub struct Catalog {
+    language: String,
+    tables: HashMap<String, Table>,
+
+    #[cfg(feature = "fluent")]
+    fluent: Option<FluentMessages>
+}
+
+impl fmt::Debug for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Catalog")
+            .field("language", &self.language)
+            .field("tables", &self.tables)
+            .finish()
+    }
+}
+
+impl Catalog {
+    /// Creates an empty catalog for `language` (e.g, `de`, `pt-BR`).
+    pub fn new(language: &str) -> Self {
+        Catalog {
+            language: language.to_string(),
+            tables: HashMap::new(),
+
+            #[cfg(feature = "fluent")]
+            fluent: None
+        }
+    }
+
+    /// Loads every `.strings` and `.stringsdict` file in `directory` (e.g, `de.lproj`) into a
+    /// catalog for `language`, each into the table it's named after. With the `fluent` feature,
+    /// `.ftl` files are loaded too.
+    pub fn load<P: AsRef<Path>>(language: &str, directory: P) -> Result<Self, LocalizationError> {
+        let mut catalog = Catalog::new(language);
+        let directory = directory.as_ref();
+
+        let entries = fs::read_dir(directory).map_err(|error| {
+            LocalizationError::Io(format!("{}: {}", directory.display(), error))
+        })?;
+
+        let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            let table = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(table) => table.to_string(),
+                None => { continue; }
+            };
+
+            let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("");
+
+            if !EXTENSIONS.contains(&extension) {
+                continue;
+            }
+
+            let source = fs::read(&path)
+                .map_err(|error| LocalizationError::Io(format!("{}: {}", path.display(), error)))
+                .and_then(|bytes| decode(&bytes))
+                .map_err(|error| error.in_file(&path))?;
+
+            let result = match extension {
+                "strings" => catalog.add_strings(&table, &source),
+                "stringsdict" => catalog.add_stringsdict(&table, &source),
+
+                #[cfg(feature = "fluent")]
+                "ftl" => catalog.add_fluent(&source),
+
+                _ => Ok(())
+            };
+
+            result.map_err(|error| error.in_file(&path))?;
+        }
+
+        Ok(catalog)
+    }
+
+    /// The language this catalog is for.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Adds (or replaces) a single string.
+    pub fn insert(&mut self, table: &str, key: &str, value: &str) {
+        let table = self.tables.entry(table.to_string()).or_default();
+        table.strings.insert(key.to_string(), value.to_string());
+    }
+
+    /// Adds the contents of a `.strings` file to `table`.
+    pub fn add_strings(&mut self, table: &str, source: &str) -> Result<(), LocalizationError> {
+        let strings = parse_strings(source)?;
+        self.tables.entry(table.to_string()).or_default().strings.extend(strings);
+        Ok(())
+    }
+
+    /// Adds the contents of a `.stringsdict` file to `table`.
+    pub fn add_stringsdict(&mut self, table: &str, source: &str) -> Result<(), LocalizationError> {
+        let plurals = parse_stringsdict(source)?;
+        self.tables.entry(table.to_string()).or_default().plurals.extend(plurals);
+        Ok(())
+    }
+
+    /// Adds the messages in a Fluent (`.ftl`) file.
+    #[cfg(feature = "fluent")]
+    pub fn add_fluent(&mut self, source: &str) -> Result<(), LocalizationError> {
+        if self.fluent.is_none() {
+            self.fluent = Some(FluentMessages::new(&self.language)?);
+        }
+
+        match &mut self.fluent {
+            Some(fluent) => fluent.add(source),
+            None => Ok(())
+        }
+    }
+
+    /// Returns the translation of `key` in `table`, if this catalog has one.
+    ///
+    /// Fluent formatting errors are dropped here; a `Localizer` records them (see
+    /// `Localizer::errors`).
+    pub fn string(&self, table: &str, key: &str) -> Option<String> {
+        self.find_string(table, key, &mut Vec::new())
+    }
+
+    /// Returns the translation of `key` in `table` for `count`, if this catalog has one. This
+    /// uses the `.stringsdict` entry for `key`; failing that, the Fluent message (with `count`
+    /// passed as `$count`).
+    pub fn plural(&self, table: &str, key: &str, count: i64) -> Option<String> {
+        self.find_plural(table, key, count, &mut Vec::new())
+    }
+
+    /// As `string`, but pushes any formatting errors onto `errors`.
+    pub(crate) fn find_string(&self, table: &str, key: &str, _errors: &mut Vec<LocalizationError>) -> Option<String> {
+        if let Some(value) = self.tables.get(table).and_then(|table| table.strings.get(key)) {
+            return Some(value.clone());
+        }
+
+        #[cfg(feature = "fluent")]
+        {
+            if let Some(value) = self.fluent.as_ref().and_then(|fluent| fluent.format(key, None, _errors)) {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+
+    /// As `plural`, but pushes any formatting errors onto `errors`.
+    pub(crate) fn find_plural(&self, table: &str, key: &str, count: i64, _errors: &mut Vec<LocalizationError>) -> Option<String> {
+        if let Some(entry) = self.tables.get(table).and_then(|table| table.plurals.get(key)) {
+            return Some(entry.format(&self.language, &[count]));
+        }
+
+        #[cfg(feature = "fluent")]
+        {
+            if let Some(value) = self.fluent.as_ref().and_then(|fluent| fluent.format(key, Some(count), _errors)) {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::Catalog;
+    use crate::localization::LocalizationError;
+
+    /// Returns an empty directory to load catalogs from.
+    fn directory(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cacao-catalog-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn keeps_tables_apart() {
+        let mut catalog = Catalog::new("de");
+        catalog.insert("Localizable", "Open", "Öffnen");
+        catalog.add_strings("Menus", "\"Open\" = \"Öffnen…\";").unwrap();
+
+        assert_eq!(catalog.language(), "de");
+        assert_eq!(catalog.string("Localizable", "Open").as_deref(), Some("Öffnen"));
+        assert_eq!(catalog.string("Menus", "Open").as_deref(), Some("Öffnen…"));
+        assert_eq!(catalog.string("Errors", "Open"), None);
+    }
+
+    #[test]
+    fn loads_a_directory() {
+        let path = directory("load");
+        fs::write(path.join("Localizable.strings"), "\"Inbox\" = \"Posteingang\";").unwrap();
+        fs::write(path.join("Menus.strings"), "\"Quit\" = \"Beenden\";").unwrap();
+        fs::write(path.join("notes.txt"), "Not a table").unwrap();
+
+        let catalog = Catalog::load("de", &path).unwrap();
+
+        assert_eq!(catalog.string("Localizable", "Inbox").as_deref(), Some("Posteingang"));
+        assert_eq!(catalog.string("Menus", "Quit").as_deref(), Some("Beenden"));
+        assert_eq!(catalog.string("notes", "Not a table"), None);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn reports_which_file_failed_to_load() {
+        let path = directory("error");
+        fs::write(path.join("Localizable.strings"), "\"Inbox\" = ").unwrap();
+
+        match Catalog::load("de", &path) {
+            Err(LocalizationError::Syntax { file: Some(file), .. }) => assert!(file.ends_with("Localizable.strings")),
+            result => panic!("expected a syntax error, got {:?}", result.map(|_| ()))
+        }
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[cfg(feature = "fluent")]
+    #[test]
+    fn prefers_tables_to_fluent_messages() {
+        let mut catalog = Catalog::new("en");
+        catalog.add_fluent("inbox = Inbox (Fluent)\nsent = Sent").unwrap();
+        catalog.insert("Localizable", "inbox", "Inbox");
+
+        assert_eq!(catalog.string("Localizable", "inbox").as_deref(), Some("Inbox"));
+
+        // Fluent has no tables, so its messages are found in any of them.
+        assert_eq!(catalog.string("Menus", "sent").as_deref(), Some("Sent"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Fluent (`.ftl`) support for catalogs. Requires the `fluent` feature.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use crate::localization::LocalizationError;
+
+/// The Fluent messages for a single language.
+pub(crate) struct FluentMessages {
+    bundle: FluentBundle<FluentResource>
+}
+
+impl FluentMessages {
+    pub fn new(language: &str) -> Result<Self, LocalizationError> {
+        let language: LanguageIdentifier = language.parse().map_err(|_| LocalizationError::Syntax {
+            file: None,
+            line: 0,
+            message: format!("'{}' isn't a valid language tag", language)
+        })?;
+
+        let mut bundle = FluentBundle::new_concurrent(vec![language]);
+
+        // Isolation marks are for mixing directions within a string; text in native controls is
+        // laid out by the system, which handles that itself.
+        bundle.set_use_isolating(false);
+
+        Ok(FluentMessages {
+            bundle: bundle
+        })
+    }
+
+    /// Adds the messages in `source`. Messages that are already defined are kept.
+    pub fn add(&mut self, source: &str) -> Result<(), LocalizationError> {
+        let resource = FluentResource::try_new(source.to_string()).map_err(|(_, errors)| {
+            LocalizationError::Fluent(errors.iter().map(|error| format!("{:?}", error)).collect::<Vec<_>>().join(", "))
+        })?;
+
+        // Overriding existing messages is reported as an error, but the rest are still added.
+        let _ = self.bundle.add_resource(resource);
+        Ok(())
+    }
+
+    /// Formats the message `key`, passing `count` (if given) as `$count`. Fluent still produces
+    /// something usable (with the broken parts left as-is) when formatting fails, so errors are
+    /// pushed onto `errors` rather than returned.
+    pub fn format(&self, key: &str, count: Option<i64>, errors: &mut Vec<LocalizationError>) -> Option<String> {
+        let message = self.bundle.get_message(key)?;
+        let pattern = message.value()?;
+
+        let args = count.map(|count| {
+            let mut args = FluentArgs::new();
+            args.set("count", FluentValue::from(count));
+            args
+        });
+
+        let mut format_errors = Vec::new();
+        let value = self.bundle.format_pattern(pattern, args.as_ref(), &mut format_errors);
+
+        if !format_errors.is_empty() {
+            errors.push(LocalizationError::Fluent(format!("Unable to fully format '{}': {:?}", key, format_errors)));
+        }
+
+        Some(value.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FluentMessages;
+    use crate::localization::LocalizationError;
+
+    const SOURCE: &str = "
+files = { $count ->
+    [one] { $count } Datei
+   *[other] { $count } Dateien
+}
+greeting = Hallo, { $name }!
+";
+
+    #[test]
+    fn formats_plurals() {
+        let mut messages = FluentMessages::new("de").unwrap();
+        messages.add(SOURCE).unwrap();
+
+        let mut errors = Vec::new();
+        assert_eq!(messages.format("files", Some(1), &mut errors).as_deref(), Some("1 Datei"));
+        assert_eq!(messages.format("files", Some(5), &mut errors).as_deref(), Some("5 Dateien"));
+        assert_eq!(messages.format("folders", Some(5), &mut errors), None);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn reports_formatting_errors() {
+        let mut messages = FluentMessages::new("de").unwrap();
+        messages.add(SOURCE).unwrap();
+
+        let mut errors = Vec::new();
+        assert_eq!(messages.format("greeting", None, &mut errors).as_deref(), Some("Hallo, {$name}!"));
+
+        match errors.as_slice() {
+            [LocalizationError::Fluent(message)] => assert!(message.contains("greeting")),
+            errors => panic!("expected one Fluent error, got {:?}", errors)
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(FluentMessages::new("not a language").is_err());
+
+        let mut messages = FluentMessages::new("de").unwrap();
+        assert!(matches!(messages.add("files = { $count"), Err(LocalizationError::Fluent(_))));
+    }
+}
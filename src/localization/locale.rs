@@ -0,0 +1,95 @@
+//! Language negotiation: matching the languages a user prefers against the ones an app has
+//! translations for.
+
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, NSArray, NSString};
+
+/// Returns the user's preferred languages, most preferred first (e.g, `["de-CH", "en-US"]`).
+pub fn preferred_languages() -> Vec<String> {
+    let languages = NSArray::wrap(unsafe {
+        msg_send![class!(NSLocale), preferredLanguages]
+    });
+
+    languages.map(|language: id| NSString::wrap(language).to_string())
+}
+
+/// Normalizes a language tag for comparison: `pt_BR` and `PT-br` both become `pt-br`.
+fn normalize(tag: &str) -> String {
+    tag.replace('_', "-").to_lowercase()
+}
+
+/// Returns the tag's subtags, minus the last one - `zh-hant-tw` becomes `zh-hant`, and `zh-hant`
+/// becomes `zh`.
+fn parent(tag: &str) -> Option<&str> {
+    tag.rfind('-').map(|index| &tag[..index])
+}
+
+/// Matches `requested` languages (most preferred first) against the `available` ones, returning
+/// the available languages to use, best first.
+///
+/// Each requested language picks up an exact match if there is one, then any available language
+/// it's a more specific form of (`de-CH` matches `de`), then any other regional form of the same
+/// language (`de-CH` matches `de-DE`). `default`, if given, always comes last, so there's
+/// something to fall back on. Tags are compared case-insensitively, and `_` is treated as `-`;
+/// the result uses the tags as they were given in `available`.
+pub fn negotiate_languages<R: AsRef<str>, A: AsRef<str>>(requested: &[R], available: &[A], default: Option<&str>) -> Vec<String> {
+    let normalized: Vec<String> = available.iter().map(|tag| normalize(tag.as_ref())).collect();
+    let mut matched: Vec<usize> = Vec::new();
+
+    fn add(index: usize, matched: &mut Vec<usize>) {
+        if !matched.contains(&index) {
+            matched.push(index);
+        }
+    }
+
+    for tag in requested {
+        let tag = normalize(tag.as_ref());
+
+        if let Some(index) = normalized.iter().position(|available| *available == tag) {
+            add(index, &mut matched);
+        }
+
+        let mut current = parent(&tag);
+
+        while let Some(ancestor) = current {
+            if let Some(index) = normalized.iter().position(|available| available == ancestor) {
+                add(index, &mut matched);
+            }
+
+            current = parent(ancestor);
+        }
+
+        let prefix = format!("{}-", tag.split('-').next().unwrap_or(""));
+
+        for (index, available) in normalized.iter().enumerate() {
+            if available.starts_with(&prefix) {
+                add(index, &mut matched);
+            }
+        }
+    }
+
+    let mut languages: Vec<String> = matched.into_iter().map(|index| available[index].as_ref().to_string()).collect();
+
+    if let Some(default) = default {
+        if !languages.iter().any(|language| normalize(language) == normalize(default)) {
+            languages.push(default.to_string());
+        }
+    }
+
+    languages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::negotiate_languages;
+
+    #[test]
+    fn negotiates_languages() {
+        let available = ["en", "de", "pt_BR", "pt-PT"];
+
+        assert_eq!(negotiate_languages(&["de-CH"], &available, Some("en")), vec!["de", "en"]);
+        assert_eq!(negotiate_languages(&["pt-PT", "fr"], &available, None), vec!["pt-PT", "pt_BR"]);
+        assert_eq!(negotiate_languages(&["fr"], &available, Some("en")), vec!["en"]);
+    }
+}
@@ -0,0 +1,408 @@
+//! The `Localizer`, which resolves keys against catalogs and the app bundle.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::localization::{bundle, negotiate_languages, Catalog, LocalizationError, DEFAULT_TABLE};
+use crate::localization::stringsdict::PluralEntry;
+
+/// What to do when a key can't be found anywhere.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MissingKeys {
+    /// Use the key itself, as `NSLocalizedString` does. This is the default.
+    #[default]
+    UseKey,
+
+    /// Use the key wrapped in `⟦` and `⟧`, so untranslated text stands out when running the app.
+    Mark,
+
+    /// Panic. Handy in tests, to make sure every key a screen uses has a translation.
+    Panic
+}
+
+/// Resolves keys to translated strings.
+///
+/// Keys are looked up in each of the `Catalog`s for the negotiated languages, best first, and
+/// then (unless disabled) in the app bundle's `.lproj` tables - so an app can ship its
+/// translations either way, or both. Every key that can't be found anywhere is recorded, and
+/// handled according to `MissingKeys`; so is every error found along the way (see `errors`).
+#[derive(Debug, Default)]
+pub struct Localizer {
+    catalogs: Vec<Catalog>,
+    requested: Option<Vec<String>>,
+    languages: Vec<String>,
+    development_language: Option<String>,
+    skip_bundle: bool,
+    missing_keys: MissingKeys,
+    missing: Mutex<BTreeSet<String>>,
+    errors: Mutex<Vec<LocalizationError>>,
+    bundle_plurals: Mutex<HashMap<String, Option<HashMap<String, PluralEntry>>>>
+}
+
+impl Localizer {
+    /// Creates a localizer that only uses the app bundle, until catalogs are added.
+    pub fn new() -> Self {
+        Localizer::default()
+    }
+
+    /// Adds a catalog. Unless `set_languages` is called, catalogs are used in the order they were
+    /// added; if it has been, languages are negotiated again to take the new catalog into account.
+    pub fn add_catalog(&mut self, catalog: Catalog) {
+        self.catalogs.push(catalog);
+        self.negotiate();
+    }
+
+    /// Loads a catalog from each language directory in `directory`: both `de.lproj`-style
+    /// directories, and Fluent-style `de` ones.
+    pub fn load_catalogs<P: AsRef<Path>>(&mut self, directory: P) -> Result<(), LocalizationError> {
+        let directory = directory.as_ref();
+
+        let entries = fs::read_dir(directory).map_err(|error| {
+            LocalizationError::Io(format!("{}: {}", directory.display(), error))
+        })?;
+
+        let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+        paths.sort();
+
+        for path in paths.into_iter().filter(|path| path.is_dir()) {
+            let name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(name) => name.trim_end_matches(".lproj").to_string(),
+                None => { continue; }
+            };
+
+            // Xcode's "Base" localization holds interface files, not strings.
+            if name == "Base" {
+                continue;
+            }
+
+            self.add_catalog(Catalog::load(&name, &path)?);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the language to fall back to when none of the requested ones match, as
+    /// `CFBundleDevelopmentRegion` does for bundles.
+    pub fn set_development_language(&mut self, language: &str) {
+        self.development_language = Some(language.to_string());
+        self.negotiate();
+    }
+
+    /// Negotiates which catalogs to use, given the languages the user prefers (most preferred
+    /// first) - e.g, `localization::preferred_languages()`. See `negotiate_languages`.
+    ///
+    /// If none of them match (and there's no development language to fall back on), no catalogs
+    /// are used at all - only the app bundle.
+    pub fn set_languages<S: AsRef<str>>(&mut self, requested: &[S]) {
+        self.requested = Some(requested.iter().map(|language| language.as_ref().to_string()).collect());
+        self.negotiate();
+    }
+
+    /// Negotiates the languages to use again, if `set_languages` has been called.
+    fn negotiate(&mut self) {
+        if let Some(requested) = &self.requested {
+            let available: Vec<&str> = self.catalogs.iter().map(Catalog::language).collect();
+            self.languages = negotiate_languages(requested, &available, self.development_language.as_deref());
+        }
+    }
+
+    /// The languages catalogs are consulted in, best first.
+    pub fn languages(&self) -> Vec<String> {
+        match self.requested.is_some() {
+            true => self.languages.clone(),
+            false => self.catalogs.iter().map(|catalog| catalog.language().to_string()).collect()
+        }
+    }
+
+    /// Sets whether to fall back to the app bundle's tables. Defaults to `true`.
+    pub fn set_uses_bundle(&mut self, uses_bundle: bool) {
+        self.skip_bundle = !uses_bundle;
+    }
+
+    /// Sets what happens when a key can't be found.
+    pub fn set_missing_keys(&mut self, missing_keys: MissingKeys) {
+        self.missing_keys = missing_keys;
+    }
+
+    /// Returns every key that couldn't be found so far, formatted as `table:key` for tables other
+    /// than `Localizable`.
+    pub fn missing_keys(&self) -> Vec<String> {
+        self.missing.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Returns every error found while looking keys up so far - e.g, a `.stringsdict` table in
+    /// the app bundle that couldn't be parsed, or a Fluent message that couldn't be fully
+    /// formatted.
+    pub fn errors(&self) -> Vec<LocalizationError> {
+        self.errors.lock().unwrap().clone()
+    }
+
+    fn record_errors(&self, errors: Vec<LocalizationError>) {
+        if !errors.is_empty() {
+            self.errors.lock().unwrap().extend(errors);
+        }
+    }
+
+    fn catalogs_in_order(&self) -> Vec<&Catalog> {
+        match self.requested.is_some() {
+            true => self.languages.iter().filter_map(|language| {
+                self.catalogs.iter().find(|catalog| catalog.language() == language)
+            }).collect(),
+
+            false => self.catalogs.iter().collect()
+        }
+    }
+
+    /// Looks `key` up without any missing key handling. Built-in strings (e.g, menu items) use
+    /// this, as they have English defaults of their own.
+    pub(crate) fn lookup(&self, table: &str, key: &str) -> Option<String> {
+        let mut errors = Vec::new();
+        let value = self.catalogs_in_order().into_iter().find_map(|catalog| catalog.find_string(table, key, &mut errors));
+        self.record_errors(errors);
+
+        value.or_else(|| match self.skip_bundle {
+            true => None,
+            false => bundle::localized_string(table, key)
+        })
+    }
+
+    fn lookup_plural(&self, table: &str, key: &str, count: i64) -> Option<String> {
+        let mut errors = Vec::new();
+        let value = self.catalogs_in_order().into_iter().find_map(|catalog| catalog.find_plural(table, key, count, &mut errors));
+        self.record_errors(errors);
+
+        if value.is_some() || self.skip_bundle {
+            return value;
+        }
+
+        let mut cache = self.bundle_plurals.lock().unwrap();
+        let plurals = cache.entry(table.to_string()).or_insert_with(|| match bundle::plurals(table) {
+            Ok(plurals) => plurals,
+
+            Err(error) => {
+                self.record_errors(vec![error]);
+                None
+            }
+        });
+
+        let entry = plurals.as_ref().and_then(|plurals| plurals.get(key))?;
+        let language = bundle::language().unwrap_or_else(|| "en".to_string());
+
+        Some(entry.format(&language, &[count]))
+    }
+
+    /// Records `key` as missing, and returns what to use in its place - or, with
+    /// `MissingKeys::Panic`, the message to panic with. Panicking is left to the caller, so it
+    /// can release any locks it holds first.
+    fn missing(&self, table: &str, key: &str) -> Result<String, String> {
+        let name = match table {
+            DEFAULT_TABLE => key.to_string(),
+            table => format!("{}:{}", table, key)
+        };
+
+        self.missing.lock().unwrap().insert(name.clone());
+
+        match self.missing_keys {
+            MissingKeys::UseKey => Ok(key.to_string()),
+            MissingKeys::Mark => Ok(format!("⟦{}⟧", key)),
+            MissingKeys::Panic => Err(format!("No translation for '{}' in {:?}", name, self.languages()))
+        }
+    }
+
+    /// As `localize_in`, but returns the panic message for `MissingKeys::Panic` instead of
+    /// panicking.
+    pub(crate) fn try_localize_in(&self, table: &str, key: &str) -> Result<String, String> {
+        match self.lookup(table, key) {
+            Some(value) => Ok(value),
+            None => self.missing(table, key)
+        }
+    }
+
+    /// As `localize_plural_in`, but returns the panic message for `MissingKeys::Panic` instead
+    /// of panicking.
+    pub(crate) fn try_localize_plural_in(&self, table: &str, key: &str, count: i64) -> Result<String, String> {
+        match self.lookup_plural(table, key, count) {
+            Some(value) => Ok(value),
+            None => self.missing(table, key)
+        }
+    }
+
+    /// Returns the translation of `key` from the `Localizable` table.
+    pub fn localize(&self, key: &str) -> String {
+        self.localize_in(DEFAULT_TABLE, key)
+    }
+
+    /// Returns the translation of `key` from `table`.
+    pub fn localize_in(&self, table: &str, key: &str) -> String {
+        self.try_localize_in(table, key).unwrap_or_else(|message| panic!("{}", message))
+    }
+
+    /// Returns the translation of `key` for `count`, from the `Localizable` table: the
+    /// `.stringsdict` entry for `key`, formatted for the count, or the Fluent message with `count`
+    /// passed as `$count`.
+    pub fn localize_plural(&self, key: &str, count: i64) -> String {
+        self.localize_plural_in(DEFAULT_TABLE, key, count)
+    }
+
+    /// Returns the translation of `key` for `count`, from `table`.
+    pub fn localize_plural_in(&self, table: &str, key: &str, count: i64) -> String {
+        self.try_localize_plural_in(table, key, count).unwrap_or_else(|message| panic!("{}", message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::{Localizer, MissingKeys};
+    use crate::localization::Catalog;
+
+    const PLURALS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>%d files</key>
+    <dict>
+        <key>NSStringLocalizedFormatKey</key>
+        <string>%#@files@</string>
+        <key>files</key>
+        <dict>
+            <key>NSStringFormatSpecTypeKey</key>
+            <string>NSStringPluralRuleType</string>
+            <key>NSStringFormatValueTypeKey</key>
+            <string>d</string>
+            <key>one</key>
+            <string>%d file</string>
+            <key>other</key>
+            <string>%d files</string>
+        </dict>
+    </dict>
+</dict>
+</plist>"#;
+
+    /// Returns a catalog for `language` with `entries` in the `Localizable` table.
+    fn catalog(language: &str, entries: &[(&str, &str)]) -> Catalog {
+        let mut catalog = Catalog::new(language);
+
+        for (key, value) in entries {
+            catalog.insert("Localizable", key, value);
+        }
+
+        catalog
+    }
+
+    /// Returns a localizer with English, German and French catalogs, that doesn't touch the app
+    /// bundle.
+    fn localizer() -> Localizer {
+        let mut localizer = Localizer::new();
+        localizer.set_uses_bundle(false);
+        localizer.add_catalog(catalog("en", &[("Inbox", "Inbox"), ("Sent", "Sent")]));
+        localizer.add_catalog(catalog("de", &[("Inbox", "Posteingang")]));
+        localizer.add_catalog(catalog("fr", &[("Inbox", "Boîte de réception")]));
+        localizer
+    }
+
+    #[test]
+    fn uses_catalogs_in_the_order_they_were_added() {
+        let localizer = localizer();
+
+        assert_eq!(localizer.languages(), vec!["en", "de", "fr"]);
+        assert_eq!(localizer.localize("Inbox"), "Inbox");
+    }
+
+    #[test]
+    fn negotiates_languages() {
+        let mut localizer = localizer();
+        localizer.set_development_language("en");
+        localizer.set_languages(&["de-CH"]);
+
+        assert_eq!(localizer.languages(), vec!["de", "en"]);
+        assert_eq!(localizer.localize("Inbox"), "Posteingang");
+
+        // Keys the German catalog doesn't have fall back to the development language.
+        assert_eq!(localizer.localize("Sent"), "Sent");
+    }
+
+    #[test]
+    fn uses_no_catalogs_when_no_language_matches() {
+        let mut localizer = Localizer::new();
+        localizer.set_uses_bundle(false);
+        localizer.add_catalog(catalog("de", &[("Inbox", "Posteingang")]));
+        localizer.set_languages(&["fr"]);
+
+        assert!(localizer.languages().is_empty());
+        assert_eq!(localizer.localize("Inbox"), "Inbox");
+    }
+
+    #[test]
+    fn negotiates_again_when_catalogs_are_added() {
+        let mut localizer = Localizer::new();
+        localizer.set_uses_bundle(false);
+        localizer.set_languages(&["fr", "de"]);
+        localizer.add_catalog(catalog("de", &[("Inbox", "Posteingang")]));
+
+        assert_eq!(localizer.localize("Inbox"), "Posteingang");
+
+        localizer.add_catalog(catalog("fr", &[("Inbox", "Boîte de réception")]));
+
+        assert_eq!(localizer.languages(), vec!["fr", "de"]);
+        assert_eq!(localizer.localize("Inbox"), "Boîte de réception");
+    }
+
+    #[test]
+    fn handles_missing_keys() {
+        let mut localizer = localizer();
+
+        assert_eq!(localizer.localize("Drafts"), "Drafts");
+
+        localizer.set_missing_keys(MissingKeys::Mark);
+        assert_eq!(localizer.localize_in("Menus", "Archive"), "⟦Archive⟧");
+
+        localizer.set_missing_keys(MissingKeys::Panic);
+        let result = catch_unwind(AssertUnwindSafe(|| localizer.localize("Trash")));
+        assert!(result.is_err());
+
+        // The lookup that panicked is still recorded, and the localizer still works.
+        assert_eq!(localizer.missing_keys(), vec!["Drafts", "Menus:Archive", "Trash"]);
+        assert_eq!(localizer.localize("Inbox"), "Inbox");
+    }
+
+    #[test]
+    fn falls_back_to_other_catalogs_for_plurals() {
+        let mut english = catalog("en", &[]);
+        english.add_stringsdict("Localizable", PLURALS).unwrap();
+
+        let mut localizer = Localizer::new();
+        localizer.set_uses_bundle(false);
+        localizer.add_catalog(catalog("de", &[("Inbox", "Posteingang")]));
+        localizer.add_catalog(english);
+        localizer.set_languages(&["de", "en"]);
+
+        assert_eq!(localizer.localize_plural("%d files", 1), "1 file");
+        assert_eq!(localizer.localize_plural("%d files", 3), "3 files");
+
+        assert_eq!(localizer.localize_plural("%d folders", 3), "%d folders");
+        assert_eq!(localizer.missing_keys(), vec!["%d folders"]);
+    }
+
+    #[cfg(feature = "fluent")]
+    #[test]
+    fn looks_up_fluent_messages() {
+        let mut german = Catalog::new("de");
+        german.add_fluent("inbox = Posteingang\nunread = { $count ->\n    [one] Eine ungelesene Nachricht\n   *[other] { $count } ungelesene Nachrichten\n}\ngreeting = Hallo, { $name }!\n").unwrap();
+
+        let mut localizer = Localizer::new();
+        localizer.set_uses_bundle(false);
+        localizer.add_catalog(german);
+
+        assert_eq!(localizer.localize("inbox"), "Posteingang");
+        assert_eq!(localizer.localize_plural("unread", 1), "Eine ungelesene Nachricht");
+        assert_eq!(localizer.localize_plural("unread", 4), "4 ungelesene Nachrichten");
+        assert!(localizer.errors().is_empty());
+
+        // `$name` is never passed, which Fluent reports (but still formats around).
+        assert_eq!(localizer.localize("greeting"), "Hallo, {$name}!");
+        assert_eq!(localizer.errors().len(), 1);
+    }
+}
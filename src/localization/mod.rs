@@ -0,0 +1,195 @@
+//! Localization: turning keys into user-facing strings in the user's language.
+//!
+//! Translations can come from the app bundle - the `.lproj` tables `NSLocalizedString` reads -
+//! or from `Catalog`s loaded at runtime from `.strings`, `.stringsdict` and (with the `fluent`
+//! feature) Fluent `.ftl` files. Either way, keys are resolved through a `Localizer`:
+//!
+//! ```rust,no_run
+//! use cacao::localization::{self, Localizer};
+//!
+//! let mut localizer = Localizer::new();
+//! localizer.load_catalogs("resources/locales").unwrap();
+//! localizer.set_development_language("en");
+//! localizer.set_languages(&localization::preferred_languages());
+//! localization::install(localizer);
+//!
+//! let title = localization::localized("Inbox");
+//! let status = localization::localized_plural("%d unread messages", 3);
+//! ```
+//!
+//! Without a call to `install`, keys are looked up in the app bundle only, just as
+//! `NSLocalizedString` would.
+//!
+//! The `.strings` and `.stringsdict` readers, plural rules and language negotiation are all plain
+//! Rust, and can be used (and tested) without the Objective-C runtime.
+//!
+//! ## Built-in strings
+//! Strings cacao supplies itself - like the titles of `MenuItem::quit()` and friends - are looked
+//! up with their English text as the key (e.g, `"Quit"`, `"About %@"`), so translating them is a
+//! matter of including those keys in your `Localizable` table. They're never reported as missing.
+//!
+//! ## Missing keys
+//! Every key that can't be found is recorded (see `missing_keys()`). To make them harder to miss,
+//! set `MissingKeys::Mark` (which wraps them in `⟦` `⟧`) or `MissingKeys::Panic` (for tests).
+//! Problems found along the way - a `.stringsdict` table in the bundle that can't be parsed, or a
+//! Fluent message that can't be fully formatted - are recorded as well (see `errors()`).
+
+use std::error;
+use std::fmt;
+use std::path::Path;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+mod bundle;
+
+mod catalog;
+pub use catalog::{Catalog, DEFAULT_TABLE};
+
+#[cfg(feature = "fluent")]
+mod fluent;
+
+mod locale;
+pub use locale::{negotiate_languages, preferred_languages};
+
+mod localizer;
+pub use localizer::{Localizer, MissingKeys};
+
+mod plural;
+pub use plural::{plural_category, PluralCategory};
+
+mod strings;
+pub use strings::parse_strings;
+
+mod stringsdict;
+pub use stringsdict::{parse_stringsdict, PluralEntry, PluralVariable};
+
+/// Errors that can occur when loading translations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LocalizationError {
+    /// A file or directory couldn't be read.
+    Io(String),
+
+    /// A `.strings` or `.stringsdict` file couldn't be parsed. `line` is `0` when it isn't known.
+    Syntax {
+        file: Option<String>,
+        line: usize,
+        message: String
+    },
+
+    /// A Fluent file couldn't be parsed, or a message in one couldn't be fully formatted.
+    Fluent(String)
+}
+
+impl LocalizationError {
+    /// Notes which file the error came from.
+    pub(crate) fn in_file(self, path: &Path) -> Self {
+        match self {
+            LocalizationError::Syntax { line, message, .. } => LocalizationError::Syntax {
+                file: Some(path.display().to_string()),
+                line: line,
+                message: message
+            },
+
+            LocalizationError::Fluent(message) => LocalizationError::Fluent(format!("{}: {}", path.display(), message)),
+            error => error
+        }
+    }
+}
+
+impl fmt::Display for LocalizationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LocalizationError::Io(error) => write!(f, "Unable to read translations: {}", error),
+
+            LocalizationError::Syntax { file, line, message } => {
+                write!(f, "Unable to parse translations")?;
+
+                if let Some(file) = file {
+                    write!(f, " in {}", file)?;
+                }
+
+                if *line > 0 {
+                    write!(f, " (line {})", line)?;
+                }
+
+                write!(f, ": {}", message)
+            },
+
+            LocalizationError::Fluent(error) => write!(f, "Unable to use Fluent translations: {}", error)
+        }
+    }
+}
+
+impl error::Error for LocalizationError {}
+
+lazy_static! {
+    static ref LOCALIZER: RwLock<Localizer> = RwLock::new(Localizer::new());
+}
+
+/// Makes `localizer` the one used by `localized()` and friends, and by cacao's own built-in
+/// strings. Anything created before this is called keeps the strings it was created with.
+pub fn install(localizer: Localizer) {
+    *LOCALIZER.write().unwrap() = localizer;
+}
+
+/// Returns the translation of `key`, from the `Localizable` table.
+pub fn localized(key: &str) -> String {
+    localized_in(DEFAULT_TABLE, key)
+}
+
+/// Returns the translation of `key`, from `table`.
+pub fn localized_in(table: &str, key: &str) -> String {
+    // The lock is released before panicking (with `MissingKeys::Panic`), so that one missing key
+    // doesn't poison it for every lookup after it.
+    let result = LOCALIZER.read().unwrap().try_localize_in(table, key);
+    result.unwrap_or_else(|message| panic!("{}", message))
+}
+
+/// Returns the translation of `key` for `count`, from the `Localizable` table.
+pub fn localized_plural(key: &str, count: i64) -> String {
+    let result = LOCALIZER.read().unwrap().try_localize_plural_in(DEFAULT_TABLE, key, count);
+    result.unwrap_or_else(|message| panic!("{}", message))
+}
+
+/// Returns every key that the installed localizer couldn't find so far.
+pub fn missing_keys() -> Vec<String> {
+    LOCALIZER.read().unwrap().missing_keys()
+}
+
+/// Returns every error the installed localizer found while looking keys up so far.
+pub fn errors() -> Vec<LocalizationError> {
+    LOCALIZER.read().unwrap().errors()
+}
+
+/// Returns the translation of one of cacao's built-in strings, keyed by its English text - or the
+/// English text, if there isn't one.
+pub(crate) fn builtin(english: &str) -> String {
+    LOCALIZER.read().unwrap().lookup(DEFAULT_TABLE, english).unwrap_or_else(|| english.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use super::{install, localized, missing_keys, Catalog, Localizer, MissingKeys};
+
+    #[test]
+    fn panicking_on_a_missing_key_does_not_poison_the_localizer() {
+        let mut catalog = Catalog::new("en");
+        catalog.insert("Localizable", "Inbox", "Inbox");
+
+        let mut localizer = Localizer::new();
+        localizer.set_uses_bundle(false);
+        localizer.set_missing_keys(MissingKeys::Panic);
+        localizer.add_catalog(catalog);
+        install(localizer);
+
+        assert!(catch_unwind(|| localized("Trash")).is_err());
+
+        assert_eq!(localized("Inbox"), "Inbox");
+        assert_eq!(missing_keys(), vec!["Trash"]);
+
+        install(Localizer::new());
+    }
+}
@@ -0,0 +1,200 @@
+//! CLDR plural rules, for choosing between the forms in a `.stringsdict` entry.
+//!
+//! Only whole numbers are handled - which is all `.stringsdict` formats get in practice - so the
+//! rules below are the integer halves of the CLDR ones.
+
+use std::fmt;
+
+/// A CLDR plural category. Every language uses `Other`; which of the rest it uses (if any)
+/// depends on the language.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other
+}
+
+impl PluralCategory {
+    /// Returns the category for a `.stringsdict` (or Fluent) key: `zero`, `one`, `two`, `few`,
+    /// `many` or `other`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "zero" => Some(PluralCategory::Zero),
+            "one" => Some(PluralCategory::One),
+            "two" => Some(PluralCategory::Two),
+            "few" => Some(PluralCategory::Few),
+            "many" => Some(PluralCategory::Many),
+            "other" => Some(PluralCategory::Other),
+            _ => None
+        }
+    }
+
+    /// Returns the key this category uses in `.stringsdict` files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other"
+        }
+    }
+}
+
+impl fmt::Display for PluralCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+/// Returns the plural category `count` falls into for `language` (a language tag, e.g `en`,
+/// `pt-PT` or `sr_Latn`). Languages without a rule here get the English rule.
+pub fn plural_category(language: &str, count: i64) -> PluralCategory {
+    use PluralCategory::{Few, Many, One, Other, Two, Zero};
+
+    let n = count.unsigned_abs();
+    let n10 = n % 10;
+    let n100 = n % 100;
+
+    let tag = language.replace('_', "-").to_lowercase();
+    let primary = tag.split('-').next().unwrap_or("");
+
+    match primary {
+        // No plural forms at all.
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "my" | "lo" | "km" | "yue" => Other,
+
+        // One for 0 and 1.
+        "fr" | "hi" | "bn" | "fa" | "gu" | "kn" | "am" | "zu" | "hy" | "ff" | "kab" => match n {
+            0 | 1 => One,
+            n if primary == "fr" && n % 1_000_000 == 0 => Many,
+            _ => Other
+        },
+
+        // Brazilian Portuguese counts 0 as singular; European Portuguese doesn't.
+        "pt" => match (tag.starts_with("pt-pt"), n) {
+            (false, 0) | (_, 1) => One,
+            (false, n) if n % 1_000_000 == 0 => Many,
+            _ => Other
+        },
+
+        "es" | "it" | "ca" => match n {
+            1 => One,
+            n if n != 0 && n % 1_000_000 == 0 => Many,
+            _ => Other
+        },
+
+        "ru" | "uk" | "be" => match (n10, n100) {
+            (1, n100) if n100 != 11 => One,
+            (2..=4, n100) if !(12..=14).contains(&n100) => Few,
+            _ => Many
+        },
+
+        "pl" => match (n, n10, n100) {
+            (1, _, _) => One,
+            (_, 2..=4, n100) if !(12..=14).contains(&n100) => Few,
+            _ => Many
+        },
+
+        "cs" | "sk" => match n {
+            1 => One,
+            2..=4 => Few,
+            _ => Other
+        },
+
+        "hr" | "sr" | "bs" | "sh" => match (n10, n100) {
+            (1, n100) if n100 != 11 => One,
+            (2..=4, n100) if !(12..=14).contains(&n100) => Few,
+            _ => Other
+        },
+
+        "lt" => match (n10, n100) {
+            (_, 11..=19) => Other,
+            (1, _) => One,
+            (2..=9, _) => Few,
+            _ => Other
+        },
+
+        "lv" => match (n10, n100) {
+            (0, _) | (_, 11..=19) => Zero,
+            (1, _) => One,
+            _ => Other
+        },
+
+        "ro" | "mo" => match (n, n100) {
+            (1, _) => One,
+            (0, _) | (_, 1..=19) => Few,
+            _ => Other
+        },
+
+        "sl" => match n100 {
+            1 => One,
+            2 => Two,
+            3 | 4 => Few,
+            _ => Other
+        },
+
+        "he" | "iw" => match n {
+            1 => One,
+            2 => Two,
+            _ => Other
+        },
+
+        "ar" => match (n, n100) {
+            (0, _) => Zero,
+            (1, _) => One,
+            (2, _) => Two,
+            (_, 3..=10) => Few,
+            (_, 11..=99) => Many,
+            _ => Other
+        },
+
+        "ga" => match n {
+            1 => One,
+            2 => Two,
+            3..=6 => Few,
+            7..=10 => Many,
+            _ => Other
+        },
+
+        "cy" => match n {
+            0 => Zero,
+            1 => One,
+            2 => Two,
+            3 => Few,
+            6 => Many,
+            _ => Other
+        },
+
+        "is" | "mk" => match (n10, n100) {
+            (1, n100) if n100 != 11 => One,
+            _ => Other
+        },
+
+        _ => match n {
+            1 => One,
+            _ => Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plural_category, PluralCategory};
+
+    #[test]
+    fn picks_categories_by_language() {
+        assert_eq!(plural_category("en", 1), PluralCategory::One);
+        assert_eq!(plural_category("en-GB", 0), PluralCategory::Other);
+        assert_eq!(plural_category("fr", 0), PluralCategory::One);
+        assert_eq!(plural_category("ja", 1), PluralCategory::Other);
+        assert_eq!(plural_category("ru", 22), PluralCategory::Few);
+        assert_eq!(plural_category("ru", 12), PluralCategory::Many);
+        assert_eq!(plural_category("pl", 21), PluralCategory::Many);
+        assert_eq!(plural_category("ar", 0), PluralCategory::Zero);
+        assert_eq!(plural_category("ar", 2), PluralCategory::Two);
+    }
+}
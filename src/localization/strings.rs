@@ -0,0 +1,307 @@
+//! A reader for `.strings` files - the `"key" = "value";` format `NSLocalizedString` tables are
+//! written in.
+//!
+//! Both quoted and bare (alphanumeric) keys and values are accepted, along with `/* */` and `//`
+//! comments and the usual escapes (`\n`, `\t`, `\"`, `\\`, `\U00E9`). Files may be UTF-8 or
+//! UTF-16 (with a byte order mark), which is what Xcode writes.
+
+use std::collections::HashMap;
+
+use crate::localization::LocalizationError;
+
+/// Decodes the contents of a `.strings` (or `.stringsdict`) file: UTF-16 if it starts with a byte
+/// order mark saying so, and UTF-8 otherwise.
+pub(crate) fn decode(bytes: &[u8]) -> Result<String, LocalizationError> {
+    let utf16 = |bytes: &[u8], little_endian: bool| -> Result<String, LocalizationError> {
+        let units: Vec<u16> = bytes.chunks(2).map(|pair| match (pair, little_endian) {
+            ([low, high], true) => u16::from_le_bytes([*low, *high]),
+            ([high, low], false) => u16::from_be_bytes([*high, *low]),
+            _ => 0xFFFD
+        }).collect();
+
+        String::from_utf16(&units).map_err(|error| LocalizationError::Syntax {
+            file: None,
+            line: 0,
+            message: format!("invalid UTF-16: {}", error)
+        })
+    };
+
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, true),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, false),
+        [0xEF, 0xBB, 0xBF, rest @ ..] | rest => String::from_utf8(rest.to_vec()).map_err(|error| LocalizationError::Syntax {
+            file: None,
+            line: 0,
+            message: format!("invalid UTF-8: {}", error)
+        })
+    }
+}
+
+/// Parses the contents of a `.strings` file into a key/value table. If a key appears more than
+/// once, the last value wins (as it does for `NSBundle`).
+pub fn parse_strings(source: &str) -> Result<HashMap<String, String>, LocalizationError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+        line: 1
+    };
+
+    let mut table = HashMap::new();
+
+    loop {
+        parser.skip_whitespace_and_comments()?;
+
+        if parser.peek().is_none() {
+            return Ok(table);
+        }
+
+        let key = parser.token()?;
+        parser.skip_whitespace_and_comments()?;
+
+        // A bare `"key";` maps the key to itself.
+        let value = match parser.peek() {
+            Some('=') => {
+                parser.position += 1;
+                parser.skip_whitespace_and_comments()?;
+                parser.token()?
+            },
+
+            _ => key.clone()
+        };
+
+        parser.skip_whitespace_and_comments()?;
+        parser.expect(';')?;
+        table.insert(key, value);
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+
+        if let Some(c) = c {
+            self.position += 1;
+
+            if c == '\n' {
+                self.line += 1;
+            }
+        }
+
+        c
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, LocalizationError> {
+        Err(LocalizationError::Syntax {
+            file: None,
+            line: self.line,
+            message: message
+        })
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), LocalizationError> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => self.error(format!("expected '{}', found '{}'", expected, c)),
+            None => self.error(format!("expected '{}', found the end of the file", expected))
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) -> Result<(), LocalizationError> {
+        loop {
+            match (self.peek(), self.chars.get(self.position + 1)) {
+                (Some(c), _) if c.is_whitespace() => { self.next(); },
+
+                (Some('/'), Some('/')) => {
+                    while let Some(c) = self.next() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                },
+
+                (Some('/'), Some('*')) => {
+                    let line = self.line;
+                    self.position += 2;
+
+                    loop {
+                        match self.next() {
+                            Some('*') if self.peek() == Some('/') => {
+                                self.position += 1;
+                                break;
+                            },
+
+                            Some(_) => {},
+
+                            None => {
+                                self.line = line;
+                                return self.error("unterminated comment".to_string());
+                            }
+                        }
+                    }
+                },
+
+                _ => return Ok(())
+            }
+        }
+    }
+
+    /// Reads a quoted string, or a bare word.
+    fn token(&mut self) -> Result<String, LocalizationError> {
+        match self.peek() {
+            Some('"') => {
+                self.position += 1;
+                self.quoted()
+            },
+
+            Some(c) if is_bare(c) => {
+                let mut token = String::new();
+
+                while let Some(c) = self.peek().filter(|c| is_bare(*c)) {
+                    token.push(c);
+                    self.position += 1;
+                }
+
+                Ok(token)
+            },
+
+            Some(c) => self.error(format!("expected a string, found '{}'", c)),
+            None => self.error("expected a string, found the end of the file".to_string())
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, LocalizationError> {
+        let line = self.line;
+        let mut value = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => value.push(self.escape()?),
+                Some(c) => value.push(c),
+
+                None => {
+                    self.line = line;
+                    return self.error("unterminated string".to_string());
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, LocalizationError> {
+        match self.next() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('a') => Ok('\u{7}'),
+            Some('b') => Ok('\u{8}'),
+            Some('f') => Ok('\u{c}'),
+            Some('v') => Ok('\u{b}'),
+
+            Some('U') | Some('u') => {
+                let high = self.hex()?;
+
+                // Characters outside the BMP are written as a surrogate pair of escapes.
+                if (0xD800..0xDC00).contains(&high) && self.peek() == Some('\\') {
+                    let checkpoint = (self.position, self.line);
+                    self.position += 1;
+
+                    if let Some('U') | Some('u') = self.next() {
+                        let low = self.hex()?;
+
+                        if let Some(c) = std::char::decode_utf16(vec![high, low]).next().and_then(Result::ok) {
+                            return Ok(c);
+                        }
+                    }
+
+                    self.position = checkpoint.0;
+                    self.line = checkpoint.1;
+                }
+
+                Ok(std::char::from_u32(high as u32).unwrap_or('\u{FFFD}'))
+            },
+
+            Some(c @ '0'..='7') => {
+                let mut value = c.to_digit(8).unwrap();
+
+                for _ in 0..2 {
+                    match self.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            self.position += 1;
+                        },
+
+                        None => break
+                    }
+                }
+
+                Ok(std::char::from_u32(value).unwrap_or('\u{FFFD}'))
+            },
+
+            Some(c) => Ok(c),
+            None => self.error("unterminated string".to_string())
+        }
+    }
+
+    fn hex(&mut self) -> Result<u16, LocalizationError> {
+        let digits: String = self.chars.iter().skip(self.position).take(4).collect();
+
+        match u16::from_str_radix(&digits, 16) {
+            Ok(value) if digits.len() == 4 => {
+                self.position += 4;
+                Ok(value)
+            },
+
+            _ => self.error(format!("invalid unicode escape '\\U{}'", digits))
+        }
+    }
+}
+
+/// Characters allowed in unquoted keys and values.
+fn is_bare(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_$+/:.-".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, parse_strings};
+    use crate::localization::LocalizationError;
+
+    #[test]
+    fn parses_strings_files() {
+        let table = parse_strings(r#"
+            /* A comment */
+            "greeting" = "Hello, \"world\"\n";
+            // Another comment
+            bare_key = bare_value;
+            "caf\U00E9";
+        "#).unwrap();
+
+        assert_eq!(table["greeting"], "Hello, \"world\"\n");
+        assert_eq!(table["bare_key"], "bare_value");
+        assert_eq!(table["café"], "café");
+    }
+
+    #[test]
+    fn reports_the_line_of_syntax_errors() {
+        match parse_strings("\"a\" = \"b\";\n\"c\" = \"d\"\n") {
+            Err(LocalizationError::Syntax { line, .. }) => assert_eq!(line, 3),
+            result => panic!("expected a syntax error, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn decodes_utf16() {
+        let bytes = [0xFF, 0xFE, b'h', 0, b'i', 0];
+        assert_eq!(decode(&bytes).unwrap(), "hi");
+    }
+}
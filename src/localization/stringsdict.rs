@@ -0,0 +1,270 @@
+//! A reader for `.stringsdict` files, and the formatting that goes with them.
+//!
+//! A `.stringsdict` entry is a format string (`NSStringLocalizedFormatKey`) containing variables
+//! like `%#@files@`, each of which is defined as a set of plural forms:
+//!
+//! ```xml
+//! <key>%d files</key>
+//! <dict>
+//!     <key>NSStringLocalizedFormatKey</key>
+//!     <string>%#@files@</string>
+//!     <key>files</key>
+//!     <dict>
+//!         <key>NSStringFormatSpecTypeKey</key>
+//!         <string>NSStringPluralRuleType</string>
+//!         <key>NSStringFormatValueTypeKey</key>
+//!         <string>d</string>
+//!         <key>one</key>
+//!         <string>%d file</string>
+//!         <key>other</key>
+//!         <string>%d files</string>
+//!     </dict>
+//! </dict>
+//! ```
+//!
+//! Formatting picks a form for each variable with the language's plural rules, and then fills in
+//! the count.
+
+use std::collections::HashMap;
+
+use crate::defaults::plist;
+use crate::defaults::Value;
+use crate::localization::{plural_category, LocalizationError, PluralCategory};
+
+/// A single `.stringsdict` entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PluralEntry {
+    /// The format string, with `%#@variable@` placeholders.
+    pub format: String,
+
+    /// The variables the format refers to.
+    pub variables: HashMap<String, PluralVariable>
+}
+
+/// A variable in a `PluralEntry`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PluralVariable {
+    /// The format specifier the count is written with in each form (e.g, `d`, `ld`).
+    pub value_type: String,
+
+    /// The form to use for each plural category. `Other` is always present.
+    pub forms: HashMap<PluralCategory, String>
+}
+
+/// Parses the contents of a `.stringsdict` file. Entries that aren't plural rules are skipped.
+pub fn parse_stringsdict(source: &str) -> Result<HashMap<String, PluralEntry>, LocalizationError> {
+    let root = plist::decode(source).map_err(|message| LocalizationError::Syntax {
+        file: None,
+        line: 0,
+        message: message
+    })?;
+
+    let mut entries = HashMap::new();
+
+    for (key, value) in root {
+        let entry = match value {
+            Value::Dictionary(entry) => entry,
+            _ => { continue; }
+        };
+
+        let format = match entry.get("NSStringLocalizedFormatKey") {
+            Some(Value::String(format)) => format.clone(),
+            _ => { continue; }
+        };
+
+        let mut variables = HashMap::new();
+
+        for (name, variable) in &entry {
+            let variable = match variable {
+                Value::Dictionary(variable) => variable,
+                _ => { continue; }
+            };
+
+            match variable.get("NSStringFormatSpecTypeKey") {
+                Some(Value::String(kind)) if kind == "NSStringPluralRuleType" => {},
+                _ => { continue; }
+            }
+
+            let value_type = match variable.get("NSStringFormatValueTypeKey") {
+                Some(Value::String(value_type)) => value_type.clone(),
+                _ => "d".to_string()
+            };
+
+            let forms: HashMap<PluralCategory, String> = variable.iter().filter_map(|(key, value)| {
+                match (PluralCategory::from_key(key), value) {
+                    (Some(category), Value::String(form)) => Some((category, form.clone())),
+                    _ => None
+                }
+            }).collect();
+
+            if !forms.contains_key(&PluralCategory::Other) {
+                return Err(LocalizationError::Syntax {
+                    file: None,
+                    line: 0,
+                    message: format!("'{}' in '{}' has no 'other' form", name, key)
+                });
+            }
+
+            variables.insert(name.clone(), PluralVariable {
+                value_type: value_type,
+                forms: forms
+            });
+        }
+
+        entries.insert(key, PluralEntry {
+            format: format,
+            variables: variables
+        });
+    }
+
+    Ok(entries)
+}
+
+impl PluralVariable {
+    /// Returns the form to use for `count` in `language`. As on Apple platforms, a `zero` form is
+    /// used for `0` whether or not the language has a zero category.
+    pub fn form(&self, language: &str, count: i64) -> &str {
+        let category = match count == 0 && self.forms.contains_key(&PluralCategory::Zero) {
+            true => PluralCategory::Zero,
+            false => plural_category(language, count)
+        };
+
+        self.forms.get(&category)
+            .or_else(|| self.forms.get(&PluralCategory::Other))
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+}
+
+impl PluralEntry {
+    /// Formats this entry for `counts` in `language`. Each variable in the format takes the next
+    /// count (and the last one is reused if there are more variables than counts).
+    pub fn format(&self, language: &str, counts: &[i64]) -> String {
+        let mut output = String::new();
+        let mut rest = self.format.as_str();
+        let mut index = 0;
+
+        while let Some(start) = rest.find("%#@") {
+            let name_start = start + 3;
+
+            let name_end = match rest[name_start..].find('@') {
+                Some(end) => name_start + end,
+                None => break
+            };
+
+            let count = counts.get(index).or_else(|| counts.last()).copied().unwrap_or(0);
+            index += 1;
+
+            output.push_str(&substitute(&rest[..start], None, count));
+
+            match self.variables.get(&rest[name_start..name_end]) {
+                Some(variable) => {
+                    let form = variable.form(language, count);
+                    output.push_str(&substitute(form, Some(&variable.value_type), count));
+                },
+
+                None => output.push_str(&rest[start..=name_end])
+            }
+
+            rest = &rest[name_end + 1..];
+        }
+
+        output.push_str(&substitute(rest, None, counts.first().copied().unwrap_or(0)));
+        output
+    }
+}
+
+/// Fills `count` in for integer specifiers (`%d`, `%ld`, `%lu`, `%i`, `%@` and so on, or just
+/// `%value_type` if one is given) in `text`, and unescapes `%%`.
+fn substitute(text: &str, value_type: Option<&str>, count: i64) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        let rest = &text[index + 1..];
+
+        if rest.starts_with('%') {
+            output.push('%');
+            chars.next();
+            continue;
+        }
+
+        // Positional specifiers (`%1$d`) are treated like their plain counterparts.
+        let position_length = match rest.find('$') {
+            Some(dollar) if dollar > 0 && rest[..dollar].chars().all(|c| c.is_ascii_digit()) => dollar + 1,
+            _ => 0
+        };
+
+        let specifier = &rest[position_length..];
+
+        let length = match value_type {
+            Some(value_type) if specifier.starts_with(value_type) => Some(value_type.len()),
+            _ => ["lld", "llu", "ld", "lu", "li", "d", "u", "i", "@"].iter()
+                .find(|candidate| specifier.starts_with(*candidate))
+                .map(|candidate| candidate.len())
+        };
+
+        match length {
+            Some(length) => {
+                output.push_str(&count.to_string());
+
+                for _ in 0..(position_length + length) {
+                    chars.next();
+                }
+            },
+
+            None => output.push('%')
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_stringsdict;
+
+    const SOURCE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>%d files</key>
+    <dict>
+        <key>NSStringLocalizedFormatKey</key>
+        <string>%#@files@ (100%%)</string>
+        <key>files</key>
+        <dict>
+            <key>NSStringFormatSpecTypeKey</key>
+            <string>NSStringPluralRuleType</string>
+            <key>NSStringFormatValueTypeKey</key>
+            <string>d</string>
+            <key>zero</key>
+            <string>No files</string>
+            <key>one</key>
+            <string>%d file</string>
+            <key>other</key>
+            <string>%d files</string>
+        </dict>
+    </dict>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn formats_plural_entries() {
+        let entries = parse_stringsdict(SOURCE).unwrap();
+        let entry = &entries["%d files"];
+
+        assert_eq!(entry.format("en", &[0]), "No files (100%)");
+        assert_eq!(entry.format("en", &[1]), "1 file (100%)");
+        assert_eq!(entry.format("en", &[7]), "7 files (100%)");
+    }
+
+    #[test]
+    fn requires_an_other_form() {
+        assert!(parse_stringsdict(&SOURCE.replace("other", "few")).is_err());
+    }
+}
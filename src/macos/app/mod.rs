@@ -41,12 +41,15 @@ use objc_id::Id;
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSUInteger, AutoReleasePool};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSUInteger, AutoReleasePool};
 use crate::invoker::TargetActionHandler;
 use crate::macos::menu::Menu;
 use crate::notification_center::Dispatcher;
 use crate::utils::activate_cocoa_multithreading;
 
+#[cfg(all(feature = "headless", not(target_vendor = "apple")))]
+use crate::headless::dispatch;
+
 mod class;
 use class::register_app_class;
 
@@ -195,9 +198,11 @@ impl<T, M> App<T, M> where M: Send + Sync + 'static, T: AppDelegate + Dispatcher
     }
 
     /// Dispatches a message by grabbing the `sharedApplication`, getting ahold of the delegate,
-    /// and passing back through there.
+    /// and passing back through there. The delegate is called on a global (background) queue.
+    ///
+    /// For anything more involved than passing a message, see the `executor` module.
     pub fn dispatch_background(message: M) {
-        let queue = dispatch::Queue::main();
+        let queue = dispatch::Queue::global(dispatch::QueuePriority::Default);
         
         queue.exec_async(move || unsafe {
             let app: id = msg_send![register_app_class(), sharedApplication];
@@ -210,6 +215,20 @@ impl<T, M> App<T, M> where M: Send + Sync + 'static, T: AppDelegate + Dispatcher
 }
 
 impl App {
+    /// Runs `handler` on the main thread, handing it a `MainThreadMarker` for creating and
+    /// updating UI. This can be called from any thread.
+    pub fn dispatch_main_with<F>(handler: F)
+    where
+        F: FnOnce(MainThreadMarker) + Send + 'static
+    {
+        let queue = dispatch::Queue::main();
+
+        queue.exec_async(move || {
+            // Jobs on the main queue always run on the main thread.
+            handler(unsafe { MainThreadMarker::new_unchecked() });
+        });
+    }
+
     /// Registers for remote notifications from APNS.
     pub fn register_for_remote_notifications() {
         shared_application(|app| unsafe {
@@ -10,6 +10,7 @@ use std::sync::Once;
 use crate::foundation::{id, nil, NSString, NSUInteger};
 use crate::events::EventModifierFlag;
 use crate::invoker::TargetActionHandler;
+use crate::localization::builtin;
 
 /// Internal method (shorthand) for generating `NSMenuItem` holders.
 fn make_menu_item(
@@ -96,19 +97,19 @@ impl MenuItem {
     
     /// Returns a standard "About" item.
     pub fn about(name: &str) -> Self {
-        let title = format!("About {}", name);
+        let title = builtin("About %@").replace("%@", name);
         make_menu_item(&title, None, Some(sel!(orderFrontStandardAboutPanel:)), None)
     }
     
     /// Returns a standard "Hide" item.
     pub fn hide() -> Self {
-        make_menu_item("Hide", Some("h"), Some(sel!(hide:)), None)
+        make_menu_item(&builtin("Hide"), Some("h"), Some(sel!(hide:)), None)
     }
 
     /// Returns the standard "Services" item. This one does some extra work to link in the default
     /// Services submenu.
     pub fn services() -> Self {
-        match make_menu_item("Services", None, None, None) {
+        match make_menu_item(&builtin("Services"), None, None, None) {
             // Link in the services menu, which is part of NSApp
             MenuItem::Entry((item, action)) => {
                 unsafe {
@@ -128,7 +129,7 @@ impl MenuItem {
     /// Returns a standard "Hide" item.
     pub fn hide_others() -> Self {
         make_menu_item(
-            "Hide Others",
+            &builtin("Hide Others"),
             Some("h"),
             Some(sel!(hide:)),
             Some(&[EventModifierFlag::Command, EventModifierFlag::Option])
@@ -137,33 +138,33 @@ impl MenuItem {
 
     /// Returns a standard "Hide" item.
     pub fn show_all() -> Self {
-        make_menu_item("Show All", None, Some(sel!(unhideAllApplications:)), None)
+        make_menu_item(&builtin("Show All"), None, Some(sel!(unhideAllApplications:)), None)
     }
 
     /// Returns a standard "Close Window" item.
     pub fn close_window() -> Self {
-        make_menu_item("Close Window", Some("w"), Some(sel!(performClose:)), None)
+        make_menu_item(&builtin("Close Window"), Some("w"), Some(sel!(performClose:)), None)
     }
 
     /// Returns a standard "Quit" item.
     pub fn quit() -> Self {
-        make_menu_item("Quit", Some("q"), Some(sel!(terminate:)), None)
+        make_menu_item(&builtin("Quit"), Some("q"), Some(sel!(terminate:)), None)
     }
 
     /// Returns a standard "Copy" item.
     pub fn copy() -> Self {
-        make_menu_item("Copy", Some("c"), Some(sel!(copy:)), None)
+        make_menu_item(&builtin("Copy"), Some("c"), Some(sel!(copy:)), None)
     }
     
     /// Returns a standard "Undo" item.
     pub fn undo() -> Self {
-        make_menu_item("Undo", Some("z"), Some(sel!(undo:)), None)
+        make_menu_item(&builtin("Undo"), Some("z"), Some(sel!(undo:)), None)
     }
 
     /// Returns a standard "Enter Full Screen" item
     pub fn enter_full_screen() -> Self {
         make_menu_item(
-            "Enter Full Screen",
+            &builtin("Enter Full Screen"),
             Some("f"),
             Some(sel!(toggleFullScreen:)),
             Some(&[EventModifierFlag::Command, EventModifierFlag::Control])
@@ -173,7 +174,7 @@ impl MenuItem {
     /// Returns a standard "Miniaturize" item
     pub fn minimize() -> Self {
         make_menu_item(
-            "Minimize",
+            &builtin("Minimize"),
             Some("m"),
             Some(sel!(performMiniaturize:)),
             None
@@ -183,7 +184,7 @@ impl MenuItem {
     /// Returns a standard "Zoom" item
     pub fn zoom() -> Self {
         make_menu_item(
-            "Zoom",
+            &builtin("Zoom"),
             None,
             Some(sel!(performZoom:)),
             None
@@ -192,21 +193,21 @@ impl MenuItem {
 
     /// Returns a standard "Redo" item.
     pub fn redo() -> Self {
-        make_menu_item("Redo", Some("Z"), Some(sel!(redo:)), None)
+        make_menu_item(&builtin("Redo"), Some("Z"), Some(sel!(redo:)), None)
     }
 
     /// Returns a standard "Cut" item.
     pub fn cut() -> Self {
-        make_menu_item("Cut", Some("x"), Some(sel!(cut:)), None)
+        make_menu_item(&builtin("Cut"), Some("x"), Some(sel!(cut:)), None)
     }
 
     /// Returns a standard "Select All" item.
     pub fn select_all() -> Self {
-        make_menu_item("Select All", Some("a"), Some(sel!(selectAll:)), None)
+        make_menu_item(&builtin("Select All"), Some("a"), Some(sel!(selectAll:)), None)
     }
 
     /// Returns a standard "Paste" item.
     pub fn paste() -> Self {
-        make_menu_item("Paste", Some("v"), Some(sel!(paste:)), None)
+        make_menu_item(&builtin("Paste"), Some("v"), Some(sel!(paste:)), None)
     }
 }
@@ -31,7 +31,7 @@ use objc::runtime::Object;
 use objc::{msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
-use crate::foundation::{id, nil};
+use crate::foundation::{id, nil, MainThreadMarker};
 use crate::utils::Controller;
 use crate::macos::window::{Window, WindowConfig, WindowDelegate, WINDOW_DELEGATE_PTR};
 
@@ -51,8 +51,8 @@ pub struct WindowController<T> {
 impl<T> WindowController<T> where T: WindowDelegate + 'static {
     /// Allocates and configures an `NSWindowController` in the Objective-C/Cocoa runtime that maps over
     /// to your supplied delegate.
-    pub fn with(config: WindowConfig, delegate: T) -> Self {
-        let mut window = Window::with(config, delegate);
+    pub fn with(config: WindowConfig, delegate: T, mtm: MainThreadMarker) -> Self {
+        let mut window = Window::with(config, delegate, mtm);
 
         let objc = unsafe {
             let window_controller_class = register_window_controller_class::<T>();
@@ -70,7 +70,8 @@ impl<T> WindowController<T> where T: WindowDelegate + 'static {
         if let Some(delegate) = &mut window.delegate {
             (*delegate).did_load(Window {
                 delegate: None,
-                objc: window.objc.clone()
+                objc: window.objc.clone(),
+                mtm: mtm
             });
         }
 
@@ -18,7 +18,8 @@ use objc::runtime::Object;
 use objc_id::ShareId;
 
 use crate::color::Color;
-use crate::foundation::{id, nil, to_bool, YES, NO, NSString, NSInteger, NSUInteger};
+use crate::foundation::{id, nil, to_bool, YES, NO, MainThreadMarker, NSString, NSInteger, NSUInteger};
+use crate::layout::direction::{self, LayoutDirection};
 use crate::layout::traits::Layout;
 use crate::macos::toolbar::{Toolbar, ToolbarDelegate};
 use crate::utils::{os, Controller};
@@ -48,13 +49,18 @@ pub struct Window<T = ()> {
     pub objc: ShareId<Object>,
 
     /// A delegate for this window.
-    pub delegate: Option<Box<T>>
+    pub delegate: Option<Box<T>>,
+
+    /// Proof that this window was created on the main thread, which keeps it there.
+    pub(crate) mtm: MainThreadMarker
 }
 
 impl Default for Window {
-    /// Returns a default `Window`, with a default `WindowConfig`.
+    /// Returns a default `Window`, with a default `WindowConfig`. This can't take a
+    /// `MainThreadMarker`, so it checks the current thread at runtime instead, and panics if it's
+    /// called off the main thread.
     fn default() -> Self {
-        Window::new(WindowConfig::default())
+        Window::new(WindowConfig::default(), MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
@@ -64,7 +70,7 @@ impl Window {
     ///
     /// Why the config? Well, certain properties of windows are really not meant to be altered
     /// after we initialize the backing `NSWindow`.
-    pub fn new(config: WindowConfig) -> Window {
+    pub fn new(config: WindowConfig, mtm: MainThreadMarker) -> Window {
         let objc = unsafe {
             // This behavior might make sense to keep as default (YES), but I think the majority of
             // apps that would use this toolkit wouldn't be tab-oriented...
@@ -108,7 +114,8 @@ impl Window {
 
         Window {
             objc: objc,
-            delegate: None
+            delegate: None,
+            mtm: mtm
         }
     }
 }
@@ -118,7 +125,7 @@ impl<T> Window<T> where T: WindowDelegate + 'static {
     /// you to respond to window lifecycle events - visibility, movement, and so on. It also
     /// enables easier structure of your codebase, and in a way simulates traditional class based
     /// architectures... just without the subclassing.
-    pub fn with(config: WindowConfig, delegate: T) -> Self {
+    pub fn with(config: WindowConfig, delegate: T, mtm: MainThreadMarker) -> Self {
         let class = register_window_class_with_delegate::<T>(&delegate);
         let mut delegate = Box::new(delegate);
         
@@ -172,18 +179,25 @@ impl<T> Window<T> where T: WindowDelegate + 'static {
         {
             (&mut delegate).did_load(Window {
                 delegate: None,
-                objc: objc.clone()
+                objc: objc.clone(),
+                mtm: mtm
             });
         }
 
         Window {
             objc: objc,
-            delegate: Some(delegate)
+            delegate: Some(delegate),
+            mtm: mtm
         }
     }
 }
 
 impl<T> Window<T> {
+    /// Returns a marker for the main thread, which this window is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Handles setting the title on the underlying window. Allocates and passes an `NSString` over
     /// to the Objective C runtime.
     pub fn set_title(&self, title: &str) {
@@ -278,6 +292,10 @@ impl<T> Window<T> {
         unsafe {
             let _: () = msg_send![&*self.objc, setContentView:&*backing_node];
         }
+
+        if self.layout_direction_override().is_some() {
+            direction::apply_layout_direction(&*backing_node as *const Object as id);
+        }
     }
 
     /// Given a view, sets it as the content view controller for this window.
@@ -320,6 +338,33 @@ impl<T> Window<T> {
         }
     }
 
+    /// Returns the direction this window lays out in: its own override if it has one, or the
+    /// app's.
+    pub fn layout_direction(&self) -> LayoutDirection {
+        direction::resolve_layout_direction(
+            vec![self.layout_direction_override()],
+            direction::application_layout_direction()
+        )
+    }
+
+    /// Returns the direction this window has been forced to lay out in, if any.
+    pub fn layout_direction_override(&self) -> Option<LayoutDirection> {
+        direction::layout_direction_override(&*self.objc as *const Object as id)
+    }
+
+    /// Forces everything in this window to lay out in `direction` - or with `None`, goes back to
+    /// following the app. Views inside of it can still override this for themselves.
+    pub fn set_layout_direction(&self, layout_direction: Option<LayoutDirection>) {
+        let window = &*self.objc as *const Object as id;
+        direction::set_layout_direction_override(window, layout_direction);
+
+        let content_view: id = unsafe { msg_send![window, contentView] };
+
+        if content_view != nil {
+            direction::apply_layout_direction(content_view);
+        }
+    }
+
     /// Returns whether this window is opaque or not.
     pub fn is_opaque(&self) -> bool {
         to_bool(unsafe {
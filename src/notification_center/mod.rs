@@ -3,71 +3,332 @@
 //! With this, you can:
 //!
 //! - Register for notifications, both from the system or posted from your code
-//! - Post your own notifications
-//! - Clean up and remove your handlers
+//! - Post your own notifications, with a user-info payload
+//! - Decode the user-info of common system notifications into typed payloads
+//! - Define typed notifications for events internal to your app
+//! - Clean up and remove your handlers (by dropping the `ObserverToken` you're handed)
 //!
 //! Note that in some cases (e.g, looping) this will be much slower than if you have a handle and
 //! can call through to your desired path directly. This control is provided due to the need for
 //! integrating with certain aspects of the underlying Cocoa/Foundation/Kit frameworks.
 //!
+//! Handlers are called synchronously, on the thread that posted the notification - which, for
+//! most system notifications, is the main thread.
+//!
 //! ## Example
+//! ```rust
+//! use cacao::notification_center::{CustomNotification, NotificationCenter, NotificationName};
+//!
+//! struct TodoCompleted {
+//!     index: usize
+//! }
+//!
+//! impl CustomNotification for TodoCompleted {
+//!     const NAME: &'static str = "com.example.todos.TodoCompleted";
+//! }
+//!
+//! let center = NotificationCenter::default();
+//!
+//! let _resize = center.observe(NotificationName::NSWindowDidResize, |notification| {
+//!     println!("A window resized: {:?}", notification.user_info);
+//! });
+//!
+//! let _completed = center.observe_custom(|event: &TodoCompleted| {
+//!     println!("Completed todo #{}", event.index);
+//! });
+//!
+//! center.post_custom(&TodoCompleted { index: 3 });
+//! ```
 
-//use std::sync::Mutex;
-//use std::collections::HashMap;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
-//use lazy_static::lazy_static;
-//use objc::{class, msg_send, sel, sel_impl};
-//use objc::runtime::Object;
-//use objc_id::ShareId;
+use block::ConcreteBlock;
+use lazy_static::lazy_static;
+use objc::{class, msg_send, sel, sel_impl};
+use objc::runtime::Object;
+use objc_id::ShareId;
+
+use crate::defaults::Value;
+use crate::foundation::{id, nil, NSDictionary, NSString};
 
 mod name;
-pub use name::NotificationName;
+pub use name::{Framework, NotificationName, Platform};
+
+pub mod user_info;
+pub use user_info::{UserInfo, UserInfoError};
+
+mod router;
+use router::{Route, Router};
 
 mod traits;
 pub use traits::Dispatcher;
 
-/*lazy_static! {
-    pub static ref DefaultNotificationCenter: NotificationCenter = {
-        NotificationCenter {
-            objc: unsafe {
-                ShareId::from_ptr(msg_send![class!(NSNotificationCenter), defaultCenter])
-            },
+lazy_static! {
+    /// Routes custom notifications for the default center. It's shared so that every handle to
+    /// the default center - however it was obtained - sees the same handlers.
+    static ref DEFAULT_ROUTER: Arc<Router> = Arc::new(Router::new());
+}
+
+/// A notification, as handed to observers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    /// The name the notification was posted under.
+    pub name: NotificationName,
+
+    /// The `userInfo` dictionary the notification was posted with. Entries that can't be
+    /// represented as a `Value` are left out.
+    pub user_info: HashMap<String, Value>
+}
+
+impl Notification {
+    /// Decodes `user_info` into a typed payload; see the `user_info` module for what's available.
+    pub fn payload<T: UserInfo>(&self) -> Result<T, UserInfoError> {
+        T::from_user_info(&self.user_info)
+    }
+}
 
-            subscribers: Mutex::new(HashMap::new())
+/// A notification that's defined (and posted) by your app, and delivered as the type itself.
+///
+/// These are routed entirely in Rust - they never pass through `NSNotificationCenter`, so they
+/// can carry any data, but can't be observed from Objective-C.
+pub trait CustomNotification: Send + Sync + 'static {
+    /// A name for this notification, used for debugging. Prefixing it with your bundle identifier
+    /// is a good habit.
+    const NAME: &'static str;
+}
+
+/// A guard for a registered observer. Dropping this unregisters the observer; if you want it to
+/// live as long as your program, keep this somewhere that does (e.g, your `AppDelegate`).
+#[must_use = "dropping an ObserverToken unregisters its observer immediately"]
+pub struct ObserverToken(Option<Box<dyn FnOnce()>>);
+
+impl ObserverToken {
+    /// Returns a token that calls `remove` when dropped.
+    pub(crate) fn new<F: FnOnce() + 'static>(remove: F) -> Self {
+        ObserverToken(Some(Box::new(remove)))
+    }
+}
+
+impl fmt::Debug for ObserverToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverToken")
+            .finish()
+    }
+}
+
+impl Drop for ObserverToken {
+    fn drop(&mut self) {
+        if let Some(remove) = self.0.take() {
+            remove();
         }
-    };
-}*/
-
-// Wraps a reference to an `NSNotificationCenter` instance. Currently this only supports the
-// default center; in the future it should aim to support custom variants.
-//#[derive(Debug)]
-//pub struct NotificationCenter {
-//    pub objc: ShareId<Object>,
-    //pub subscribers: Mutex<HashMap<String, Vec<Dispatcher>>>
-//}
-
-/*impl Default for NotificationCenter {
+    }
+}
+
+/// Wraps a reference to an `NSNotificationCenter` instance - or, for an in-process center, just
+/// the Rust side of routing.
+#[derive(Clone, Debug)]
+pub struct NotificationCenter {
+    /// The underlying `NSNotificationCenter`, unless this is an in-process center.
+    pub objc: Option<ShareId<Object>>,
+
+    router: Arc<Router>
+}
+
+impl Default for NotificationCenter {
     /// Returns a wrapper over `[NSNotificationCenter defaultCenter]`. From here you can handle
     /// observing, removing, and posting notifications.
     fn default() -> Self {
         NotificationCenter {
-            objc: unsafe {
+            objc: Some(unsafe {
                 ShareId::from_ptr(msg_send![class!(NSNotificationCenter), defaultCenter])
-            }
+            }),
+
+            router: DEFAULT_ROUTER.clone()
+        }
+    }
+}
+
+impl NotificationCenter {
+    /// Returns a new `NSNotificationCenter`, separate from the default one. Notifications posted
+    /// here are only seen by observers registered here.
+    pub fn new() -> Self {
+        NotificationCenter {
+            objc: Some(unsafe {
+                ShareId::from_ptr(msg_send![class!(NSNotificationCenter), new])
+            }),
+
+            router: Arc::new(Router::new())
+        }
+    }
+
+    /// Returns a center that routes everything in Rust, without touching the Objective-C runtime.
+    /// It won't see system notifications, but otherwise behaves the same - which makes it useful
+    /// for testing code that posts and observes notifications.
+    pub fn in_process() -> Self {
+        NotificationCenter {
+            objc: None,
+            router: Arc::new(Router::new())
         }
     }
-}*/
 
-/*impl NotificationCenter {
-    pub fn observe<T: Dispatcher>(&self, name: &str, handler: &T) {
+    /// Calls `handler` whenever a notification named `name` is posted, until the returned token
+    /// is dropped.
+    pub fn observe<F>(&self, name: NotificationName, handler: F) -> ObserverToken
+    where
+        F: Fn(&Notification) + Send + Sync + 'static
+    {
+        let center = match &self.objc {
+            Some(center) => center.clone(),
+            None => { return self.router.add(Route::Named(name), handler); }
+        };
+
+        let notification_name = name.clone();
+        let block = ConcreteBlock::new(move |notification: id| {
+            let user_info: id = unsafe { msg_send![notification, userInfo] };
+
+            handler(&Notification {
+                name: notification_name.clone(),
+                user_info: user_info::decode(user_info)
+            });
+        });
+
+        let block = block.copy();
+
+        let observer = unsafe {
+            let name: NSString = name.into();
+            let observer: id = msg_send![&*center, addObserverForName:name.into_inner()
+                object:nil
+                queue:nil
+                usingBlock:block];
 
+            ShareId::<Object>::from_ptr(observer)
+        };
+
+        ObserverToken::new(move || unsafe {
+            let _: () = msg_send![&*center, removeObserver:&*observer];
+        })
     }
 
-    pub fn remove<T: Dispatcher>(&self, name: &str, handler: &T) {
+    /// Calls `handler` with a decoded payload whenever a notification named `name` is posted,
+    /// until the returned token is dropped. Notifications whose `userInfo` can't be decoded as a
+    /// `T` are skipped; use `observe_payload_result` to be told about those too.
+    pub fn observe_payload<T, F>(&self, name: NotificationName, handler: F) -> ObserverToken
+    where
+        T: UserInfo,
+        F: Fn(&T) + Send + Sync + 'static
+    {
+        self.observe_payload_result(name, move |payload: Result<T, UserInfoError>| {
+            if let Ok(payload) = payload {
+                handler(&payload);
+            }
+        })
+    }
 
+    /// Calls `handler` with the result of decoding the payload whenever a notification named
+    /// `name` is posted, until the returned token is dropped - so notifications whose `userInfo`
+    /// can't be decoded as a `T` can be logged or otherwise handled.
+    pub fn observe_payload_result<T, F>(&self, name: NotificationName, handler: F) -> ObserverToken
+    where
+        T: UserInfo,
+        F: Fn(Result<T, UserInfoError>) + Send + Sync + 'static
+    {
+        self.observe(name, move |notification| handler(notification.payload::<T>()))
     }
 
-    pub fn post(&self, name: &str) {
+    /// Posts a notification named `name`, with a `userInfo` dictionary built from `user_info`.
+    pub fn post<K: AsRef<str>>(&self, name: NotificationName, user_info: HashMap<K, Value>) {
+        match &self.objc {
+            Some(center) => unsafe {
+                let name: NSString = name.into();
+                let user_info = NSDictionary::from(user_info);
+
+                let _: () = msg_send![&**center, postNotificationName:name.into_inner()
+                    object:nil
+                    userInfo:user_info.into_inner()];
+            },
+
+            None => {
+                let route = Route::Named(name.clone());
+                let notification = Notification {
+                    name: name,
+                    user_info: user_info.into_iter().map(|(key, value)| {
+                        (key.as_ref().to_string(), value)
+                    }).collect()
+                };
+
+                self.router.dispatch(&route, &notification);
+            }
+        }
+    }
+
+    /// Calls `handler` whenever a `T` is posted with `post_custom`, until the returned token is
+    /// dropped.
+    pub fn observe_custom<T, F>(&self, handler: F) -> ObserverToken
+    where
+        T: CustomNotification,
+        F: Fn(&T) + Send + Sync + 'static
+    {
+        self.router.add(Route::Custom(TypeId::of::<T>(), T::NAME), handler)
+    }
+
+    /// Posts a custom notification to everything observing `T` on this center. Returns how many
+    /// observers were called.
+    pub fn post_custom<T: CustomNotification>(&self, notification: &T) -> usize {
+        self.router.dispatch(&Route::Custom(TypeId::of::<T>(), T::NAME), notification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::defaults::ValueKind;
+    use user_info::AccessibilityAnnouncement;
+
+    #[test]
+    fn reports_payloads_that_fail_to_decode() {
+        let center = NotificationCenter::in_process();
+        let name = NotificationName::NSAccessibilityAnnouncementRequested;
+
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let decoded = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = results.clone();
+        let _result = center.observe_payload_result(name.clone(), move |payload: Result<AccessibilityAnnouncement, _>| {
+            seen.lock().unwrap().push(payload);
+        });
+
+        let seen = decoded.clone();
+        let _payload = center.observe_payload(name.clone(), move |payload: &AccessibilityAnnouncement| {
+            seen.lock().unwrap().push(payload.clone());
+        });
+
+        let mut user_info = HashMap::new();
+        user_info.insert("AXAnnouncementKey", Value::Integer(1));
+        center.post(name.clone(), user_info);
+
+        let mut user_info = HashMap::new();
+        user_info.insert("AXAnnouncementKey", Value::String("Saved".to_string()));
+        center.post(name, user_info);
+
+        let announcement = AccessibilityAnnouncement {
+            announcement: "Saved".to_string(),
+            priority: None
+        };
+
+        assert_eq!(*results.lock().unwrap(), vec![
+            Err(UserInfoError::InvalidValue {
+                key: "AXAnnouncementKey",
+                expected: ValueKind::String,
+                found: ValueKind::Integer
+            }),
+            Ok(announcement.clone())
+        ]);
 
+        assert_eq!(*decoded.lock().unwrap(), vec![announcement]);
     }
-}*/
+}
@@ -1,12 +1,19 @@
 #[allow(non_camel_case_types)]
 
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+
 use crate::foundation::NSString;
 
 /// An enum that wraps NSNotificationName.
 ///
 /// Since this framework utilizes Objective-C, these are ultimately backed by `NSString`... but we
 /// want them to be a bit more type-friendly and autocomplete-able.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum NotificationName {
     /// Posted when the audio engine config changes.
     ///
@@ -554,7 +561,7 @@ pub enum NotificationName {
     ///
     NSColorPanelColorDidChange,
 
-    ///
+    /// An alias for `NSColorPanelColorDidChange`, which is what parsing its string returns.
     NSColorPanel,
 
     ///
@@ -1311,13 +1318,712 @@ pub enum NotificationName {
     SKStorefrontCountryCodeDidChange,
 
     ///
-    WKAccessibilityReduceMotionStatusDidChange
+    WKAccessibilityReduceMotionStatusDidChange,
+
+    /// Any other notification name, e.g one posted by another library or process. Parsing a
+    /// string that isn't in the table above returns this.
+    Unknown(String)
+}
+
+/// The framework that declares a `NotificationName`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Framework {
+    /// Accounts.
+    Accounts,
+
+    /// AddressBook.
+    AddressBook,
+
+    /// AppKit.
+    AppKit,
+
+    /// AVFoundation.
+    AVFoundation,
+
+    /// AVKit.
+    AVKit,
+
+    /// ClockKit.
+    ClockKit,
+
+    /// CloudKit.
+    CloudKit,
+
+    /// Contacts.
+    Contacts,
+
+    /// CoreData.
+    CoreData,
+
+    /// CoreTelephony.
+    CoreTelephony,
+
+    /// CoreWLAN.
+    CoreWLAN,
+
+    /// EventKit.
+    EventKit,
+
+    /// ExternalAccessory.
+    ExternalAccessory,
+
+    /// Foundation.
+    Foundation,
+
+    /// GameController.
+    GameController,
+
+    /// GameKit.
+    GameKit,
+
+    /// HealthKit.
+    HealthKit,
+
+    /// HomeKit.
+    HomeKit,
+
+    /// IOBluetooth.
+    IOBluetooth,
+
+    /// MapKit.
+    MapKit,
+
+    /// MediaPlayer.
+    MediaPlayer,
+
+    /// MessageUI.
+    MessageUI,
+
+    /// NetworkExtension.
+    NetworkExtension,
+
+    /// PassKit.
+    PassKit,
+
+    /// PDFKit.
+    PDFKit,
+
+    /// PreferencePanes.
+    PreferencePanes,
+
+    /// Quartz.
+    Quartz,
+
+    /// StoreKit.
+    StoreKit,
+
+    /// UIKit.
+    UIKit,
+
+    /// WatchKit.
+    WatchKit
+}
+
+/// An OS that a `NotificationName` can be posted on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// macOS.
+    MacOS,
+
+    /// iOS (and iPadOS).
+    IOS,
+
+    /// tvOS.
+    TvOS,
+
+    /// watchOS.
+    WatchOS
+}
+
+/// Generates the string, framework, and platform lookups for `NotificationName`, plus the table
+/// that parsing searches, from a single list - so that they can't drift apart.
+macro_rules! notification_names {
+    ($($variant:ident => $name:expr, $framework:ident, [$($platform:ident),*];)*) => {
+        /// Every known name, in declaration order. Parsing returns the first match, which is what
+        /// makes `NSColorPanel` an alias.
+        static NAMES: &[NotificationName] = &[$(NotificationName::$variant),*];
+
+        impl NotificationName {
+            /// Returns the string this name is posted under - i.e, the value of the Objective-C
+            /// constant. This is usually the constant's own name (`NSWindowDidResizeNotification`),
+            /// but not always: accessibility notifications use their `AX` names, and a few Core
+            /// Data ones predate the current naming.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(NotificationName::$variant => $name,)*
+                    NotificationName::Unknown(name) => name
+                }
+            }
+
+            /// Returns the framework that declares this name, or `None` if it's `Unknown`.
+            pub fn framework(&self) -> Option<Framework> {
+                match self {
+                    $(NotificationName::$variant => Some(Framework::$framework),)*
+                    NotificationName::Unknown(_) => None
+                }
+            }
+
+            /// Returns the platforms this name can be posted on. `Unknown` names return an empty
+            /// slice, as there's no way to tell.
+            pub fn platforms(&self) -> &'static [Platform] {
+                match self {
+                    $(NotificationName::$variant => &[$(Platform::$platform),*],)*
+                    NotificationName::Unknown(_) => &[]
+                }
+            }
+        }
+    }
+}
+
+notification_names! {
+    AudioEngineConfigurationChange => "AVAudioEngineConfigurationChangeNotification", AVFoundation, [MacOS, IOS, TvOS, WatchOS];
+    AudioSessionInterruption => "AVAudioSessionInterruptionNotification", AVFoundation, [IOS, TvOS, WatchOS];
+    AudioSessionMediaServicesWereLost => "AVAudioSessionMediaServicesWereLostNotification", AVFoundation, [IOS, TvOS, WatchOS];
+    AudioSessionMediaServicesWereReset => "AVAudioSessionMediaServicesWereResetNotification", AVFoundation, [IOS, TvOS, WatchOS];
+    AudioSessionRouteChange => "AVAudioSessionRouteChangeNotification", AVFoundation, [IOS, TvOS, WatchOS];
+    AudioSessionSilenceSecondaryAudioHint => "AVAudioSessionSilenceSecondaryAudioHintNotification", AVFoundation, [IOS, TvOS, WatchOS];
+    AudioUnitComponentTagsDidChange => "AVAudioUnitComponentTagsDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    CloudKitAccountChanged => "CKAccountChangedNotification", CloudKit, [MacOS, IOS, TvOS, WatchOS];
+    CLKComplicationServerActiveComplicationsDidChange => "CLKComplicationServerActiveComplicationsDidChangeNotification", ClockKit, [WatchOS];
+    CNContactStoreDidChange => "CNContactStoreDidChangeNotification", Contacts, [MacOS, IOS, WatchOS];
+    EKEventStoreChanged => "EKEventStoreChangedNotification", EventKit, [MacOS, IOS, WatchOS];
+    HKUserPreferencesDidChange => "HKUserPreferencesDidChangeNotification", HealthKit, [IOS, WatchOS];
+    HMCharacteristicPropertySupportsEvent => "HMCharacteristicPropertySupportsEventNotification", HomeKit, [IOS, TvOS, WatchOS];
+    NSBundleResourceRequestLowDiskSpace => "NSBundleResourceRequestLowDiskSpaceNotification", Foundation, [IOS, TvOS];
+    NSCalendarDayChanged => "NSCalendarDayChangedNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSExtensionHostDidBecomeActive => "NSExtensionHostDidBecomeActiveNotification", Foundation, [IOS];
+    NSExtensionHostDidEnterBackground => "NSExtensionHostDidEnterBackgroundNotification", Foundation, [IOS];
+    NSExtensionHostWillEnterForeground => "NSExtensionHostWillEnterForegroundNotification", Foundation, [IOS];
+    NSExtensionHostWillResignActive => "NSExtensionHostWillResignActiveNotification", Foundation, [IOS];
+    NSFileHandleConnectionAccepted => "NSFileHandleConnectionAcceptedNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSFileHandleDataAvailable => "NSFileHandleDataAvailableNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSFileHandleReadToEndOfFileCompletion => "NSFileHandleReadToEndOfFileCompletionNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSHTTPCookieManagerAcceptPolicyChanged => "NSHTTPCookieManagerAcceptPolicyChangedNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSHTTPCookieManagerCookiesChanged => "NSHTTPCookieManagerCookiesChangedNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSManagedObjectContextDidSave => "NSManagingContextDidSaveChangesNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSManagedObjectContextObjectsDidChange => "NSObjectsChangedInManagingContextNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSManagedObjectContextWillSave => "NSManagingContextWillSaveChangesNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSMetadataQueryDidFinishGathering => "NSMetadataQueryDidFinishGatheringNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSMetadataQueryDidStartGathering => "NSMetadataQueryDidStartGatheringNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSMetadataQueryDidUpdate => "NSMetadataQueryDidUpdateNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSMetadataQueryGatheringProgress => "NSMetadataQueryGatheringProgressNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSPersistentStoreCoordinatorStoresDidChange => "NSPersistentStoreCoordinatorStoresDidChangeNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSPersistentStoreCoordinatorStoresWillChange => "NSPersistentStoreCoordinatorStoresWillChangeNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSPersistentStoreCoordinatorWillRemoveStore => "NSPersistentStoreCoordinatorWillRemoveStoreNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSProcessInfoPowerStateDidChange => "NSProcessInfoPowerStateDidChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSSystemClockDidChange => "NSSystemClockDidChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSSystemTimeZoneDidChange => "NSSystemTimeZoneDidChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSURLCredentialStorageChanged => "NSURLCredentialStorageChangedNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUbiquityIdentityDidChange => "NSUbiquityIdentityDidChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerCheckpoint => "NSUndoManagerCheckpointNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerDidCloseUndoGroup => "NSUndoManagerDidCloseUndoGroupNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerDidOpenUndoGroup => "NSUndoManagerDidOpenUndoGroupNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerDidRedoChange => "NSUndoManagerDidRedoChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerDidUndoChange => "NSUndoManagerDidUndoChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerWillCloseUndoGroup => "NSUndoManagerWillCloseUndoGroupNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerWillRedoChange => "NSUndoManagerWillRedoChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    NSUndoManagerWillUndoChange => "NSUndoManagerWillUndoChangeNotification", Foundation, [MacOS, IOS, TvOS, WatchOS];
+    PKPassLibraryDidChange => "PKPassLibraryDidChangeNotification", PassKit, [IOS, WatchOS];
+    PKPassLibraryRemotePaymentPassesDidChange => "PKPassLibraryRemotePaymentPassesDidChangeNotification", PassKit, [IOS, WatchOS];
+    UIAccessibilityAnnouncementDidFinish => "UIAccessibilityAnnouncementDidFinishNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityElementFocused => "UIAccessibilityElementFocusedNotification", UIKit, [IOS, TvOS];
+    WKAudioFilePlayerItemDidPlayToEndTime => "WKAudioFilePlayerItemDidPlayToEndTimeNotification", WatchKit, [WatchOS];
+    WKAudioFilePlayerItemFailedToPlayToEndTime => "WKAudioFilePlayerItemFailedToPlayToEndTimeNotification", WatchKit, [WatchOS];
+    WKAudioFilePlayerItemTimeJumped => "WKAudioFilePlayerItemTimeJumpedNotification", WatchKit, [WatchOS];
+    ABPeoplePickerDisplayedPropertyDidChange => "ABPeoplePickerDisplayedPropertyDidChangeNotification", AddressBook, [MacOS];
+    ABPeoplePickerGroupSelectionDidChange => "ABPeoplePickerGroupSelectionDidChangeNotification", AddressBook, [MacOS];
+    ABPeoplePickerNameSelectionDidChange => "ABPeoplePickerNameSelectionDidChangeNotification", AddressBook, [MacOS];
+    ABPeoplePickerValueSelectionDidChange => "ABPeoplePickerValueSelectionDidChangeNotification", AddressBook, [MacOS];
+    ACAccountStoreDidChange => "ACAccountStoreDidChangeNotification", Accounts, [MacOS, IOS];
+    AVAssetChapterMetadataGroupsDidChange => "AVAssetChapterMetadataGroupsDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetContainsFragmentsDidChange => "AVAssetContainsFragmentsDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetDurationDidChange => "AVAssetDurationDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetMediaSelectionGroupsDidChange => "AVAssetMediaSelectionGroupsDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetTrackSegmentsDidChange => "AVAssetTrackSegmentsDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetTrackTimeRangeDidChange => "AVAssetTrackTimeRangeDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetTrackTrackAssociationsDidChange => "AVAssetTrackTrackAssociationsDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVAssetWasDefragmented => "AVAssetWasDefragmentedNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVCaptureDeviceWasConnected => "AVCaptureDeviceWasConnectedNotification", AVFoundation, [MacOS, IOS];
+    AVCaptureDeviceWasDisconnected => "AVCaptureDeviceWasDisconnectedNotification", AVFoundation, [MacOS, IOS];
+    AVCaptureInputPortFormatDescriptionDidChange => "AVCaptureInputPortFormatDescriptionDidChangeNotification", AVFoundation, [MacOS, IOS];
+    AVCaptureSessionDidStartRunning => "AVCaptureSessionDidStartRunningNotification", AVFoundation, [MacOS, IOS];
+    AVCaptureSessionDidStopRunning => "AVCaptureSessionDidStopRunningNotification", AVFoundation, [MacOS, IOS];
+    AVCaptureSessionRuntimeError => "AVCaptureSessionRuntimeErrorNotification", AVFoundation, [MacOS, IOS];
+    AVFragmentedMovieContainsMovieFragmentsDidChange => "AVFragmentedMovieContainsMovieFragmentsDidChangeNotification", AVFoundation, [MacOS];
+    AVFragmentedMovieDurationDidChange => "AVFragmentedMovieDurationDidChangeNotification", AVFoundation, [MacOS];
+    AVFragmentedMovieTrackSegmentsDidChange => "AVFragmentedMovieTrackSegmentsDidChangeNotification", AVFoundation, [MacOS];
+    AVFragmentedMovieTrackTimeRangeDidChange => "AVFragmentedMovieTrackTimeRangeDidChangeNotification", AVFoundation, [MacOS];
+    AVFragmentedMovieTrackTotalSampleDataLengthDidChange => "AVFragmentedMovieTrackTotalSampleDataLengthDidChangeNotification", AVFoundation, [MacOS];
+    AVFragmentedMovieWasDefragmented => "AVFragmentedMovieWasDefragmentedNotification", AVFoundation, [MacOS];
+    AVPlayerItemDidPlayToEndTime => "AVPlayerItemDidPlayToEndTimeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVPlayerItemFailedToPlayToEndTime => "AVPlayerItemFailedToPlayToEndTimeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVPlayerItemNewAccessLogEntry => "AVPlayerItemNewAccessLogEntryNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVPlayerItemNewErrorLogEntry => "AVPlayerItemNewErrorLogEntryNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVPlayerItemPlaybackStalled => "AVPlayerItemPlaybackStalledNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVPlayerItemTimeJumped => "AVPlayerItemTimeJumpedNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVSampleBufferDisplayLayerFailedToDecode => "AVSampleBufferDisplayLayerFailedToDecodeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    CWBSSIDDidChange => "CWBSSIDDidChangeNotification", CoreWLAN, [MacOS];
+    CWCountryCodeDidChange => "CWCountryCodeDidChangeNotification", CoreWLAN, [MacOS];
+    CWLinkDidChange => "CWLinkDidChangeNotification", CoreWLAN, [MacOS];
+    CWLinkQualityDidChange => "CWLinkQualityDidChangeNotification", CoreWLAN, [MacOS];
+    CWModeDidChange => "CWModeDidChangeNotification", CoreWLAN, [MacOS];
+    CWPowerDidChange => "CWPowerDidChangeNotification", CoreWLAN, [MacOS];
+    CWSSIDDidChange => "CWSSIDDidChangeNotification", CoreWLAN, [MacOS];
+    CWScanCacheDidUpdate => "CWScanCacheDidUpdateNotification", CoreWLAN, [MacOS];
+    GCControllerDidConnect => "GCControllerDidConnectNotification", GameController, [MacOS, IOS, TvOS];
+    GCControllerDidDisconnect => "GCControllerDidDisconnectNotification", GameController, [MacOS, IOS, TvOS];
+    IKFilterBrowserFilterDoubleClick => "IKFilterBrowserFilterDoubleClickNotification", Quartz, [MacOS];
+    IKFilterBrowserFilterSelected => "IKFilterBrowserFilterSelectedNotification", Quartz, [MacOS];
+    IKFilterBrowserWillPreviewFilter => "IKFilterBrowserWillPreviewFilterNotification", Quartz, [MacOS];
+    IOBluetoothHostControllerPoweredOff => "IOBluetoothHostControllerPoweredOffNotification", IOBluetooth, [MacOS];
+    IOBluetoothHostControllerPoweredOn => "IOBluetoothHostControllerPoweredOnNotification", IOBluetooth, [MacOS];
+    IOBluetoothL2CAPChannelPublished => "IOBluetoothL2CAPChannelPublishedNotification", IOBluetooth, [MacOS];
+    IOBluetoothL2CAPChannelTerminated => "IOBluetoothL2CAPChannelTerminatedNotification", IOBluetooth, [MacOS];
+    MKAnnotationCalloutInfoDidChange => "MKAnnotationCalloutInfoDidChangeNotification", MapKit, [MacOS, IOS, TvOS];
+    NEFilterConfigurationDidChange => "NEFilterConfigurationDidChangeNotification", NetworkExtension, [MacOS, IOS];
+    NEVPNConfigurationChange => "NEVPNConfigurationChangeNotification", NetworkExtension, [MacOS, IOS];
+    NEVPNStatusDidChange => "NEVPNStatusDidChangeNotification", NetworkExtension, [MacOS, IOS];
+    NSAccessibilityAnnouncementRequested => "AXAnnouncementRequested", AppKit, [MacOS];
+    NSAccessibilityAnnouncementKey => "AXAnnouncementKey", AppKit, [MacOS];
+    NSAccessibilityPriorityKey => "AXPriorityKey", AppKit, [MacOS];
+    NSAccessibilityApplicationActivated => "AXApplicationActivated", AppKit, [MacOS];
+    NSAccessibilityApplicationDeactivated => "AXApplicationDeactivated", AppKit, [MacOS];
+    NSAccessibilityApplicationHidden => "AXApplicationHidden", AppKit, [MacOS];
+    NSAccessibilityApplicationShown => "AXApplicationShown", AppKit, [MacOS];
+    NSAccessibilityCreated => "AXCreated", AppKit, [MacOS];
+    NSAccessibilityDrawerCreated => "AXDrawerCreated", AppKit, [MacOS];
+    NSAccessibilityFocusedUIElementChanged => "AXFocusedUIElementChanged", AppKit, [MacOS];
+    NSAccessibilityFocusedWindowChanged => "AXFocusedWindowChanged", AppKit, [MacOS];
+    NSAccessibilityHelpTagCreated => "AXHelpTagCreated", AppKit, [MacOS];
+    NSAccessibilityLayoutChanged => "AXLayoutChanged", AppKit, [MacOS];
+    NSAccessibilityUIElementsKey => "AXUIElementsKey", AppKit, [MacOS];
+    NSAccessibilityMainWindowChanged => "AXMainWindowChanged", AppKit, [MacOS];
+    NSAccessibilityMoved => "AXMoved", AppKit, [MacOS];
+    NSAccessibilityResized => "AXResized", AppKit, [MacOS];
+    NSAccessibilityRowCollapsed => "AXRowCollapsed", AppKit, [MacOS];
+    NSAccessibilityRowCountChanged => "AXRowCountChanged", AppKit, [MacOS];
+    NSAccessibilityRowExpanded => "AXRowExpanded", AppKit, [MacOS];
+    NSAccessibilitySelectedCellsChanged => "AXSelectedCellsChanged", AppKit, [MacOS];
+    NSAccessibilitySelectedChildrenChanged => "AXSelectedChildrenChanged", AppKit, [MacOS];
+    NSAccessibilitySelectedChildrenMoved => "AXSelectedChildrenMoved", AppKit, [MacOS];
+    NSAccessibilitySelectedColumnsChanged => "AXSelectedColumnsChanged", AppKit, [MacOS];
+    NSAccessibilitySelectedRowsChanged => "AXSelectedRowsChanged", AppKit, [MacOS];
+    NSAccessibilitySelectedTextChanged => "AXSelectedTextChanged", AppKit, [MacOS];
+    NSAccessibilitySheetCreated => "AXSheetCreated", AppKit, [MacOS];
+    NSAccessibilityTitleChanged => "AXTitleChanged", AppKit, [MacOS];
+    NSAccessibilityUIElementDestroyed => "AXUIElementDestroyed", AppKit, [MacOS];
+    NSAccessibilityUnitsChanged => "AXUnitsChanged", AppKit, [MacOS];
+    NSAccessibilityValueChanged => "AXValueChanged", AppKit, [MacOS];
+    NSAccessibilityWindowCreated => "AXWindowCreated", AppKit, [MacOS];
+    NSAccessibilityWindowDeminiaturized => "AXWindowDeminiaturized", AppKit, [MacOS];
+    NSAccessibilityWindowMiniaturized => "AXWindowMiniaturized", AppKit, [MacOS];
+    NSAccessibilityWindowMoved => "AXWindowMoved", AppKit, [MacOS];
+    NSAccessibilityWindowResized => "AXWindowResized", AppKit, [MacOS];
+    NSAnimationProgressMark => "NSAnimationProgressMarkNotification", AppKit, [MacOS];
+    NSAntialiasThresholdChanged => "NSAntialiasThresholdChangedNotification", AppKit, [MacOS];
+    NSAppleEventManagerWillProcessFirstEvent => "NSAppleEventManagerWillProcessFirstEventNotification", Foundation, [MacOS];
+    NSApplicationDidBecomeActive => "NSApplicationDidBecomeActiveNotification", AppKit, [MacOS];
+    NSApplicationDidChangeOcclusionState => "NSApplicationDidChangeOcclusionStateNotification", AppKit, [MacOS];
+    NSApplicationDidChangeScreenParameters => "NSApplicationDidChangeScreenParametersNotification", AppKit, [MacOS];
+    NSApplicationDidFinishLaunching => "NSApplicationDidFinishLaunchingNotification", AppKit, [MacOS];
+    NSApplicationDidFinishRestoringWindows => "NSApplicationDidFinishRestoringWindowsNotification", AppKit, [MacOS];
+    NSApplicationDidHide => "NSApplicationDidHideNotification", AppKit, [MacOS];
+    NSApplicationDidResignActive => "NSApplicationDidResignActiveNotification", AppKit, [MacOS];
+    NSApplicationDidUnhide => "NSApplicationDidUnhideNotification", AppKit, [MacOS];
+    NSApplicationDidUpdate => "NSApplicationDidUpdateNotification", AppKit, [MacOS];
+    NSApplicationWillBecomeActive => "NSApplicationWillBecomeActiveNotification", AppKit, [MacOS];
+    NSApplicationWillFinishLaunching => "NSApplicationWillFinishLaunchingNotification", AppKit, [MacOS];
+    NSApplicationWillHide => "NSApplicationWillHideNotification", AppKit, [MacOS];
+    NSApplicationWillResignActive => "NSApplicationWillResignActiveNotification", AppKit, [MacOS];
+    NSApplicationWillTerminate => "NSApplicationWillTerminateNotification", AppKit, [MacOS];
+    NSApplicationWillUnhide => "NSApplicationWillUnhideNotification", AppKit, [MacOS];
+    NSApplicationWillUpdate => "NSApplicationWillUpdateNotification", AppKit, [MacOS];
+    NSBrowserColumnConfigurationDidChange => "NSBrowserColumnConfigurationDidChangeNotification", AppKit, [MacOS];
+    NSClassDescriptionNeededForClass => "NSClassDescriptionNeededForClassNotification", Foundation, [MacOS];
+    NSColorListDidChange => "NSColorListDidChangeNotification", AppKit, [MacOS];
+    NSColorPanelColorDidChange => "NSColorPanelColorDidChangeNotification", AppKit, [MacOS];
+    NSColorPanel => "NSColorPanelColorDidChangeNotification", AppKit, [MacOS];
+    NSComboBoxSelectionDidChange => "NSComboBoxSelectionDidChangeNotification", AppKit, [MacOS];
+    NSComboBoxSelectionIsChanging => "NSComboBoxSelectionIsChangingNotification", AppKit, [MacOS];
+    NSComboBoxWillDismiss => "NSComboBoxWillDismissNotification", AppKit, [MacOS];
+    NSComboBoxWillPopUp => "NSComboBoxWillPopUpNotification", AppKit, [MacOS];
+    NSContextHelpModeDidActivate => "NSContextHelpModeDidActivateNotification", AppKit, [MacOS];
+    NSContextHelpModeDidDeactivate => "NSContextHelpModeDidDeactivateNotification", AppKit, [MacOS];
+    NSControlTextDidBeginEditing => "NSControlTextDidBeginEditingNotification", AppKit, [MacOS];
+    NSControlTextDidChange => "NSControlTextDidChangeNotification", AppKit, [MacOS];
+    NSControlTextDidEndEditing => "NSControlTextDidEndEditingNotification", AppKit, [MacOS];
+    NSControlTintDidChange => "NSControlTintDidChangeNotification", AppKit, [MacOS];
+    NSDrawerDidClose => "NSDrawerDidCloseNotification", AppKit, [MacOS];
+    NSDrawerDidOpen => "NSDrawerDidOpenNotification", AppKit, [MacOS];
+    NSDrawerWillClose => "NSDrawerWillCloseNotification", AppKit, [MacOS];
+    NSDrawerWillOpen => "NSDrawerWillOpenNotification", AppKit, [MacOS];
+    NSFontCollectionDidChange => "NSFontCollectionDidChangeNotification", AppKit, [MacOS];
+    NSFontSetChanged => "NSFontSetChangedNotification", AppKit, [MacOS];
+    NSImageRepRegistryDidChange => "NSImageRepRegistryDidChangeNotification", AppKit, [MacOS];
+    NSMenuDidAddItem => "NSMenuDidAddItemNotification", AppKit, [MacOS];
+    NSMenuDidBeginTracking => "NSMenuDidBeginTrackingNotification", AppKit, [MacOS];
+    NSMenuDidChangeItem => "NSMenuDidChangeItemNotification", AppKit, [MacOS];
+    NSMenuDidEndTracking => "NSMenuDidEndTrackingNotification", AppKit, [MacOS];
+    NSMenuDidRemoveItem => "NSMenuDidRemoveItemNotification", AppKit, [MacOS];
+    NSMenuDidSendAction => "NSMenuDidSendActionNotification", AppKit, [MacOS];
+    NSMenuWillSendAction => "NSMenuWillSendActionNotification", AppKit, [MacOS];
+    NSOutlineViewColumnDidMove => "NSOutlineViewColumnDidMoveNotification", AppKit, [MacOS];
+    NSOutlineViewColumnDidResize => "NSOutlineViewColumnDidResizeNotification", AppKit, [MacOS];
+    NSOutlineViewItemDidCollapse => "NSOutlineViewItemDidCollapseNotification", AppKit, [MacOS];
+    NSOutlineViewItemDidExpand => "NSOutlineViewItemDidExpandNotification", AppKit, [MacOS];
+    NSOutlineViewItemWillCollapse => "NSOutlineViewItemWillCollapseNotification", AppKit, [MacOS];
+    NSOutlineViewItemWillExpand => "NSOutlineViewItemWillExpandNotification", AppKit, [MacOS];
+    NSOutlineViewSelectionDidChange => "NSOutlineViewSelectionDidChangeNotification", AppKit, [MacOS];
+    NSOutlineViewSelectionIsChanging => "NSOutlineViewSelectionIsChangingNotification", AppKit, [MacOS];
+    NSPersistentStoreDidImportUbiquitousContentChanges => "com.apple.coredata.ubiquity.importer.didfinishimport", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    NSPopUpButtonCellWillPopUp => "NSPopUpButtonCellWillPopUpNotification", AppKit, [MacOS];
+    NSPopUpButtonWillPopUp => "NSPopUpButtonWillPopUpNotification", AppKit, [MacOS];
+    NSPopoverDidClose => "NSPopoverDidCloseNotification", AppKit, [MacOS];
+    NSPopoverDidShow => "NSPopoverDidShowNotification", AppKit, [MacOS];
+    NSPopoverWillClose => "NSPopoverWillCloseNotification", AppKit, [MacOS];
+    NSPopoverWillShow => "NSPopoverWillShowNotification", AppKit, [MacOS];
+    NSPreferencePaneCancelUnselect => "NSPreferencePaneCancelUnselectNotification", PreferencePanes, [MacOS];
+    NSPreferencePaneDoUnselect => "NSPreferencePaneDoUnselectNotification", PreferencePanes, [MacOS];
+    NSPreferencePaneSwitchToPane => "NSPreferencePaneSwitchToPaneNotification", PreferencePanes, [MacOS];
+    NSPreferencePaneUpdateHelpMenu => "NSPreferencePaneUpdateHelpMenuNotification", PreferencePanes, [MacOS];
+    NSPreferencePrefPaneIsAvailable => "NSPreferencePrefPaneIsAvailableNotification", PreferencePanes, [MacOS];
+    NSPreferredScrollerStyleDidChange => "NSPreferredScrollerStyleDidChangeNotification", AppKit, [MacOS];
+    NSRuleEditorRowsDidChange => "NSRuleEditorRowsDidChangeNotification", AppKit, [MacOS];
+    NSScreenColorSpaceDidChange => "NSScreenColorSpaceDidChangeNotification", AppKit, [MacOS];
+    NSScrollViewDidEndLiveMagnify => "NSScrollViewDidEndLiveMagnifyNotification", AppKit, [MacOS];
+    NSScrollViewDidEndLiveScroll => "NSScrollViewDidEndLiveScrollNotification", AppKit, [MacOS];
+    NSScrollViewDidLiveScroll => "NSScrollViewDidLiveScrollNotification", AppKit, [MacOS];
+    NSScrollViewWillStartLiveMagnify => "NSScrollViewWillStartLiveMagnifyNotification", AppKit, [MacOS];
+    NSScrollViewWillStartLiveScroll => "NSScrollViewWillStartLiveScrollNotification", AppKit, [MacOS];
+    NSSpellCheckerDidChangeAutomaticCapitalization => "NSSpellCheckerDidChangeAutomaticCapitalizationNotification", AppKit, [MacOS];
+    NSSpellCheckerDidChangeAutomaticDashSubstitution => "NSSpellCheckerDidChangeAutomaticDashSubstitutionNotification", AppKit, [MacOS];
+    NSSpellCheckerDidChangeAutomaticPeriodSubstitution => "NSSpellCheckerDidChangeAutomaticPeriodSubstitutionNotification", AppKit, [MacOS];
+    NSSpellCheckerDidChangeAutomaticQuoteSubstitution => "NSSpellCheckerDidChangeAutomaticQuoteSubstitutionNotification", AppKit, [MacOS];
+    NSSpellCheckerDidChangeAutomaticSpellingCorrection => "NSSpellCheckerDidChangeAutomaticSpellingCorrectionNotification", AppKit, [MacOS];
+    NSSpellCheckerDidChangeAutomaticTextReplacement => "NSSpellCheckerDidChangeAutomaticTextReplacementNotification", AppKit, [MacOS];
+    NSSplitViewDidResizeSubviews => "NSSplitViewDidResizeSubviewsNotification", AppKit, [MacOS];
+    NSSplitViewWillResizeSubviews => "NSSplitViewWillResizeSubviewsNotification", AppKit, [MacOS];
+    NSSystemColorsDidChange => "NSSystemColorsDidChangeNotification", AppKit, [MacOS];
+    NSTableViewColumnDidMove => "NSTableViewColumnDidMoveNotification", AppKit, [MacOS];
+    NSTableViewColumnDidResize => "NSTableViewColumnDidResizeNotification", AppKit, [MacOS];
+    NSTableViewSelectionDidChange => "NSTableViewSelectionDidChangeNotification", AppKit, [MacOS];
+    NSTableViewSelectionIsChanging => "NSTableViewSelectionIsChangingNotification", AppKit, [MacOS];
+    NSTextAlternativesSelectedAlternativeString => "NSTextAlternativesSelectedAlternativeStringNotification", AppKit, [MacOS];
+    NSTextDidBeginEditing => "NSTextDidBeginEditingNotification", AppKit, [MacOS];
+    NSTextDidChange => "NSTextDidChangeNotification", AppKit, [MacOS];
+    NSTextDidEndEditing => "NSTextDidEndEditingNotification", AppKit, [MacOS];
+    NSTextInputContextKeyboardSelectionDidChange => "NSTextInputContextKeyboardSelectionDidChangeNotification", AppKit, [MacOS];
+    NSTextStorageDidProcessEditing => "NSTextStorageDidProcessEditingNotification", AppKit, [MacOS];
+    NSTextStorageWillProcessEditing => "NSTextStorageWillProcessEditingNotification", AppKit, [MacOS];
+    NSTextViewDidChangeSelection => "NSTextViewDidChangeSelectionNotification", AppKit, [MacOS];
+    NSTextViewDidChangeTypingAttributes => "NSTextViewDidChangeTypingAttributesNotification", AppKit, [MacOS];
+    NSTextViewWillChangeNotifyingTextView => "NSTextViewWillChangeNotifyingTextViewNotification", AppKit, [MacOS];
+    NSToolbarDidRemoveItem => "NSToolbarDidRemoveItemNotification", AppKit, [MacOS];
+    NSToolbarWillAddItem => "NSToolbarWillAddItemNotification", AppKit, [MacOS];
+    NSViewBoundsDidChange => "NSViewBoundsDidChangeNotification", AppKit, [MacOS];
+    NSViewDidUpdateTrackingAreas => "NSViewDidUpdateTrackingAreasNotification", AppKit, [MacOS];
+    NSViewFocusDidChange => "NSViewFocusDidChangeNotification", AppKit, [MacOS];
+    NSViewFrameDidChange => "NSViewFrameDidChangeNotification", AppKit, [MacOS];
+    NSViewGlobalFrameDidChange => "NSViewGlobalFrameDidChangeNotification", AppKit, [MacOS];
+    NSWindowDidBecomeKey => "NSWindowDidBecomeKeyNotification", AppKit, [MacOS];
+    NSWindowDidBecomeMain => "NSWindowDidBecomeMainNotification", AppKit, [MacOS];
+    NSWindowDidChangeBackingProperties => "NSWindowDidChangeBackingPropertiesNotification", AppKit, [MacOS];
+    NSWindowDidChangeOcclusionState => "NSWindowDidChangeOcclusionStateNotification", AppKit, [MacOS];
+    NSWindowDidChangeScreen => "NSWindowDidChangeScreenNotification", AppKit, [MacOS];
+    NSWindowDidChangeScreenProfile => "NSWindowDidChangeScreenProfileNotification", AppKit, [MacOS];
+    NSWindowDidDeminiaturize => "NSWindowDidDeminiaturizeNotification", AppKit, [MacOS];
+    NSWindowDidEndLiveResize => "NSWindowDidEndLiveResizeNotification", AppKit, [MacOS];
+    NSWindowDidEndSheet => "NSWindowDidEndSheetNotification", AppKit, [MacOS];
+    NSWindowDidEnterFullScreen => "NSWindowDidEnterFullScreenNotification", AppKit, [MacOS];
+    NSWindowDidEnterVersionBrowser => "NSWindowDidEnterVersionBrowserNotification", AppKit, [MacOS];
+    NSWindowDidExitFullScreen => "NSWindowDidExitFullScreenNotification", AppKit, [MacOS];
+    NSWindowDidExitVersionBrowser => "NSWindowDidExitVersionBrowserNotification", AppKit, [MacOS];
+    NSWindowDidExpose => "NSWindowDidExposeNotification", AppKit, [MacOS];
+    NSWindowDidMiniaturize => "NSWindowDidMiniaturizeNotification", AppKit, [MacOS];
+    NSWindowDidMove => "NSWindowDidMoveNotification", AppKit, [MacOS];
+    NSWindowDidResignKey => "NSWindowDidResignKeyNotification", AppKit, [MacOS];
+    NSWindowDidResignMain => "NSWindowDidResignMainNotification", AppKit, [MacOS];
+    NSWindowDidResize => "NSWindowDidResizeNotification", AppKit, [MacOS];
+    NSWindowDidUpdate => "NSWindowDidUpdateNotification", AppKit, [MacOS];
+    NSWindowWillBeginSheet => "NSWindowWillBeginSheetNotification", AppKit, [MacOS];
+    NSWindowWillClose => "NSWindowWillCloseNotification", AppKit, [MacOS];
+    NSWindowWillEnterFullScreen => "NSWindowWillEnterFullScreenNotification", AppKit, [MacOS];
+    NSWindowWillEnterVersionBrowser => "NSWindowWillEnterVersionBrowserNotification", AppKit, [MacOS];
+    NSWindowWillExitFullScreen => "NSWindowWillExitFullScreenNotification", AppKit, [MacOS];
+    NSWindowWillExitVersionBrowser => "NSWindowWillExitVersionBrowserNotification", AppKit, [MacOS];
+    NSWindowWillMiniaturize => "NSWindowWillMiniaturizeNotification", AppKit, [MacOS];
+    NSWindowWillMove => "NSWindowWillMoveNotification", AppKit, [MacOS];
+    NSWindowWillStartLiveResize => "NSWindowWillStartLiveResizeNotification", AppKit, [MacOS];
+    NSWorkspaceAccessibilityDisplayOptionsDidChange => "NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification", AppKit, [MacOS];
+    NSWorkspaceActiveSpaceDidChange => "NSWorkspaceActiveSpaceDidChangeNotification", AppKit, [MacOS];
+    NSWorkspaceDidActivateApplication => "NSWorkspaceDidActivateApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceDidChangeFileLabels => "NSWorkspaceDidChangeFileLabelsNotification", AppKit, [MacOS];
+    NSWorkspaceDidDeactivateApplication => "NSWorkspaceDidDeactivateApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceDidHideApplication => "NSWorkspaceDidHideApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceDidLaunchApplication => "NSWorkspaceDidLaunchApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceDidMount => "NSWorkspaceDidMountNotification", AppKit, [MacOS];
+    NSWorkspaceDidPerformFileOperation => "NSWorkspaceDidPerformFileOperationNotification", AppKit, [MacOS];
+    NSWorkspaceDidRenameVolume => "NSWorkspaceDidRenameVolumeNotification", AppKit, [MacOS];
+    NSWorkspaceDidTerminateApplication => "NSWorkspaceDidTerminateApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceDidUnhideApplication => "NSWorkspaceDidUnhideApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceDidUnmount => "NSWorkspaceDidUnmountNotification", AppKit, [MacOS];
+    NSWorkspaceDidWake => "NSWorkspaceDidWakeNotification", AppKit, [MacOS];
+    NSWorkspaceScreensDidSleep => "NSWorkspaceScreensDidSleepNotification", AppKit, [MacOS];
+    NSWorkspaceScreensDidWake => "NSWorkspaceScreensDidWakeNotification", AppKit, [MacOS];
+    NSWorkspaceSessionDidBecomeActive => "NSWorkspaceSessionDidBecomeActiveNotification", AppKit, [MacOS];
+    NSWorkspaceSessionDidResignActive => "NSWorkspaceSessionDidResignActiveNotification", AppKit, [MacOS];
+    NSWorkspaceWillLaunchApplication => "NSWorkspaceWillLaunchApplicationNotification", AppKit, [MacOS];
+    NSWorkspaceWillPowerOff => "NSWorkspaceWillPowerOffNotification", AppKit, [MacOS];
+    NSWorkspaceWillSleep => "NSWorkspaceWillSleepNotification", AppKit, [MacOS];
+    NSWorkspaceWillUnmount => "NSWorkspaceWillUnmountNotification", AppKit, [MacOS];
+    PDFDocumentDidBeginFind => "PDFDocumentDidBeginFindNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidBeginPageFind => "PDFDocumentDidBeginPageFindNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidBeginPageWrite => "PDFDocumentDidBeginPageWriteNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidBeginWrite => "PDFDocumentDidBeginWriteNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidEndFind => "PDFDocumentDidEndFindNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidEndPageFind => "PDFDocumentDidEndPageFindNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidEndPageWrite => "PDFDocumentDidEndPageWriteNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidEndWrite => "PDFDocumentDidEndWriteNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidFindMatch => "PDFDocumentDidFindMatchNotification", PDFKit, [MacOS, IOS];
+    PDFDocumentDidUnlock => "PDFDocumentDidUnlockNotification", PDFKit, [MacOS, IOS];
+    PDFThumbnailViewDocumentEdited => "PDFThumbnailViewDocumentEditedNotification", PDFKit, [MacOS, IOS];
+    PDFViewAnnotationHit => "PDFViewAnnotationHitNotification", PDFKit, [MacOS, IOS];
+    PDFViewAnnotationWillHit => "PDFViewAnnotationWillHitNotification", PDFKit, [MacOS, IOS];
+    PDFViewChangedHistory => "PDFViewChangedHistoryNotification", PDFKit, [MacOS, IOS];
+    PDFViewCopyPermission => "PDFViewCopyPermissionNotification", PDFKit, [MacOS, IOS];
+    PDFViewDisplayBoxChanged => "PDFViewDisplayBoxChangedNotification", PDFKit, [MacOS, IOS];
+    PDFViewDisplayModeChanged => "PDFViewDisplayModeChangedNotification", PDFKit, [MacOS, IOS];
+    PDFViewDocumentChanged => "PDFViewDocumentChangedNotification", PDFKit, [MacOS, IOS];
+    PDFViewPageChanged => "PDFViewPageChangedNotification", PDFKit, [MacOS, IOS];
+    PDFViewPrintPermission => "PDFViewPrintPermissionNotification", PDFKit, [MacOS, IOS];
+    PDFViewScaleChanged => "PDFViewScaleChangedNotification", PDFKit, [MacOS, IOS];
+    PDFViewSelectionChanged => "PDFViewSelectionChangedNotification", PDFKit, [MacOS, IOS];
+    PDFViewVisiblePagesChanged => "PDFViewVisiblePagesChangedNotification", PDFKit, [MacOS, IOS];
+    KABDatabaseChanged => "kABDatabaseChangedNotification", AddressBook, [MacOS];
+    KABDatabaseChangedExternally => "kABDatabaseChangedExternallyNotification", AddressBook, [MacOS];
+    KQuartzFilterManagerDidAddFilter => "kQuartzFilterManagerDidAddFilterNotification", Quartz, [MacOS];
+    KQuartzFilterManagerDidModifyFilter => "kQuartzFilterManagerDidModifyFilterNotification", Quartz, [MacOS];
+    KQuartzFilterManagerDidRemoveFilter => "kQuartzFilterManagerDidRemoveFilterNotification", Quartz, [MacOS];
+    KQuartzFilterManagerDidSelectFilter => "kQuartzFilterManagerDidSelectFilterNotification", Quartz, [MacOS];
+    EAAccessoryDidConnect => "EAAccessoryDidConnectNotification", ExternalAccessory, [IOS];
+    EAAccessoryDidDisconnect => "EAAccessoryDidDisconnectNotification", ExternalAccessory, [IOS];
+    SKCloudServiceCapabilitiesDidChange => "SKCloudServiceCapabilitiesDidChangeNotification", StoreKit, [IOS, TvOS];
+    SKStorefrontIdentifierDidChange => "SKStorefrontIdentifierDidChangeNotification", StoreKit, [IOS];
+    UIAccessibilityAssistiveTouchStatusDidChange => "UIAccessibilityAssistiveTouchStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityBoldTextStatusDidChange => "UIAccessibilityBoldTextStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityClosedCaptioningStatusDidChange => "UIAccessibilityClosedCaptioningStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityDarkerSystemColorsStatusDidChange => "UIAccessibilityDarkerSystemColorsStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityGrayscaleStatusDidChange => "UIAccessibilityGrayscaleStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityGuidedAccessStatusDidChange => "UIAccessibilityGuidedAccessStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityHearingDevicePairedEarDidChange => "UIAccessibilityHearingDevicePairedEarDidChangeNotification", UIKit, [IOS];
+    UIAccessibilityInvertColorsStatusDidChange => "UIAccessibilityInvertColorsStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityMonoAudioStatusDidChange => "UIAccessibilityMonoAudioStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityReduceMotionStatusDidChange => "UIAccessibilityReduceMotionStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityReduceTransparencyStatusDidChange => "UIAccessibilityReduceTransparencyStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilityShakeToUndoDidChange => "UIAccessibilityShakeToUndoDidChangeNotification", UIKit, [IOS];
+    UIAccessibilitySpeakScreenStatusDidChange => "UIAccessibilitySpeakScreenStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilitySpeakSelectionStatusDidChange => "UIAccessibilitySpeakSelectionStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIAccessibilitySwitchControlStatusDidChange => "UIAccessibilitySwitchControlStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIApplicationDidBecomeActive => "UIApplicationDidBecomeActiveNotification", UIKit, [IOS, TvOS];
+    UIApplicationDidEnterBackground => "UIApplicationDidEnterBackgroundNotification", UIKit, [IOS, TvOS];
+    UIApplicationDidFinishLaunching => "UIApplicationDidFinishLaunchingNotification", UIKit, [IOS, TvOS];
+    UIApplicationDidReceiveMemoryWarning => "UIApplicationDidReceiveMemoryWarningNotification", UIKit, [IOS, TvOS];
+    UIApplicationSignificantTimeChange => "UIApplicationSignificantTimeChangeNotification", UIKit, [IOS, TvOS];
+    UIApplicationUserDidTakeScreenshot => "UIApplicationUserDidTakeScreenshotNotification", UIKit, [IOS];
+    UIApplicationWillEnterForeground => "UIApplicationWillEnterForegroundNotification", UIKit, [IOS, TvOS];
+    UIApplicationWillResignActive => "UIApplicationWillResignActiveNotification", UIKit, [IOS, TvOS];
+    UIApplicationWillTerminate => "UIApplicationWillTerminateNotification", UIKit, [IOS, TvOS];
+    UIContentSizeCategoryDidChange => "UIContentSizeCategoryDidChangeNotification", UIKit, [IOS, TvOS];
+    UIDeviceProximityStateDidChange => "UIDeviceProximityStateDidChangeNotification", UIKit, [IOS];
+    UIScreenBrightnessDidChange => "UIScreenBrightnessDidChangeNotification", UIKit, [IOS];
+    UIScreenDidConnect => "UIScreenDidConnectNotification", UIKit, [IOS, TvOS];
+    UIScreenDidDisconnect => "UIScreenDidDisconnectNotification", UIKit, [IOS, TvOS];
+    UIScreenModeDidChange => "UIScreenModeDidChangeNotification", UIKit, [IOS, TvOS];
+    UITableViewSelectionDidChange => "UITableViewSelectionDidChangeNotification", UIKit, [IOS, TvOS];
+    UITextFieldTextDidBeginEditing => "UITextFieldTextDidBeginEditingNotification", UIKit, [IOS, TvOS];
+    UITextFieldTextDidChange => "UITextFieldTextDidChangeNotification", UIKit, [IOS, TvOS];
+    UITextFieldTextDidEndEditing => "UITextFieldTextDidEndEditingNotification", UIKit, [IOS, TvOS];
+    UITextInputCurrentInputModeDidChange => "UITextInputCurrentInputModeDidChangeNotification", UIKit, [IOS, TvOS];
+    UITextViewTextDidBeginEditing => "UITextViewTextDidBeginEditingNotification", UIKit, [IOS, TvOS];
+    UITextViewTextDidChange => "UITextViewTextDidChangeNotification", UIKit, [IOS, TvOS];
+    UITextViewTextDidEndEditing => "UITextViewTextDidEndEditingNotification", UIKit, [IOS, TvOS];
+    UIViewControllerShowDetailTargetDidChange => "UIViewControllerShowDetailTargetDidChangeNotification", UIKit, [IOS, TvOS];
+    UIWindowDidBecomeHidden => "UIWindowDidBecomeHiddenNotification", UIKit, [IOS, TvOS];
+    UIWindowDidBecomeKey => "UIWindowDidBecomeKeyNotification", UIKit, [IOS, TvOS];
+    UIWindowDidBecomeVisible => "UIWindowDidBecomeVisibleNotification", UIKit, [IOS, TvOS];
+    UIWindowDidResignKey => "UIWindowDidResignKeyNotification", UIKit, [IOS, TvOS];
+    AVCaptureDeviceSubjectAreaDidChange => "AVCaptureDeviceSubjectAreaDidChangeNotification", AVFoundation, [IOS];
+    AVCaptureSessionInterruptionEnded => "AVCaptureSessionInterruptionEndedNotification", AVFoundation, [IOS];
+    AVCaptureSessionWasInterrupted => "AVCaptureSessionWasInterruptedNotification", AVFoundation, [IOS];
+    MFMessageComposeViewControllerTextMessageAvailabilityDidChange => "MFMessageComposeViewControllerTextMessageAvailabilityDidChangeNotification", MessageUI, [IOS];
+    MPMediaLibraryDidChange => "MPMediaLibraryDidChangeNotification", MediaPlayer, [IOS];
+    MPMusicPlayerControllerNowPlayingItemDidChange => "MPMusicPlayerControllerNowPlayingItemDidChangeNotification", MediaPlayer, [IOS];
+    MPMusicPlayerControllerPlaybackStateDidChange => "MPMusicPlayerControllerPlaybackStateDidChangeNotification", MediaPlayer, [IOS];
+    MPMusicPlayerControllerVolumeDidChange => "MPMusicPlayerControllerVolumeDidChangeNotification", MediaPlayer, [IOS];
+    UIApplicationBackgroundRefreshStatusDidChange => "UIApplicationBackgroundRefreshStatusDidChangeNotification", UIKit, [IOS, TvOS];
+    UIDeviceBatteryLevelDidChange => "UIDeviceBatteryLevelDidChangeNotification", UIKit, [IOS];
+    UIDeviceBatteryStateDidChange => "UIDeviceBatteryStateDidChangeNotification", UIKit, [IOS];
+    UIDeviceOrientationDidChange => "UIDeviceOrientationDidChangeNotification", UIKit, [IOS];
+    UIDocumentStateChanged => "UIDocumentStateChangedNotification", UIKit, [IOS];
+    UIKeyboardDidChangeFrame => "UIKeyboardDidChangeFrameNotification", UIKit, [IOS];
+    UIKeyboardDidHide => "UIKeyboardDidHideNotification", UIKit, [IOS];
+    UIKeyboardDidShow => "UIKeyboardDidShowNotification", UIKit, [IOS];
+    UIKeyboardWillChangeFrame => "UIKeyboardWillChangeFrameNotification", UIKit, [IOS];
+    UIKeyboardWillHide => "UIKeyboardWillHideNotification", UIKit, [IOS];
+    UIKeyboardWillShow => "UIKeyboardWillShowNotification", UIKit, [IOS];
+    UIMenuControllerDidHideMenu => "UIMenuControllerDidHideMenuNotification", UIKit, [IOS];
+    UIMenuControllerDidShowMenu => "UIMenuControllerDidShowMenuNotification", UIKit, [IOS];
+    UIMenuControllerMenuFrameDidChange => "UIMenuControllerMenuFrameDidChangeNotification", UIKit, [IOS];
+    UIMenuControllerWillHideMenu => "UIMenuControllerWillHideMenuNotification", UIKit, [IOS];
+    UIMenuControllerWillShowMenu => "UIMenuControllerWillShowMenuNotification", UIKit, [IOS];
+    UIPasteboardChanged => "UIPasteboardChangedNotification", UIKit, [IOS];
+    UIPasteboardRemoved => "UIPasteboardRemovedNotification", UIKit, [IOS];
+    UIApplicationProtectedDataDidBecomeAvailable => "UIApplicationProtectedDataDidBecomeAvailable", UIKit, [IOS];
+    UIApplicationProtectedDataWillBecomeUnavailable => "UIApplicationProtectedDataWillBecomeUnavailable", UIKit, [IOS];
+    NSSpellCheckerDidChangeAutomaticTextCompletion => "NSSpellCheckerDidChangeAutomaticTextCompletionNotification", AppKit, [MacOS];
+    MPMusicPlayerControllerQueueDidChange => "MPMusicPlayerControllerQueueDidChangeNotification", MediaPlayer, [IOS];
+    AVDisplayManagerModeSwitchEnd => "AVDisplayManagerModeSwitchEndNotification", AVKit, [TvOS];
+    AVDisplayManagerModeSwitchSettingsChanged => "AVDisplayManagerModeSwitchSettingsChangedNotification", AVKit, [TvOS];
+    AVDisplayManagerModeSwitchStart => "AVDisplayManagerModeSwitchStartNotification", AVKit, [TvOS];
+    AVPlayerAvailableHDRModesDidChange => "AVPlayerAvailableHDRModesDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVRouteDetectorMultipleRoutesDetectedDidChange => "AVRouteDetectorMultipleRoutesDetectedDidChangeNotification", AVFoundation, [MacOS, IOS, TvOS];
+    AVSampleBufferAudioRendererWasFlushedAutomatically => "AVSampleBufferAudioRendererWasFlushedAutomaticallyNotification", AVFoundation, [MacOS, IOS, TvOS];
+    CTServiceRadioAccessTechnologyDidChange => "CTServiceRadioAccessTechnologyDidChangeNotification", CoreTelephony, [IOS];
+    GKPlayerAuthenticationDidChangeNotificationName => "GKPlayerAuthenticationDidChangeNotificationName", GameKit, [MacOS, IOS, TvOS];
+    GKPlayerDidChangeNotificationName => "GKPlayerDidChangeNotificationName", GameKit, [MacOS, IOS, TvOS];
+    NEDNSProxyConfigurationDidChange => "NEDNSProxyConfigurationDidChangeNotification", NetworkExtension, [MacOS, IOS];
+    NSPersistentStoreRemoteChange => "NSPersistentStoreRemoteChangeNotification", CoreData, [MacOS, IOS, TvOS, WatchOS];
+    SKStorefrontCountryCodeDidChange => "SKStorefrontCountryCodeDidChangeNotification", StoreKit, [IOS];
+    WKAccessibilityReduceMotionStatusDidChange => "WKAccessibilityReduceMotionStatusDidChangeNotification", WatchKit, [WatchOS];
+}
+
+impl NotificationName {
+    /// Returns whether this name can be posted on `platform`.
+    pub fn is_available_on(&self, platform: Platform) -> bool {
+        self.platforms().contains(&platform)
+    }
+
+    /// Returns whether this is a name from the table, rather than `Unknown`.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, NotificationName::Unknown(_))
+    }
+}
+
+impl fmt::Display for NotificationName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for NotificationName {
+    type Err = Infallible;
+
+    /// Parses a notification string (e.g `NSWindowDidResizeNotification`). This never fails:
+    /// strings that aren't known come back as `Unknown`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(NotificationName::from(name))
+    }
+}
+
+impl From<&str> for NotificationName {
+    fn from(name: &str) -> Self {
+        match NAME_LOOKUP.get(name) {
+            Some(index) => NAMES[*index].clone(),
+            None => NotificationName::Unknown(name.to_string())
+        }
+    }
+}
+
+impl From<String> for NotificationName {
+    fn from(name: String) -> Self {
+        match NAME_LOOKUP.get(name.as_str()) {
+            Some(index) => NAMES[*index].clone(),
+            None => NotificationName::Unknown(name)
+        }
+    }
 }
 
 impl From<NotificationName> for NSString {
     fn from(name: NotificationName) -> Self {
-        match name {
-            _ => NSString::new("")
+        NSString::new(name.as_str())
+    }
+}
+
+impl From<&NotificationName> for NSString {
+    fn from(name: &NotificationName) -> Self {
+        NSString::new(name.as_str())
+    }
+}
+
+lazy_static! {
+    /// Maps strings back to their index in `NAMES`. Where two names share a string, the first
+    /// one wins.
+    static ref NAME_LOOKUP: HashMap<&'static str, usize> = {
+        let mut lookup = HashMap::with_capacity(NAMES.len());
+
+        for (index, name) in NAMES.iter().enumerate() {
+            lookup.entry(name.as_str()).or_insert(index);
+        }
+
+        lookup
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_name_round_trips_through_its_string() {
+        for name in NAMES {
+            let parsed: NotificationName = name.as_str().parse().unwrap();
+
+            match name {
+                NotificationName::NSColorPanel => {
+                    assert_eq!(parsed, NotificationName::NSColorPanelColorDidChange);
+                },
+
+                _ => { assert_eq!(&parsed, name, "{} didn't round-trip", name.as_str()); }
+            }
+
+            assert_eq!(parsed.as_str(), name.as_str());
+        }
+    }
+
+    #[test]
+    fn every_name_has_metadata() {
+        for name in NAMES {
+            assert!(name.is_known());
+            assert!(name.framework().is_some(), "{:?} has no framework", name);
+            assert!(!name.platforms().is_empty(), "{:?} has no platforms", name);
         }
     }
+
+    #[test]
+    fn strings_match_their_constants() {
+        assert_eq!(NotificationName::NSWindowDidResize.as_str(), "NSWindowDidResizeNotification");
+        assert_eq!(NotificationName::NSAccessibilityFocusedUIElementChanged.as_str(), "AXFocusedUIElementChanged");
+        assert_eq!(NotificationName::AudioSessionRouteChange.as_str(), "AVAudioSessionRouteChangeNotification");
+        assert_eq!(NotificationName::KABDatabaseChanged.as_str(), "kABDatabaseChangedNotification");
+        assert_eq!(NotificationName::NSManagedObjectContextDidSave.as_str(), "NSManagingContextDidSaveChangesNotification");
+    }
+
+    #[test]
+    fn unknown_strings_are_kept() {
+        let name: NotificationName = "com.example.SomethingHappened".parse().unwrap();
+
+        assert_eq!(name, NotificationName::Unknown("com.example.SomethingHappened".to_string()));
+        assert_eq!(name.as_str(), "com.example.SomethingHappened");
+        assert_eq!(name.framework(), None);
+        assert!(!name.is_available_on(Platform::MacOS));
+    }
+
+    #[test]
+    fn metadata_is_per_platform() {
+        assert_eq!(NotificationName::NSWindowDidResize.framework(), Some(Framework::AppKit));
+        assert!(NotificationName::NSWindowDidResize.is_available_on(Platform::MacOS));
+        assert!(!NotificationName::NSWindowDidResize.is_available_on(Platform::IOS));
+        assert_eq!(NotificationName::UIKeyboardWillShow.framework(), Some(Framework::UIKit));
+        assert!(NotificationName::UIKeyboardWillShow.is_available_on(Platform::IOS));
+    }
 }
@@ -0,0 +1,233 @@
+//! In-process routing of notifications to Rust handlers.
+//!
+//! This holds no Objective-C state: `NotificationCenter` uses it for custom notifications (which
+//! never leave Rust), and for everything when it's created with `NotificationCenter::in_process()`.
+
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::notification_center::{NotificationName, ObserverToken};
+
+/// What a handler is registered for.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Route {
+    /// A system (or otherwise string-named) notification, delivered as a `Notification`.
+    Named(NotificationName),
+
+    /// A custom notification, delivered as the type itself. The name is only kept for debugging.
+    Custom(TypeId, &'static str)
+}
+
+type Handler = dyn Fn(&dyn Any) + Send + Sync + 'static;
+
+#[derive(Default)]
+struct Handlers {
+    next_id: usize,
+    entries: Vec<(usize, Route, Arc<Handler>)>
+}
+
+/// Tracks handlers by route, and calls them when something is posted.
+///
+/// Handlers are called synchronously, on the posting thread, in the order they were added. The
+/// router isn't locked while they run, so handlers can post, observe, and drop tokens freely.
+#[derive(Default)]
+pub(crate) struct Router(Arc<Mutex<Handlers>>);
+
+impl Router {
+    /// Returns a new router with no handlers.
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    /// Registers `handler` for payloads of type `T` posted to `route`. Payloads of any other type
+    /// are ignored, so a mismatched route can't cause a bad cast.
+    pub fn add<T, F>(&self, route: Route, handler: F) -> ObserverToken
+    where
+        T: Any,
+        F: Fn(&T) + Send + Sync + 'static
+    {
+        let handler: Arc<Handler> = Arc::new(move |payload: &dyn Any| {
+            if let Some(payload) = payload.downcast_ref::<T>() {
+                handler(payload);
+            }
+        });
+
+        let id = {
+            let mut handlers = self.0.lock().unwrap();
+            let id = handlers.next_id;
+            handlers.next_id += 1;
+            handlers.entries.push((id, route, handler));
+            id
+        };
+
+        let handlers = Arc::downgrade(&self.0);
+
+        ObserverToken::new(move || {
+            if let Some(handlers) = handlers.upgrade() {
+                handlers.lock().unwrap().entries.retain(|(entry_id, _, _)| *entry_id != id);
+            }
+        })
+    }
+
+    /// Calls every handler registered for `route` with `payload`. Returns how many were called.
+    pub fn dispatch(&self, route: &Route, payload: &dyn Any) -> usize {
+        let handlers: Vec<Arc<Handler>> = {
+            let handlers = self.0.lock().unwrap();
+            handlers.entries.iter()
+                .filter(|(_, entry_route, _)| entry_route == route)
+                .map(|(_, _, handler)| handler.clone())
+                .collect()
+        };
+
+        for handler in &handlers {
+            handler(payload);
+        }
+
+        handlers.len()
+    }
+
+    /// Returns how many handlers are registered for `route`.
+    #[cfg(test)]
+    pub fn count(&self, route: &Route) -> usize {
+        let handlers = self.0.lock().unwrap();
+        handlers.entries.iter().filter(|(_, entry_route, _)| entry_route == route).count()
+    }
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let handlers = self.0.lock().unwrap();
+        let routes: Vec<&Route> = handlers.entries.iter().map(|(_, route, _)| route).collect();
+
+        f.debug_struct("Router")
+            .field("routes", &routes)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use super::{Route, Router};
+    use crate::notification_center::NotificationName;
+
+    fn named(name: &str) -> Route {
+        Route::Named(NotificationName::from(name))
+    }
+
+    /// Returns a counter, and a handler that bumps it by the payload.
+    fn counter() -> (Arc<AtomicUsize>, impl Fn(&usize) + Send + Sync + 'static) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let handler_count = count.clone();
+
+        (count, move |amount: &usize| {
+            handler_count.fetch_add(*amount, Ordering::SeqCst);
+        })
+    }
+
+    #[test]
+    fn dispatches_by_route() {
+        let router = Router::new();
+        let (first, first_handler) = counter();
+        let (second, second_handler) = counter();
+
+        let _first = router.add(named("CacaoFirst"), first_handler);
+        let _second = router.add(Route::Custom(TypeId::of::<usize>(), "usize"), second_handler);
+
+        assert_eq!(router.dispatch(&named("CacaoFirst"), &2usize), 1);
+        assert_eq!(router.dispatch(&named("CacaoOther"), &5usize), 0);
+        assert_eq!(router.dispatch(&Route::Custom(TypeId::of::<usize>(), "usize"), &3usize), 1);
+
+        assert_eq!(first.load(Ordering::SeqCst), 2);
+        assert_eq!(second.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn calls_handlers_in_order() {
+        let router = Router::new();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let tokens: Vec<_> = (0..3).map(|index| {
+            let order = order.clone();
+            router.add(named("CacaoOrdered"), move |_: &()| order.lock().unwrap().push(index))
+        }).collect();
+
+        assert_eq!(router.dispatch(&named("CacaoOrdered"), &()), 3);
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+        drop(tokens);
+    }
+
+    #[test]
+    fn ignores_mismatched_payloads() {
+        let router = Router::new();
+        let (count, handler) = counter();
+        let _token = router.add(named("CacaoTyped"), handler);
+
+        // The handler is still called (and counted), but can't see a payload it didn't ask for.
+        assert_eq!(router.dispatch(&named("CacaoTyped"), &"not a usize"), 1);
+        assert_eq!(router.dispatch(&named("CacaoTyped"), &7u32), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        router.dispatch(&named("CacaoTyped"), &7usize);
+        assert_eq!(count.load(Ordering::SeqCst), 7);
+    }
+
+    #[test]
+    fn dropping_a_token_unregisters_its_handler() {
+        let router = Router::new();
+        let (count, handler) = counter();
+        let (kept, kept_handler) = counter();
+
+        let token = router.add(named("CacaoDropped"), handler);
+        let _kept = router.add(named("CacaoDropped"), kept_handler);
+        assert_eq!(router.count(&named("CacaoDropped")), 2);
+
+        drop(token);
+        assert_eq!(router.count(&named("CacaoDropped")), 1);
+        assert_eq!(router.dispatch(&named("CacaoDropped"), &1usize), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        assert_eq!(kept.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn tokens_outliving_their_router_are_harmless() {
+        let router = Router::new();
+        let (_, handler) = counter();
+        let token = router.add(named("CacaoOutlived"), handler);
+
+        drop(router);
+        drop(token);
+    }
+
+    #[test]
+    fn handlers_can_add_post_and_drop_reentrantly() {
+        let router = Arc::new(Router::new());
+        let (inner, inner_handler) = counter();
+        let inner_handler = Arc::new(inner_handler);
+        let seen = Arc::new(AtomicUsize::new(0));
+
+        let handler_router = router.clone();
+        let handler_seen = seen.clone();
+
+        let _outer = router.add(named("CacaoOuter"), move |_: &()| {
+            // Adding and dropping from inside a handler mustn't deadlock...
+            let handler = inner_handler.clone();
+            let token = handler_router.add(named("CacaoInner"), move |amount: &usize| handler(amount));
+            handler_seen.store(handler_router.count(&named("CacaoInner")), Ordering::SeqCst);
+
+            // ...and neither must posting.
+            handler_router.dispatch(&named("CacaoInner"), &4usize);
+            drop(token);
+        });
+
+        assert_eq!(router.dispatch(&named("CacaoOuter"), &()), 1);
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+        assert_eq!(inner.load(Ordering::SeqCst), 4);
+        assert_eq!(router.count(&named("CacaoInner")), 0);
+        assert_eq!(router.count(&named("CacaoOuter")), 1);
+    }
+}
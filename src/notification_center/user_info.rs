@@ -0,0 +1,713 @@
+//! Typed payloads for the `userInfo` dictionaries that system notifications carry.
+//!
+//! Most of the interesting data in a system notification lives in its `userInfo`, keyed by string
+//! constants that are easy to get wrong. The types here pull the commonly used ones out into
+//! structs, and report a missing (or mistyped) key as an error rather than a silent default.
+//!
+//! ```rust,no_run
+//! use cacao::notification_center::{NotificationCenter, NotificationName};
+//! use cacao::notification_center::user_info::KeyboardChange;
+//!
+//! let center = NotificationCenter::default();
+//!
+//! let _token = center.observe_payload(NotificationName::UIKeyboardWillShow, |change: &KeyboardChange| {
+//!     println!("Keyboard will end up at {:?}", change.frame_end);
+//! });
+//! ```
+
+use std::collections::HashMap;
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+
+use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+use objc::{class, msg_send, sel, sel_impl};
+use objc::runtime::Object;
+
+use crate::defaults::{Value, ValueKind};
+use crate::foundation::{id, nil, to_bool, BOOL, NSArray, NSDictionary, NSString};
+use crate::geometry::Rect;
+use crate::notification_center::NotificationName;
+
+/// Errors that can occur when decoding a `userInfo` dictionary into a payload.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserInfoError {
+    /// A key the payload requires wasn't in the dictionary.
+    MissingKey(&'static str),
+
+    /// A key was present, but its value wasn't something the payload could read.
+    InvalidValue {
+        /// The key that was read.
+        key: &'static str,
+
+        /// The kind of value the payload expected.
+        expected: ValueKind,
+
+        /// The kind of value that was found.
+        found: ValueKind
+    }
+}
+
+impl fmt::Display for UserInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UserInfoError::MissingKey(key) => write!(f, "userInfo has no value for '{}'", key),
+
+            UserInfoError::InvalidValue { key, expected, found } => {
+                write!(f, "userInfo has a {} for '{}', but a {} was expected", found, key, expected)
+            }
+        }
+    }
+}
+
+impl error::Error for UserInfoError {}
+
+/// A payload that can be decoded from a notification's `userInfo` dictionary.
+pub trait UserInfo: Sized {
+    /// The notifications that are posted with this payload.
+    fn names() -> &'static [NotificationName];
+
+    /// Decodes the payload from an already-converted `userInfo` (e.g, `Notification::user_info`).
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError>;
+
+    /// Decodes the payload from an `NSDictionary`.
+    fn from_dictionary(dictionary: &NSDictionary) -> Result<Self, UserInfoError> {
+        let dictionary = &*dictionary.0 as *const Object as id;
+        Self::from_user_info(&decode(dictionary))
+    }
+}
+
+/// Converts a `userInfo` dictionary into `Value`s. Entries that can't be represented are left
+/// out, with one exception: rects boxed in `NSValue` (e.g, keyboard frames) are converted into the
+/// dictionary form CoreGraphics uses - `X`, `Y`, `Width` and `Height` - so that payloads can
+/// read them.
+pub(crate) fn decode(user_info: id) -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+
+    if user_info == nil {
+        return map;
+    }
+
+    let keys = NSArray::wrap(unsafe { msg_send![user_info, allKeys] });
+
+    if keys.count() > 0 {
+        for key in keys.map(|key| key) {
+            if !NSString::is(key) {
+                continue;
+            }
+
+            let object: id = unsafe { msg_send![user_info, objectForKey:key] };
+
+            if let Some(value) = Value::from_objc(object).or_else(|| rect_from_objc(object)) {
+                map.insert(NSString::wrap(key).to_string(), value);
+            }
+        }
+    }
+
+    map
+}
+
+/// If `object` is an `NSValue` holding a rect, returns it as a CoreGraphics-style dictionary.
+fn rect_from_objc(object: id) -> Option<Value> {
+    if object == nil {
+        return None;
+    }
+
+    let is_value: BOOL = unsafe { msg_send![object, isKindOfClass:class!(NSValue)] };
+
+    if !to_bool(is_value) {
+        return None;
+    }
+
+    let encoding = unsafe {
+        let encoding: *const c_char = msg_send![object, objCType];
+        CStr::from_ptr(encoding).to_string_lossy().into_owned()
+    };
+
+    // `NSRect` is a typedef of `CGRect` on 64-bit, but is still encoded under its own name when
+    // boxed by AppKit.
+    if !(encoding.starts_with("{CGRect=") || encoding.starts_with("{_NSRect=")) {
+        return None;
+    }
+
+    let mut rect = CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(0., 0.));
+
+    unsafe {
+        let _: () = msg_send![object, getValue:&mut rect as *mut CGRect as *mut c_void
+            size:mem::size_of::<CGRect>()];
+    }
+
+    let mut dictionary = HashMap::new();
+    dictionary.insert("X".to_string(), Value::Float(rect.origin.x));
+    dictionary.insert("Y".to_string(), Value::Float(rect.origin.y));
+    dictionary.insert("Width".to_string(), Value::Float(rect.size.width));
+    dictionary.insert("Height".to_string(), Value::Float(rect.size.height));
+    Some(Value::Dictionary(dictionary))
+}
+
+fn value<'a>(user_info: &'a HashMap<String, Value>, key: &'static str) -> Result<&'a Value, UserInfoError> {
+    user_info.get(key).ok_or(UserInfoError::MissingKey(key))
+}
+
+fn invalid(key: &'static str, expected: ValueKind, found: &Value) -> UserInfoError {
+    UserInfoError::InvalidValue {
+        key: key,
+        expected: expected,
+        found: found.kind()
+    }
+}
+
+/// Reads a boolean. `NSNumber`s created from ints are common here, so any integer is accepted,
+/// with zero being `false`.
+fn read_bool(user_info: &HashMap<String, Value>, key: &'static str) -> Result<bool, UserInfoError> {
+    let value = value(user_info, key)?;
+
+    match value {
+        Value::Bool(b) => Ok(*b),
+        Value::Integer(i) => Ok(*i != 0),
+        Value::UnsignedInteger(u) => Ok(*u != 0),
+        _ => Err(invalid(key, ValueKind::Bool, value))
+    }
+}
+
+/// Reads a number as `f64`, whatever it was stored as.
+fn read_f64(user_info: &HashMap<String, Value>, key: &'static str) -> Result<f64, UserInfoError> {
+    let value = value(user_info, key)?;
+
+    match value {
+        Value::Float(f) => Ok(*f),
+        Value::Integer(i) => Ok(*i as f64),
+        Value::UnsignedInteger(u) => Ok(*u as f64),
+        _ => Err(invalid(key, ValueKind::Float, value))
+    }
+}
+
+fn read_i64(user_info: &HashMap<String, Value>, key: &'static str) -> Result<i64, UserInfoError> {
+    let value = value(user_info, key)?;
+    value.as_i64().ok_or_else(|| invalid(key, ValueKind::Integer, value))
+}
+
+fn read_u64(user_info: &HashMap<String, Value>, key: &'static str) -> Result<u64, UserInfoError> {
+    let value = value(user_info, key)?;
+    value.as_u64().ok_or_else(|| invalid(key, ValueKind::UnsignedInteger, value))
+}
+
+fn read_string(user_info: &HashMap<String, Value>, key: &'static str) -> Result<String, UserInfoError> {
+    let value = value(user_info, key)?;
+    value.as_str().map(str::to_string).ok_or_else(|| invalid(key, ValueKind::String, value))
+}
+
+/// Reads a rect from its CoreGraphics dictionary form; see `decode`.
+fn read_rect(user_info: &HashMap<String, Value>, key: &'static str) -> Result<Rect, UserInfoError> {
+    let value = value(user_info, key)?;
+    let rect = value.as_dictionary().ok_or_else(|| invalid(key, ValueKind::Dictionary, value))?;
+
+    let component = |name: &'static str| -> Result<f64, UserInfoError> {
+        read_f64(rect, name).map_err(|_| invalid(key, ValueKind::Dictionary, value))
+    };
+
+    Ok(Rect::new(component("Y")?, component("X")?, component("Width")?, component("Height")?))
+}
+
+/// Makes a key optional: a missing key becomes `None`, but a mistyped one is still an error.
+fn optional<T>(result: Result<T, UserInfoError>) -> Result<Option<T>, UserInfoError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(UserInfoError::MissingKey(_)) => Ok(None),
+        Err(error) => Err(error)
+    }
+}
+
+/// Posted with `NSWindowDidChangeBackingProperties`, when a window's scale factor (or color
+/// space) changes - e.g, when it's dragged to a display with a different resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowBackingChange {
+    /// The scale factor the window had before the change. Read the new one from the window.
+    pub old_scale_factor: f64
+}
+
+impl UserInfo for WindowBackingChange {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[NotificationName::NSWindowDidChangeBackingProperties];
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(WindowBackingChange {
+            old_scale_factor: read_f64(user_info, "NSBackingPropertyOldScaleFactorKey")?
+        })
+    }
+}
+
+/// Posted with `NSApplicationDidFinishLaunching`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApplicationLaunch {
+    /// `false` if the app was launched to open or print a file, or to handle a user
+    /// notification; `true` otherwise.
+    pub is_default_launch: bool
+}
+
+impl UserInfo for ApplicationLaunch {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[NotificationName::NSApplicationDidFinishLaunching];
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(ApplicationLaunch {
+            is_default_launch: read_bool(user_info, "NSApplicationLaunchIsDefaultLaunchKey")?
+        })
+    }
+}
+
+/// Posted by `NSWorkspace` when another app launches or terminates. Note that these are posted on
+/// the workspace's own notification center, not the default one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkspaceApplication {
+    /// The app's name.
+    pub name: String,
+
+    /// The path the app was launched from.
+    pub path: String,
+
+    /// The app's bundle identifier. Apps that aren't bundled don't have one.
+    pub bundle_identifier: Option<String>,
+
+    /// The app's process identifier.
+    pub process_identifier: i64
+}
+
+impl UserInfo for WorkspaceApplication {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[
+            NotificationName::NSWorkspaceWillLaunchApplication,
+            NotificationName::NSWorkspaceDidLaunchApplication,
+            NotificationName::NSWorkspaceDidTerminateApplication
+        ];
+
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(WorkspaceApplication {
+            name: read_string(user_info, "NSApplicationName")?,
+            path: read_string(user_info, "NSApplicationPath")?,
+            bundle_identifier: optional(read_string(user_info, "NSApplicationBundleIdentifier"))?,
+            process_identifier: read_i64(user_info, "NSApplicationProcessIdentifier")?
+        })
+    }
+}
+
+/// How the user left a text field or view - which is how you'd tell a Tab from a Return.
+/// Mirrors `NSTextMovement`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextMovement {
+    /// Editing ended for some other reason, e.g the user clicked elsewhere.
+    Other,
+
+    /// The Return key.
+    Return,
+
+    /// The Tab key.
+    Tab,
+
+    /// Shift-Tab.
+    Backtab,
+
+    /// The left arrow key.
+    Left,
+
+    /// The right arrow key.
+    Right,
+
+    /// The up arrow key.
+    Up,
+
+    /// The down arrow key.
+    Down,
+
+    /// Editing was cancelled, e.g with Escape.
+    Cancel,
+
+    /// A value this version doesn't know about.
+    Unknown(i64)
+}
+
+impl From<i64> for TextMovement {
+    fn from(value: i64) -> Self {
+        match value {
+            0x00 => TextMovement::Other,
+            0x10 => TextMovement::Return,
+            0x11 => TextMovement::Tab,
+            0x12 => TextMovement::Backtab,
+            0x13 => TextMovement::Left,
+            0x14 => TextMovement::Right,
+            0x15 => TextMovement::Up,
+            0x16 => TextMovement::Down,
+            0x17 => TextMovement::Cancel,
+            value => TextMovement::Unknown(value)
+        }
+    }
+}
+
+/// Posted with `NSTextDidEndEditing` and `NSControlTextDidEndEditing`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEditingEnded {
+    /// How the user left the field.
+    pub movement: TextMovement
+}
+
+impl UserInfo for TextEditingEnded {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[
+            NotificationName::NSTextDidEndEditing,
+            NotificationName::NSControlTextDidEndEditing
+        ];
+
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(TextEditingEnded {
+            movement: TextMovement::from(read_i64(user_info, "NSTextMovement")?)
+        })
+    }
+}
+
+/// Posted with the `UIKeyboard*` show, hide, and frame change notifications.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardChange {
+    /// The keyboard's frame before the change, in screen coordinates.
+    pub frame_begin: Rect,
+
+    /// The keyboard's frame after the change, in screen coordinates.
+    pub frame_end: Rect,
+
+    /// How long the keyboard animates for, in seconds.
+    pub animation_duration: f64,
+
+    /// The raw `UIViewAnimationCurve` the keyboard animates with. This is often a private value,
+    /// so it's passed through as-is.
+    pub animation_curve: i64,
+
+    /// Whether the keyboard belongs to this app, rather than another one in multitasking.
+    pub is_local: bool
+}
+
+impl UserInfo for KeyboardChange {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[
+            NotificationName::UIKeyboardWillShow,
+            NotificationName::UIKeyboardDidShow,
+            NotificationName::UIKeyboardWillHide,
+            NotificationName::UIKeyboardDidHide,
+            NotificationName::UIKeyboardWillChangeFrame,
+            NotificationName::UIKeyboardDidChangeFrame
+        ];
+
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(KeyboardChange {
+            frame_begin: read_rect(user_info, "UIKeyboardFrameBeginUserInfoKey")?,
+            frame_end: read_rect(user_info, "UIKeyboardFrameEndUserInfoKey")?,
+            animation_duration: read_f64(user_info, "UIKeyboardAnimationDurationUserInfoKey")?,
+            animation_curve: read_i64(user_info, "UIKeyboardAnimationCurveUserInfoKey")?,
+            is_local: read_bool(user_info, "UIKeyboardIsLocalUserInfoKey")?
+        })
+    }
+}
+
+/// Why the audio route changed. Mirrors `AVAudioSessionRouteChangeReason`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AudioRouteChangeReason {
+    /// The reason is unknown.
+    Unknown,
+
+    /// A device became available, e.g headphones were plugged in.
+    NewDeviceAvailable,
+
+    /// The previous device is gone, e.g headphones were unplugged.
+    OldDeviceUnavailable,
+
+    /// The session's category changed.
+    CategoryChange,
+
+    /// The route was overridden.
+    Override,
+
+    /// The device woke from sleep.
+    WakeFromSleep,
+
+    /// No route is suitable for the session's category.
+    NoSuitableRouteForCategory,
+
+    /// The set of inputs or outputs didn't change, but their configuration did.
+    RouteConfigurationChange,
+
+    /// A value this version doesn't know about.
+    Other(u64)
+}
+
+impl From<u64> for AudioRouteChangeReason {
+    fn from(value: u64) -> Self {
+        match value {
+            0 => AudioRouteChangeReason::Unknown,
+            1 => AudioRouteChangeReason::NewDeviceAvailable,
+            2 => AudioRouteChangeReason::OldDeviceUnavailable,
+            3 => AudioRouteChangeReason::CategoryChange,
+            4 => AudioRouteChangeReason::Override,
+            6 => AudioRouteChangeReason::WakeFromSleep,
+            7 => AudioRouteChangeReason::NoSuitableRouteForCategory,
+            8 => AudioRouteChangeReason::RouteConfigurationChange,
+            value => AudioRouteChangeReason::Other(value)
+        }
+    }
+}
+
+/// Posted with `AudioSessionRouteChange`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioRouteChange {
+    /// Why the route changed.
+    pub reason: AudioRouteChangeReason
+}
+
+impl UserInfo for AudioRouteChange {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[NotificationName::AudioSessionRouteChange];
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(AudioRouteChange {
+            reason: AudioRouteChangeReason::from(read_u64(user_info, "AVAudioSessionRouteChangeReasonKey")?)
+        })
+    }
+}
+
+/// How urgently an announcement should be spoken. Mirrors `NSAccessibilityPriorityLevel`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AnnouncementPriority {
+    /// Spoken when nothing else is.
+    Low,
+
+    /// Interrupts low priority announcements.
+    Medium,
+
+    /// Interrupts everything, and can't be interrupted.
+    High,
+
+    /// A value this version doesn't know about.
+    Other(i64)
+}
+
+impl From<i64> for AnnouncementPriority {
+    fn from(value: i64) -> Self {
+        match value {
+            10 => AnnouncementPriority::Low,
+            50 => AnnouncementPriority::Medium,
+            90 => AnnouncementPriority::High,
+            value => AnnouncementPriority::Other(value)
+        }
+    }
+}
+
+/// Posted with `NSAccessibilityAnnouncementRequested`, when something asks assistive technology
+/// to speak.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityAnnouncement {
+    /// What should be spoken.
+    pub announcement: String,
+
+    /// How urgently, if specified.
+    pub priority: Option<AnnouncementPriority>
+}
+
+impl UserInfo for AccessibilityAnnouncement {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[NotificationName::NSAccessibilityAnnouncementRequested];
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(AccessibilityAnnouncement {
+            announcement: read_string(user_info, "AXAnnouncementKey")?,
+            priority: optional(read_i64(user_info, "AXPriorityKey"))?.map(AnnouncementPriority::from)
+        })
+    }
+}
+
+/// Posted with `UIAccessibilityAnnouncementDidFinish`, when VoiceOver is done with an
+/// announcement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnouncementFinished {
+    /// What was announced.
+    pub announcement: String,
+
+    /// Whether it was spoken in full, rather than interrupted.
+    pub was_successful: bool
+}
+
+impl UserInfo for AnnouncementFinished {
+    fn names() -> &'static [NotificationName] {
+        static NAMES: &[NotificationName] = &[NotificationName::UIAccessibilityAnnouncementDidFinish];
+        NAMES
+    }
+
+    fn from_user_info(user_info: &HashMap<String, Value>) -> Result<Self, UserInfoError> {
+        Ok(AnnouncementFinished {
+            announcement: read_string(user_info, "UIAccessibilityAnnouncementKeyStringValue")?,
+            was_successful: read_bool(user_info, "UIAccessibilityAnnouncementKeyWasSuccessful")?
+        })
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    use super::*;
+    use crate::foundation::NSNumber;
+
+    /// Builds an `NSDictionary` from string keys, the way the system hands `userInfo` over.
+    fn user_info(entries: Vec<(&str, id)>) -> NSDictionary {
+        let mut dictionary = NSDictionary::new();
+
+        for (key, object) in entries {
+            dictionary.insert(NSString::new(key), object);
+        }
+
+        dictionary
+    }
+
+    fn string(value: &str) -> id {
+        NSString::new(value).into_inner()
+    }
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> id {
+        let rect = CGRect::new(&CGPoint::new(x, y), &CGSize::new(width, height));
+        unsafe { msg_send![class!(NSValue), valueWithCGRect:rect] }
+    }
+
+    #[test]
+    fn decodes_keyboard_changes() {
+        let dictionary = user_info(vec![
+            ("UIKeyboardFrameBeginUserInfoKey", rect(0., 844., 390., 336.)),
+            ("UIKeyboardFrameEndUserInfoKey", rect(0., 508., 390., 336.)),
+            ("UIKeyboardAnimationDurationUserInfoKey", NSNumber::float(0.25).into_inner()),
+            ("UIKeyboardAnimationCurveUserInfoKey", NSNumber::integer(7).into_inner()),
+            ("UIKeyboardIsLocalUserInfoKey", NSNumber::bool(true).into_inner())
+        ]);
+
+        assert_eq!(KeyboardChange::from_dictionary(&dictionary), Ok(KeyboardChange {
+            frame_begin: Rect::new(844., 0., 390., 336.),
+            frame_end: Rect::new(508., 0., 390., 336.),
+            animation_duration: 0.25,
+            animation_curve: 7,
+            is_local: true
+        }));
+    }
+
+    #[test]
+    fn decodes_optional_keys() {
+        let mut entries = vec![
+            ("NSApplicationName", string("Calculator")),
+            ("NSApplicationPath", string("/System/Applications/Calculator.app")),
+            ("NSApplicationProcessIdentifier", NSNumber::integer(512).into_inner())
+        ];
+
+        let application = WorkspaceApplication::from_dictionary(&user_info(entries.clone())).unwrap();
+        assert_eq!(application.name, "Calculator");
+        assert_eq!(application.bundle_identifier, None);
+        assert_eq!(application.process_identifier, 512);
+
+        entries.push(("NSApplicationBundleIdentifier", string("com.apple.calculator")));
+        let application = WorkspaceApplication::from_dictionary(&user_info(entries)).unwrap();
+        assert_eq!(application.bundle_identifier, Some("com.apple.calculator".to_string()));
+
+        // Optional keys that are present still have to be the right type.
+        let dictionary = user_info(vec![
+            ("AXAnnouncementKey", string("Saved")),
+            ("AXPriorityKey", string("high"))
+        ]);
+
+        assert_eq!(AccessibilityAnnouncement::from_dictionary(&dictionary), Err(UserInfoError::InvalidValue {
+            key: "AXPriorityKey",
+            expected: ValueKind::Integer,
+            found: ValueKind::String
+        }));
+    }
+
+    #[test]
+    fn accepts_numbers_as_booleans_and_floats() {
+        let dictionary = user_info(vec![("NSApplicationLaunchIsDefaultLaunchKey", NSNumber::integer(0).into_inner())]);
+        assert_eq!(ApplicationLaunch::from_dictionary(&dictionary), Ok(ApplicationLaunch { is_default_launch: false }));
+
+        let dictionary = user_info(vec![("NSBackingPropertyOldScaleFactorKey", NSNumber::integer(2).into_inner())]);
+        assert_eq!(WindowBackingChange::from_dictionary(&dictionary), Ok(WindowBackingChange { old_scale_factor: 2. }));
+    }
+
+    #[test]
+    fn reports_missing_keys() {
+        assert_eq!(
+            ApplicationLaunch::from_dictionary(&NSDictionary::new()),
+            Err(UserInfoError::MissingKey("NSApplicationLaunchIsDefaultLaunchKey"))
+        );
+
+        let dictionary = user_info(vec![("UIKeyboardFrameBeginUserInfoKey", rect(0., 0., 10., 10.))]);
+        assert_eq!(
+            KeyboardChange::from_dictionary(&dictionary),
+            Err(UserInfoError::MissingKey("UIKeyboardFrameEndUserInfoKey"))
+        );
+
+        assert_eq!(
+            UserInfoError::MissingKey("AXAnnouncementKey").to_string(),
+            "userInfo has no value for 'AXAnnouncementKey'"
+        );
+    }
+
+    #[test]
+    fn reports_mistyped_values() {
+        let dictionary = user_info(vec![("NSTextMovement", string("tab"))]);
+
+        assert_eq!(TextEditingEnded::from_dictionary(&dictionary), Err(UserInfoError::InvalidValue {
+            key: "NSTextMovement",
+            expected: ValueKind::Integer,
+            found: ValueKind::String
+        }));
+
+        // Rects must be boxed rects, not just any dictionary.
+        let dictionary = user_info(vec![("UIKeyboardFrameBeginUserInfoKey", string("{{0, 0}, {10, 10}}"))]);
+
+        match KeyboardChange::from_dictionary(&dictionary) {
+            Err(UserInfoError::InvalidValue { key: "UIKeyboardFrameBeginUserInfoKey", expected: ValueKind::Dictionary, .. }) => {},
+            other => panic!("Expected an invalid rect, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn maps_raw_values() {
+        let movement = |value: i64| {
+            let dictionary = user_info(vec![("NSTextMovement", NSNumber::integer(value).into_inner())]);
+            TextEditingEnded::from_dictionary(&dictionary).unwrap().movement
+        };
+
+        assert_eq!(movement(0x11), TextMovement::Tab);
+        assert_eq!(movement(0x17), TextMovement::Cancel);
+        assert_eq!(movement(0x99), TextMovement::Unknown(0x99));
+
+        let dictionary = user_info(vec![("AVAudioSessionRouteChangeReasonKey", NSNumber::integer(2).into_inner())]);
+        assert_eq!(AudioRouteChange::from_dictionary(&dictionary).unwrap().reason, AudioRouteChangeReason::OldDeviceUnavailable);
+        assert_eq!(AudioRouteChangeReason::from(5), AudioRouteChangeReason::Other(5));
+        assert_eq!(AnnouncementPriority::from(90), AnnouncementPriority::High);
+    }
+
+    #[test]
+    fn decodes_nothing_from_nil() {
+        assert!(decode(nil).is_empty());
+    }
+}
@@ -2,14 +2,14 @@ use objc_id::ShareId;
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSUInteger};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSUInteger};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::{register_progress_indicator_class};
 
 #[cfg(target_os = "ios")]
@@ -26,6 +26,8 @@ pub struct ProgressIndicator {
     /// A pointer to the Objective-C runtime view controller.
     pub objc: ShareId<Object>,
 
+    mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -52,39 +54,47 @@ pub struct ProgressIndicator {
 }
 
 impl Default for ProgressIndicator {
+    /// Returns a new `ProgressIndicator`. This can't take a `MainThreadMarker`, so it checks the
+    /// current thread at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        ProgressIndicator::new()
+        ProgressIndicator::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl ProgressIndicator {
     /// Returns a default `ProgressIndicator`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let view = unsafe {
             let view: id = msg_send![register_progress_indicator_class(), new];
             let _: () = msg_send![view, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", feature = "headless"))]
             let _: () = msg_send![view, setWantsLayer:YES];
 
             view
         };
 
         ProgressIndicator {
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         }
     }
 }
 
 impl ProgressIndicator {
+    /// Returns a marker for the main thread, which this progress indicator is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Call this to set the background color for the backing layer.
     pub fn set_background_color(&self, color: Color) {
         let bg = color.into_platform_specific_color();
@@ -7,6 +7,7 @@ use objc_id::ShareId;
 use block::ConcreteBlock;
 
 use crate::error::Error;
+use crate::executor::{oneshot, Receiver};
 use crate::foundation::{id, nil, NSUInteger};
 use crate::image::Image;
 
@@ -48,4 +49,35 @@ impl ThumbnailGenerator {
                 updateHandler:block];
         }
     }
+
+    /// Generates the best thumbnail available for `path`, and returns a future that resolves to
+    /// it. Unlike `generate`, which can call back once per quality level, this resolves once,
+    /// with the highest quality the generator can produce.
+    ///
+    /// This resolves to `None` only if the generator goes away without completing.
+    pub fn generate_async(&self, path: &Path, config: ThumbnailConfig) -> Receiver<Result<(Image, ThumbnailQuality), Error>> {
+        let (sender, receiver) = oneshot();
+
+        let block = ConcreteBlock::new(move |thumbnail: id, error: id| {
+            if error == nil {
+                unsafe {
+                    let image = Image::with(msg_send![thumbnail, NSImage]);
+                    let thumbnail_type: NSUInteger = msg_send![thumbnail, type];
+                    sender.send(Ok((image, ThumbnailQuality::from(thumbnail_type))));
+                }
+            } else {
+                sender.send(Err(Error::new(error)));
+            }
+        });
+
+        let block = block.copy();
+        let request = config.to_request(path);
+
+        unsafe {
+            let _: () = msg_send![&*self.0, generateBestRepresentationForRequest:request
+                completionHandler:block];
+        }
+
+        receiver
+    }
 }
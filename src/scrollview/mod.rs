@@ -45,15 +45,15 @@ use objc_id::ShareId;
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSArray, NSString};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSArray, NSString};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 use crate::pasteboard::PasteboardType;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::{register_scrollview_class, register_scrollview_class_with_delegate};
 
 #[cfg(target_os = "ios")]
@@ -73,7 +73,7 @@ fn allocate_view(registration_fn: fn() -> *const Class) -> id {
         let view: id = msg_send![registration_fn(), new];
         let _: () = msg_send![view, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         {
             let _: () = msg_send![view, setDrawsBackground:NO];
             let _: () = msg_send![view, setWantsLayer:YES];
@@ -95,6 +95,8 @@ pub struct ScrollView<T = ()> {
     /// A pointer to the delegate for this view.
     pub delegate: Option<Box<T>>,
 
+    mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -121,26 +123,29 @@ pub struct ScrollView<T = ()> {
 }
 
 impl Default for ScrollView {
+    /// Returns a new `ScrollView`. This can't take a `MainThreadMarker`, so it checks the current
+    /// thread at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        ScrollView::new()
+        ScrollView::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl ScrollView {
     /// Returns a default `View`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let view = allocate_view(register_scrollview_class);
 
         ScrollView {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         }
     }
@@ -149,7 +154,7 @@ impl ScrollView {
 impl<T> ScrollView<T> where T: ScrollViewDelegate + 'static {
     /// Initializes a new View with a given `ViewDelegate`. This enables you to respond to events
     /// and customize the view as a module, similar to class-based systems.
-    pub fn with(delegate: T) -> ScrollView<T> {
+    pub fn with(delegate: T, mtm: MainThreadMarker) -> ScrollView<T> {
         let mut delegate = Box::new(delegate);
         
         let view = allocate_view(register_scrollview_class_with_delegate::<T>);
@@ -162,14 +167,15 @@ impl<T> ScrollView<T> where T: ScrollViewDelegate + 'static {
 
         let mut view = ScrollView {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         };
 
@@ -187,6 +193,7 @@ impl<T> ScrollView<T> {
     pub(crate) fn clone_as_handle(&self) -> ScrollView {
         ScrollView {
             delegate: None,
+            mtm: self.mtm,
             top: self.top.clone(),
             leading: self.leading.clone(),
             trailing: self.trailing.clone(),
@@ -199,6 +206,11 @@ impl<T> ScrollView<T> {
         }
     }
 
+    /// Returns a marker for the main thread, which this scroll view is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Call this to set the background color for the backing layer.
     pub fn set_background_color(&self, color: Color) {
         let bg = color.into_platform_specific_color();
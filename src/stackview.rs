@@ -0,0 +1,200 @@
+//! A container that lays its children out in a row or column, generating the constraints for you.
+//!
+//! Rather than hand-writing anchors for every control in a row of buttons, add them to a
+//! `StackView` and describe how they should be arranged:
+//!
+//! ```rust,no_run
+//! use cacao::button::Button;
+//! use cacao::foundation::MainThreadMarker;
+//! use cacao::layout::{Layout, StackAxis, StackDistribution};
+//! use cacao::stackview::StackView;
+//!
+//! let mtm = MainThreadMarker::new().unwrap();
+//! let row = StackView::new(StackAxis::Horizontal, mtm);
+//! row.set_spacing(1.);
+//! row.set_distribution(StackDistribution::FillEqually);
+//!
+//! let buttons = vec![Button::new("7", mtm), Button::new("8", mtm), Button::new("9", mtm)];
+//! for button in &buttons {
+//!     row.add_arranged_subview(button);
+//! }
+//! ```
+//!
+//! The constraints themselves are generated by `layout::StackLayout`, which you can use on its
+//! own (e.g, with the `layout::solver` module) to check what a stack will produce.
+
+use std::cell::RefCell;
+
+use objc::{msg_send, sel, sel_impl};
+use objc::runtime::Object;
+use objc_id::ShareId;
+
+use crate::color::Color;
+use crate::foundation::MainThreadMarker;
+use crate::geometry::EdgeInsets;
+use crate::layout::{
+    Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension, LayoutConstraint, LayoutItem,
+    StackAlignment, StackAxis, StackDistribution, StackLayout
+};
+use crate::view::View;
+
+/// Returns the `LayoutItem` for a given backing node.
+pub(crate) fn layout_item(node: &ShareId<Object>) -> LayoutItem {
+    LayoutItem(&**node as *const Object as usize)
+}
+
+/// A container view that stacks its arranged subviews along an axis.
+#[derive(Debug)]
+pub struct StackView {
+    /// The container view that arranged subviews are added to.
+    pub view: View,
+
+    /// A pointer to the Objective-C runtime top layout constraint.
+    pub top: LayoutAnchorY,
+
+    /// A pointer to the Objective-C runtime leading layout constraint.
+    pub leading: LayoutAnchorX,
+
+    /// A pointer to the Objective-C runtime trailing layout constraint.
+    pub trailing: LayoutAnchorX,
+
+    /// A pointer to the Objective-C runtime bottom layout constraint.
+    pub bottom: LayoutAnchorY,
+
+    /// A pointer to the Objective-C runtime width layout constraint.
+    pub width: LayoutAnchorDimension,
+
+    /// A pointer to the Objective-C runtime height layout constraint.
+    pub height: LayoutAnchorDimension,
+
+    /// A pointer to the Objective-C runtime center X layout constraint.
+    pub center_x: LayoutAnchorX,
+
+    /// A pointer to the Objective-C runtime center Y layout constraint.
+    pub center_y: LayoutAnchorY,
+
+    layout: RefCell<StackLayout>,
+    arranged: RefCell<Vec<ShareId<Object>>>,
+    constraints: RefCell<Vec<LayoutConstraint>>
+}
+
+impl Default for StackView {
+    /// Returns a new, vertical `StackView`. This can't take a `MainThreadMarker`, so it checks the
+    /// current thread at runtime instead, and panics if it's called off the main thread.
+    fn default() -> Self {
+        StackView::new(StackAxis::Vertical, MainThreadMarker::new().expect("UI types must be created on the main thread."))
+    }
+}
+
+impl StackView {
+    /// Returns a new, empty `StackView` that stacks along the given axis.
+    pub fn new(axis: StackAxis, mtm: MainThreadMarker) -> Self {
+        let view = View::new(mtm);
+
+        StackView {
+            top: view.top.clone(),
+            leading: view.leading.clone(),
+            trailing: view.trailing.clone(),
+            bottom: view.bottom.clone(),
+            width: view.width.clone(),
+            height: view.height.clone(),
+            center_x: view.center_x.clone(),
+            center_y: view.center_y.clone(),
+            view: view,
+            layout: RefCell::new(StackLayout::new(axis)),
+            arranged: RefCell::new(Vec::new()),
+            constraints: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Returns the current layout configuration.
+    pub fn stack_layout(&self) -> StackLayout {
+        *self.layout.borrow()
+    }
+
+    /// Replaces the layout configuration wholesale, and re-lays out the arranged subviews.
+    pub fn set_stack_layout(&self, layout: StackLayout) {
+        *self.layout.borrow_mut() = layout;
+        self.relayout();
+    }
+
+    /// Sets the axis to stack along.
+    pub fn set_axis(&self, axis: StackAxis) {
+        self.layout.borrow_mut().axis = axis;
+        self.relayout();
+    }
+
+    /// Sets the space between adjacent arranged subviews, in points.
+    pub fn set_spacing<F: Into<f64>>(&self, spacing: F) {
+        self.layout.borrow_mut().spacing = spacing.into();
+        self.relayout();
+    }
+
+    /// Sets how arranged subviews are positioned on the perpendicular axis.
+    pub fn set_alignment(&self, alignment: StackAlignment) {
+        self.layout.borrow_mut().alignment = alignment;
+        self.relayout();
+    }
+
+    /// Sets how arranged subviews are sized and positioned along the stack axis.
+    pub fn set_distribution(&self, distribution: StackDistribution) {
+        self.layout.borrow_mut().distribution = distribution;
+        self.relayout();
+    }
+
+    /// Sets the insets from the edges of this view.
+    pub fn set_insets(&self, insets: EdgeInsets) {
+        self.layout.borrow_mut().insets = insets;
+        self.relayout();
+    }
+
+    /// Sets the background color of the container.
+    pub fn set_background_color<C: AsRef<Color>>(&self, color: C) {
+        self.view.set_background_color(color);
+    }
+
+    /// Adds a view to the end of the stack.
+    pub fn add_arranged_subview<V: Layout>(&self, view: &V) {
+        self.view.add_subview(view);
+        self.arranged.borrow_mut().push(view.get_backing_node());
+        self.relayout();
+    }
+
+    /// Removes every arranged subview from the stack, and from the view hierarchy.
+    pub fn remove_arranged_subviews(&self) {
+        LayoutConstraint::deactivate(&self.constraints.borrow_mut().split_off(0));
+
+        for node in self.arranged.borrow_mut().drain(..) {
+            unsafe {
+                let _: () = msg_send![&*node, removeFromSuperview];
+            }
+        }
+    }
+
+    /// Regenerates and activates the constraints for the current configuration.
+    fn relayout(&self) {
+        let container = layout_item(&self.view.get_backing_node());
+        let children: Vec<LayoutItem> = self.arranged.borrow().iter().map(layout_item).collect();
+
+        let constraints: Vec<LayoutConstraint> = self.layout.borrow()
+            .constraints(container, &children)
+            .into_iter()
+            .map(LayoutConstraint::from_record)
+            .collect();
+
+        let mut active = self.constraints.borrow_mut();
+        LayoutConstraint::deactivate(&active);
+        LayoutConstraint::activate(&constraints);
+        *active = constraints;
+    }
+}
+
+impl Layout for StackView {
+    fn get_backing_node(&self) -> ShareId<Object> {
+        self.view.get_backing_node()
+    }
+
+    fn add_subview<V: Layout>(&self, view: &V) {
+        self.view.add_subview(view);
+    }
+}
@@ -9,7 +9,7 @@ use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{class, msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, BOOL, YES, NO, NSString};
+use crate::foundation::{id, nil, BOOL, YES, NO, MainThreadMarker, NSString};
 use crate::invoker::TargetActionHandler;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 use crate::utils::load;
@@ -20,6 +20,7 @@ use crate::utils::load;
 pub struct Switch {
     pub objc: ShareId<Object>,
     handler: Option<TargetActionHandler>,
+    mtm: MainThreadMarker,
     
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
@@ -49,7 +50,7 @@ pub struct Switch {
 impl Switch {
     /// Creates a new `NSSwitch` instance, configures it appropriately,
     /// and retains the necessary Objective-C runtime pointer.
-    pub fn new(text: &str) -> Self {
+    pub fn new(text: &str, mtm: MainThreadMarker) -> Self {
         let title = NSString::new(text);
 
         let view: id = unsafe {
@@ -61,18 +62,24 @@ impl Switch {
         
         Switch {
             handler: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         }
     }
 
+    /// Returns a marker for the main thread, which this switch is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Sets whether this is checked on or off.
     pub fn set_checked(&mut self, checked: bool) {
         unsafe {
@@ -1,14 +1,34 @@
 
+use core_graphics::base::CGFloat;
+
 use crate::foundation::{NSInteger, NSUInteger};
+use crate::layout::LayoutDirection;
 
+/// How text is aligned within a control.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TextAlign {
     Left,
     Right,
     Center,
     Justified,
+
+    /// Aligned to the leading edge: left for left-to-right layouts, and right for right-to-left
+    /// ones.
     Natural
 }
 
+impl TextAlign {
+    /// Resolves `Natural` to `Left` or `Right` for `direction`. Every other alignment is returned
+    /// as-is.
+    pub fn resolve(self, direction: LayoutDirection) -> TextAlign {
+        match (self, direction) {
+            (TextAlign::Natural, LayoutDirection::LeftToRight) => TextAlign::Left,
+            (TextAlign::Natural, LayoutDirection::RightToLeft) => TextAlign::Right,
+            (alignment, _) => alignment
+        }
+    }
+}
+
 impl From<TextAlign> for NSInteger {
     fn from(alignment: TextAlign) -> Self {
         match alignment {
@@ -56,3 +76,74 @@ impl Into<NSUInteger> for LineBreakMode {
 }
 
 
+
+/// Standard font weights, from thinnest to heaviest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FontWeight {
+    /// The thinnest weight.
+    UltraLight,
+
+    /// A thin weight.
+    Thin,
+
+    /// A light weight.
+    Light,
+
+    /// The default weight.
+    Regular,
+
+    /// A medium weight.
+    Medium,
+
+    /// A semi-bold weight.
+    Semibold,
+
+    /// A bold weight.
+    Bold,
+
+    /// A heavy weight.
+    Heavy,
+
+    /// The heaviest weight.
+    Black
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        FontWeight::Regular
+    }
+}
+
+impl FontWeight {
+    /// Maps a CSS-style numeric weight (`100` through `900`) to the nearest `FontWeight`.
+    pub fn from_numeric(weight: u16) -> Self {
+        match weight {
+            0..=149 => FontWeight::UltraLight,
+            150..=249 => FontWeight::Thin,
+            250..=349 => FontWeight::Light,
+            350..=449 => FontWeight::Regular,
+            450..=549 => FontWeight::Medium,
+            550..=649 => FontWeight::Semibold,
+            650..=749 => FontWeight::Bold,
+            750..=849 => FontWeight::Heavy,
+            _ => FontWeight::Black
+        }
+    }
+}
+
+impl From<FontWeight> for CGFloat {
+    /// The values of the `NSFontWeight`/`UIFontWeight` constants.
+    fn from(weight: FontWeight) -> Self {
+        match weight {
+            FontWeight::UltraLight => -0.8,
+            FontWeight::Thin => -0.6,
+            FontWeight::Light => -0.4,
+            FontWeight::Regular => 0.,
+            FontWeight::Medium => 0.23,
+            FontWeight::Semibold => 0.3,
+            FontWeight::Bold => 0.4,
+            FontWeight::Heavy => 0.56,
+            FontWeight::Black => 0.62
+        }
+    }
+}
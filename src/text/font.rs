@@ -7,6 +7,7 @@ use objc::runtime::{Class, Object};
 use objc::{class, msg_send, sel, sel_impl};
 
 use crate::foundation::{id, nil, YES, NO, NSArray, NSString};
+use crate::text::FontWeight;
 
 #[derive(Debug)]
 pub struct Font {
@@ -33,4 +34,15 @@ impl Font {
             }
         }
     }
+
+    /// Returns the system font at the given size and weight.
+    pub fn system_with_weight(size: CGFloat, weight: FontWeight) -> Self {
+        let weight: CGFloat = weight.into();
+
+        Font {
+            objc: unsafe {
+                msg_send![class!(NSFont), systemFontOfSize:size weight:weight]
+            }
+        }
+    }
 }
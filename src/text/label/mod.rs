@@ -44,15 +44,15 @@ use objc_id::ShareId;
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::{id, nil, YES, NO, NSArray, NSInteger, NSUInteger, NSString};
+use crate::foundation::{id, nil, YES, NO, MainThreadMarker, NSArray, NSInteger, NSUInteger, NSString};
 use crate::color::Color;
 use crate::layout::{Layout, LayoutAnchorX, LayoutAnchorY, LayoutAnchorDimension};
 use crate::text::{Font, TextAlign, LineBreakMode};
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::{register_view_class, register_view_class_with_delegate};
 
 #[cfg(target_os = "ios")]
@@ -72,7 +72,7 @@ pub(crate) static LABEL_DELEGATE_PTR: &str = "rstLabelDelegatePtr";
 /// A helper method for instantiating view classes and applying default settings to them.
 fn allocate_view(registration_fn: fn() -> *const Class) -> id { 
     unsafe {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let view: id = {
             // This sucks, but for now, sure.
             let blank = NSString::new("");
@@ -84,7 +84,7 @@ fn allocate_view(registration_fn: fn() -> *const Class) -> id {
 
         let _: () = msg_send![view, setTranslatesAutoresizingMaskIntoConstraints:NO];
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         let _: () = msg_send![view, setWantsLayer:YES];
 
         view 
@@ -101,6 +101,9 @@ pub struct Label<T = ()> {
     /// A pointer to the delegate for this view.
     pub delegate: Option<Box<T>>,
 
+    /// Proof that this label was created on the main thread, which keeps it there.
+    pub(crate) mtm: MainThreadMarker,
+
     /// A pointer to the Objective-C runtime top layout constraint.
     pub top: LayoutAnchorY,
 
@@ -127,26 +130,29 @@ pub struct Label<T = ()> {
 }
 
 impl Default for Label {
+    /// Returns a new `Label`. This can't take a `MainThreadMarker`, so it checks the current thread
+    /// at runtime instead, and panics if it's called off the main thread.
     fn default() -> Self {
-        Label::new()
+        Label::new(MainThreadMarker::new().expect("UI types must be created on the main thread."))
     }
 }
 
 impl Label {
     /// Returns a default `Label`, suitable for 
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
         let view = allocate_view(register_view_class);
 
         Label {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![view, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![view, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![view, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![view, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![view, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![view, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![view, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![view, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            trailing: LayoutAnchorX::trailing(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center_x(view),
+            center_y: LayoutAnchorY::center_y(view),
             objc: unsafe { ShareId::from_ptr(view) },
         }
     }
@@ -155,7 +161,7 @@ impl Label {
 impl<T> Label<T> where T: LabelDelegate + 'static {
     /// Initializes a new Label with a given `LabelDelegate`. This enables you to respond to events
     /// and customize the view as a module, similar to class-based systems.
-    pub fn with(delegate: T) -> Label<T> {
+    pub fn with(delegate: T, mtm: MainThreadMarker) -> Label<T> {
         let delegate = Box::new(delegate);
         
         let label = allocate_view(register_view_class_with_delegate::<T>);
@@ -168,14 +174,15 @@ impl<T> Label<T> where T: LabelDelegate + 'static {
 
         let mut label = Label {
             delegate: None,
-            top: LayoutAnchorY::new(unsafe { msg_send![label, topAnchor] }),
-            leading: LayoutAnchorX::new(unsafe { msg_send![label, leadingAnchor] }),
-            trailing: LayoutAnchorX::new(unsafe { msg_send![label, trailingAnchor] }),
-            bottom: LayoutAnchorY::new(unsafe { msg_send![label, bottomAnchor] }),
-            width: LayoutAnchorDimension::new(unsafe { msg_send![label, widthAnchor] }),
-            height: LayoutAnchorDimension::new(unsafe { msg_send![label, heightAnchor] }),
-            center_x: LayoutAnchorX::new(unsafe { msg_send![label, centerXAnchor] }),
-            center_y: LayoutAnchorY::new(unsafe { msg_send![label, centerYAnchor] }),
+            mtm: mtm,
+            top: LayoutAnchorY::top(label),
+            leading: LayoutAnchorX::leading(label),
+            trailing: LayoutAnchorX::trailing(label),
+            bottom: LayoutAnchorY::bottom(label),
+            width: LayoutAnchorDimension::width(label),
+            height: LayoutAnchorDimension::height(label),
+            center_x: LayoutAnchorX::center_x(label),
+            center_y: LayoutAnchorY::center_y(label),
             objc: unsafe { ShareId::from_ptr(label) },
         };
 
@@ -193,6 +200,7 @@ impl<T> Label<T> {
     pub(crate) fn clone_as_handle(&self) -> Label {
         Label {
             delegate: None,
+            mtm: self.mtm,
             top: self.top.clone(),
             leading: self.leading.clone(),
             trailing: self.trailing.clone(),
@@ -205,6 +213,11 @@ impl<T> Label<T> {
         }
     }
 
+    /// Returns a marker for the main thread, which this label is tied to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        self.mtm
+    }
+
     /// Call this to set the background color for the backing layer.
     pub fn set_background_color(&self, color: Color) {
         let bg = color.into_platform_specific_color();
@@ -243,7 +256,12 @@ impl<T> Label<T> {
         s.to_str().to_string()
     }
 
+    /// Sets the alignment of the text. `TextAlign::Natural` is resolved against this control's
+    /// layout direction when this is called, so it follows any direction forced on it (or on a
+    /// view containing it) - see `Layout::set_layout_direction`.
     pub fn set_text_alignment(&self, alignment: TextAlign) {
+        let alignment = alignment.resolve(self.layout_direction());
+
         unsafe {
             let alignment: NSInteger = alignment.into();
             let _: () = msg_send![&*self.objc, setAlignment:alignment];
@@ -257,7 +275,7 @@ impl<T> Label<T> {
     }
 
     pub fn set_line_break_mode(&self, mode: LineBreakMode) {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", feature = "headless"))]
         unsafe {
             let cell: id = msg_send![&*self.objc, cell];
             let mode = mode as NSUInteger;
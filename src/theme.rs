@@ -0,0 +1,676 @@
+//! Loads design tokens - colors, fonts and spacing - from a JSON or TOML file, and turns them into
+//! `Color` and `Font` instances.
+//!
+//! Tokens are keyed by name. Colors can be a single value, used for every appearance, or a table
+//! of variants:
+//!
+//! ```toml
+//! [colors]
+//! accent = "#0A84FF"
+//! text = { light = "#333333", dark = "rgb(238, 238, 238)", dark-high-contrast = "white" }
+//!
+//! [fonts]
+//! body = { size = 13 }
+//! title = { size = 22, weight = "semibold" }
+//!
+//! [spacing]
+//! small = 4
+//! medium = 8
+//! ```
+//!
+//! Color tables must include both `light` and `dark`; the high contrast variants are optional and
+//! fall back to their normal contrast counterparts. Color strings accept anything `Color::parse`
+//! does, and font weights can be names (`"bold"`) or CSS-style numbers (`700`).
+//!
+//! Loading and validation don't touch the Objective-C runtime, so a theme file can be checked in a
+//! test on any platform:
+//!
+//! ```rust
+//! use cacao::theme::DesignTokens;
+//!
+//! let tokens = DesignTokens::from_json(r##"{
+//!     "colors": { "text": { "light": "#333", "dark": "#EEE" } },
+//!     "spacing": { "small": 4 }
+//! }"##).unwrap();
+//!
+//! assert_eq!(tokens.spacing("small"), Some(4.));
+//! ```
+//!
+//! This module requires the `theme` feature.
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use crate::color::{Color, ColorParseError, ColorValue, Contrast, Style, Theme};
+use crate::text::{Font, FontWeight};
+
+/// Errors that can occur when loading a set of design tokens.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThemeError {
+    /// The file couldn't be read.
+    Io(String),
+
+    /// The file isn't valid JSON or TOML, or doesn't have the expected structure.
+    Syntax(String),
+
+    /// The file was parsed, but one or more tokens are invalid.
+    Invalid(Vec<TokenError>)
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(error) => write!(f, "Unable to read theme: {}", error),
+            ThemeError::Syntax(error) => write!(f, "Unable to parse theme: {}", error),
+
+            ThemeError::Invalid(errors) => {
+                write!(f, "Theme has {} invalid token(s)", errors.len())?;
+
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for ThemeError {}
+
+/// A problem with a single token.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenError {
+    /// A color table is missing a required variant (`light` or `dark`).
+    MissingVariant {
+        /// The name of the color token.
+        token: String,
+
+        /// The name of the missing variant.
+        variant: &'static str
+    },
+
+    /// A color string couldn't be parsed.
+    InvalidColor {
+        /// The name of the color token.
+        token: String,
+
+        /// The variant the color was for.
+        variant: &'static str,
+
+        /// Why the color couldn't be parsed.
+        error: ColorParseError
+    },
+
+    /// A font weight isn't one we know about.
+    InvalidFontWeight {
+        /// The name of the font token.
+        token: String,
+
+        /// The weight, as written.
+        weight: String
+    },
+
+    /// A font size or spacing value is negative, or not a finite number.
+    InvalidSize {
+        /// The name of the font or spacing token.
+        token: String,
+
+        /// The value, as written.
+        value: f64
+    }
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenError::MissingVariant { token, variant } => {
+                write!(f, "Color \"{}\" is missing its \"{}\" variant", token, variant)
+            },
+
+            TokenError::InvalidColor { token, variant, error } => {
+                write!(f, "Color \"{}\" has an invalid \"{}\" variant: {}", token, variant, error)
+            },
+
+            TokenError::InvalidFontWeight { token, weight } => {
+                write!(f, "Font \"{}\" has an unknown weight: {}", token, weight)
+            },
+
+            TokenError::InvalidSize { token, value } => write!(f, "\"{}\" has an invalid size: {}", token, value)
+        }
+    }
+}
+
+impl error::Error for TokenError {}
+
+/// A color that can differ by theme and contrast level.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorToken {
+    /// The color in light mode.
+    pub light: ColorValue,
+
+    /// The color in dark mode.
+    pub dark: ColorValue,
+
+    /// The color in light mode with increased contrast. Falls back to `light`.
+    pub light_high_contrast: Option<ColorValue>,
+
+    /// The color in dark mode with increased contrast. Falls back to `dark`.
+    pub dark_high_contrast: Option<ColorValue>
+}
+
+impl ColorToken {
+    /// Returns a token that uses the same color everywhere.
+    pub fn single(color: ColorValue) -> Self {
+        ColorToken {
+            light: color,
+            dark: color,
+            light_high_contrast: None,
+            dark_high_contrast: None
+        }
+    }
+
+    /// Returns the color for a given style.
+    pub fn resolve(&self, style: Style) -> ColorValue {
+        match (style.theme, style.contrast) {
+            (Theme::Light, Contrast::Normal) => self.light,
+            (Theme::Light, Contrast::High) => self.light_high_contrast.unwrap_or(self.light),
+            (Theme::Dark, Contrast::Normal) => self.dark,
+            (Theme::Dark, Contrast::High) => self.dark_high_contrast.unwrap_or(self.dark)
+        }
+    }
+
+    /// Returns a dynamic `Color` that switches between the variants of this token.
+    pub fn color(&self) -> Color {
+        let token = *self;
+        Color::dynamic(move |style| token.resolve(style).into())
+    }
+}
+
+/// A font size and weight.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FontToken {
+    /// The point size.
+    pub size: f64,
+
+    /// The weight.
+    pub weight: FontWeight
+}
+
+impl FontToken {
+    /// Returns the system font at this size and weight.
+    pub fn font(&self) -> Font {
+        Font::system_with_weight(self.size, self.weight)
+    }
+}
+
+/// A set of named colors, fonts and spacing values.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DesignTokens {
+    /// Color tokens, by name.
+    pub colors: BTreeMap<String, ColorToken>,
+
+    /// Font tokens, by name.
+    pub fonts: BTreeMap<String, FontToken>,
+
+    /// Spacing values in points, by name.
+    pub spacing: BTreeMap<String, f64>
+}
+
+impl DesignTokens {
+    /// Parses and validates tokens from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, ThemeError> {
+        let raw: RawTokens = serde_json::from_str(json).map_err(|e| ThemeError::Syntax(e.to_string()))?;
+        raw.validate()
+    }
+
+    /// Parses and validates tokens from a TOML string.
+    pub fn from_toml(toml: &str) -> Result<Self, ThemeError> {
+        let raw: RawTokens = toml::from_str(toml).map_err(|e| ThemeError::Syntax(e.to_string()))?;
+        raw.validate()
+    }
+
+    /// Loads tokens from a file. Files ending in `.toml` are parsed as TOML, and everything else
+    /// as JSON.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| ThemeError::Io(e.to_string()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => DesignTokens::from_toml(&contents),
+            _ => DesignTokens::from_json(&contents)
+        }
+    }
+
+    /// Returns the color value for a token in a given style, without creating a `Color`.
+    pub fn color_value(&self, name: &str, style: Style) -> Option<ColorValue> {
+        self.colors.get(name).map(|token| token.resolve(style))
+    }
+
+    /// Returns a dynamic `Color` for a token.
+    pub fn color(&self, name: &str) -> Option<Color> {
+        self.colors.get(name).map(ColorToken::color)
+    }
+
+    /// Returns a `Font` for a token.
+    pub fn font(&self, name: &str) -> Option<Font> {
+        self.fonts.get(name).map(FontToken::font)
+    }
+
+    /// Returns a spacing value, in points.
+    pub fn spacing(&self, name: &str) -> Option<f64> {
+        self.spacing.get(name).copied()
+    }
+}
+
+/// Watches a token file on disk, and reloads it when it changes. Call `poll` periodically (e.g,
+/// from a timer, or when your app becomes active) and re-apply the tokens when it returns
+/// `Ok(true)`.
+#[derive(Debug)]
+pub struct ThemeFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    tokens: DesignTokens
+}
+
+impl ThemeFile {
+    /// Loads tokens from `path`, and remembers it for reloading.
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, ThemeError> {
+        let path = path.into();
+        let modified = modified(&path);
+        let tokens = DesignTokens::from_path(&path)?;
+
+        Ok(ThemeFile {
+            path: path,
+            modified: modified,
+            tokens: tokens
+        })
+    }
+
+    /// Returns the most recently loaded tokens.
+    pub fn tokens(&self) -> &DesignTokens {
+        &self.tokens
+    }
+
+    /// Reloads the file if it's changed since it was last loaded, returning whether it did.
+    ///
+    /// If the new contents are invalid, the error is returned and the previous tokens are kept -
+    /// so a half-saved file doesn't take your app's colors down with it.
+    pub fn poll(&mut self) -> Result<bool, ThemeError> {
+        let modified = modified(&self.path);
+
+        if modified == self.modified {
+            return Ok(false);
+        }
+
+        let tokens = DesignTokens::from_path(&self.path)?;
+        self.modified = modified;
+        self.tokens = tokens;
+        Ok(true)
+    }
+}
+
+/// Returns the modification time of a file, if it can be read.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// The on-disk shape of a token file, before validation.
+#[derive(Deserialize)]
+struct RawTokens {
+    #[serde(default)]
+    colors: BTreeMap<String, RawColor>,
+
+    #[serde(default)]
+    fonts: BTreeMap<String, RawFont>,
+
+    #[serde(default)]
+    spacing: BTreeMap<String, f64>
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Single(String),
+    Variants(RawVariants)
+}
+
+/// A color with a value per appearance. Unknown keys are rejected, so that a misspelled variant
+/// is reported rather than quietly ignored.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawVariants {
+    light: Option<String>,
+    dark: Option<String>,
+
+    #[serde(alias = "light-high-contrast", alias = "lightHighContrast")]
+    light_high_contrast: Option<String>,
+
+    #[serde(alias = "dark-high-contrast", alias = "darkHighContrast")]
+    dark_high_contrast: Option<String>
+}
+
+#[derive(Deserialize)]
+struct RawFont {
+    size: f64,
+
+    #[serde(default)]
+    weight: Option<RawWeight>
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawWeight {
+    Name(String),
+    Number(f64)
+}
+
+impl RawTokens {
+    /// Converts to `DesignTokens`, collecting every invalid token rather than stopping at the
+    /// first.
+    fn validate(self) -> Result<DesignTokens, ThemeError> {
+        let mut errors = Vec::new();
+        let mut tokens = DesignTokens::default();
+
+        for (name, raw) in self.colors {
+            if let Some(token) = validate_color(&name, raw, &mut errors) {
+                tokens.colors.insert(name, token);
+            }
+        }
+
+        for (name, raw) in self.fonts {
+            if !raw.size.is_finite() || raw.size < 0. {
+                errors.push(TokenError::InvalidSize { token: name.clone(), value: raw.size });
+                continue;
+            }
+
+            let weight = match raw.weight {
+                None => FontWeight::Regular,
+
+                Some(weight) => match parse_weight(&weight) {
+                    Some(weight) => weight,
+
+                    None => {
+                        let weight = match weight {
+                            RawWeight::Name(name) => name,
+                            RawWeight::Number(number) => number.to_string()
+                        };
+
+                        errors.push(TokenError::InvalidFontWeight { token: name, weight: weight });
+                        continue;
+                    }
+                }
+            };
+
+            tokens.fonts.insert(name, FontToken { size: raw.size, weight: weight });
+        }
+
+        for (name, value) in self.spacing {
+            match value.is_finite() && value >= 0. {
+                true => { tokens.spacing.insert(name, value); },
+                false => { errors.push(TokenError::InvalidSize { token: name, value: value }); }
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(tokens),
+            false => Err(ThemeError::Invalid(errors))
+        }
+    }
+}
+
+/// Validates a single color token, pushing any problems onto `errors`.
+fn validate_color(name: &str, raw: RawColor, errors: &mut Vec<TokenError>) -> Option<ColorToken> {
+    let start = errors.len();
+
+    let mut parse = |variant: &'static str, value: Option<String>, required: bool| -> Option<ColorValue> {
+        match value {
+            Some(value) => match value.parse::<crate::color::Rgba>() {
+                Ok(rgba) => Some(ColorValue::Srgb(rgba)),
+
+                Err(error) => {
+                    errors.push(TokenError::InvalidColor { token: name.to_string(), variant: variant, error: error });
+                    None
+                }
+            },
+
+            None => {
+                if required {
+                    errors.push(TokenError::MissingVariant { token: name.to_string(), variant: variant });
+                }
+
+                None
+            }
+        }
+    };
+
+    let token = match raw {
+        RawColor::Single(value) => parse("light", Some(value), true).map(ColorToken::single),
+
+        RawColor::Variants(RawVariants { light, dark, light_high_contrast, dark_high_contrast }) => {
+            let light = parse("light", light, true);
+            let dark = parse("dark", dark, true);
+            let light_high_contrast = parse("light-high-contrast", light_high_contrast, false);
+            let dark_high_contrast = parse("dark-high-contrast", dark_high_contrast, false);
+
+            match (light, dark) {
+                (Some(light), Some(dark)) => Some(ColorToken {
+                    light: light,
+                    dark: dark,
+                    light_high_contrast: light_high_contrast,
+                    dark_high_contrast: dark_high_contrast
+                }),
+
+                _ => None
+            }
+        }
+    };
+
+    match errors.len() == start {
+        true => token,
+        false => None
+    }
+}
+
+/// Parses a font weight name or CSS-style number.
+fn parse_weight(weight: &RawWeight) -> Option<FontWeight> {
+    match weight {
+        RawWeight::Number(number) if *number >= 1. && *number <= 1000. => {
+            Some(FontWeight::from_numeric(*number as u16))
+        },
+
+        RawWeight::Number(_) => None,
+
+        RawWeight::Name(name) => match name.to_ascii_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "ultralight" | "extralight" => Some(FontWeight::UltraLight),
+            "thin" => Some(FontWeight::Thin),
+            "light" => Some(FontWeight::Light),
+            "regular" | "normal" => Some(FontWeight::Regular),
+            "medium" => Some(FontWeight::Medium),
+            "semibold" | "demibold" => Some(FontWeight::Semibold),
+            "bold" => Some(FontWeight::Bold),
+            "heavy" | "extrabold" => Some(FontWeight::Heavy),
+            "black" => Some(FontWeight::Black),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+    use std::process;
+    use std::time::{Duration, SystemTime};
+
+    use super::{DesignTokens, FontToken, ThemeError, ThemeFile, TokenError};
+    use crate::color::{ColorValue, Contrast, Rgba, Style, Theme};
+    use crate::text::FontWeight;
+
+    /// Returns a path in the temporary directory that's unique to this process and test.
+    fn temporary_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("cacao-theme-{}-{}", process::id(), name))
+    }
+
+    /// Writes `contents` to `path`, and pushes its modification time `seconds` into the future so
+    /// that the change is seen regardless of the filesystem's timestamp resolution.
+    fn write(path: &PathBuf, contents: &str, seconds: u64) {
+        fs::write(path, contents).unwrap();
+
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(seconds)).unwrap();
+    }
+
+    fn style(theme: Theme, contrast: Contrast) -> Style {
+        Style { theme: theme, contrast: contrast, ..Style::default() }
+    }
+
+    fn srgb(hex: u32) -> ColorValue {
+        ColorValue::Srgb(Rgba::from_hex(hex))
+    }
+
+    #[test]
+    fn parses_json() {
+        let tokens = DesignTokens::from_json(r##"{
+            "colors": {
+                "accent": "#0A84FF",
+                "text": { "light": "#333333", "dark": "#EEEEEE", "darkHighContrast": "white" }
+            },
+            "fonts": { "body": { "size": 13 }, "title": { "size": 22, "weight": 700 } },
+            "spacing": { "small": 4, "medium": 8.5 }
+        }"##).unwrap();
+
+        assert_eq!(tokens.color_value("accent", style(Theme::Dark, Contrast::High)), Some(srgb(0x0A84FF)));
+        assert_eq!(tokens.color_value("text", style(Theme::Light, Contrast::Normal)), Some(srgb(0x333333)));
+        assert_eq!(tokens.color_value("text", style(Theme::Light, Contrast::High)), Some(srgb(0x333333)));
+        assert_eq!(tokens.color_value("text", style(Theme::Dark, Contrast::Normal)), Some(srgb(0xEEEEEE)));
+        assert_eq!(tokens.color_value("text", style(Theme::Dark, Contrast::High)), Some(srgb(0xFFFFFF)));
+        assert_eq!(tokens.color_value("missing", Style::default()), None);
+
+        assert_eq!(tokens.fonts["body"], FontToken { size: 13., weight: FontWeight::Regular });
+        assert_eq!(tokens.fonts["title"], FontToken { size: 22., weight: FontWeight::Bold });
+        assert_eq!(tokens.spacing("medium"), Some(8.5));
+    }
+
+    #[test]
+    fn parses_toml() {
+        let tokens = DesignTokens::from_toml(r##"
+            [colors]
+            accent = "rgb(10, 132, 255)"
+            text = { light = "#333", dark = "#EEE", light-high-contrast = "black" }
+
+            [fonts]
+            title = { size = 22, weight = "semi-bold" }
+
+            [spacing]
+            small = 4
+        "##).unwrap();
+
+        assert_eq!(tokens.color_value("accent", Style::default()), Some(srgb(0x0A84FF)));
+        assert_eq!(tokens.color_value("text", style(Theme::Light, Contrast::High)), Some(srgb(0x000000)));
+        assert_eq!(tokens.color_value("text", style(Theme::Dark, Contrast::High)), Some(srgb(0xEEEEEE)));
+        assert_eq!(tokens.fonts["title"].weight, FontWeight::Semibold);
+        assert_eq!(tokens.spacing("small"), Some(4.));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        match DesignTokens::from_json("{ \"colors\": ") {
+            Err(ThemeError::Syntax(_)) => {},
+            other => panic!("Expected a syntax error, got {:?}", other)
+        }
+
+        match DesignTokens::from_toml("[fonts]\nbody = { weight = \"bold\" }") {
+            Err(ThemeError::Syntax(_)) => {},
+            other => panic!("Expected a syntax error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn reports_missing_variants() {
+        let error = DesignTokens::from_json(r##"{ "colors": { "text": { "light": "#333" } } }"##).unwrap_err();
+
+        assert_eq!(error, ThemeError::Invalid(vec![
+            TokenError::MissingVariant { token: "text".to_string(), variant: "dark" }
+        ]));
+    }
+
+    #[test]
+    fn rejects_misspelled_variants() {
+        match DesignTokens::from_json(r##"{ "colors": { "text": { "light": "#333", "drak": "#EEE" } } }"##) {
+            Err(ThemeError::Syntax(_)) => {},
+            other => panic!("Expected a syntax error, got {:?}", other)
+        }
+
+        match DesignTokens::from_toml("[colors]\ntext = { light = \"#333\", dark = \"#EEE\", dark-high-contarst = \"white\" }") {
+            Err(ThemeError::Syntax(_)) => {},
+            other => panic!("Expected a syntax error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn reports_every_invalid_token() {
+        let error = DesignTokens::from_json(r##"{
+            "colors": { "text": { "light": "#33", "dark": "#EEE" } },
+            "fonts": {
+                "body": { "size": 13, "weight": "chunky" },
+                "caption": { "size": 11, "weight": 1200 },
+                "title": { "size": -1 }
+            },
+            "spacing": { "small": -4 }
+        }"##).unwrap_err();
+
+        let errors = match error {
+            ThemeError::Invalid(errors) => errors,
+            other => panic!("Expected invalid tokens, got {:?}", other)
+        };
+
+        assert_eq!(errors.len(), 5);
+
+        match &errors[0] {
+            TokenError::InvalidColor { token, variant, .. } => {
+                assert_eq!(token, "text");
+                assert_eq!(*variant, "light");
+            },
+
+            other => panic!("Expected an invalid color, got {:?}", other)
+        }
+
+        assert_eq!(errors[1..].to_vec(), vec![
+            TokenError::InvalidFontWeight { token: "body".to_string(), weight: "chunky".to_string() },
+            TokenError::InvalidFontWeight { token: "caption".to_string(), weight: "1200".to_string() },
+            TokenError::InvalidSize { token: "title".to_string(), value: -1. },
+            TokenError::InvalidSize { token: "small".to_string(), value: -4. }
+        ]);
+    }
+
+    #[test]
+    fn polls_for_changes() {
+        let path = temporary_path("poll.toml");
+        write(&path, "[spacing]\nsmall = 4", 0);
+
+        let mut file = ThemeFile::open(&path).unwrap();
+        assert_eq!(file.tokens().spacing("small"), Some(4.));
+        assert_eq!(file.poll(), Ok(false));
+
+        write(&path, "[spacing]\nsmall = 6", 10);
+        assert_eq!(file.poll(), Ok(true));
+        assert_eq!(file.tokens().spacing("small"), Some(6.));
+        assert_eq!(file.poll(), Ok(false));
+
+        // A broken save keeps the previous tokens around.
+        write(&path, "[spacing]\nsmall = ", 20);
+        assert!(file.poll().is_err());
+        assert_eq!(file.tokens().spacing("small"), Some(6.));
+
+        fs::remove_file(&path).unwrap();
+        assert!(ThemeFile::open(&path).is_err());
+    }
+}
@@ -12,6 +12,9 @@ use objc_id::ShareId;
 
 use crate::foundation::{id, BOOL, YES, NO};
 
+#[cfg(all(feature = "headless", not(target_vendor = "apple")))]
+use crate::headless::dispatch;
+
 pub mod os {
     use lazy_static::lazy_static;
     use os_info::Version;
@@ -77,14 +80,6 @@ where
     queue.exec_async(method);
 }
 
-pub fn sync_main_thread<F>(method: F)
-where
-    F: Fn() + Send + 'static
-{
-    let queue = dispatch::Queue::main();    
-    queue.exec_sync(method);    
-}
-
 /// Upstream core graphics does not implement Encode for certain things, so we wrap them here -
 /// these are only used in reading certain types passed to us from some delegate methods.
 #[repr(C)]
@@ -2,15 +2,15 @@ use objc_id::ShareId;
 use objc::runtime::Object;
 use objc::{msg_send, sel, sel_impl};
 
-use crate::foundation::id;
+use crate::foundation::{id, MainThreadMarker};
 use crate::layout::{Layout};
 use crate::view::{VIEW_DELEGATE_PTR, View, ViewDelegate};
 use crate::utils::Controller;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "headless"))]
 use macos::register_view_controller_class;
 
 #[cfg(target_os = "ios")]
@@ -26,9 +26,9 @@ pub struct ViewController<T> {
 }
 
 impl<T> ViewController<T> where T: ViewDelegate + 'static {
-    pub fn new(delegate: T) -> Self {
+    pub fn new(delegate: T, mtm: MainThreadMarker) -> Self {
         let class = register_view_controller_class::<T>(&delegate);
-        let view = View::with(delegate);
+        let view = View::with(delegate, mtm);
 
         let objc = unsafe {
             let vc: id = msg_send![class, new];
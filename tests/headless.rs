@@ -0,0 +1,66 @@
+//! Builds a small window with the headless runtime, and checks what it recorded.
+
+#![cfg(all(feature = "headless", not(target_vendor = "apple")))]
+
+use cacao::color::{Color, ColorValue, Rgba};
+use cacao::foundation::MainThreadMarker;
+use cacao::headless::{self, Value};
+use cacao::layout::{Layout, LayoutConstraint, LayoutItem, LayoutRelation};
+use cacao::macos::window::{Window, WindowConfig};
+use cacao::text::Label;
+use cacao::view::View;
+
+#[test]
+fn records_a_window_and_its_views() {
+    let mtm = MainThreadMarker::new().unwrap();
+
+    let window = Window::new(WindowConfig::default(), mtm);
+    window.set_title("Headless");
+
+    let content = View::new(mtm);
+    content.set_background_color(Color::rgb(255, 0, 0));
+
+    let label = Label::new(mtm);
+    label.set_text("Hello, world");
+    label.set_background_color(Color::SystemBlue);
+    content.add_subview(&label);
+
+    LayoutConstraint::activate(&[
+        label.top.constraint_equal_to(&content.top).offset(16.),
+        label.leading.constraint_equal_to(&content.leading).offset(16.),
+        label.width.constraint_greater_than_or_equal_to_constant(120.)
+    ]);
+
+    window.set_content_view(&content);
+    window.show();
+
+    let tree = headless::inspect_window(&window);
+    assert_eq!(tree.text(), Some("Headless"));
+    assert_eq!(tree.property("visible"), Some(&Value::Bool(true)));
+
+    let content_node = &tree.children[0];
+    assert!(content_node.is_kind_of("NSView"));
+    assert_eq!(
+        content_node.background_color(),
+        Some(&Value::Color(ColorValue::Srgb(Rgba::new(1., 0., 0., 1.))))
+    );
+
+    let label_node = &content_node.children[0];
+    assert!(label_node.is_kind_of("NSTextField"));
+    assert_eq!(label_node.text(), Some("Hello, world"));
+    assert_eq!(label_node.background_color(), Some(&Value::SystemColor("systemBlueColor".to_string())));
+
+    assert_eq!(label_node.constraints.len(), 3);
+    let top = &label_node.constraints[0].record;
+    assert_eq!(top.second.map(|anchor| anchor.item), Some(LayoutItem(content_node.id.0)));
+    assert_eq!(top.offset, 16.);
+
+    let width = &label_node.constraints[2].record;
+    assert_eq!(width.relation, LayoutRelation::GreaterThanOrEqual);
+    assert_eq!(width.second, None);
+    assert_eq!(width.offset, 120.);
+
+    // Nothing cacao sent along the way should have gone unanswered.
+    let label_class = format!("-[{} ", label_node.class_name);
+    assert!(headless::unhandled_selectors().iter().all(|s| !s.starts_with(&label_class)));
+}